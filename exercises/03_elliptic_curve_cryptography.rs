@@ -19,6 +19,9 @@ fn main() {
             other::public_key_recovery::run();
             other::private_key_recovery::run();
             other::signing_nonsense::run();
+            other::test_schnorr_signature::run();
+            other::test_digest_engine::run();
+            other::test_musig2::run();
         }
         n => no_exercise_found(n),
     }
@@ -19,6 +19,9 @@ fn main() {
             other::test_base58::run();
             other::test_addresses::run();
             other::test_wif::run();
+            other::test_bech32::run();
+            other::test_address_types::run();
+            other::test_networks::run();
         }
         n => no_exercise_found(n),
     }
@@ -14,6 +14,9 @@ fn main() {
         6 => ex06_test_fee::exercise(),
         0 => {
             other::test_transaction::run();
+            other::test_coinbase::run();
+            other::test_json::run();
+            other::test_amount::run();
         },
         n => no_exercise_found(n),
     }
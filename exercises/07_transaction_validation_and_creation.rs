@@ -10,6 +10,14 @@ fn main() {
         2 => ex02_test_verify_p2pkh::exercise(),
         3 => ex03_test_sign_input::exercise(),
         4 => ex04_testnet_faucet_1::exercise(),
+        0 => {
+            other::test_sighash_vectors::run();
+            other::test_segwit_sighash_vectors::run();
+            other::test_timelock::run();
+            other::test_fee_bumping::run();
+            other::test_check_transaction::run();
+            other::test_utxo_provider::run();
+        }
         n => no_exercise_found(n),
     }
 }
@@ -13,6 +13,17 @@ fn main() {
         5 => ex05_test_verify_p2sh::exercise(),
         0 => {
             other::test_sign_input_p2sh::run();
+            other::test_sign_input_segwit::run();
+            other::test_witness_commitment::run();
+            other::test_verify_taproot::run();
+            other::test_taproot_tree::run();
+            other::test_verify_witness::run();
+            other::test_verify_witness_opcodes::run();
+            other::test_malleability::run();
+            other::test_signature_inspection::run();
+            other::test_transaction_explanation::run();
+            other::test_transaction_diff::run();
+            other::test_size_estimation::run();
         }
         n => no_exercise_found(n),
     }
@@ -3,3 +3,6 @@ pub mod rfc_6979;
 pub mod public_key_recovery;
 pub mod private_key_recovery;
 pub mod signing_nonsense;
+pub mod test_schnorr_signature;
+pub mod test_digest_engine;
+pub mod test_musig2;
@@ -0,0 +1,96 @@
+use std::io;
+
+use programming_bitcoin_in_rust::*;
+
+use crypto::digest::sha_1;
+use crypto::digest::sha_256;
+use crypto::digest::ripemd_160;
+use crypto::digest::hash_256;
+use crypto::digest::hash_160;
+use crypto::digest::tagged_hash;
+use crypto::digest::engine::DigestEngine;
+use crypto::digest::engine::Sha1Engine;
+use crypto::digest::engine::Sha256Engine;
+use crypto::digest::engine::Ripemd160Engine;
+use crypto::digest::engine::Hash256Engine;
+use crypto::digest::engine::Hash160Engine;
+use crypto::digest::tagged::TaggedHash;
+use crypto::digest::tagged::TAP_LEAF;
+use crypto::digest::tagged::TAP_BRANCH;
+use crypto::digest::tagged::TAP_TWEAK;
+use crypto::digest::tagged::TAP_SIGHASH;
+use crypto::digest::tagged::BIP0340_AUX;
+use crypto::digest::tagged::BIP0340_NONCE;
+use crypto::digest::tagged::BIP0340_CHALLENGE;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// Provide `data` to a digest engine in parts of `size` bytes.
+fn digest_in_parts<E: DigestEngine>(mut engine: E, data: &[u8], size: usize) -> E::Output {
+    for part in data.chunks(size) { engine.update(part) }
+
+    engine.finalize()
+}
+
+pub fn run() {
+    assert_eq!(
+        sha_256(b"abc").to_vec(),
+        hexadecimal_bytes("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    );
+
+    // A payload spanning several blocks, provided in parts that are not aligned to them.
+    let data: Vec<u8> = (0..10_000_u32).map(|i| (i * 31 % 251) as u8).collect();
+
+    for size in [1, 7, 64, 100, 10_000] {
+        assert_eq!(digest_in_parts(Sha1Engine::new(), &data, size), sha_1(&data));
+        assert_eq!(digest_in_parts(Sha256Engine::new(), &data, size), sha_256(&data));
+        assert_eq!(digest_in_parts(Ripemd160Engine::new(), &data, size), ripemd_160(&data));
+        assert_eq!(digest_in_parts(Hash256Engine::new(), &data, size), hash_256(&data));
+        assert_eq!(digest_in_parts(Hash160Engine::new(), &data, size), hash_160(&data));
+        assert_eq!(digest_in_parts(TAP_SIGHASH.engine(), &data, size), tagged_hash("TapSighash", &data));
+    }
+
+    // Streaming from a reader.
+    let mut engine = Hash256Engine::new();
+
+    io::copy(&mut io::Cursor::new(&data), &mut engine).unwrap();
+
+    assert_eq!(engine.finalize(), hash_256(&data));
+
+    // An engine may be cloned, to digest data sharing a common prefix.
+    let mut prefix_engine = Sha256Engine::new();
+
+    prefix_engine.update(&data[..5_000]);
+
+    let mut engine_1 = prefix_engine.clone();
+    let mut engine_2 = prefix_engine;
+
+    engine_1.update(&data[5_000..]);
+    engine_2.update(b"abc");
+
+    assert_eq!(engine_1.finalize(), sha_256(&data));
+    assert_eq!(engine_2.finalize(), sha_256([&data[..5_000], b"abc"].concat()));
+
+    // Precomputed tagged hashes.
+    let tags = [
+        (&*TAP_LEAF, "TapLeaf"),
+        (&*TAP_BRANCH, "TapBranch"),
+        (&*TAP_TWEAK, "TapTweak"),
+        (&*TAP_SIGHASH, "TapSighash"),
+        (&*BIP0340_AUX, "BIP0340/aux"),
+        (&*BIP0340_NONCE, "BIP0340/nonce"),
+        (&*BIP0340_CHALLENGE, "BIP0340/challenge"),
+    ];
+
+    for (tagged, tag) in tags {
+        let tag_digest = sha_256(tag.as_bytes());
+
+        assert_eq!(tagged.hash(b"abc"), sha_256([&tag_digest[..], &tag_digest[..], b"abc"].concat()));
+        assert_eq!(tagged.hash(&data), tagged_hash(tag, &data));
+        assert_eq!(tagged.hash(&data), TaggedHash::new(tag).hash(&data));
+    }
+
+    // The midstate is not altered by its use.
+    assert_eq!(TAP_LEAF.hash([]), TAP_LEAF.hash([]));
+    assert_ne!(TAP_LEAF.hash([]), TAP_BRANCH.hash([]));
+}
@@ -0,0 +1,297 @@
+use programming_bitcoin_in_rust::*;
+
+use crypto::schnorr::musig::MuSigError;
+use crypto::schnorr::musig::key_agg::KeyAggContext;
+use crypto::schnorr::musig::key_agg::key_sort;
+use crypto::schnorr::musig::nonce::SecretNonce;
+use crypto::schnorr::musig::nonce::PublicNonce;
+use crypto::schnorr::musig::nonce::AggregateNonce;
+use crypto::schnorr::musig::nonce::nonce_gen;
+use crypto::schnorr::musig::session::SessionContext;
+use crypto::schnorr::musig::session::PartialSignature;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::sha_256;
+use bitcoin::taproot::tweak::tweak_public_key;
+use util::number::U256;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+fn public_key(hexadecimal: &str) -> [u8; 33] {
+    hexadecimal_bytes(hexadecimal).try_into().unwrap()
+}
+
+fn public_nonce(hexadecimal: &str) -> PublicNonce {
+    PublicNonce::parse_bytes(&hexadecimal_bytes(hexadecimal)).unwrap()
+}
+
+const X_1: &str = "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9";
+const X_2: &str = "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659";
+const X_3: &str = "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66";
+
+/// BIP-327 key aggregation test vectors.
+fn key_aggregation() {
+    let (x_1, x_2, x_3) = (public_key(X_1), public_key(X_2), public_key(X_3));
+
+    let cases = [
+        (vec![x_1, x_2, x_3], "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C"),
+        (vec![x_3, x_2, x_1], "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B"),
+        (vec![x_1, x_1, x_1], "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935"),
+        (vec![x_1, x_1, x_2, x_2], "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E"),
+    ];
+
+    for (public_keys, expected) in cases {
+        let context = KeyAggContext::new(&public_keys).unwrap();
+
+        assert_eq!(context.x_only_public_key().bytes().to_vec(), hexadecimal_bytes(expected));
+    }
+
+    // The aggregate public key depends upon the order of the public keys, unless they are sorted.
+    assert_eq!(key_sort(&[x_3, x_2, x_1]), key_sort(&[x_1, x_2, x_3]));
+    assert_eq!(key_sort(&[x_1, x_2, x_3]), vec![x_3, x_1, x_2]);
+
+    // Invalid public keys; i.e.; an invalid header byte, and an `x` coordinate not on the curve.
+    let mut invalid_header = x_2;
+    invalid_header[0] = 4_u8;
+
+    let not_on_curve = public_key("020000000000000000000000000000000000000000000000000000000000000007");
+
+    assert_eq!(KeyAggContext::new(&[x_1, invalid_header]).unwrap_err(), MuSigError::InvalidPublicKey(1));
+    assert_eq!(KeyAggContext::new(&[not_on_curve, x_1]).unwrap_err(), MuSigError::InvalidPublicKey(0));
+    assert_eq!(KeyAggContext::new(&[]).unwrap_err(), MuSigError::InvalidAggregateKey);
+
+    // A tweak that is not less than the elliptic curve order.
+    let mut context = KeyAggContext::new(&[x_1, x_2, x_3]).unwrap();
+    let public_key = context.plain_public_key();
+
+    let invalid_tweak: [u8; 32] = hexadecimal_bytes("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").try_into().unwrap();
+
+    assert_eq!(context.apply_tweak(&invalid_tweak, true), Err(MuSigError::InvalidTweak));
+    assert_eq!(context.plain_public_key(), public_key);
+}
+
+/// BIP-327 nonce generation test vectors.
+fn nonce_generation() {
+    let public_key_1 = public_key("024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766");
+    let secret_e = U256::from_be_slice(&[2_u8; 32]);
+
+    let cases: [(Option<U256>, [u8; 33], Option<[u8; 32]>, Option<&[u8]>, Option<&[u8]>, &str); 3] = [
+        (
+            Some(secret_e), public_key_1, Some([7_u8; 32]), Some(&[1_u8; 32]), Some(&[8_u8; 32]),
+            "B114E502BEAA4E301DD08A50264172C84E41650E6CB726B410C0694D59EFFB6495B5CAF28D045B973D63E3C99A44B807BDE375FD6CB39E46DC4A511708D0E9D2024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766",
+        ),
+        (
+            Some(secret_e), public_key_1, Some([7_u8; 32]), Some(&[]), Some(&[8_u8; 32]),
+            "E862B068500320088138468D47E0E6F147E01B6024244AE45EAC40ACE5929B9F0789E051170B9E705D0B9EB49049A323BBBBB206D8E05C19F46C6228742AA7A9024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766",
+        ),
+        (
+            None, public_key(X_1), None, None, None,
+            "89BDD787D0284E5E4D5FC572E49E316BAB7E21E3B1830DE37DFE80156FA41A6D0B17AE8D024C53679699A6FD7944D9C4A366B514BAF43088E0708B1023DD289702F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+        ),
+    ];
+
+    for (secret_e, public_key, aggregate_public_key, message, extra_in, expected) in cases {
+        let (secret_nonce, public_nonce) = nonce_gen(&[15_u8; 32], secret_e, &public_key, aggregate_public_key.as_ref(), message, extra_in).unwrap();
+
+        assert_eq!(secret_nonce.bytes().to_vec(), hexadecimal_bytes(expected));
+        assert_eq!(secret_nonce.public_key(), public_key);
+
+        let (k_1, k_2) = (U256::from_be_slice(&secret_nonce.bytes()[0..32]), U256::from_be_slice(&secret_nonce.bytes()[32..64]));
+
+        let mut expected_public_nonce: Vec<u8> = vec![];
+
+        for k in [k_1, k_2] {
+            let (x, y) = (k * Secp256k1Point::generator_point()).coordinates().unwrap();
+
+            expected_public_nonce.push(if y.bit(0) { 3_u8 } else { 2_u8 });
+            expected_public_nonce.extend_from_slice(&x.to_be_bytes::<32>());
+        }
+
+        assert_eq!(public_nonce.bytes().to_vec(), expected_public_nonce);
+    }
+
+    assert_eq!(
+        nonce_gen(&[0_u8; 32], Some(U256::ZERO), &public_key_1, None, None, None).err(),
+        Some(MuSigError::InvalidSecret),
+    );
+}
+
+/// BIP-327 partial signing (and verification) test vectors.
+fn partial_signing() {
+    let secret_e = U256::from_be_slice(&hexadecimal_bytes("7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671"));
+
+    let public_keys = [
+        public_key("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+        public_key(X_1),
+        public_key("02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661"),
+    ];
+
+    let secret_nonce = "508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F703935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9";
+
+    let public_nonces = [
+        public_nonce("0337C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"),
+        public_nonce("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F817980279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+        public_nonce("032DE2662628C90B03F5E720284EB52FF7D71F4284F627B68A853D78C78E1FFE9303E4C5524E83FFE1493B9077CF1CA6BEB2090C93D930321071AD40B2F44E599046"),
+        public_nonce("0237C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA0387BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"),
+    ];
+
+    let message = hexadecimal_bytes("F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF");
+
+    // The aggregate nonce of the first three public nonces.
+    assert_eq!(
+        AggregateNonce::new(&public_nonces[0..3]).bytes().to_vec(),
+        hexadecimal_bytes("028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9"),
+    );
+
+    // Signer `0` is the owner of the secret key; `(key indices, nonce indices, signer index,
+    // expected partial signature)`.
+    let cases = [
+        (vec![0, 1, 2], vec![0, 1, 2], 0, "012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB"),
+        (vec![1, 0, 2], vec![1, 0, 2], 1, "9FF2F7AAA856150CC8819254218D3ADEEB0535269051897724F9DB3789513A52"),
+        (vec![1, 2, 0], vec![1, 2, 0], 2, "FA23C359F6FAC4E7796BB93BC9F0532A95468C539BA20FF86D7C76ED92227900"),
+        // Both aggregate nonce points are the point at infinity.
+        (vec![0, 1], vec![0, 3], 0, "AE386064B26105404798F75DE2EB9AF5EDA5387B064B83D049CB7C5E08879531"),
+    ];
+
+    for (key_indices, nonce_indices, signer_index, expected) in cases {
+        let keys: Vec<[u8; 33]> = key_indices.iter().map(|i| public_keys[*i]).collect();
+        let nonces: Vec<PublicNonce> = nonce_indices.iter().map(|i| public_nonces[*i]).collect();
+
+        let aggregate_nonce = AggregateNonce::new(&nonces);
+        let session = SessionContext::new(KeyAggContext::new(&keys).unwrap(), &aggregate_nonce, &message);
+
+        let partial_signature = session.sign(SecretNonce::parse_bytes(&hexadecimal_bytes(secret_nonce)).unwrap(), secret_e).unwrap();
+
+        assert_eq!(partial_signature.bytes().to_vec(), hexadecimal_bytes(expected));
+        assert!(session.verify_partial_signature(&partial_signature, &nonces[signer_index], &keys[signer_index]));
+
+        // The partial signature does not verify for another signer, or for another message.
+        let other_index = (signer_index + 1) % keys.len();
+
+        assert!(!session.verify_partial_signature(&partial_signature, &nonces[other_index], &keys[other_index]));
+
+        let other_session = SessionContext::new(KeyAggContext::new(&keys).unwrap(), &aggregate_nonce, &[]);
+
+        assert!(!other_session.verify_partial_signature(&partial_signature, &nonces[signer_index], &keys[signer_index]));
+    }
+
+    assert_eq!(
+        AggregateNonce::new(&[public_nonces[0], public_nonces[3]]).bytes(),
+        [0_u8; 66],
+    );
+
+    // Signing errors.
+    let session = SessionContext::new(
+        KeyAggContext::new(&public_keys[1..3]).unwrap(),
+        &AggregateNonce::new(&public_nonces[1..3]),
+        &message,
+    );
+
+    let secret_nonce = || SecretNonce::parse_bytes(&hexadecimal_bytes(secret_nonce)).unwrap();
+
+    assert_eq!(session.sign(secret_nonce(), secret_e), Err(MuSigError::PublicKeyNotFound));
+    assert_eq!(session.sign(secret_nonce(), secret_e + U256::from(1)), Err(MuSigError::SecretNonceMismatch));
+    assert_eq!(session.sign(secret_nonce(), U256::ZERO), Err(MuSigError::InvalidSecret));
+    assert_eq!(session.sign(SecretNonce::parse_bytes(&[0_u8; 97]).unwrap(), secret_e), Err(MuSigError::InvalidSecretNonce));
+
+    // Invalid public nonces, and partial signatures not less than the elliptic curve order.
+    assert!(PublicNonce::parse_bytes(&[0_u8; 66]).is_none());
+    assert!(AggregateNonce::parse_bytes(&[0_u8; 66]).is_some());
+    assert!(PartialSignature::parse_bytes(&hexadecimal_bytes("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")).is_none());
+}
+
+/// A 3-of-3 Taproot key path spend; i.e.; the aggregate public key is the internal key, and the
+/// final signature is valid under the output key.
+fn signing_session() {
+    let mut signers: Vec<(U256, [u8; 33])> = (1..=3)
+        .map(|i| {
+            let secret_e = U256::from_be_bytes(sha_256(format!("musig2 signer {}", i)));
+            let (x, y) = (secret_e * Secp256k1Point::generator_point()).coordinates().unwrap();
+
+            (secret_e, [&[if y.bit(0) { 3_u8 } else { 2_u8 }][..], &x.to_be_bytes::<32>()[..]].concat().try_into().unwrap())
+        })
+        .collect();
+
+    // Each signer's secret, in the order of the sorted public keys.
+    signers.sort_by_key(|(_, public_key)| *public_key);
+
+    let secrets: Vec<U256> = signers.iter().map(|(secret_e, _)| *secret_e).collect();
+    let public_keys: Vec<[u8; 33]> = signers.iter().map(|(_, public_key)| *public_key).collect();
+
+    assert_eq!(key_sort(&public_keys), public_keys);
+    let merkle_root = sha_256(b"script tree");
+    let message = sha_256(b"signature message");
+
+    let internal_context = KeyAggContext::new(&public_keys).unwrap();
+    let internal_key = internal_context.x_only_public_key().bytes();
+
+    for merkle_root in [None, Some(&merkle_root)] {
+        let mut context = internal_context.clone();
+
+        context.apply_taproot_tweak(merkle_root).unwrap();
+
+        let (output_key, _) = tweak_public_key(&internal_key, merkle_root).unwrap();
+
+        assert_eq!(context.x_only_public_key().bytes(), output_key);
+
+        let mut secret_nonces: Vec<SecretNonce> = vec![];
+        let mut public_nonces: Vec<PublicNonce> = vec![];
+
+        for (i, (secret_e, public_key)) in secrets.iter().zip(public_keys.iter()).enumerate() {
+            let rand = sha_256(format!("musig2 nonce {}", i));
+            let (secret_nonce, public_nonce) = nonce_gen(&rand, Some(*secret_e), public_key, Some(&output_key), Some(&message), None).unwrap();
+
+            secret_nonces.push(secret_nonce);
+            public_nonces.push(public_nonce);
+        }
+
+        let session = SessionContext::new(context, &AggregateNonce::new(&public_nonces), &message);
+
+        let partial_signatures: Vec<PartialSignature> = secrets.iter().zip(secret_nonces)
+            .map(|(secret_e, secret_nonce)| session.sign(secret_nonce, *secret_e).unwrap())
+            .collect();
+
+        for (i, partial_signature) in partial_signatures.iter().enumerate() {
+            assert!(session.verify_partial_signature(partial_signature, &public_nonces[i], &public_keys[i]));
+        }
+
+        let signature = session.aggregate(&partial_signatures);
+
+        assert!(signature.verify_secp256k1(&message, &output_key));
+        assert!(!signature.verify_secp256k1(&message, &internal_key));
+        assert!(!session.aggregate(&partial_signatures[0..2]).verify_secp256k1(&message, &output_key));
+    }
+
+    // Plain and x-only tweaks (e.g.; BIP-32 derivation, then Taproot).
+    let mut context = internal_context.clone();
+
+    context.apply_tweak(&sha_256(b"plain tweak"), false).unwrap();
+    context.apply_tweak(&sha_256(b"x-only tweak 1"), true).unwrap();
+    context.apply_tweak(&sha_256(b"x-only tweak 2"), true).unwrap();
+
+    let output_key = context.x_only_public_key().bytes();
+
+    let mut secret_nonces: Vec<SecretNonce> = vec![];
+    let mut public_nonces: Vec<PublicNonce> = vec![];
+
+    for public_key in &public_keys {
+        let (secret_nonce, public_nonce) = nonce_gen(&sha_256(public_key), None, public_key, None, None, None).unwrap();
+
+        secret_nonces.push(secret_nonce);
+        public_nonces.push(public_nonce);
+    }
+
+    let session = SessionContext::new(context, &AggregateNonce::new(&public_nonces), &message);
+
+    let partial_signatures: Vec<PartialSignature> = secrets.iter().zip(secret_nonces)
+        .map(|(secret_e, secret_nonce)| session.sign(secret_nonce, *secret_e).unwrap())
+        .collect();
+
+    assert!(session.aggregate(&partial_signatures).verify_secp256k1(&message, &output_key));
+}
+
+pub fn run() {
+    key_aggregation();
+    nonce_generation();
+    partial_signing();
+    signing_session();
+}
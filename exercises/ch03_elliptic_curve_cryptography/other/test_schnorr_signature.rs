@@ -0,0 +1,91 @@
+use programming_bitcoin_in_rust::*;
+
+use crypto::schnorr::signature::SchnorrSignature;
+use crypto::schnorr::signature::SchnorrSigningError;
+use crypto::schnorr::x_only::XOnlyPublicKey;
+use crypto::schnorr::x_only::even_y_secret_e;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crypto::digest::sha_256;
+use util::number::U256;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// The BIP-340 test vectors; i.e.; `index, secret key, public key, aux_rand, message, signature,
+/// verification result, comment`.
+const TEST_VECTORS: &str = include_str!("bip340_test_vectors.csv");
+
+pub fn run() {
+    let mut signatures: Vec<(SchnorrSignature, Vec<u8>, [u8; 32])> = vec![];
+
+    for line in TEST_VECTORS.lines().skip(1) {
+        let fields: Vec<&str> = line.splitn(8, ',').collect();
+
+        let public_key: [u8; 32] = hexadecimal_bytes(fields[2]).try_into().unwrap();
+        let message = hexadecimal_bytes(fields[4]);
+        let signature_bytes = hexadecimal_bytes(fields[5]);
+        let expected = fields[6] == "TRUE";
+
+        let signature = SchnorrSignature::parse_bytes(&signature_bytes).unwrap();
+
+        assert_eq!(signature.verify_secp256k1(&message, &public_key), expected, "test vector {}", fields[0]);
+
+        // Signing vectors; the signature is reproduced exactly.
+        if !fields[1].is_empty() {
+            let secret_e = U256::from_be_slice(&hexadecimal_bytes(fields[1]));
+            let aux_rand: [u8; 32] = hexadecimal_bytes(fields[3]).try_into().unwrap();
+
+            assert_eq!(XOnlyPublicKey::from_secret_e(secret_e).unwrap().bytes(), public_key);
+            assert_eq!(SchnorrSignature::sign_secp256k1(&message, secret_e, &aux_rand).unwrap(), signature);
+        }
+
+        if expected { signatures.push((signature, message, public_key)) }
+    }
+
+    // Batch verification succeeds only if every signature verifies.
+    let seed = sha_256(b"batch verification seed");
+
+    let batch: Vec<(&SchnorrSignature, &[u8], &[u8; 32])> = signatures
+        .iter()
+        .map(|(signature, message, public_key)| (signature, message.as_slice(), public_key))
+        .collect();
+
+    assert!(SchnorrSignature::batch_verify_secp256k1(&batch, &seed));
+    assert!(SchnorrSignature::batch_verify_secp256k1(&[], &seed));
+
+    let forged_signature = SchnorrSignature { r: signatures[0].0.r, s: signatures[0].0.s + U256::from(1) };
+    let mut forged_batch = batch.clone();
+
+    forged_batch[0] = (&forged_signature, batch[0].1, batch[0].2);
+
+    assert!(!SchnorrSignature::batch_verify_secp256k1(&forged_batch, &seed));
+
+    // Swapping two messages of the batch is detected.
+    let mut swapped_batch = batch.clone();
+
+    swapped_batch[1].1 = batch[2].1;
+    swapped_batch[2].1 = batch[1].1;
+
+    assert!(!SchnorrSignature::batch_verify_secp256k1(&swapped_batch, &seed));
+
+    // A secret whose point has an odd y coordinate is negated; both have the same x-only public key.
+    let secret_e = U256::from_be_bytes(sha_256(b"my little secret"));
+    let normalized_secret_e = even_y_secret_e(secret_e).unwrap();
+
+    let (_, odd) = XOnlyPublicKey::from_point(normalized_secret_e * Secp256k1Point::generator_point()).unwrap();
+
+    assert!(!odd);
+    assert_eq!(XOnlyPublicKey::from_secret_e(secret_e), XOnlyPublicKey::from_secret_e(ELLIPTIC_CURVE_ORDER - secret_e));
+
+    let public_key = XOnlyPublicKey::from_secret_e(secret_e).unwrap();
+    let signature = SchnorrSignature::sign_secp256k1(b"Programming Bitcoin!", secret_e, &[0_u8; 32]).unwrap();
+
+    assert!(signature.verify_secp256k1(b"Programming Bitcoin!", &public_key.bytes()));
+    assert!(!signature.verify_secp256k1(b"Programming Bitcoin?", &public_key.bytes()));
+
+    assert_eq!(XOnlyPublicKey::parse_bytes(&public_key.bytes()), Some(public_key));
+    assert_eq!(SchnorrSignature::parse_bytes(&signature.bytes()), Some(signature));
+
+    assert_eq!(SchnorrSignature::sign_secp256k1(b"", U256::ZERO, &[0_u8; 32]), Err(SchnorrSigningError::InvalidSecret));
+    assert_eq!(SchnorrSignature::sign_secp256k1(b"", ELLIPTIC_CURVE_ORDER, &[0_u8; 32]), Err(SchnorrSigningError::InvalidSecret));
+}
@@ -3,3 +3,6 @@ pub mod test_der_format;
 pub mod test_base58;
 pub mod test_addresses;
 pub mod test_wif;
+pub mod test_bech32;
+pub mod test_address_types;
+pub mod test_networks;
@@ -0,0 +1,113 @@
+use programming_bitcoin_in_rust::*;
+
+use serialization::bitcoin_address::BitcoinAddress;
+use serialization::bitcoin_address::BitcoinAddressError;
+use serialization::bitcoin_base58check::Base58CheckBitcoinEncoding;
+use serialization::bitcoin_base58check::BitcoinEncodingPrefix;
+use serialization::bech32::Bech32Error;
+use serialization::point::CompressedPointSecFormatBytes;
+use bitcoin::network::BitcoinNetworkType;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script_types::BitcoinTransactionType;
+use crypto::secp256k1::Secp256k1Point;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::number::U256;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+pub fn run() {
+    // Addresses of each kind, their networks, and their locking scripts.
+    let cases = [
+        ("148dY81A9BmdpMhvYEVznrM45kWN32vSCN", BitcoinTransactionType::P2pkh, BitcoinNetworkType::Mainnet, "76a914".to_owned() + "2259c9cc3016e4ec0109b15b09e752ae5a08a51f" + "88ac"),
+        ("mgY3bVusRUL6ZB2Ss999CSrGVbdRwVpM8s", BitcoinTransactionType::P2pkh, BitcoinNetworkType::Testnet, "76a914".to_owned() + "0b2cf7740924e1ed9eb1bb363de06d2de8c17c5a" + "88ac"),
+        ("3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh", BitcoinTransactionType::P2sh, BitcoinNetworkType::Mainnet, "a914".to_owned() + "74d691da1574e6b3c192ecfb52cc8984ee7b6c56" + "87"),
+        ("2N3u1R6uwQfuobCqbCgBkpsgBxvr1tZpe7B", BitcoinTransactionType::P2sh, BitcoinNetworkType::Testnet, "a914".to_owned() + "74d691da1574e6b3c192ecfb52cc8984ee7b6c56" + "87"),
+        ("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", BitcoinTransactionType::P2wpkh, BitcoinNetworkType::Mainnet, "0014".to_owned() + "751e76e8199196d454941c45d1b3a323f1433bd6"),
+        ("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", BitcoinTransactionType::P2wsh, BitcoinNetworkType::Testnet, "0020".to_owned() + "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", BitcoinTransactionType::P2tr, BitcoinNetworkType::Mainnet, "5120".to_owned() + "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        ("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", BitcoinTransactionType::WitnessUnknown, BitcoinNetworkType::Mainnet, "5210".to_owned() + "751e76e8199196d454941c45d1b3a323"),
+        ("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", BitcoinTransactionType::WitnessUnknown, BitcoinNetworkType::Mainnet, "5128".to_owned() + "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+    ];
+
+    for (string, transaction_type, network, script) in cases {
+        let address: BitcoinAddress = string.parse().unwrap();
+        let script = ScriptBytes::of(&hexadecimal_bytes(&script));
+
+        assert_eq!(address.transaction_type(), transaction_type);
+        assert_eq!(address.network(), network);
+        assert_eq!(address.script_pubkey(), script);
+        assert_eq!(address.to_string(), string);
+
+        assert_eq!(script.locking_script_type(), transaction_type);
+        assert_eq!(BitcoinAddress::from_script(&script, network), Some(address));
+
+        assert_eq!(address.require_network(network), Ok(address));
+    }
+
+    // Network validation.
+    let address: BitcoinAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".parse().unwrap();
+
+    assert_eq!(address.require_network(BitcoinNetworkType::Testnet), Err(BitcoinAddressError::NetworkMismatch(BitcoinNetworkType::Mainnet)));
+
+    // Constructors.
+    let point = U256::from(1) * Secp256k1Point::generator_point();
+    let point_bytes = CompressedPointSecFormatBytes::from(&point.into());
+
+    let witness_script = ScriptBytes::of(&[&[0x21_u8][..], point_bytes.bytes(), &[0xac_u8]].concat());
+
+    assert_eq!(
+        BitcoinAddress::for_compressed_point_p2wpkh(BitcoinNetworkType::Mainnet, point_bytes).to_string(),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+    );
+    assert_eq!(
+        BitcoinAddress::for_witness_script(BitcoinNetworkType::Mainnet, &witness_script).to_string(),
+        "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+    );
+
+    let output_key: [u8; 32] = hexadecimal_bytes("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").try_into().unwrap();
+
+    assert_eq!(
+        BitcoinAddress::for_output_key(BitcoinNetworkType::Mainnet, output_key).to_string(),
+        "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+    );
+
+    assert_eq!(
+        BitcoinAddress::for_witness_program(BitcoinNetworkType::Mainnet, 0, &[0_u8; 16]),
+        Err(Bech32Error::InvalidProgramLength(16)),
+    );
+
+    // Locking scripts without an address.
+    let p2pk = ScriptBytes::of(&[&[0x21_u8][..], point_bytes.bytes(), &[0xac_u8]].concat());
+    let null_data = ScriptBytes::of(&[0x6a_u8, 0x01_u8, 0x00_u8]);
+
+    assert_eq!(BitcoinAddress::from_script(&p2pk, BitcoinNetworkType::Mainnet), None);
+    assert_eq!(BitcoinAddress::from_script(&null_data, BitcoinNetworkType::Mainnet), None);
+
+    // Invalid Base58Check addresses; an invalid character, an invalid checksum, an invalid
+    // length, and an unknown prefix (e.g.; a Litecoin address).
+    assert_eq!("148dY81A9BmdpMhvYEVznrM45kWN32vSC0".parse::<BitcoinAddress>(), Err(BitcoinAddressError::Base58Check));
+    assert_eq!("148dY81A9BmdpMhvYEVznrM45kWN32vSCM".parse::<BitcoinAddress>(), Err(BitcoinAddressError::Base58Check));
+    assert_eq!("148dY81A9BmdpMhvYEVznrM45kWN32vSCN148dY81A9B".parse::<BitcoinAddress>(), Err(BitcoinAddressError::Base58Check));
+    assert_eq!("".parse::<BitcoinAddress>(), Err(BitcoinAddressError::Base58Check));
+
+    let unknown_prefix = Base58CheckBitcoinEncoding::from_bytes(&[&[0x30_u8][..], &[0x11_u8; 20]].concat()).to_string();
+
+    assert_eq!(unknown_prefix.parse::<BitcoinAddress>(), Err(BitcoinAddressError::UnknownPrefix(0x30)));
+
+    // Base58Check addresses for some hash and prefix; the hash must be 20 bytes long, and the
+    // prefix must denote an address type.
+    let p2sh_prefix = BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2sh).unwrap();
+
+    assert_eq!(
+        BitcoinAddress::for_hash_bytes(p2sh_prefix, &hexadecimal_bytes("74d691da1574e6b3c192ecfb52cc8984ee7b6c56")).unwrap().to_string(),
+        "3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh"
+    );
+    assert_eq!(BitcoinAddress::for_hash_bytes(p2sh_prefix, &[0x11_u8; 32]), Err(BitcoinAddressError::HashLength(32)));
+    assert_eq!(BitcoinAddress::for_hash_bytes(BitcoinEncodingPrefix::MainnetWifPrivateKey, &[0x11_u8; 20]), Err(BitcoinAddressError::UnknownPrefix(0x80)));
+
+    assert_eq!(
+        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2wpkh),
+        Err(BitcoinAddressError::NotBase58Check(BitcoinTransactionType::P2wpkh))
+    );
+}
@@ -49,12 +49,12 @@ pub fn run() {
 
     assert_eq!("mgY3bVusRUL6ZB2Ss999CSrGVbdRwVpM8s", address.to_string());
 
-    let address = BitcoinAddress::base58("mgY3bVusRUL6ZB2Ss999CSrGVbdRwVpM8s");
+    let address = BitcoinAddress::base58("mgY3bVusRUL6ZB2Ss999CSrGVbdRwVpM8s").unwrap();
 
     assert_eq!(address.network(), BitcoinNetworkType::Testnet);
     assert_eq!(address.transaction_type(), BitcoinTransactionType::P2pkh);
 
-    let address = BitcoinAddress::base58("148dY81A9BmdpMhvYEVznrM45kWN32vSCN");
+    let address = BitcoinAddress::base58("148dY81A9BmdpMhvYEVznrM45kWN32vSCN").unwrap();
 
     assert_eq!(address.network(), BitcoinNetworkType::Mainnet);
     assert_eq!(address.transaction_type(), BitcoinTransactionType::P2pkh);
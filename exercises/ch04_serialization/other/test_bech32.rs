@@ -0,0 +1,145 @@
+use programming_bitcoin_in_rust::*;
+
+use serialization::bech32::Bech32Error;
+use serialization::bech32::Bech32Variant;
+use serialization::bech32::bech32_decode;
+use serialization::bech32::bech32_encode;
+use serialization::bech32::segwit_address_decode;
+use serialization::bech32::segwit_address_encode;
+use serialization::bitcoin_address::BitcoinAddress;
+use serialization::bitcoin_address::BitcoinAddressError;
+use bitcoin::network::BitcoinNetworkType;
+use bitcoin::script_types::BitcoinTransactionType;
+use util::byte_string::ByteSlice;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// BIP-173 and BIP-350 test vectors; valid strings.
+const VALID_STRINGS: [(&str, Bech32Variant); 12] = [
+    ("A12UEL5L", Bech32Variant::Bech32),
+    ("a12uel5l", Bech32Variant::Bech32),
+    ("an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs", Bech32Variant::Bech32),
+    ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Bech32Variant::Bech32),
+    ("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", Bech32Variant::Bech32),
+    ("?1ezyfcl", Bech32Variant::Bech32),
+    ("A1LQFN3A", Bech32Variant::Bech32m),
+    ("a1lqfn3a", Bech32Variant::Bech32m),
+    ("an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6", Bech32Variant::Bech32m),
+    ("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Bech32Variant::Bech32m),
+    ("split1checkupstagehandshakeupstreamerranterredcaperredlc445v", Bech32Variant::Bech32m),
+    ("?1v759aa", Bech32Variant::Bech32m),
+];
+
+/// BIP-173 and BIP-350 test vectors; valid segregated witness addresses, and their locking scripts.
+const VALID_ADDRESSES: [(&str, &str); 8] = [
+    ("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+    ("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+    ("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+    ("BC1SW50QGDZ25J", "6002751e"),
+    ("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", "5210751e76e8199196d454941c45d1b3a323"),
+    ("tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy", "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+    ("tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c", "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+    ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+];
+
+pub fn run() {
+    for (string, variant) in VALID_STRINGS {
+        let (hrp, data, decoded_variant) = bech32_decode(string).unwrap();
+
+        assert_eq!(decoded_variant, variant);
+        assert_eq!(bech32_encode(&hrp, &data, variant), string.to_lowercase());
+    }
+
+    // BIP-173 test vectors; invalid strings.
+    assert_eq!(bech32_decode(" 1nwldj5"), Err(Bech32Error::InvalidCharacter(0)));
+    assert_eq!(bech32_decode("\u{7f}1axkwrx"), Err(Bech32Error::InvalidCharacter(0)));
+    assert_eq!(bech32_decode("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx"), Err(Bech32Error::InvalidLength(91)));
+    assert_eq!(bech32_decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+    assert_eq!(bech32_decode("1pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+    assert_eq!(bech32_decode("x1b4n0q5v"), Err(Bech32Error::InvalidCharacter(2)));
+    assert_eq!(bech32_decode("li1dgmt3"), Err(Bech32Error::InvalidLength(8)));
+    assert_eq!(bech32_decode("de1lg7wt\u{ff}"), Err(Bech32Error::InvalidCharacter(8)));
+    assert_eq!(bech32_decode("10a06t8"), Err(Bech32Error::MissingSeparator));
+    assert_eq!(bech32_decode("1qzzfhee"), Err(Bech32Error::MissingSeparator));
+    assert_eq!(bech32_decode("a12UEL5L"), Err(Bech32Error::MixedCase));
+    assert!(matches!(bech32_decode("A1G7SGD8"), Err(Bech32Error::InvalidChecksum(_))));
+
+    for (address, script) in VALID_ADDRESSES {
+        let (_, version, program) = segwit_address_decode(address).unwrap();
+
+        let parsed = BitcoinAddress::parse(address).unwrap();
+
+        assert_eq!(parsed.script_pubkey().bytes(), hexadecimal_bytes(script));
+        assert_eq!(parsed.witness_program(), Some((version, &program[..])));
+        assert_eq!(parsed.to_string(), address.to_lowercase());
+
+        let network = if address.to_lowercase().starts_with("bc") { BitcoinNetworkType::Mainnet } else { BitcoinNetworkType::Testnet };
+
+        assert_eq!(parsed.network(), network);
+        assert_eq!(segwit_address_encode(network.bech32_hrp(), version, &program), address.to_lowercase());
+        assert_eq!(BitcoinAddress::for_witness_program(network, version, &program), Ok(parsed));
+    }
+
+    let address = BitcoinAddress::parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+
+    assert_eq!(address.transaction_type(), BitcoinTransactionType::P2wpkh);
+    assert!(address.script_pubkey().is_p2wpkh_locking());
+
+    let address = BitcoinAddress::parse("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0").unwrap();
+
+    assert_eq!(address.transaction_type(), BitcoinTransactionType::P2tr);
+    assert!(address.script_pubkey().is_p2tr_locking());
+
+    // Base58Check addresses are still parsed.
+    let address = BitcoinAddress::parse("148dY81A9BmdpMhvYEVznrM45kWN32vSCN").unwrap();
+
+    assert_eq!(address.transaction_type(), BitcoinTransactionType::P2pkh);
+    assert!(address.script_pubkey().is_p2pkh_locking());
+    assert_eq!(address.witness_program(), None);
+
+    // BIP-350 test vectors; invalid segregated witness addresses.
+    assert_eq!(
+        BitcoinAddress::parse("tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut"),
+        Err(BitcoinAddressError::UnknownNetwork(String::from("tc"))),
+    );
+
+    let invalid_addresses = [
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd", Bech32Error::InvalidVariant(Bech32Variant::Bech32)),
+        ("tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf", Bech32Error::InvalidVariant(Bech32Variant::Bech32)),
+        ("BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL", Bech32Error::InvalidVariant(Bech32Variant::Bech32)),
+        ("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh", Bech32Error::InvalidVariant(Bech32Variant::Bech32m)),
+        ("tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47", Bech32Error::InvalidVariant(Bech32Variant::Bech32m)),
+        ("bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4", Bech32Error::InvalidCharacter(59)),
+        ("BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R", Bech32Error::InvalidWitnessVersion(17)),
+        ("bc1pw5dgrnzv", Bech32Error::InvalidProgramLength(1)),
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav", Bech32Error::InvalidProgramLength(41)),
+        ("BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P", Bech32Error::InvalidProgramLength(16)),
+        ("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq", Bech32Error::MixedCase),
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf", Bech32Error::InvalidPadding),
+        ("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j", Bech32Error::InvalidPadding),
+        ("bc1gmk9yu", Bech32Error::InvalidWitnessVersion(0)),
+    ];
+
+    for (address, error) in invalid_addresses {
+        assert_eq!(BitcoinAddress::parse(address), Err(BitcoinAddressError::Bech32(error)), "{}", address);
+    }
+
+    // Error location; a single substituted character is located.
+    let address = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+
+    for position in [3, 20, 40, 58, address.len() - 1] {
+        let mut characters: Vec<char> = address.chars().collect();
+
+        characters[position] = if characters[position] == 'q' { 'p' } else { 'q' };
+
+        let typo: String = characters.into_iter().collect();
+
+        assert_eq!(bech32_decode(&typo), Err(Bech32Error::InvalidChecksum(vec![position])));
+    }
+
+    // Two substituted characters are detected (although not located).
+    assert!(matches!(
+        bech32_decode("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5qq0"),
+        Err(Bech32Error::InvalidChecksum(_)),
+    ));
+}
@@ -0,0 +1,92 @@
+use programming_bitcoin_in_rust::*;
+
+use serialization::bitcoin_address::BitcoinAddress;
+use serialization::bitcoin_address::BitcoinAddressError;
+use serialization::bitcoin_wif::BitcoinWif;
+use serialization::point::CompressedPointSecFormatBytes;
+use bitcoin::network::BitcoinNetworkType;
+use crypto::digest::hash_256;
+use crypto::secp256k1::Secp256k1Point;
+use util::hexadecimal::hexadecimal_string;
+use util::number::U256;
+
+pub fn run() {
+    // Each network's genesis block hash is the digest of its genesis block header.
+    for network in BitcoinNetworkType::ALL {
+        let parameters = network.parameters();
+        let mut hash = [0_u8; 32];
+
+        hexadecimal_string(parameters.genesis_block.hash, &mut hash).unwrap();
+
+        assert_eq!(parameters.genesis_block.block_hash(), hash);
+
+        assert_eq!(BitcoinNetworkType::from_name(parameters.name), Some(network));
+        assert_eq!(BitcoinNetworkType::from_magic(parameters.magic), Some(network));
+    }
+
+    assert_eq!(BitcoinNetworkType::from_name("signet"), Some(BitcoinNetworkType::Signet));
+    assert_eq!(BitcoinNetworkType::from_name("testnet3"), None);
+
+    // The genesis block's proof of work is of the network's minimum difficulty.
+    for network in BitcoinNetworkType::ALL {
+        let parameters = network.parameters();
+
+        assert_eq!(parameters.genesis_block.bits, parameters.consensus.pow_limit_bits);
+    }
+
+    // The signet message start bytes are derived from its block signing challenge.
+    let parameters = BitcoinNetworkType::Signet.parameters();
+    let challenge = parameters.consensus.signet_challenge.unwrap();
+    let mut challenge_bytes = vec![0_u8; challenge.len() / 2 + 1];
+
+    challenge_bytes[0] = u8::try_from(challenge.len() / 2).unwrap();
+    hexadecimal_string(challenge, &mut challenge_bytes[1..]).unwrap();
+
+    assert_eq!(hash_256(&challenge_bytes)[0..4], parameters.magic);
+
+    // Segregated witness human-readable parts; `tb` is attributed to testnet.
+    assert_eq!(BitcoinNetworkType::from_bech32_hrp("bc"), Some(BitcoinNetworkType::Mainnet));
+    assert_eq!(BitcoinNetworkType::from_bech32_hrp("tb"), Some(BitcoinNetworkType::Testnet));
+    assert_eq!(BitcoinNetworkType::from_bech32_hrp("bcrt"), Some(BitcoinNetworkType::Regtest));
+    assert_eq!(BitcoinNetworkType::from_bech32_hrp("tbs"), None);
+
+    // Keys and addresses of the test networks.
+    let secret_e = U256::from(1);
+    let point = secret_e * Secp256k1Point::generator_point();
+    let point_bytes = CompressedPointSecFormatBytes::from(&point.into());
+
+    let testnet_wif = BitcoinWif::for_secret_e(BitcoinNetworkType::Testnet, secret_e, true).to_string();
+
+    for network in [BitcoinNetworkType::Testnet4, BitcoinNetworkType::Signet, BitcoinNetworkType::Regtest] {
+        assert_eq!(BitcoinWif::for_secret_e(network, secret_e, true).to_string(), testnet_wif);
+    }
+
+    let address = BitcoinAddress::for_compressed_point_p2wpkh(BitcoinNetworkType::Regtest, point_bytes);
+
+    assert_eq!(address.to_string(), "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080");
+    assert_eq!("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080".parse::<BitcoinAddress>(), Ok(address));
+
+    let output_key = point.coordinates().unwrap().0.to_be_bytes::<32>();
+    let address = BitcoinAddress::for_output_key(BitcoinNetworkType::Regtest, output_key);
+
+    assert_eq!(address.to_string(), "bcrt1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqc8gma6");
+
+    // Testnet addresses are valid on testnet4 and signet (and Base58Check testnet addresses are
+    // also valid on regtest), but regtest segregated witness addresses are not valid on testnet.
+    let signet = BitcoinAddress::for_compressed_point_p2wpkh(BitcoinNetworkType::Signet, point_bytes);
+    let testnet: BitcoinAddress = signet.to_string().parse().unwrap();
+
+    assert_eq!(signet.to_string(), "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx");
+    assert_eq!(testnet.network(), BitcoinNetworkType::Testnet);
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Signet), Ok(signet));
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Testnet4).unwrap().network(), BitcoinNetworkType::Testnet4);
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Regtest), Err(BitcoinAddressError::NetworkMismatch(BitcoinNetworkType::Testnet)));
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Mainnet), Err(BitcoinAddressError::NetworkMismatch(BitcoinNetworkType::Testnet)));
+
+    let regtest = BitcoinAddress::for_compressed_point(BitcoinNetworkType::Regtest, point_bytes);
+    let testnet: BitcoinAddress = regtest.to_string().parse().unwrap();
+
+    assert_eq!(testnet.network(), BitcoinNetworkType::Testnet);
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Regtest), Ok(regtest));
+    assert_eq!(testnet.require_network(BitcoinNetworkType::Mainnet), Err(BitcoinAddressError::NetworkMismatch(BitcoinNetworkType::Testnet)));
+}
@@ -32,8 +32,8 @@ pub fn exercise() {
     script_bytes.resize(script.len() / 2, 0_u8);
     hexadecimal_string(script, &mut script_bytes).unwrap();
 
-    assert_eq!(input.txid, TransactionId::new(&txid_bytes));
-    assert_eq!(input.utxo_index, 0);
+    assert_eq!(input.outpoint.txid, TransactionId::new(&txid_bytes));
+    assert_eq!(input.outpoint.index, 0);
     assert_eq!(input.script, ScriptBytes::of(&script_bytes));
     assert_eq!(input.sequence, 4294967294);
 }
@@ -25,7 +25,7 @@ pub fn exercise() {
     script_bytes.resize(script.len() / 2, 0_u8);
     hexadecimal_string(script, &mut script_bytes).unwrap();
 
-    assert_eq!(utxo.amount, 32454049);
+    assert_eq!(utxo.amount.to_sat(), 32454049);
     assert_eq!(utxo.script, ScriptBytes::of(&script_bytes));
 
     let utxo = UnspentTransactionOutput::of(&txn_bytes[188..222]);
@@ -38,6 +38,6 @@ pub fn exercise() {
     script_bytes.resize(script.len() / 2, 0_u8);
     hexadecimal_string(script, &mut script_bytes).unwrap();
 
-    assert_eq!(utxo.amount, 10011545);
+    assert_eq!(utxo.amount.to_sat(), 10011545);
     assert_eq!(utxo.script, ScriptBytes::of(&script_bytes));
 }
@@ -2,7 +2,7 @@ use programming_bitcoin_in_rust::*;
 use crate::util::println_exercise::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use util::byte_string::ByteString;
 use util::hexadecimal::hexadecimal_string;
 
@@ -33,15 +33,13 @@ pub fn exercise() {
 
     section(&format!("Fee for {:?}", &transaction.txid()));
 
-    let retrieve_transaction_d1c789a9 = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, transaction_d1c789a9.txid());
+    let mut transaction_d1c789a9_utxos = InMemoryUtxoProvider::new();
 
-        Some(&transaction_d1c789a9)
-    };
+    transaction_d1c789a9_utxos.insert_transaction(&transaction_d1c789a9, None);
 
-    let fee = transaction.fee(retrieve_transaction_d1c789a9).unwrap();
+    let fee = transaction.fee(&transaction_d1c789a9_utxos).unwrap();
 
     show_display(&fee);
 
-    assert_eq!(fee, 40000);
+    assert_eq!(fee.to_sat(), 40000);
 }
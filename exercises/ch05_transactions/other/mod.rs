@@ -1 +1,4 @@
 pub mod test_transaction;
+pub mod test_coinbase;
+pub mod test_json;
+pub mod test_amount;
@@ -0,0 +1,180 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::OutPointParsingError;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::AmountUnit;
+use bitcoin::transaction::AmountError;
+use bitcoin::transaction::MAX_MONEY;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue8;
+use util::byte_value::ByteValue32;
+
+pub fn run() {
+    test_parse_amount();
+    test_format_amount();
+    test_outpoint();
+    test_fee();
+}
+
+fn test_parse_amount() {
+    // The same amount, in each denomination.
+    let amounts: [(&str, AmountUnit); 6] = [
+        ("0.0015", AmountUnit::Bitcoin),
+        ("0.00150000", AmountUnit::Bitcoin),
+        (".0015", AmountUnit::Bitcoin),
+        ("1.5", AmountUnit::MilliBitcoin),
+        ("1.50000", AmountUnit::MilliBitcoin),
+        ("150000", AmountUnit::Satoshi),
+    ];
+
+    for (s, unit) in amounts {
+        assert_eq!(Amount::parse_in(s, unit).unwrap().to_sat(), 150000);
+    }
+
+    for s in ["0.0015 BTC", "1.5 mBTC", "150000 sat", " 150000 sats ", "150000 satoshi"] {
+        assert_eq!(s.parse::<Amount>().unwrap().to_sat(), 150000);
+    }
+
+    assert_eq!(Amount::parse_in("1", AmountUnit::Bitcoin).unwrap().to_sat(), 100000000);
+    assert_eq!(Amount::parse_in("1.", AmountUnit::Bitcoin).unwrap().to_sat(), 100000000);
+    assert_eq!(Amount::parse_in("0", AmountUnit::Satoshi).unwrap(), Amount::ZERO);
+    assert_eq!(Amount::parse_in("21000000", AmountUnit::Bitcoin).unwrap(), Amount::MAX_MONEY);
+
+    // Too many decimal places for the denomination (trailing zeros are allowed).
+    assert_eq!(Amount::parse_in("0.000000001", AmountUnit::Bitcoin), Err(AmountError::TooPrecise));
+    assert_eq!(Amount::parse_in("0.000001", AmountUnit::MilliBitcoin), Err(AmountError::TooPrecise));
+    assert_eq!(Amount::parse_in("1.5", AmountUnit::Satoshi), Err(AmountError::TooPrecise));
+    assert_eq!(Amount::parse_in("1.0", AmountUnit::Satoshi).unwrap().to_sat(), 1);
+
+    // Above `MAX_MONEY`, or too large to represent.
+    assert_eq!(Amount::parse_in("21000000.00000001", AmountUnit::Bitcoin), Err(AmountError::OutOfRange));
+    assert_eq!(Amount::parse_in(&(MAX_MONEY + 1).to_string(), AmountUnit::Satoshi), Err(AmountError::OutOfRange));
+    assert_eq!(Amount::parse_in("92233720368", AmountUnit::Bitcoin), Err(AmountError::OutOfRange));
+    assert_eq!(Amount::parse_in("99999999999999999999", AmountUnit::Satoshi), Err(AmountError::OutOfRange));
+
+    // Negative, empty, or otherwise malformed.
+    for s in ["-1", "-0.5", "", ".", "1.2.3", "1,5", "1e8", "+1", " 1"] {
+        assert_eq!(Amount::parse_in(s, AmountUnit::Bitcoin), Err(AmountError::InvalidFormat));
+    }
+
+    assert_eq!("-1 BTC".parse::<Amount>(), Err(AmountError::InvalidFormat));
+    assert_eq!(" BTC".parse::<Amount>(), Err(AmountError::UnknownUnit));
+    assert_eq!("".parse::<Amount>(), Err(AmountError::UnknownUnit));
+    assert_eq!("150000".parse::<Amount>(), Err(AmountError::UnknownUnit));
+    assert_eq!("150000 bits".parse::<Amount>(), Err(AmountError::UnknownUnit));
+
+    // Checked constructors.
+    assert!(Amount::from_sat(MAX_MONEY).is_ok());
+    assert_eq!(Amount::from_sat(MAX_MONEY + 1), Err(AmountError::OutOfRange));
+    assert_eq!(Amount::from_sat(-1), Err(AmountError::OutOfRange));
+}
+
+fn test_format_amount() {
+    let amount = Amount::from_sat(150000).unwrap();
+
+    assert_eq!(amount.format_in(AmountUnit::Bitcoin), "0.00150000");
+    assert_eq!(amount.format_in(AmountUnit::MilliBitcoin), "1.50000");
+    assert_eq!(amount.format_in(AmountUnit::Satoshi), "150000");
+    assert_eq!(amount.to_string(), "0.00150000 BTC");
+
+    // Amounts are formatted and parsed back unchanged, in each denomination.
+    let units = [AmountUnit::Bitcoin, AmountUnit::MilliBitcoin, AmountUnit::Satoshi];
+
+    for satoshi in [0, 1, 99999, 100000, 150000, 100000000, 123456789, MAX_MONEY] {
+        let amount = Amount::from_sat(satoshi).unwrap();
+
+        for unit in units {
+            assert_eq!(Amount::parse_in(&amount.format_in(unit), unit).unwrap(), amount);
+            assert_eq!(format!("{} {}", amount.format_in(unit), unit.symbol()).parse::<Amount>().unwrap(), amount);
+        }
+
+        assert_eq!(amount.to_string().parse::<Amount>().unwrap(), amount);
+    }
+
+    // Unchecked amounts (e.g.; `Amount::NULL`) are formatted with their sign.
+    assert_eq!(Amount::NULL.format_in(AmountUnit::Bitcoin), "-0.00000001");
+    assert_eq!(Amount::NULL.to_string(), "-0.00000001 BTC");
+}
+
+fn test_outpoint() {
+    let s = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:7";
+
+    let outpoint: OutPoint = s.parse().unwrap();
+
+    assert_eq!(outpoint.index, 7);
+    assert_eq!(outpoint.txid.to_string(), "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+    assert_eq!(outpoint.to_string(), s);
+    assert_eq!(outpoint.to_string().parse::<OutPoint>().unwrap(), outpoint);
+
+    let null_outpoint = OutPoint::null();
+
+    assert_eq!(null_outpoint.to_string().parse::<OutPoint>().unwrap(), null_outpoint);
+
+    // A missing separator.
+    assert!(matches!(
+        "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b".parse::<OutPoint>(),
+        Err(OutPointParsingError::MissingSeparator)
+    ));
+
+    // An output index that is negative, too large, not a number, or missing.
+    for vout in ["-1", "4294967296", "x", ""] {
+        let s = format!("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:{}", vout);
+
+        assert!(matches!(s.parse::<OutPoint>(), Err(OutPointParsingError::ParseIntError(_))));
+    }
+
+    // A malformed transaction ID.
+    assert!(matches!("4a5e1e:0".parse::<OutPoint>(), Err(OutPointParsingError::TransactionIdParsingError(_))));
+}
+
+fn test_fee() {
+    let outpoint_1 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+    let outpoint_2 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 1);
+
+    let utxo = |satoshi: i64| UnspentTransactionOutput::new(Amount::of(&satoshi.to_le_bytes()), ScriptBytes::of(&[0x51_u8]));
+
+    let transaction = |utxos: Vec<UnspentTransactionOutput>| Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(outpoint_1, ScriptBytes::of(&[]), SEQUENCE_FINAL),
+            TransactionInput::new(outpoint_2, ScriptBytes::of(&[]), SEQUENCE_FINAL),
+        ],
+        utxos: utxos,
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    };
+
+    let utxo_provider = |satoshi_1: i64, satoshi_2: i64| {
+        let mut utxo_provider = InMemoryUtxoProvider::new();
+
+        utxo_provider.insert(outpoint_1, UtxoEntry::new(utxo(satoshi_1), None, false));
+        utxo_provider.insert(outpoint_2, UtxoEntry::new(utxo(satoshi_2), None, false));
+
+        utxo_provider
+    };
+
+    assert_eq!(transaction(vec![utxo(20000), utxo(9000)]).fee(&utxo_provider(10000, 20000)).unwrap().to_sat(), 1000);
+    assert_eq!(transaction(vec![utxo(30000)]).fee(&utxo_provider(10000, 20000)).unwrap(), Amount::ZERO);
+
+    // The UTXOs exceed the transaction inputs.
+    assert!(matches!(transaction(vec![utxo(30001)]).fee(&utxo_provider(10000, 20000)), Err(FeeCalculationError::NegativeFee)));
+
+    // The amounts, or their sums, exceed `MAX_MONEY` (or would overflow).
+    assert!(matches!(transaction(vec![utxo(1)]).fee(&utxo_provider(MAX_MONEY, 1)), Err(FeeCalculationError::AmountOutOfRange)));
+    assert!(matches!(transaction(vec![utxo(1)]).fee(&utxo_provider(i64::MAX, i64::MAX)), Err(FeeCalculationError::AmountOutOfRange)));
+    assert!(matches!(transaction(vec![utxo(1)]).fee(&utxo_provider(-1, 2)), Err(FeeCalculationError::AmountOutOfRange)));
+    assert!(matches!(transaction(vec![utxo(MAX_MONEY), utxo(1)]).fee(&utxo_provider(MAX_MONEY, 1)), Err(FeeCalculationError::AmountOutOfRange)));
+    assert!(matches!(transaction(vec![utxo(i64::MAX), utxo(i64::MAX)]).fee(&utxo_provider(10000, 20000)), Err(FeeCalculationError::AmountOutOfRange)));
+}
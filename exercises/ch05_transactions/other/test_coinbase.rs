@@ -0,0 +1,104 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::coinbase::CoinbaseError;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_string::ByteVector;
+use util::hexadecimal::hexadecimal_string;
+
+/// The genesis block's coinbase transaction (which predates BIP-34).
+const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
+pub fn run() {
+    // Block heights are pushed as Bitcoin Core's `CScript() << height` does (BIP-34).
+    let heights: [(u32, &[u8]); 8] = [
+        (0, &[0x00]),
+        (1, &[0x51]),
+        (16, &[0x60]),
+        (17, &[0x01, 0x11]),
+        (127, &[0x01, 0x7f]),
+        (128, &[0x02, 0x80, 0x00]),
+        (32768, &[0x03, 0x00, 0x80, 0x00]),
+        (500000, &[0x03, 0x20, 0xa1, 0x07]),
+    ];
+
+    for (height, push) in heights {
+        let transaction = Transaction::coinbase(height, 4, b"", vec![]).unwrap();
+        let script = transaction.inputs[0].script.bytes();
+
+        assert!(transaction.is_coinbase());
+        assert_eq!(&script[0..push.len()], push);
+        assert_eq!(transaction.coinbase_height(), Some(height));
+    }
+
+    // A negative height.
+    let mut transaction = Transaction::coinbase(17, 4, b"", vec![]).unwrap();
+
+    transaction.inputs[0].script = ScriptBytes::of(&[0x01, 0x81, 0x00]);
+
+    assert_eq!(transaction.coinbase_height(), None);
+
+    // The extra nonce follows the block height, and precedes any other data.
+    let mut transaction = Transaction::coinbase(32768, 4, b"/pool/", vec![]).unwrap();
+    let size = transaction.bytes().len();
+
+    assert_eq!(transaction.coinbase_extra_nonce(), Some(&[0_u8; 4][..]));
+
+    transaction.set_coinbase_extra_nonce(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+
+    assert_eq!(transaction.coinbase_extra_nonce(), Some(&[0x01_u8, 0x02, 0x03, 0x04][..]));
+    assert_eq!(transaction.coinbase_height(), Some(32768));
+    assert_eq!(transaction.bytes().len(), size);
+    assert!(transaction.inputs[0].script.bytes().ends_with(b"/pool/"));
+
+    assert_eq!(transaction.set_coinbase_extra_nonce(&[0x01, 0x02]), Err(CoinbaseError::ExtraNonceSize));
+    assert_eq!(transaction.set_coinbase_extra_nonce(&[0x00; 5]), Err(CoinbaseError::ExtraNonceSize));
+
+    // An extra nonce of the smallest height.
+    let mut transaction = Transaction::coinbase(0, 8, b"", vec![]).unwrap();
+
+    transaction.set_coinbase_extra_nonce(&[0xff; 8]).unwrap();
+
+    assert_eq!(transaction.coinbase_extra_nonce(), Some(&[0xff_u8; 8][..]));
+    assert_eq!(transaction.coinbase_height(), Some(0));
+
+    // Without an extra nonce (nor any other data), only the block height is pushed.
+    let transaction = Transaction::coinbase(17, 0, b"", vec![]).unwrap();
+
+    assert_eq!(transaction.coinbase_extra_nonce(), None);
+
+    // The transaction input script must be between 2 and 100 bytes long.
+    assert_eq!(Transaction::coinbase(0, 0, b"", vec![]).err(), Some(CoinbaseError::ScriptSize));
+    assert_eq!(Transaction::coinbase(32768, 95, b"", vec![]).err(), Some(CoinbaseError::ScriptSize));
+
+    // An extra nonce longer than 75 bytes is pushed with `OP_PUSHDATA1`.
+    let mut transaction = Transaction::coinbase(32768, 94, b"", vec![]).unwrap();
+
+    assert_eq!(transaction.inputs[0].script.bytes().len(), 100);
+    assert_eq!(transaction.coinbase_extra_nonce(), Some(&[0_u8; 94][..]));
+
+    transaction.set_coinbase_extra_nonce(&[0x01; 94]).unwrap();
+
+    assert_eq!(transaction.coinbase_extra_nonce(), Some(&[0x01_u8; 94][..]));
+
+    // A transaction that is not a coinbase transaction.
+    let mut bytes = vec![0_u8; GENESIS_COINBASE.len() / 2];
+
+    hexadecimal_string(GENESIS_COINBASE, &mut bytes).unwrap();
+
+    let genesis = Transaction::of(&bytes);
+
+    assert!(genesis.is_coinbase());
+    assert_eq!(genesis.txid().to_string(), "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+
+    let mut transaction = genesis.clone();
+
+    transaction.inputs[0].outpoint.index = 0;
+
+    assert!(!transaction.is_coinbase());
+    assert_eq!(transaction.coinbase_height(), None);
+    assert_eq!(transaction.coinbase_extra_nonce(), None);
+    assert_eq!(transaction.set_coinbase_extra_nonce(&[0x00; 4]), Err(CoinbaseError::NotFound));
+}
@@ -0,0 +1,144 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::network::BitcoinNetworkType;
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::json::TransactionJsonError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script_types::BitcoinTransactionType;
+use serialization::json::JsonValue;
+use serialization::json::JsonParsingError;
+use serialization::json::JSON_MAX_DEPTH;
+use util::byte_string::ByteString;
+use util::byte_string::ByteVector;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// A transaction spending four P2PKH UTXOs, and its `decoderawtransaction` representation.
+const LEGACY_TRANSACTION: &str = "010000000456919960ac691763688d3d3bcea9ad6ecaf875df5339e148a1fc61c6ed7a069e010000006a47304402204585bcdef85e6b1c6af5c2669d4830ff86e42dd205c0e089bc2a821657e951c002201024a10366077f87d6bce1f7100ad8cfa8a064b39d4e8fe4ea13a7b71aa8180f012102f0da57e85eec2934a82a585ea337ce2f4998b50ae699dd79f5880e253dafafb7feffffffeb8f51f4038dc17e6313cf831d4f02281c2a468bde0fafd37f1bf882729e7fd3000000006a47304402207899531a52d59a6de200179928ca900254a36b8dff8bb75f5f5d71b1cdc26125022008b422690b8461cb52c3cc30330b23d574351872b7c361e9aae3649071c1a7160121035d5c93d9ac96881f19ba1f686f15f009ded7c62efe85a872e6a19b43c15a2937feffffff567bf40595119d1bb8a3037c356efd56170b64cbcc160fb028fa10704b45d775000000006a47304402204c7c7818424c7f7911da6cddc59655a70af1cb5eaf17c69dadbfc74ffa0b662f02207599e08bc8023693ad4e9527dc42c34210f7a7d1d1ddfc8492b654a11e7620a0012102158b46fbdff65d0172b7989aec8850aa0dae49abfb84c81ae6e5b251a58ace5cfeffffffd63a5e6c16e620f86f375925b21cabaf736c779f88fd04dcad51d26690f7f345010000006a47304402200633ea0d3314bea0d95b3cd8dadb2ef79ea8331ffe1e61f762c0f6daea0fabde022029f23b3e9c30f080446150b23852028751635dcee2be669c2a1686a4b5edf304012103ffd6f4a67e94aba353a00882e563ff2722eb4cff0ad6006e86ee20dfe7520d55feffffff0251430f00000000001976a914ab0c0b2e98b1ab6dbf67d4750b0a56244948a87988ac005a6202000000001976a9143c82d7df364eb6c75be8c80df2b3eda8db57397088ac46430600";
+const LEGACY_TRANSACTION_JSON: &str = include_str!("decoderawtransaction_legacy.json");
+
+/// The signed P2SH-P2WPKH transaction from BIP-143's examples, and its `decoderawtransaction`
+/// representation.
+const SEGWIT_TRANSACTION: &str = "01000000000101db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a5477010000001716001479091972186c449eb1ded22b78e40d009bdf0089feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac02473044022047ac8e878352d3ebbde1c94ce3a10d057c24175747116f8288e5d794d12d482f0220217f36a485cae903c713331d877c1f64677e3622ad4010726870540656fe9dcb012103ad1d8e89212f0b92c74d23bb710c00662ad1470198ac48c43f7d6f93a2a2687392040000";
+const SEGWIT_TRANSACTION_JSON: &str = include_str!("decoderawtransaction_segwit.json");
+
+/// Removes output descriptors (`"desc"`), which are not produced.
+fn without_descriptors(json: &JsonValue) -> JsonValue {
+    match json {
+        JsonValue::Array(elements) => JsonValue::Array(elements.iter().map(without_descriptors).collect()),
+        JsonValue::Object(members) => JsonValue::Object(
+            members.iter()
+                .filter(|(key, _)| key != "desc")
+                .map(|(key, value)| (key.clone(), without_descriptors(value)))
+                .collect()
+        ),
+        value => value.clone(),
+    }
+}
+
+fn json_documents() {
+    let json: JsonValue = r#"{"a": [1, -0.5, 2e10, true, false, null], "b": "é😀\n"}"#.parse().unwrap();
+
+    assert_eq!(json.get("b").unwrap().as_str(), Some("\u{e9}\u{1f600}\n"));
+    assert_eq!(json.to_string(), r#"{"a":[1,-0.5,2e10,true,false,null],"b":"é😀\n"}"#);
+    assert_eq!(json.to_string().parse::<JsonValue>(), Ok(json));
+
+    // Numbers; without leading zeros (RFC 8259).
+    assert_eq!(JsonValue::parse("0"), Ok(JsonValue::Number(String::from("0"))));
+    assert_eq!(JsonValue::parse("-0.05"), Ok(JsonValue::Number(String::from("-0.05"))));
+    assert_eq!(JsonValue::parse("0123"), Err(JsonParsingError::InvalidNumber(0)));
+    assert_eq!(JsonValue::parse("-00"), Err(JsonParsingError::InvalidNumber(0)));
+    assert_eq!(JsonValue::parse("[1, 01]"), Err(JsonParsingError::InvalidNumber(4)));
+    assert_eq!(JsonValue::parse("1."), Err(JsonParsingError::InvalidNumber(0)));
+
+    // Escapes; exactly four hexadecimal digits.
+    assert_eq!(JsonValue::parse(r#""\u+041""#), Err(JsonParsingError::InvalidEscape(3)));
+    assert_eq!(JsonValue::parse(r#""\u 041""#), Err(JsonParsingError::InvalidEscape(3)));
+    assert_eq!(JsonValue::parse(r#""\u0041""#), Ok(JsonValue::string("A")));
+    assert_eq!(JsonValue::parse(r#""\ud83d""#), Err(JsonParsingError::InvalidEscape(1)));
+    assert_eq!(JsonValue::parse(r#""\x""#), Err(JsonParsingError::InvalidEscape(1)));
+
+    assert_eq!(JsonValue::parse("[1, 2"), Err(JsonParsingError::UnexpectedEnd));
+    assert_eq!(JsonValue::parse("[1 2]"), Err(JsonParsingError::UnexpectedCharacter(3)));
+    assert_eq!(JsonValue::parse("{} {}"), Err(JsonParsingError::TrailingCharacters(3)));
+
+    // Nesting is bounded.
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    assert!(JsonValue::parse(&nested(JSON_MAX_DEPTH)).is_ok());
+    assert_eq!(JsonValue::parse(&nested(JSON_MAX_DEPTH + 1)), Err(JsonParsingError::DepthExceeded(JSON_MAX_DEPTH)));
+    assert_eq!(JsonValue::parse(&"[".repeat(200000)), Err(JsonParsingError::DepthExceeded(JSON_MAX_DEPTH)));
+    assert_eq!(JsonValue::parse(&"{\"a\":".repeat(200000)), Err(JsonParsingError::DepthExceeded(5 * JSON_MAX_DEPTH)));
+}
+
+fn transaction_json() {
+    // A transaction without witness data.
+    let bytes = hexadecimal_bytes(LEGACY_TRANSACTION);
+    let transaction = Transaction::of(&bytes);
+    let json: JsonValue = LEGACY_TRANSACTION_JSON.parse().unwrap();
+
+    assert_eq!(transaction.to_json(BitcoinNetworkType::Mainnet), without_descriptors(&json));
+    assert_eq!(Transaction::from_json(&json).unwrap().bytes(), bytes);
+    assert_eq!(SegWitTransaction::from_json(&json).unwrap().bytes(), bytes);
+
+    // A transaction with witness data.
+    let bytes = hexadecimal_bytes(SEGWIT_TRANSACTION);
+    let transaction = SegWitTransaction::parse_bytes(&bytes).unwrap();
+    let json: JsonValue = SEGWIT_TRANSACTION_JSON.parse().unwrap();
+
+    assert_eq!(transaction.to_json(BitcoinNetworkType::Mainnet), without_descriptors(&json));
+    assert_eq!(SegWitTransaction::from_json(&json).unwrap().bytes(), bytes);
+    assert_eq!(Transaction::from_json(&json).err(), Some(TransactionJsonError::UnexpectedWitness));
+
+    // The (witness) transaction IDs are checked against the reconstructed transaction.
+    let tamper = |key: &str, value: JsonValue| {
+        let JsonValue::Object(members) = &json else { unreachable!() };
+
+        JsonValue::Object(
+            members.iter()
+                .map(|(k, v)| if k == key { (k.clone(), value.clone()) } else { (k.clone(), v.clone()) })
+                .collect()
+        )
+    };
+
+    let txid = JsonValue::string(LEGACY_TRANSACTION_JSON.parse::<JsonValue>().unwrap().get("txid").unwrap().as_str().unwrap());
+
+    assert_eq!(SegWitTransaction::from_json(&tamper("txid", txid.clone())).err(), Some(TransactionJsonError::TransactionIdMismatch));
+    assert_eq!(SegWitTransaction::from_json(&tamper("hash", txid)).err(), Some(TransactionJsonError::WitnessTransactionIdMismatch));
+    assert_eq!(SegWitTransaction::from_json(&tamper("locktime", JsonValue::integer(-1))).err(), Some(TransactionJsonError::InvalidField("locktime")));
+    assert_eq!(SegWitTransaction::from_json(&tamper("vin", JsonValue::Null)).err(), Some(TransactionJsonError::InvalidField("vin")));
+    assert_eq!(SegWitTransaction::from_json(&JsonValue::Object(vec![])).err(), Some(TransactionJsonError::MissingField("version")));
+
+    // A bare multisig locking script with 256 public keys is not a standard locking script.
+    let mut script: Vec<u8> = vec![0x51_u8];
+
+    for _ in 0..256 {
+        script.push(0x21_u8);
+        script.push(0x02_u8);
+        script.extend_from_slice(&[0x01_u8; 32]);
+    }
+
+    script.extend_from_slice(&[0x51_u8, 0xae_u8]);
+
+    assert_eq!(script.len(), 8707);
+
+    let script = ScriptBytes::of(&script);
+
+    assert_eq!(script.locking_script_type(), BitcoinTransactionType::Unknown);
+
+    let mut transaction = Transaction::of(&hexadecimal_bytes(LEGACY_TRANSACTION));
+
+    transaction.utxos.push(UnspentTransactionOutput::new(Amount::ZERO, script));
+
+    let json = transaction.to_json(BitcoinNetworkType::Mainnet);
+
+    assert_eq!(Transaction::from_json(&json).unwrap().bytes(), transaction.bytes());
+}
+
+pub fn run() {
+    json_documents();
+    transaction_json();
+}
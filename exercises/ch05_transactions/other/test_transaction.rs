@@ -36,8 +36,8 @@ pub fn run() {
 
     let input = &transaction.inputs[1];
 
-    assert_eq!(input.txid, TransactionId::new(&txid_bytes));
-    assert_eq!(input.utxo_index, 0);
+    assert_eq!(input.outpoint.txid, TransactionId::new(&txid_bytes));
+    assert_eq!(input.outpoint.index, 0);
     assert_eq!(input.script, ScriptBytes::of(&script_bytes));
     assert_eq!(input.sequence, 4294967294);
 
@@ -51,7 +51,7 @@ pub fn run() {
 
     let utxo = &transaction.utxos[0];
 
-    assert_eq!(utxo.amount, 1000273);
+    assert_eq!(utxo.amount.to_sat(), 1000273);
     assert_eq!(utxo.script, ScriptBytes::of(&script_bytes));
 
     assert_eq!(transaction.locktime.interpretation(), LocktimeType::BlockHeight);
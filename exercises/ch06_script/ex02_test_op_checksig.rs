@@ -117,6 +117,8 @@ pub fn exercise() {
         timestamp: EXAMPLE_TIMESTAMP,
         block_height: EXAMPLE_BLOCK_HEIGHT,
         checksig_digest: &OpCheckSigDigestOverride { bytes: data_bytes },
+        segwit: None,
+        taproot: None,
     };
 
     let mut stack = Stack::new(&script, &context);
@@ -2,7 +2,7 @@ use programming_bitcoin_in_rust::*;
 use crate::util::println_exercise::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use util::byte_string::ByteString;
 use util::hexadecimal::hexadecimal_string;
 
@@ -37,15 +37,13 @@ pub fn exercise() {
     show_display(&transaction_d1c789a9.txid());
     show_pretty_print(&transaction_d1c789a9);
 
-    let retrieve_transaction_d1c789a9 = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, transaction_d1c789a9.txid());
+    let mut transaction_d1c789a9_utxos = InMemoryUtxoProvider::new();
 
-        Some(&transaction_d1c789a9)
-    };
+    transaction_d1c789a9_utxos.insert_transaction(&transaction_d1c789a9, None);
 
     section("Verification result");
 
-    let result = transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, retrieve_transaction_d1c789a9).unwrap();
+    let result = transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &transaction_d1c789a9_utxos).unwrap();
 
     show_debug(&result);
 
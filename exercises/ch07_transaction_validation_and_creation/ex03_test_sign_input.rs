@@ -2,7 +2,7 @@ use programming_bitcoin_in_rust::*;
 use crate::util::println_exercise::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use bitcoin::script::SigHashFlag;
 use util::byte_string::ByteString;
 use util::byte_string::ByteVector;
@@ -48,17 +48,15 @@ pub fn exercise() {
 
     section("Signing the transaction");
 
-    let retrieve_transaction_0d6fe521 = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, transaction_0d6fe521.txid());
+    let mut transaction_0d6fe521_utxos = InMemoryUtxoProvider::new();
 
-        Some(&transaction_0d6fe521)
-    };
+    transaction_0d6fe521_utxos.insert_transaction(&transaction_0d6fe521, None);
 
     let input_script = transaction.signed_input_bytes_p2pkh(
         0,
         U256::from(secret_e),
         SigHashFlag::try_from(0x01_u8).unwrap(),
-        retrieve_transaction_0d6fe521
+        &transaction_0d6fe521_utxos
     );
 
     transaction.inputs[0].script = input_script.unwrap();
@@ -2,9 +2,11 @@ use programming_bitcoin_in_rust::*;
 use crate::util::println_exercise::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use bitcoin::transaction::TransactionInput;
 use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
 use bitcoin::transaction::Version;
 use bitcoin::transaction::Locktime;
 use bitcoin::segwit::transaction::SegWitTransaction;
@@ -47,7 +49,7 @@ pub fn exercise() {
     section("Target address");
 
     let target_address_base58 = "mwJn1YPMq7y5F8J3LkC5Hxg9PHyZ5K4cFv";
-    let target_address = BitcoinAddress::base58(&target_address_base58);
+    let target_address = BitcoinAddress::base58(&target_address_base58).unwrap();
 
     show_display(&target_address);
     show_debug(&target_address.network());
@@ -84,8 +86,7 @@ pub fn exercise() {
 
     let inputs: Vec<TransactionInput> = vec![
         TransactionInput::new(
-            funding_txn.txid(),
-            funding_txn_utxo_index,
+            OutPoint::new(funding_txn.txid(), funding_txn_utxo_index),
             ScriptBytes::of(&[]),
             u32::MAX
         ),
@@ -93,11 +94,11 @@ pub fn exercise() {
 
     let utxos: Vec<UnspentTransactionOutput> = vec![
         UnspentTransactionOutput::new(
-            amount_satoshi_send_to_target,
-            ScriptBytes::locking_script_p2pkh_address(&target_address),
+            Amount::from_sat(amount_satoshi_send_to_target).unwrap(),
+            target_address.script_pubkey(),
         ),
         UnspentTransactionOutput::new(
-            amount_satoshi_return_as_change,
+            Amount::from_sat(amount_satoshi_return_as_change).unwrap(),
             ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes),
         ),
     ];
@@ -109,17 +110,15 @@ pub fn exercise() {
         locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
     };
 
-    let retrieve_funding_txn = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, funding_txn.txid());
+    let mut funding_txn_utxos = InMemoryUtxoProvider::new();
 
-        Some(&*funding_txn)
-    };
+    funding_txn_utxos.insert_transaction(&funding_txn, None);
 
     let input_script = transaction.signed_input_bytes_p2pkh(
         0,
         U256::from(secret_e),
         SigHashFlag::try_from(0x01_u8).unwrap(),
-        retrieve_funding_txn
+        &funding_txn_utxos
     );
 
     transaction.inputs[0].script = input_script.unwrap();
@@ -131,13 +130,7 @@ pub fn exercise() {
     message("Our transaction:");
     show_pretty_print(&transaction);
 
-    let retrieve_funding_txn = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, funding_txn.txid());
-
-        Some(&*funding_txn)
-    };
-
-    assert!(transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, retrieve_funding_txn).unwrap());
+    assert!(transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &funding_txn_utxos).unwrap());
 
     let transaction_bytes = &transaction.bytes();
     let mut transaction_hexadecimal: Vec<u8> = vec![];
@@ -2,3 +2,5 @@ pub mod ex01_test_sig_hash;
 pub mod ex02_test_verify_p2pkh;
 pub mod ex03_test_sign_input;
 pub mod ex04_testnet_faucet_1;
+
+pub mod other;
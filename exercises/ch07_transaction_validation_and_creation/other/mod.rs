@@ -0,0 +1,6 @@
+pub mod test_sighash_vectors;
+pub mod test_segwit_sighash_vectors;
+pub mod test_timelock;
+pub mod test_fee_bumping;
+pub mod test_check_transaction;
+pub mod test_utxo_provider;
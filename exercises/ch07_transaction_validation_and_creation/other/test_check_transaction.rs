@@ -0,0 +1,97 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::verify::verification::TransactionVerificationError;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue8;
+use util::byte_value::ByteValue32;
+
+/// Returns a transaction input spending some outpoint, with some input script.
+fn input(outpoint: OutPoint, script: &[u8]) -> TransactionInput {
+    TransactionInput::new(outpoint, ScriptBytes::of(script), SEQUENCE_FINAL)
+}
+
+/// Returns a UTXO of some amount of satoshi (which is not checked), with some locking script.
+fn utxo(satoshi: i64, script: &[u8]) -> UnspentTransactionOutput {
+    UnspentTransactionOutput::new(Amount::of(&satoshi.to_le_bytes()), ScriptBytes::of(script))
+}
+
+/// Returns a version 1 transaction without a time lock.
+fn transaction(inputs: Vec<TransactionInput>, utxos: Vec<UnspentTransactionOutput>) -> Transaction {
+    Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: inputs,
+        utxos: utxos,
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    }
+}
+
+/// Checks a transaction, returning the error (if any).
+fn check(transaction: &Transaction) -> Option<TransactionVerificationError> {
+    transaction.check_transaction().err()
+}
+
+pub fn run() {
+    let outpoint_1 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+    let outpoint_2 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 1);
+
+    // A valid transaction.
+    assert!(transaction(vec![input(outpoint_1, &[])], vec![utxo(1000, &[0x51_u8])]).check_transaction().is_ok());
+
+    // No transaction inputs, or no UTXOs.
+    assert!(matches!(check(&transaction(vec![], vec![utxo(1000, &[0x51_u8])])), Some(TransactionVerificationError::EmptyInputs)));
+    assert!(matches!(check(&transaction(vec![input(outpoint_1, &[])], vec![])), Some(TransactionVerificationError::EmptyOutputs)));
+
+    // The transaction has a base size of 64 bytes plus the size of its locking script; at most
+    // 1000000 bytes (a weight of 4000000 weight units) is allowed.
+    let largest = transaction(vec![input(outpoint_1, &[])], vec![utxo(0, &vec![0x6a_u8; 999936])]);
+    let oversized = transaction(vec![input(outpoint_1, &[])], vec![utxo(0, &vec![0x6a_u8; 999937])]);
+
+    assert_eq!(largest.base_size(), 1000000);
+    assert!(largest.check_transaction().is_ok());
+    assert!(matches!(check(&oversized), Some(TransactionVerificationError::Oversized)));
+
+    // UTXO amounts must be within `0..=MAX_MONEY`.
+    let max_money = Amount::MAX_MONEY.to_sat();
+
+    assert!(transaction(vec![input(outpoint_1, &[])], vec![utxo(max_money, &[0x51_u8])]).check_transaction().is_ok());
+    assert!(matches!(check(&transaction(vec![input(outpoint_1, &[])], vec![utxo(0, &[0x51_u8]), utxo(-1, &[0x51_u8])])), Some(TransactionVerificationError::OutputAmountOutOfRange(1))));
+    assert!(matches!(check(&transaction(vec![input(outpoint_1, &[])], vec![utxo(max_money + 1, &[0x51_u8])])), Some(TransactionVerificationError::OutputAmountOutOfRange(0))));
+
+    // As must their sum.
+    assert!(matches!(
+        check(&transaction(vec![input(outpoint_1, &[])], vec![utxo(max_money, &[0x51_u8]), utxo(1, &[0x51_u8])])),
+        Some(TransactionVerificationError::TotalOutputAmountOutOfRange)
+    ));
+
+    // Transaction inputs must spend distinct outpoints.
+    assert!(transaction(vec![input(outpoint_1, &[]), input(outpoint_2, &[])], vec![utxo(1000, &[0x51_u8])]).check_transaction().is_ok());
+    assert!(matches!(
+        check(&transaction(vec![input(outpoint_1, &[]), input(outpoint_2, &[]), input(outpoint_1, &[0x51_u8])], vec![utxo(1000, &[0x51_u8])])),
+        Some(TransactionVerificationError::DuplicateInput(outpoint)) if outpoint == outpoint_1
+    ));
+
+    // A coinbase transaction input script must be between 2 and 100 bytes long.
+    let coinbase = |script_size: usize| transaction(vec![input(OutPoint::null(), &vec![0x51_u8; script_size])], vec![utxo(5000000000, &[0x51_u8])]);
+
+    assert!(coinbase(2).check_transaction().is_ok());
+    assert!(coinbase(100).check_transaction().is_ok());
+    assert!(matches!(check(&coinbase(1)), Some(TransactionVerificationError::CoinbaseScriptSize)));
+    assert!(matches!(check(&coinbase(101)), Some(TransactionVerificationError::CoinbaseScriptSize)));
+
+    // Only a coinbase transaction (with a single transaction input) may spend the null outpoint.
+    assert!(matches!(
+        check(&transaction(vec![input(outpoint_1, &[]), input(OutPoint::null(), &[0x51_u8, 0x51_u8])], vec![utxo(1000, &[0x51_u8])])),
+        Some(TransactionVerificationError::NullPrevout(1))
+    ));
+}
@@ -0,0 +1,323 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::fee::transaction_fee::FeeRate;
+use bitcoin::transaction::fee::replace_by_fee::FeeBumpError;
+use bitcoin::transaction::fee::replace_by_fee::MAX_BIP125_RBF_SEQUENCE;
+use bitcoin::transaction::size::size_estimation::InputSizeTemplate;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::SigHashFlag;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+/// Returns an amount of satoshi.
+fn sat(satoshi: i64) -> Amount {
+    Amount::from_sat(satoshi).unwrap()
+}
+
+/// Returns a version 2 transaction without a time lock.
+fn transaction(inputs: Vec<TransactionInput>, utxos: Vec<UnspentTransactionOutput>) -> Transaction {
+    Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: inputs,
+        utxos: utxos,
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    }
+}
+
+/// Returns a copy of a transaction, paying a different amount to its UTXO at some index (but
+/// otherwise unchanged; i.e.; with the same virtual size).
+fn with_amount(transaction: &Transaction, index: usize, satoshi: i64) -> Transaction {
+    let mut transaction = transaction.clone();
+
+    transaction.utxos[index].amount = sat(satoshi);
+
+    transaction
+}
+
+pub fn run() {
+    test_feerate();
+    test_replace_by_fee();
+    test_replacement_rules();
+    test_replace_by_fee_segwit();
+    test_child_pays_for_parent();
+}
+
+fn test_feerate() {
+    // Fees are rounded up, and fee rates down.
+    assert_eq!(FeeRate::from_fee(sat(5000), 355), FeeRate::from_sat_per_kvb(14084));
+    assert_eq!(FeeRate::from_sat_per_kvb(14084).fee(355), Some(sat(5000)));
+    assert_eq!(FeeRate::from_sat_per_kvb(14084).to_string(), "14.084 sat/vB");
+
+    assert_eq!(FeeRate::from_sat_per_vb(1), FeeRate::from_sat_per_kvb(1000));
+    assert_eq!(FeeRate::from_sat_per_vb(1).fee(226), Some(sat(226)));
+    assert_eq!(FeeRate::from_sat_per_kvb(1).fee(1), Some(sat(1)));
+    assert_eq!(FeeRate::from_sat_per_kvb(999).fee(0), Some(Amount::ZERO));
+    assert_eq!(FeeRate::from_fee(sat(1000), 0), FeeRate::ZERO);
+
+    // Fees above `MAX_MONEY`.
+    assert_eq!(FeeRate::from_sat_per_vb(u64::MAX).fee(1000), None);
+    assert_eq!(FeeRate::from_sat_per_kvb(21_000_000_000_000_001).fee(100_000), None);
+}
+
+/// A P2PKH transaction paying 60000 satoshi externally and 39000 satoshi as change, with a fee of
+/// 1000 satoshi; and the UTXO provider it spends from.
+fn original() -> (Transaction, InMemoryUtxoProvider, U256) {
+    let secret_e = U256::from_be_bytes(hash_256(b"my little secret"));
+
+    let point: EllipticCurvePoint = (secret_e * Secp256k1Point::generator_point()).into();
+    let locking_script = ScriptBytes::locking_script_p2pkh_compressed_point(&CompressedPointSecFormatBytes::from(&point));
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    let outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+
+    utxo_provider.insert(outpoint, UtxoEntry::new(UnspentTransactionOutput::new(sat(100000), locking_script.clone()), Some(100), false));
+
+    let mut original = transaction(
+        vec![TransactionInput::new(outpoint, ScriptBytes::of(&[]), MAX_BIP125_RBF_SEQUENCE)],
+        vec![
+            UnspentTransactionOutput::new(sat(60000), ScriptBytes::of(&[0x51_u8])),
+            UnspentTransactionOutput::new(sat(39000), locking_script),
+        ]
+    );
+
+    original.inputs[0].script = original.signed_input_bytes_p2pkh(0, secret_e, SigHashFlag::try_from(0x01_u8).unwrap(), &utxo_provider).unwrap();
+
+    (original, utxo_provider, secret_e)
+}
+
+fn test_replace_by_fee() {
+    let (original, utxo_provider, secret_e) = original();
+    let vsize = original.vsize();
+
+    assert_eq!(original.fee(&utxo_provider).unwrap(), sat(1000));
+    assert_eq!(original.feerate(&utxo_provider).unwrap(), FeeRate::from_fee(sat(1000), vsize));
+
+    // A target fee rate of 10 satoshi per virtual byte.
+    let mut replacement = original.replace_by_fee(1, FeeRate::from_sat_per_vb(10), &[], &utxo_provider).unwrap();
+
+    let fee = i64::try_from(vsize * 10).unwrap();
+
+    assert_eq!(replacement.utxos[0].amount, sat(60000));
+    assert_eq!(replacement.utxos[1].amount, sat(39000 - (fee - 1000)));
+    assert_eq!(replacement.fee(&utxo_provider).unwrap(), sat(fee));
+    assert_eq!(replacement.inputs[0].sequence, MAX_BIP125_RBF_SEQUENCE);
+    assert!(replacement.inputs[0].script.bytes().is_empty());
+
+    replacement.inputs[0].script = replacement.signed_input_bytes_p2pkh(0, secret_e, SigHashFlag::try_from(0x01_u8).unwrap(), &utxo_provider).unwrap();
+
+    assert_eq!(replacement.vsize(), vsize);
+    assert!(replacement.validate_replacement(&original, &[], &utxo_provider).is_ok());
+
+    // A target fee rate below that of the original; the fee is still increased by the incremental
+    // relay fee (1 satoshi per virtual byte).
+    let replacement = original.replace_by_fee(1, FeeRate::from_sat_per_vb(1), &[], &utxo_provider).unwrap();
+
+    assert_eq!(replacement.fee(&utxo_provider).unwrap(), sat(1000 + i64::try_from(vsize).unwrap()));
+
+    // The change UTXO must exist, and must not become dust.
+    assert!(matches!(original.replace_by_fee(2, FeeRate::from_sat_per_vb(10), &[], &utxo_provider), Err(FeeBumpError::OutputNotFound(2))));
+    assert!(matches!(original.replace_by_fee(1, FeeRate::from_sat_per_vb(1000), &[], &utxo_provider), Err(FeeBumpError::InsufficientOutputAmount(1))));
+
+    // BIP-125 rule 1; the original must signal replaceability.
+    let mut final_original = original.clone();
+
+    final_original.inputs[0].sequence = SEQUENCE_FINAL;
+
+    assert!(matches!(final_original.replace_by_fee(1, FeeRate::from_sat_per_vb(10), &[], &utxo_provider), Err(FeeBumpError::NotReplaceable)));
+}
+
+fn test_replacement_rules() {
+    let (original, mut utxo_provider, _) = original();
+    let vsize = i64::try_from(original.vsize()).unwrap();
+
+    utxo_provider.insert_transaction(&original, None);
+
+    // BIP-125 rule 4; the fee increase must be at least the incremental relay fee of the
+    // replacement (which has the same virtual size as the original).
+    let replacement = with_amount(&original, 1, 39000 - vsize);
+
+    assert!(replacement.validate_replacement(&original, &[], &utxo_provider).is_ok());
+    assert!(matches!(with_amount(&original, 1, 39000 - vsize + 1).validate_replacement(&original, &[], &utxo_provider), Err(FeeBumpError::InsufficientFeeIncrement)));
+
+    // BIP-125 rule 1.
+    let mut final_original = original.clone();
+
+    final_original.inputs[0].sequence = SEQUENCE_FINAL;
+
+    assert!(matches!(replacement.validate_replacement(&final_original, &[], &utxo_provider), Err(FeeBumpError::NotReplaceable)));
+
+    // BIP-125 rule 2; a new transaction input may only spend a confirmed UTXO.
+    let confirmed = OutPoint::new(TransactionId::of(&[0x02_u8; 32]), 0);
+    let unconfirmed = OutPoint::new(TransactionId::of(&[0x03_u8; 32]), 0);
+
+    utxo_provider.insert(confirmed, UtxoEntry::new(UnspentTransactionOutput::new(sat(50000), ScriptBytes::of(&[0x51_u8])), Some(100), false));
+    utxo_provider.insert(unconfirmed, UtxoEntry::new(UnspentTransactionOutput::new(sat(50000), ScriptBytes::of(&[0x51_u8])), None, false));
+
+    let mut with_confirmed = replacement.clone();
+
+    with_confirmed.inputs.push(TransactionInput::new(confirmed, ScriptBytes::of(&[]), MAX_BIP125_RBF_SEQUENCE));
+    with_confirmed.utxos[1].amount = sat(39000 + 50000 - 2 * vsize);
+
+    assert!(with_confirmed.validate_replacement(&original, &[], &utxo_provider).is_ok());
+
+    let mut with_unconfirmed = replacement.clone();
+
+    with_unconfirmed.inputs.push(TransactionInput::new(unconfirmed, ScriptBytes::of(&[]), MAX_BIP125_RBF_SEQUENCE));
+
+    assert!(matches!(with_unconfirmed.validate_replacement(&original, &[], &utxo_provider), Err(FeeBumpError::NewUnconfirmedInput(outpoint)) if outpoint == unconfirmed));
+
+    // A descendant spending the original's change, paying a fee of 5000 satoshi.
+    let descendant = transaction(
+        vec![TransactionInput::new(OutPoint::new(original.txid(), 1), ScriptBytes::of(&[]), SEQUENCE_FINAL)],
+        vec![UnspentTransactionOutput::new(sat(34000), ScriptBytes::of(&[0x51_u8]))]
+    );
+
+    assert_eq!(descendant.fee(&utxo_provider).unwrap(), sat(5000));
+
+    // BIP-125 rule 3; the replacement must pay at least the fees of the original and its
+    // descendants (6000 satoshi).
+    assert!(matches!(with_amount(&original, 1, 34001).validate_replacement(&original, &[descendant.clone()], &utxo_provider), Err(FeeBumpError::InsufficientAbsoluteFee)));
+
+    // BIP-125 rule 4, on top of the fees of the descendants.
+    assert!(matches!(with_amount(&original, 1, 34000).validate_replacement(&original, &[descendant.clone()], &utxo_provider), Err(FeeBumpError::InsufficientFeeIncrement)));
+    assert!(with_amount(&original, 1, 34000 - vsize).validate_replacement(&original, &[descendant.clone()], &utxo_provider).is_ok());
+
+    // A replacement created for the original also pays for the evicted descendant.
+    let replacement_with_descendant = original.replace_by_fee(1, FeeRate::from_sat_per_vb(1), &[descendant.clone()], &utxo_provider).unwrap();
+
+    assert_eq!(replacement_with_descendant.utxos[1].amount, sat(34000 - vsize));
+    assert!(replacement_with_descendant.validate_replacement(&original, &[descendant.clone()], &utxo_provider).is_ok());
+
+    // BIP-125 rule 5; at most 100 transactions (the original and 99 descendants) are replaced.
+    let descendant = with_amount(&descendant, 0, 39000);
+
+    assert!(replacement.validate_replacement(&original, &vec![descendant.clone(); 99], &utxo_provider).is_ok());
+    assert!(matches!(replacement.validate_replacement(&original, &vec![descendant; 100], &utxo_provider), Err(FeeBumpError::TooManyReplacements)));
+}
+
+fn test_replace_by_fee_segwit() {
+    let (_, _, secret_e) = original();
+
+    let point: EllipticCurvePoint = (secret_e * Secp256k1Point::generator_point()).into();
+    let locking_script = ScriptBytes::locking_script_p2wpkh_compressed_point(&CompressedPointSecFormatBytes::from(&point));
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    let outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+
+    utxo_provider.insert(outpoint, UtxoEntry::new(UnspentTransactionOutput::new(sat(100000), locking_script.clone()), Some(100), false));
+
+    // A P2WPKH transaction paying 60000 satoshi externally and 39000 satoshi as change.
+    let signed = |transaction: Transaction| {
+        let segwit_field = transaction.signed_witness_p2wpkh(0, secret_e, SigHashFlag::try_from(0x01_u8).unwrap(), &utxo_provider).unwrap();
+
+        let mut transaction = SegWitTransaction::from_transaction(transaction);
+        transaction.set_signed_input(0, ScriptBytes::of(&[]), segwit_field).unwrap();
+
+        transaction
+    };
+
+    let original = signed(transaction(
+        vec![TransactionInput::new(outpoint, ScriptBytes::of(&[]), MAX_BIP125_RBF_SEQUENCE)],
+        vec![
+            UnspentTransactionOutput::new(sat(60000), ScriptBytes::of(&[0x51_u8])),
+            UnspentTransactionOutput::new(sat(39000), locking_script),
+        ]
+    ));
+
+    // The virtual size includes the (discounted) witness data, which the stripped transaction
+    // does not.
+    let vsize = original.vsize();
+
+    assert!(vsize > original.stripped().vsize());
+
+    // A target fee rate of 10 satoshi per virtual byte.
+    let replacement = original.replace_by_fee(1, FeeRate::from_sat_per_vb(10), &[], &utxo_provider).unwrap();
+
+    let fee = i64::try_from(vsize * 10).unwrap();
+
+    assert_eq!(replacement.fee(&utxo_provider).unwrap(), sat(fee));
+    assert!(!replacement.has_witness());
+
+    let replacement = signed(replacement.stripped());
+
+    assert_eq!(replacement.vsize(), vsize);
+    assert!(replacement.validate_replacement(&original, &[], &utxo_provider).is_ok());
+
+    // BIP-125 rule 4, per the virtual size of the replacement (rather than its stripped size).
+    let vsize = i64::try_from(vsize).unwrap();
+    let stripped_vsize = i64::try_from(original.stripped().vsize()).unwrap();
+
+    let replacement = signed(with_amount(&original.stripped(), 1, 39000 - stripped_vsize));
+
+    assert!(replacement.stripped().validate_replacement(&original, &[], &utxo_provider).is_ok());
+    assert!(matches!(replacement.validate_replacement(&original, &[], &utxo_provider), Err(FeeBumpError::InsufficientFeeIncrement)));
+
+    let replacement = signed(with_amount(&original.stripped(), 1, 39000 - vsize));
+
+    assert!(replacement.validate_replacement(&original, &[], &utxo_provider).is_ok());
+}
+
+fn test_child_pays_for_parent() {
+    let (parent, mut utxo_provider, secret_e) = original();
+    let parent_vsize = i64::try_from(parent.vsize()).unwrap();
+
+    utxo_provider.insert_transaction(&parent, None);
+
+    let locking_script = parent.utxos[1].script.clone();
+
+    // A target package fee rate of 10 satoshi per virtual byte.
+    let mut child = parent.child_pays_for_parent(1, locking_script.clone(), InputSizeTemplate::P2pkh, FeeRate::from_sat_per_vb(10), &utxo_provider).unwrap();
+
+    let child_vsize = child.estimated_vsize(&[InputSizeTemplate::P2pkh]).unwrap();
+    let child_fee = 10 * (parent_vsize + i64::try_from(child_vsize).unwrap()) - 1000;
+
+    assert_eq!(child.inputs[0].outpoint, OutPoint::new(parent.txid(), 1));
+    assert_eq!(child.inputs[0].sequence, MAX_BIP125_RBF_SEQUENCE);
+    assert_eq!(child.utxos.len(), 1);
+    assert_eq!(child.utxos[0].amount, sat(39000 - child_fee));
+    assert_eq!(child.utxos[0].script, locking_script);
+    assert_eq!(child.fee(&utxo_provider).unwrap(), sat(child_fee));
+
+    // The estimate matches the signed child.
+    child.inputs[0].script = child.signed_input_bytes_p2pkh(0, secret_e, SigHashFlag::try_from(0x01_u8).unwrap(), &utxo_provider).unwrap();
+
+    assert_eq!(child.vsize(), child_vsize);
+    assert_eq!(FeeRate::from_fee(sat(1000 + child_fee), parent.vsize() + child.vsize()), FeeRate::from_sat_per_vb(10));
+
+    // A target package fee rate that the parent already meets; the child pays the minimum relay
+    // fee for itself.
+    let child = parent.child_pays_for_parent(1, locking_script.clone(), InputSizeTemplate::P2pkh, FeeRate::from_sat_per_vb(1), &utxo_provider).unwrap();
+
+    assert_eq!(child.fee(&utxo_provider).unwrap(), sat(i64::try_from(child_vsize).unwrap()));
+
+    // The spent UTXO must exist, and the child's UTXO must not become dust.
+    assert!(matches!(
+        parent.child_pays_for_parent(2, locking_script.clone(), InputSizeTemplate::P2pkh, FeeRate::from_sat_per_vb(10), &utxo_provider),
+        Err(FeeBumpError::OutputNotFound(2))
+    ));
+    assert!(matches!(
+        parent.child_pays_for_parent(1, locking_script, InputSizeTemplate::P2pkh, FeeRate::from_sat_per_vb(1000), &utxo_provider),
+        Err(FeeBumpError::InsufficientOutputAmount(1))
+    ));
+}
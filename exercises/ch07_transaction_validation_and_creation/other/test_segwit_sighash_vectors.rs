@@ -0,0 +1,182 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::script::segwit_signature_hash;
+use bitcoin::script::SegWitSignatureHashCache;
+use bitcoin::script::DefaultOpCheckSigDigest;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::segwit_execution_script_code;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Script;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use util::byte_string::ByteString;
+use util::hexadecimal::hexadecimal_encode;
+
+use crate::util::bitcoin::script::context::*;
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// Unsigned transaction, signing input index, UTXO locking script, UTXO amount, and the expected
+/// signature hash (`SIGHASH_ALL`); from BIP-143's native P2WPKH and P2SH-P2WPKH examples.
+const VECTORS: [(&str, usize, &str, i64, &str); 2] = [
+    (
+        "0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000",
+        1,
+        "00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1",
+        600000000,
+        "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670",
+    ),
+    (
+        "0100000001db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a54770100000000feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac92040000",
+        0,
+        "001479091972186c449eb1ded22b78e40d009bdf0089",
+        1000000000,
+        "64f3b0f4dd2bb3aa1ce8566d220cc74dda9df97d8490cc81d89d735c92e59fb6",
+    ),
+];
+
+/// BIP-143's native P2WSH example; the second transaction input spends a P2WSH UTXO with the
+/// witness script `<K1> OP_CHECKSIGVERIFY OP_CODESEPARATOR <K2> OP_CHECKSIG` (`SIGHASH_SINGLE`).
+const P2WSH_UNSIGNED_TRANSACTION: &str = "0100000002fe3dc9208094f3ffd12645477b3dc56f60ec4fa8e6f5d67c565d1c6b9216b36e0000000000ffffffff0815cf020f013ed6cf91d29f4202e8a58726b1ac6c79da47c23d1bee0a6925f80000000000ffffffff0100f2052a010000001976a914a30741f8145e5acadf23f751864167f32e0963f788ac00000000";
+const P2WSH_SIGNED_TRANSACTION: &str = "01000000000102fe3dc9208094f3ffd12645477b3dc56f60ec4fa8e6f5d67c565d1c6b9216b36e000000004847304402200af4e47c9b9629dbecc21f73af989bdaa911f7e6f6c2e9394588a3aa68f81e9902204f3fcf6ade7e5abb1295b6774c8e0abd94ae62217367096bc02ee5e435b67da201ffffffff0815cf020f013ed6cf91d29f4202e8a58726b1ac6c79da47c23d1bee0a6925f80000000000ffffffff0100f2052a010000001976a914a30741f8145e5acadf23f751864167f32e0963f788ac000347304402200de66acf4527789bfda55fc5459e214fa6083f936b430a762c629656216805ac0220396f550692cd347171cbc1ef1f51e15282e837bb2b30860dc77c8f78bc8501e503473044022027dc95ad6b740fe5129e7e62a75dd00f291a2aeb1200b84b09d9e3789406b6c002201a9ecd315dd6a0e632ab20bbb98948bc0c6fb204f2c286963bb48517a7058e27034721026dccc749adc2a9d0d89497ac511f760f45c47dc5ed9cf352a58ac706453880aeadab210255a9626aebf5e29c0e6538428ba0d1dcf6ca98ffdf086aa8ced5e0d0215ea465ac00000000";
+const P2WSH_LOCKING_SCRIPT: &str = "00205d1b56b63d714eebe542309525f484b7e9d6f686b3781b6f61ef925d66d6f6a0";
+const P2WSH_AMOUNT: i64 = 4900000000;
+const P2WSH_WITNESS_SCRIPT: &str = "21026dccc749adc2a9d0d89497ac511f760f45c47dc5ed9cf352a58ac706453880aeadab210255a9626aebf5e29c0e6538428ba0d1dcf6ca98ffdf086aa8ced5e0d0215ea465ac";
+
+/// The signature hash committed to by `OP_CHECKSIGVERIFY` (the entire witness script), and by
+/// `OP_CHECKSIG` (the witness script following `OP_CODESEPARATOR`).
+const P2WSH_SIGHASHES: [(usize, &str); 2] = [
+    (1, "82dde6e4f1e94d02c2b7ad03d2115d691f48d064e9d52f58194a6637e4194391"),
+    (4, "fef7bd749cce710c5c052bd796df1af0d935e59cea63736268bcbe2d2134fc47"),
+];
+
+/// BIP-143's P2SH-P2WSH example; a 6-of-6 multisig witness script, with a signature for each
+/// `SIGHASH` flag.
+const MULTISIG_UNSIGNED_TRANSACTION: &str = "010000000136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000000ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac00000000";
+const MULTISIG_SIGNED_TRANSACTION: &str = "0100000000010136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000023220020a16b5755f7f6f96dbd65f5f0d6ab9418b89af4b1f14a1bb8a09062c35f0dcb54ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac080047304402206ac44d672dac41f9b00e28f4df20c52eeb087207e8d758d76d92c6fab3b73e2b0220367750dbbe19290069cba53d096f44530e4f98acaa594810388cf7409a1870ce01473044022068c7946a43232757cbdf9176f009a928e1cd9a1a8c212f15c1e11ac9f2925d9002205b75f937ff2f9f3c1246e547e54f62e027f64eefa2695578cc6432cdabce271502473044022059ebf56d98010a932cf8ecfec54c48e6139ed6adb0728c09cbe1e4fa0915302e022007cd986c8fa870ff5d2b3a89139c9fe7e499259875357e20fcbb15571c76795403483045022100fbefd94bd0a488d50b79102b5dad4ab6ced30c4069f1eaa69a4b5a763414067e02203156c6a5c9cf88f91265f5a942e96213afae16d83321c8b31bb342142a14d16381483045022100a5263ea0553ba89221984bd7f0b13613db16e7a70c549a86de0cc0444141a407022005c360ef0ae5a5d4f9f2f87a56c1546cc8268cab08c73501d6b3be2e1e1a8a08824730440220525406a1482936d5a21888260dc165497a90a15669636d8edca6b9fe490d309c022032af0c646a34a44d1f4576bf6a4a74b67940f8faa84c7df9abe12a01a11e2b4783cf56210307b8ae49ac90a048e9b53357a2354b3334e9c8bee813ecb98e99a7e07e8c3ba32103b28f0c28bfab54554ae8c658ac5c3e0ce6e79ad336331f78c428dd43eea8449b21034b8113d703413d57761b8b9781957b8c0ac1dfe69f492580ca4195f50376ba4a21033400f6afecb833092a9a21cfdf1ed1376e58c5d1f47de74683123987e967a8f42103a6d48b1131e94ba04d9737d61acdaa1322008af9602b3b14862c07a1789aac162102d8b661b0b3302ee2f162b09e07a55ad5dfbe673a9f01d9f0c19617681024306b56ae00000000";
+const MULTISIG_REDEEM_SCRIPT: &str = "0020a16b5755f7f6f96dbd65f5f0d6ab9418b89af4b1f14a1bb8a09062c35f0dcb54";
+const MULTISIG_AMOUNT: i64 = 987654321;
+const MULTISIG_WITNESS_SCRIPT: &str = "56210307b8ae49ac90a048e9b53357a2354b3334e9c8bee813ecb98e99a7e07e8c3ba32103b28f0c28bfab54554ae8c658ac5c3e0ce6e79ad336331f78c428dd43eea8449b21034b8113d703413d57761b8b9781957b8c0ac1dfe69f492580ca4195f50376ba4a21033400f6afecb833092a9a21cfdf1ed1376e58c5d1f47de74683123987e967a8f42103a6d48b1131e94ba04d9737d61acdaa1322008af9602b3b14862c07a1789aac162102d8b661b0b3302ee2f162b09e07a55ad5dfbe673a9f01d9f0c19617681024306b56ae";
+
+const MULTISIG_SIGHASHES: [(u8, &str); 6] = [
+    (0x01_u8, "185c0be5263dce5b4bb50a047973c1b6272bfbd0103a89444597dc40b248ee7c"),
+    (0x02_u8, "e9733bc60ea13c95c6527066bb975a2ff29a925e80aa14c213f686cbae5d2f36"),
+    (0x03_u8, "1e1f1c303dc025bd664acb72e583e933fae4cff9148bf78c157d1e8f78530aea"),
+    (0x81_u8, "2a67f03e63a6a422125878b40b82da593be8d4efaafe88ee528af6e5a9955c6e"),
+    (0x82_u8, "781ba15f3779d5542ce8ecb5c18716733a5ee42a6f51488ec96154934e2c890a"),
+    (0x83_u8, "511e8e52ed574121fc1b654970395502128263f62662e076dc6baf05c2e6a99b"),
+];
+
+fn hexadecimal_digest(digest: &[u8; 32]) -> String {
+    let mut result_hexadecimal: [u8; 64] = [0_u8; 64];
+
+    hexadecimal_encode(digest, &mut result_hexadecimal).unwrap();
+
+    std::str::from_utf8(&result_hexadecimal).unwrap().to_string()
+}
+
+pub fn run() {
+    for (transaction_hexadecimal, input_index, script_hexadecimal, amount, expected_sighash_hexadecimal) in VECTORS {
+        let transaction = Transaction::of(&hexadecimal_bytes(transaction_hexadecimal));
+
+        let script_code = ScriptBytes::of(&hexadecimal_bytes(script_hexadecimal)).segwit_script_code(None).unwrap();
+        let cache = SegWitSignatureHashCache::new(&transaction);
+
+        let result = segwit_signature_hash(
+            &transaction,
+            input_index,
+            &script_code,
+            Amount::from_sat(amount).unwrap(),
+            SigHashFlag::try_from(0x01_u8).unwrap(),
+            &cache,
+            &DefaultOpCheckSigDigest {},
+        ).unwrap();
+
+        assert_eq!(hexadecimal_digest(&result), expected_sighash_hexadecimal);
+    }
+
+    // A P2WSH witness script commits to the script following the last executed `OP_CODESEPARATOR`.
+    let transaction = Transaction::of(&hexadecimal_bytes(P2WSH_UNSIGNED_TRANSACTION));
+    let witness_script = Script::try_from(&ScriptBytes::of(&hexadecimal_bytes(P2WSH_WITNESS_SCRIPT))).unwrap();
+    let cache = SegWitSignatureHashCache::new(&transaction);
+
+    for (instruction_pointer, expected_sighash_hexadecimal) in P2WSH_SIGHASHES {
+        let script_code = segwit_execution_script_code(&witness_script, instruction_pointer).unwrap();
+
+        let result = segwit_signature_hash(
+            &transaction,
+            1,
+            &script_code,
+            Amount::from_sat(P2WSH_AMOUNT).unwrap(),
+            SigHashFlag::try_from(0x03_u8).unwrap(),
+            &cache,
+            &DefaultOpCheckSigDigest {},
+        ).unwrap();
+
+        assert_eq!(hexadecimal_digest(&result), expected_sighash_hexadecimal);
+    }
+
+    // There is no signature hash for a transaction input that does not exist.
+    assert!(segwit_signature_hash(
+        &transaction,
+        transaction.inputs.len(),
+        &ScriptBytes::from(&witness_script),
+        Amount::from_sat(P2WSH_AMOUNT).unwrap(),
+        SigHashFlag::try_from(0x01_u8).unwrap(),
+        &cache,
+        &DefaultOpCheckSigDigest {},
+    ).is_none());
+
+    let signed_transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2WSH_SIGNED_TRANSACTION)).unwrap();
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert(
+        signed_transaction.inputs[1].outpoint.clone(),
+        UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(P2WSH_AMOUNT).unwrap(), ScriptBytes::of(&hexadecimal_bytes(P2WSH_LOCKING_SCRIPT))), None, false),
+    );
+
+    assert!(signed_transaction.verify_input(1, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+
+    // A P2SH-P2WSH multisig witness script; each signature commits to a different `SIGHASH` flag.
+    let transaction = Transaction::of(&hexadecimal_bytes(MULTISIG_UNSIGNED_TRANSACTION));
+    let witness_script = ScriptBytes::of(&hexadecimal_bytes(MULTISIG_WITNESS_SCRIPT));
+    let redeem_script = ScriptBytes::of(&hexadecimal_bytes(MULTISIG_REDEEM_SCRIPT));
+    let cache = SegWitSignatureHashCache::new(&transaction);
+
+    let script_code = redeem_script.segwit_script_code(Some(&witness_script)).unwrap();
+
+    for (sighash_byte, expected_sighash_hexadecimal) in MULTISIG_SIGHASHES {
+        let result = segwit_signature_hash(
+            &transaction,
+            0,
+            &script_code,
+            Amount::from_sat(MULTISIG_AMOUNT).unwrap(),
+            SigHashFlag::try_from(sighash_byte).unwrap(),
+            &cache,
+            &DefaultOpCheckSigDigest {},
+        ).unwrap();
+
+        assert_eq!(hexadecimal_digest(&result), expected_sighash_hexadecimal);
+    }
+
+    let signed_transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(MULTISIG_SIGNED_TRANSACTION)).unwrap();
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert(
+        signed_transaction.inputs[0].outpoint.clone(),
+        UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(MULTISIG_AMOUNT).unwrap(), ScriptBytes::locking_script_p2sh_redeem_script(&redeem_script)), None, false),
+    );
+
+    assert!(signed_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+
+    // The amount is committed to.
+    utxo_provider.insert(
+        signed_transaction.inputs[0].outpoint.clone(),
+        UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(MULTISIG_AMOUNT + 1).unwrap(), ScriptBytes::locking_script_p2sh_redeem_script(&redeem_script)), None, false),
+    );
+
+    assert!(!signed_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+}
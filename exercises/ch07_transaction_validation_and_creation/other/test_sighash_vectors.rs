@@ -0,0 +1,207 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::script::signature_verification_hash;
+use bitcoin::script::TransactionInputCommitment;
+use bitcoin::script::DefaultOpCheckSigDigest;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::Opcode;
+use bitcoin::script::opcode::ConstantOpcode;
+use bitcoin::script::opcode::StackOpcode;
+use bitcoin::script::opcode::CryptographicOpcode;
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::hexadecimal::hexadecimal_encode;
+use util::number::U256;
+
+use crate::util::bitcoin::script::context::*;
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// Transaction, script code, input index, raw (signed) hash type, and the expected signature hash
+/// (as displayed by Bitcoin Core; i.e.; byte-reversed); from Bitcoin Core's `sighash.json`.
+const VECTORS: [(&str, &str, usize, i32, &str); 6] = [
+    (
+        "907c2bc503ade11cc3b04eb2918b6f547b0630ab569273824748c87ea14b0696526c66ba740200000004ab65ababfd1f9bdd4ef073c7afc4ae00da8a66f429c917a0081ad1e1dabce28d373eab81d8628de802000000096aab5253ab52000052ad042b5f25efb33beec9f3364e8a9139e8439d9d7e26529c3c30b6c3fd89f8684cfd68ea0200000009ab53526500636a52ab599ac2fe02a526ed040000000008535300516352515164370e010000000003006300ab2ec229",
+        "",
+        2,
+        1864164639,
+        "31af167a6cf3f9d5f6875caa4d31704ceb0eba078d132b78dab52c3b8997317e",
+    ),
+    (
+        "73107cbd025c22ebc8c3e0a47b2a760739216a528de8d4dab5d45cbeb3051cebae73b01ca10200000007ab6353656a636affffffffe26816dffc670841e6a6c8c61c586da401df1261a330a6c6b3dd9f9a0789bc9e000000000800ac6552ac6aac51ffffffff0174a8f0010000000004ac52515100000000",
+        "5163ac63635151ac",
+        1,
+        1190874345,
+        "06e328de263a87b09beabe222a21627a6ea5c7f560030da31610c4611f4a46bc",
+    ),
+    // "OP_CODESEPARATOR" is removed from the script code.
+    (
+        "6e7e9d4b04ce17afa1e8546b627bb8d89a6a7fefd9d892ec8a192d79c2ceafc01694a6a7e7030000000953ac6a51006353636a33bced1544f797f08ceed02f108da22cd24c9e7809a446c61eb3895914508ac91f07053a01000000055163ab516affffffff11dc54eee8f9e4ff0bcf6b1a1a35b1cd10d63389571375501af7444073bcec3c02000000046aab53514a821f0ce3956e235f71e4c69d91abe1e93fb703bd33039ac567249ed339bf0ba0883ef300000000090063ab65000065ac654bec3cc504bcf499020000000005ab6a52abac64eb060100000000076a6a5351650053bbbc130100000000056a6aab53abd6e1380100000000026a51c4e509b8",
+        "acab655151",
+        0,
+        479279909,
+        "2a3d95b09237b72034b23f2d2bb29fa32a58ab5c6aa72f6aafdfa178ab1dd01c",
+    ),
+    (
+        "cf781855040a755f5ba85eef93837236b34a5d3daeb2dbbdcf58bb811828d806ed05754ab8010000000351ac53ffffffffda1e264727cf55c67f06ebcc56dfe7fa12ac2a994fecd0180ce09ee15c480f7d00000000096351516a51acac00ab53dd49ff9f334befd6d6f87f1a832cddfd826a90b78fd8cf19a52cb8287788af94e939d6020000000700525251ac526310d54a7e8900ed633f0f6f0841145aae7ee0cbbb1e2a0cae724ee4558dbabfdc58ba6855010000000552536a53abfd1b101102c51f910500000000096300656a525252656a300bee010000000009ac52005263635151abe19235c9",
+        "53005365",
+        2,
+        1422854188,
+        "d5981bd4467817c1330da72ddb8760d6c2556cd809264b2d85e6d274609fc3a3",
+    ),
+    (
+        "b3cad3a7041c2c17d90a2cd994f6c37307753fa3635e9ef05ab8b1ff121ca11239a0902e700300000009ab635300006aac5163ffffffffcec91722c7468156dce4664f3c783afef147f0e6f80739c83b5f09d5a09a57040200000004516a6552ffffffff969d1c6daf8ef53a70b7cdf1b4102fb3240055a8eaeaed2489617cd84cfd56cf020000000352ab53ffffffff46598b6579494a77b593681c33422a99559b9993d77ca2fa97833508b0c169f80200000009655300655365516351ffffffff04d7ddf800000000000853536a65ac6351ab09f3420300000000056aab65abac33589d04000000000952656a65655151acac944d6f0400000000006a8004ba",
+        "005165",
+        1,
+        1035865506,
+        "fe1dc9e8554deecf8f50c417c670b839cc9d650722ebaaf36572418756075d58",
+    ),
+    // A negative hash type.
+    (
+        "d3b7421e011f4de0f1cea9ba7458bf3486bee722519efab711a963fa8c100970cf7488b7bb0200000003525352dcd61b300148be5d05000000000000000000",
+        "535251536aac536a",
+        0,
+        -1960128125,
+        "29aa6d2d752d3310eba20442770ad345b7f6a35f96161ede5f07b33e92053e2a",
+    ),
+];
+
+/// The "one" hash, as displayed by Bitcoin Core.
+const SIGHASH_ONE: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+pub fn run() {
+    for (transaction_hexadecimal, script_hexadecimal, input_index, hash_type, expected_sighash_hexadecimal) in VECTORS {
+        let transaction = Transaction::of(&hexadecimal_bytes(transaction_hexadecimal));
+        let commitment = TransactionInputCommitment::RedeemScript(ScriptBytes::of(&hexadecimal_bytes(script_hexadecimal)));
+
+        assert_eq!(sighash_hexadecimal(&transaction, input_index, &commitment, hash_type), expected_sighash_hexadecimal);
+    }
+
+    // The first transaction has three transaction inputs and two UTXOs.
+    let transaction = Transaction::of(&hexadecimal_bytes(VECTORS[0].0));
+    let commitment = TransactionInputCommitment::RedeemScript(ScriptBytes::of(&[]));
+
+    // "SIGHASH_SINGLE" bug; there is no UTXO at output index 2, with or without
+    // "SIGHASH_ANYONECANPAY".
+    assert_eq!(sighash_hexadecimal(&transaction, 2, &commitment, 0x03), SIGHASH_ONE);
+    assert_eq!(sighash_hexadecimal(&transaction, 2, &commitment, 0x83), SIGHASH_ONE);
+    assert!(sighash_hexadecimal(&transaction, 1, &commitment, 0x03) != SIGHASH_ONE);
+
+    // There is no transaction input at index 3.
+    assert_eq!(sighash_hexadecimal(&transaction, 3, &commitment, 0x01), SIGHASH_ONE);
+
+    // During execution, the script code begins after the last executed "OP_CODESEPARATOR", and
+    // the data push of the signature being checked is removed (Bitcoin Core's "FindAndDelete").
+    let signature = DataElement::of(&[0x30_u8; 71]);
+    let other_signature = DataElement::of(&[0x30_u8; 72]);
+
+    let script = Script::new(&[
+        Element::Opcode(Opcode::Constant(ConstantOpcode::OpTrue)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)),
+        Element::Data(signature.clone()),
+        Element::Data(signature.clone()),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)),
+        Element::Data(other_signature.clone()),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Data(signature.clone()),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig)),
+    ]).unwrap();
+
+    // Executing "OP_CHECKSIG", after both "OP_CODESEPARATOR"s.
+    let mut expected_script_code = other_signature.bytes_with_opcode();
+    expected_script_code.extend_from_slice(&[0x52_u8, 0xac_u8]);
+
+    let commitment = TransactionInputCommitment::ScriptCode(&script, 8, std::slice::from_ref(&signature));
+    let expected_commitment = TransactionInputCommitment::RedeemScript(ScriptBytes::of(&expected_script_code));
+
+    assert_eq!(sighash_hexadecimal(&transaction, 1, &commitment, 0x01), sighash_hexadecimal(&transaction, 1, &expected_commitment, 0x01));
+
+    // Executing "OP_CHECKSIG" as if after the first "OP_CODESEPARATOR" only; consecutive pushes
+    // are removed, and "OP_CODESEPARATOR" is still removed from the script code.
+    let mut expected_script_code = other_signature.bytes_with_opcode();
+    expected_script_code.extend_from_slice(&[0x52_u8, 0xac_u8]);
+
+    let commitment = TransactionInputCommitment::ScriptCode(&script, 3, std::slice::from_ref(&signature));
+
+    assert_eq!(sighash_hexadecimal(&transaction, 1, &commitment, 0x01), sighash_hexadecimal(&transaction, 1, &expected_commitment, 0x01));
+
+    // Both signatures are removed for "OP_CHECKMULTISIG".
+    let commitment = TransactionInputCommitment::ScriptCode(&script, 8, &[signature, other_signature]);
+    let expected_commitment = TransactionInputCommitment::RedeemScript(ScriptBytes::of(&[0x52_u8, 0xac_u8]));
+
+    assert_eq!(sighash_hexadecimal(&transaction, 1, &commitment, 0x01), sighash_hexadecimal(&transaction, 1, &expected_commitment, 0x01));
+
+    // A locking script that contains the signature which unlocks it; the signature commits to the
+    // locking script without it.
+    let secret_e = U256::from_be_bytes(hash_256(b"my little secret"));
+
+    let point: EllipticCurvePoint = (secret_e * Secp256k1Point::generator_point()).into();
+    let point_bytes = CompressedPointSecFormatBytes::from(&point);
+
+    let signed_script = Script::new(&[
+        Element::Opcode(Opcode::Stack(StackOpcode::OpDrop)),
+        Element::Data(DataElement::of(point_bytes.bytes())),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig)),
+    ]).unwrap();
+
+    // The outpoint is arbitrary; i.e.; it does not commit to the locking script.
+    let outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+
+    let mut spending_transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![TransactionInput::new(outpoint.clone(), ScriptBytes::of(&[]), u32::MAX)],
+        utxos: vec![UnspentTransactionOutput::new(Amount::from_sat(1000).unwrap(), ScriptBytes::of(&[0x51_u8]))],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    };
+
+    let input_script = spending_transaction
+        .signed_input_bytes_p2sh(0, secret_e, &ScriptBytes::from(&signed_script), SigHashFlag::try_from(0x01_u8).unwrap())
+        .unwrap();
+
+    // The transaction input script is the data push of the signature.
+    let mut locking_script = input_script.bytes().to_vec();
+    locking_script.extend(ScriptBytes::from(&signed_script).bytes());
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert(
+        outpoint,
+        UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(2000).unwrap(), ScriptBytes::of(&locking_script)), None, false),
+    );
+
+    spending_transaction.inputs[0].script = input_script;
+
+    assert!(spending_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+}
+
+/// Returns the signature hash of a transaction input, as displayed by Bitcoin Core.
+fn sighash_hexadecimal(transaction: &Transaction, input_index: usize, commitment: &TransactionInputCommitment, hash_type: i32) -> String {
+    let sighash_flag = SigHashFlag::from(hash_type as u32);
+
+    let mut result = signature_verification_hash(transaction, input_index, commitment, sighash_flag, &DefaultOpCheckSigDigest {}).unwrap();
+    let mut result_hexadecimal: [u8; 64] = [0_u8; 64];
+
+    result.reverse();
+    hexadecimal_encode(&result, &mut result_hexadecimal).unwrap();
+
+    String::from(std::str::from_utf8(&result_hexadecimal).unwrap())
+}
@@ -0,0 +1,104 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::RelativeLockTime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::SEQUENCE_LOCKTIME_DISABLE_FLAG;
+use bitcoin::transaction::verify::timelock::SequenceLocks;
+use bitcoin::transaction::verify::timelock::SequenceLocksError;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+
+/// Returns a transaction of some version and time lock, with a transaction input for each sequence
+/// number.
+fn transaction(version: u32, locktime: u32, sequences: &[u32]) -> Transaction {
+    Transaction {
+        version: Version::of(&version.to_le_bytes()),
+        inputs: sequences
+            .iter()
+            .enumerate()
+            .map(|(n, sequence)| TransactionInput::new(OutPoint::new(TransactionId::of(&[0x01_u8; 32]), u32::try_from(n).unwrap()), ScriptBytes::of(&[]), *sequence))
+            .collect(),
+        utxos: vec![UnspentTransactionOutput::new(Amount::from_sat(1000).unwrap(), ScriptBytes::of(&[0x51_u8]))],
+        locktime: Locktime::of(&locktime.to_le_bytes())
+    }
+}
+
+pub fn run() {
+    // A relative time lock of 10 blocks, spending a UTXO confirmed at block height 100; the
+    // transaction may be included in block 110.
+    let tx = transaction(2, 0, &[RelativeLockTime::Blocks(10).to_sequence()]);
+
+    let sequence_locks = tx.sequence_locks(&[100], &[0]).unwrap();
+
+    assert_eq!(sequence_locks, SequenceLocks { min_height: 109, min_time: -1 });
+    assert!(!tx.sequence_locks_satisfied(&[100], &[0], 109, u32::MAX).unwrap());
+    assert!(tx.sequence_locks_satisfied(&[100], &[0], 110, 0).unwrap());
+
+    // A relative time lock of 1024 seconds (two 512 second intervals), spending a UTXO whose block
+    // was preceded by a median time past of 1600000000.
+    let tx = transaction(2, 0, &[RelativeLockTime::Time(2).to_sequence()]);
+
+    let sequence_locks = tx.sequence_locks(&[100], &[1600000000]).unwrap();
+
+    assert_eq!(sequence_locks, SequenceLocks { min_height: -1, min_time: 1600001023 });
+
+    // The median time past must exceed the time lock.
+    assert!(!tx.sequence_locks_satisfied(&[100], &[1600000000], u32::MAX, 1600001023).unwrap());
+    assert!(tx.sequence_locks_satisfied(&[100], &[1600000000], 101, 1600001024).unwrap());
+
+    // The latest time lock of each kind applies.
+    let tx = transaction(2, 0, &[
+        RelativeLockTime::Blocks(10).to_sequence(),
+        RelativeLockTime::Blocks(5).to_sequence(),
+        RelativeLockTime::Time(1).to_sequence(),
+    ]);
+
+    let sequence_locks = tx.sequence_locks(&[100, 200, 300], &[1000, 2000, 3000]).unwrap();
+
+    assert_eq!(sequence_locks, SequenceLocks { min_height: 204, min_time: 3511 });
+
+    // The disable flag.
+    let tx = transaction(2, 0, &[SEQUENCE_LOCKTIME_DISABLE_FLAG | RelativeLockTime::Blocks(10).to_sequence(), SEQUENCE_FINAL]);
+
+    assert_eq!(tx.sequence_locks(&[100, 100], &[0, 0]).unwrap(), SequenceLocks { min_height: -1, min_time: -1 });
+    assert!(tx.sequence_locks_satisfied(&[100, 100], &[0, 0], 100, 0).unwrap());
+
+    // Relative time locks are not enforced for transaction versions below 2.
+    let tx = transaction(1, 0, &[RelativeLockTime::Blocks(10).to_sequence()]);
+
+    assert_eq!(tx.sequence_locks(&[100], &[0]).unwrap(), SequenceLocks { min_height: -1, min_time: -1 });
+
+    // One block height and median time past per transaction input.
+    let tx = transaction(2, 0, &[RelativeLockTime::Blocks(10).to_sequence(), RelativeLockTime::Blocks(10).to_sequence()]);
+
+    assert_eq!(tx.sequence_locks(&[100], &[0, 0]), Err(SequenceLocksError::PrevHeightsMismatch));
+    assert_eq!(tx.sequence_locks(&[100, 100], &[0]), Err(SequenceLocksError::PrevMtpsMismatch));
+    assert_eq!(tx.sequence_locks_satisfied(&[], &[], 0, 0), Err(SequenceLocksError::PrevHeightsMismatch));
+
+    // An absolute time lock by timestamp is compared against the median time past (BIP-113).
+    let tx = transaction(2, 1600000000, &[0xfffffffe_u32]);
+
+    assert!(!tx.is_final(u32::MAX, 1600000000));
+    assert!(tx.is_final(0, 1600000001));
+
+    // An absolute time lock by block height.
+    let tx = transaction(2, 800000, &[0xfffffffe_u32]);
+
+    assert!(!tx.is_final(800000, u32::MAX));
+    assert!(tx.is_final(800001, 0));
+
+    // Final transaction inputs disable the absolute time lock.
+    let tx = transaction(2, 800000, &[SEQUENCE_FINAL]);
+
+    assert!(tx.is_final(0, 0));
+}
@@ -0,0 +1,174 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::file_provider::FileUtxoProvider;
+use bitcoin::transaction::verify::verification::TransactionVerificationError;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+
+/// Returns a UTXO entry of some amount of satoshi, locked by `OP_1` (i.e.; spendable by anyone).
+fn entry(satoshi: i64, height: Option<u32>, coinbase: bool) -> UtxoEntry {
+    UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(satoshi).unwrap(), ScriptBytes::of(&[0x51_u8])), height, coinbase)
+}
+
+/// Asserts that a UTXO provider provides some UTXO entry for an outpoint.
+fn assert_provides<P>(utxo_provider: &P, outpoint: &OutPoint, expected: &UtxoEntry)
+where P: UtxoProvider {
+    let entry = utxo_provider.utxo(outpoint).unwrap();
+
+    assert_eq!(entry.utxo.amount, expected.utxo.amount);
+    assert_eq!(entry.utxo.script, expected.utxo.script);
+    assert_eq!(entry.height, expected.height);
+    assert_eq!(entry.coinbase, expected.coinbase);
+}
+
+/// Returns a version 1 transaction without a time lock.
+fn transaction(inputs: Vec<TransactionInput>, utxos: Vec<UnspentTransactionOutput>) -> Transaction {
+    Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: inputs,
+        utxos: utxos,
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    }
+}
+
+pub fn run() {
+    test_in_memory_provider();
+    test_file_provider();
+    test_coinbase_maturity();
+}
+
+fn test_in_memory_provider() {
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    assert!(utxo_provider.is_empty());
+
+    let outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+
+    assert!(matches!(utxo_provider.utxo(&outpoint), Err(UtxoProviderError::UtxoNotFound(missing)) if missing == outpoint));
+
+    // Later entries replace earlier entries.
+    utxo_provider.insert(outpoint, entry(1000, None, false));
+    utxo_provider.insert(outpoint, entry(2000, Some(100), false));
+
+    assert_eq!(utxo_provider.len(), 1);
+    assert_provides(&utxo_provider, &outpoint, &entry(2000, Some(100), false));
+
+    // Every UTXO of a coinbase transaction.
+    let coinbase = transaction(
+        vec![TransactionInput::new(OutPoint::null(), ScriptBytes::of(&[0x01_u8, 0x65_u8]), SEQUENCE_FINAL)],
+        vec![entry(5000000000, None, true).utxo, entry(0, None, true).utxo]
+    );
+
+    utxo_provider.insert_transaction(&coinbase, Some(101));
+
+    assert_eq!(utxo_provider.len(), 3);
+    assert_provides(&utxo_provider, &OutPoint::new(coinbase.txid(), 0), &entry(5000000000, Some(101), true));
+    assert_provides(&utxo_provider, &OutPoint::new(coinbase.txid(), 1), &entry(0, Some(101), true));
+
+    // Spent UTXOs are removed.
+    assert!(utxo_provider.remove(&outpoint).is_some());
+    assert!(utxo_provider.remove(&outpoint).is_none());
+    assert!(utxo_provider.utxo(&outpoint).is_err());
+    assert_eq!(utxo_provider.len(), 2);
+}
+
+fn test_file_provider() {
+    let path = std::env::temp_dir().join(format!("test_utxo_provider_{}.dat", std::process::id()));
+
+    let _ = std::fs::remove_file(&path);
+
+    let outpoint_1 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+    let outpoint_2 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 7);
+    let outpoint_3 = OutPoint::new(TransactionId::of(&[0x02_u8; 32]), 0);
+
+    {
+        let mut utxo_provider = FileUtxoProvider::open(&path).unwrap();
+
+        assert!(utxo_provider.is_empty());
+
+        utxo_provider.append(outpoint_1, &entry(1000, None, false)).unwrap();
+        utxo_provider.append(outpoint_2, &entry(2000, Some(100), true)).unwrap();
+
+        assert_eq!(utxo_provider.len(), 2);
+        assert_provides(&utxo_provider, &outpoint_1, &entry(1000, None, false));
+        assert_provides(&utxo_provider, &outpoint_2, &entry(2000, Some(100), true));
+        assert!(matches!(utxo_provider.utxo(&outpoint_3), Err(UtxoProviderError::UtxoNotFound(missing)) if missing == outpoint_3));
+    }
+
+    // Records are indexed when the file is reopened; later records for the same outpoint take
+    // precedence.
+    {
+        let mut utxo_provider = FileUtxoProvider::open(&path).unwrap();
+
+        assert_eq!(utxo_provider.len(), 2);
+        assert_provides(&utxo_provider, &outpoint_1, &entry(1000, None, false));
+        assert_provides(&utxo_provider, &outpoint_2, &entry(2000, Some(100), true));
+
+        utxo_provider.append(outpoint_1, &entry(3000, Some(200), false)).unwrap();
+        utxo_provider.append(outpoint_3, &entry(4000, Some(0), false)).unwrap();
+
+        assert_provides(&utxo_provider, &outpoint_1, &entry(3000, Some(200), false));
+    }
+
+    {
+        let utxo_provider = FileUtxoProvider::open(&path).unwrap();
+
+        assert_eq!(utxo_provider.len(), 3);
+        assert_provides(&utxo_provider, &outpoint_1, &entry(3000, Some(200), false));
+        assert_provides(&utxo_provider, &outpoint_3, &entry(4000, Some(0), false));
+    }
+
+    // A truncated record.
+    let bytes = std::fs::read(&path).unwrap();
+
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+
+    assert!(matches!(FileUtxoProvider::open(&path), Err(UtxoProviderError::TransactionParsingError(_))));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+fn test_coinbase_maturity() {
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    let coinbase_outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+    let unconfirmed_coinbase_outpoint = OutPoint::new(TransactionId::of(&[0x02_u8; 32]), 0);
+    let outpoint = OutPoint::new(TransactionId::of(&[0x03_u8; 32]), 0);
+
+    utxo_provider.insert(coinbase_outpoint, entry(5000000000, Some(100), true));
+    utxo_provider.insert(unconfirmed_coinbase_outpoint, entry(5000000000, None, true));
+    utxo_provider.insert(outpoint, entry(5000000000, Some(100), false));
+
+    let spend = |outpoint: OutPoint| transaction(
+        vec![TransactionInput::new(outpoint, ScriptBytes::of(&[0x51_u8]), SEQUENCE_FINAL)],
+        vec![entry(4999990000, None, false).utxo]
+    );
+
+    // A coinbase transaction's UTXO confirmed at block height 100 may be spent from block height
+    // 200 (`COINBASE_MATURITY` blocks later).
+    assert!(matches!(spend(coinbase_outpoint).verify_input(0, 0, 199, &utxo_provider), Err(TransactionVerificationError::PrematureCoinbaseSpend(0))));
+    assert!(matches!(spend(coinbase_outpoint).verify(0, 199, &utxo_provider), Err(TransactionVerificationError::PrematureCoinbaseSpend(0))));
+    assert!(spend(coinbase_outpoint).verify_input(0, 0, 200, &utxo_provider).unwrap());
+    assert!(spend(coinbase_outpoint).verify(0, 200, &utxo_provider).unwrap());
+
+    // An unconfirmed coinbase transaction's UTXO is never mature.
+    assert!(matches!(spend(unconfirmed_coinbase_outpoint).verify_input(0, 0, u64::MAX, &utxo_provider), Err(TransactionVerificationError::PrematureCoinbaseSpend(0))));
+
+    // Other UTXOs may be spent immediately.
+    assert!(spend(outpoint).verify_input(0, 0, 100, &utxo_provider).unwrap());
+}
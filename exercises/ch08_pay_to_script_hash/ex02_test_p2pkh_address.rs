@@ -17,9 +17,9 @@ pub fn exercise() {
     message(&public_key_hash);
 
     let address = BitcoinAddress::for_hash_bytes(
-        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2pkh),
+        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2pkh).unwrap(),
         &public_key_hash_bytes
-    );
+    ).unwrap();
 
     show_debug(&BitcoinNetworkType::Mainnet);
     show_display(&address);
@@ -27,9 +27,9 @@ pub fn exercise() {
     assert_eq!(address.to_string(), "1BenRpVUFK65JFWcQSuHnJKzc4M8ZP8Eqa");
 
     let address = BitcoinAddress::for_hash_bytes(
-        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Testnet, BitcoinTransactionType::P2pkh),
+        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Testnet, BitcoinTransactionType::P2pkh).unwrap(),
         &public_key_hash_bytes
-    );
+    ).unwrap();
 
     show_debug(&BitcoinNetworkType::Testnet);
     show_display(&address);
@@ -17,9 +17,9 @@ pub fn exercise() {
     message(&redeem_script_hash);
 
     let address = BitcoinAddress::for_hash_bytes(
-        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2sh),
+        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2sh).unwrap(),
         &redeem_script_hash_bytes
-    );
+    ).unwrap();
 
     show_debug(&BitcoinNetworkType::Mainnet);
     show_display(&address);
@@ -27,9 +27,9 @@ pub fn exercise() {
     assert_eq!(address.to_string(), "3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh");
 
     let address = BitcoinAddress::for_hash_bytes(
-        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Testnet, BitcoinTransactionType::P2sh),
+        BitcoinAddress::base58_encoding_type(BitcoinNetworkType::Testnet, BitcoinTransactionType::P2sh).unwrap(),
         &redeem_script_hash_bytes
-    );
+    ).unwrap();
 
     show_debug(&BitcoinNetworkType::Testnet);
     show_display(&address);
@@ -2,7 +2,7 @@ use programming_bitcoin_in_rust::*;
 use crate::util::println_exercise::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use util::byte_string::ByteString;
 use util::hexadecimal::hexadecimal_string;
 
@@ -37,15 +37,13 @@ pub fn exercise() {
     show_display(&transaction_22874d30.txid());
     show_pretty_print(&transaction_22874d30);
 
-    let retrieve_transaction_22874d30 = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, transaction_22874d30.txid());
+    let mut transaction_22874d30_utxos = InMemoryUtxoProvider::new();
 
-        Some(&transaction_22874d30)
-    };
+    transaction_22874d30_utxos.insert_transaction(&transaction_22874d30, None);
 
     section("Verification result");
 
-    let result = transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, retrieve_transaction_22874d30).unwrap();
+    let result = transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &transaction_22874d30_utxos).unwrap();
 
     show_debug(&result);
 
@@ -1 +1,12 @@
 pub mod test_sign_input_p2sh;
+pub mod test_sign_input_segwit;
+pub mod test_witness_commitment;
+pub mod test_verify_taproot;
+pub mod test_taproot_tree;
+pub mod test_verify_witness;
+pub mod test_verify_witness_opcodes;
+pub mod test_malleability;
+pub mod test_signature_inspection;
+pub mod test_transaction_explanation;
+pub mod test_size_estimation;
+pub mod test_transaction_diff;
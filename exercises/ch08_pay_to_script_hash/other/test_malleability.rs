@@ -0,0 +1,214 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Locktime;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::Opcode;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::opcode::ConstantOpcode;
+use bitcoin::script::opcode::CryptographicOpcode;
+use crypto::ecdsa::signature::Signature;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use serialization::signature::SignatureDerFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+use crate::util::bitcoin::script::context::*;
+
+pub fn run() {
+    let secret_e_1 = U256::from_be_bytes(hash_256(b"my little secret"));
+    let secret_e_2 = U256::from_be_bytes(hash_256(b"yet another secret"));
+
+    let point_1: EllipticCurvePoint = (secret_e_1 * Secp256k1Point::generator_point()).into();
+    let point_bytes_1 = CompressedPointSecFormatBytes::from(&point_1);
+
+    let point_2: EllipticCurvePoint = (secret_e_2 * Secp256k1Point::generator_point()).into();
+    let point_bytes_2 = CompressedPointSecFormatBytes::from(&point_2);
+
+    let multisig_redeem_script = ScriptBytes::from(&Script::new(&[
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Data(DataElement::of(point_bytes_1.bytes())),
+        Element::Data(DataElement::of(point_bytes_2.bytes())),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckMultisig)),
+    ]).unwrap());
+
+    // A redeem script that is not recognized; its arguments are arbitrary.
+    let unknown_redeem_script = ScriptBytes::of(&[0x51_u8]);
+
+    let locking_transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(
+                OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0),
+                ScriptBytes::of(&[]),
+                u32::MAX
+            ),
+        ],
+        utxos: [
+            ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_1),
+            ScriptBytes::locking_script_p2sh_redeem_script(&multisig_redeem_script),
+            ScriptBytes::locking_script_p2sh_redeem_script(&unknown_redeem_script),
+        ]
+            .into_iter()
+            .map(|script| UnspentTransactionOutput::new(Amount::from_sat(2500).unwrap(), script))
+            .collect(),
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert_transaction(&locking_transaction, None);
+
+    let mut transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: (0..3)
+            .map(|n| TransactionInput::new(OutPoint::new(locking_transaction.txid(), n), ScriptBytes::of(&[]), u32::MAX))
+            .collect(),
+        utxos: vec![
+            UnspentTransactionOutput::new(
+                Amount::from_sat(7000).unwrap(),
+                ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_2),
+            ),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let sighash = SigHashFlag::try_from(0x01_u8).unwrap();
+
+    let p2pkh_signature = pushed_data(&transaction.signed_input_bytes_p2pkh(0, secret_e_1, sighash, &utxo_provider).unwrap(), 0);
+    let multisig_signature_1 = pushed_data(&transaction.signed_input_bytes_p2sh(1, secret_e_1, &multisig_redeem_script, sighash).unwrap(), 0);
+    let multisig_signature_2 = pushed_data(&transaction.signed_input_bytes_p2sh(1, secret_e_2, &multisig_redeem_script, sighash).unwrap(), 0);
+
+    // A data element that resembles a signature, with a high s-value.
+    let unknown_argument = high_s(&p2pkh_signature);
+
+    transaction.inputs[0].script = ScriptBytes::of(&push_only_script(&[&p2pkh_signature, point_bytes_1.bytes()]));
+    transaction.inputs[1].script = ScriptBytes::of(&push_only_script(&[&[], &multisig_signature_1, &multisig_signature_2, multisig_redeem_script.bytes()]));
+    transaction.inputs[2].script = ScriptBytes::of(&push_only_script(&[&unknown_argument, unknown_redeem_script.bytes()]));
+
+    assert!(transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+
+    // A transaction in canonical form is unchanged.
+    let normalized = transaction.normalize(&utxo_provider).unwrap();
+
+    assert!(!normalized.txid_changed);
+    assert!(normalized.normalized_inputs.is_empty());
+    assert!(normalized.transaction.txid() == transaction.txid());
+
+    // High s-values; a non-minimal push; an extra data element; a non-empty `OP_CHECKMULTISIG`
+    // dummy element (BIP-147).
+    let mut malleated_transaction = transaction.clone();
+
+    let mut input_script_p2pkh = push_only_script(&[&[0x01_u8]]);
+    input_script_p2pkh.extend(pushdata1(&high_s(&p2pkh_signature)));
+    input_script_p2pkh.extend(push_only_script(&[point_bytes_1.bytes()]));
+
+    let mut input_script_multisig = push_only_script(&[&[0x01_u8], &high_s(&multisig_signature_1), &multisig_signature_2]);
+    input_script_multisig.extend(pushdata1(multisig_redeem_script.bytes()));
+
+    malleated_transaction.inputs[0].script = ScriptBytes::of(&input_script_p2pkh);
+    malleated_transaction.inputs[1].script = ScriptBytes::of(&input_script_multisig);
+
+    assert!(malleated_transaction.txid() != transaction.txid());
+
+    let normalized = malleated_transaction.normalize(&utxo_provider).unwrap();
+
+    assert!(normalized.txid_changed);
+    assert_eq!(normalized.normalized_inputs, vec![0, 1]);
+    assert_eq!(normalized.transaction.inputs[0].script.bytes(), transaction.inputs[0].script.bytes());
+    assert_eq!(normalized.transaction.inputs[1].script.bytes(), transaction.inputs[1].script.bytes());
+    assert!(normalized.transaction.txid() == transaction.txid());
+    assert!(malleated_transaction.normalized_txid(&utxo_provider).unwrap() == transaction.normalized_txid(&utxo_provider).unwrap());
+
+    assert!(normalized.transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider).unwrap());
+
+    // Arguments to an unrecognized redeem script are not rewritten, even if they resemble
+    // signatures, or are pushed with non-minimal data opcodes.
+    let mut unknown_transaction = transaction.clone();
+
+    let mut input_script_unknown = pushdata1(&unknown_argument);
+    input_script_unknown.extend(push_only_script(&[unknown_redeem_script.bytes()]));
+
+    unknown_transaction.inputs[2].script = ScriptBytes::of(&input_script_unknown);
+
+    let normalized = unknown_transaction.normalize(&utxo_provider).unwrap();
+
+    assert!(!normalized.txid_changed);
+    assert_eq!(normalized.transaction.inputs[2].script.bytes(), input_script_unknown.as_slice());
+
+    // Too few data elements to satisfy the locking script; the script is not rewritten.
+    let mut incomplete_transaction = transaction.clone();
+
+    let input_script_incomplete = pushdata1(&high_s(&p2pkh_signature));
+
+    incomplete_transaction.inputs[0].script = ScriptBytes::of(&input_script_incomplete);
+
+    let normalized = incomplete_transaction.normalize(&utxo_provider).unwrap();
+
+    assert!(!normalized.txid_changed);
+    assert_eq!(normalized.transaction.inputs[0].script.bytes(), input_script_incomplete.as_slice());
+}
+
+/// Returns a script that pushes each data element, with minimal data opcodes.
+fn push_only_script(data: &[&[u8]]) -> Vec<u8> {
+    let elements: Vec<Element> = data
+        .iter()
+        .map(|data| match data {
+            [] => Element::Opcode(Opcode::Constant(ConstantOpcode::OpFalse)),
+            [n @ 0x01_u8..=0x10_u8] => Element::Opcode(Opcode::try_from(0x50_u8 + n).unwrap()),
+            _ => Element::Data(DataElement::of(data)),
+        })
+        .collect();
+
+    ScriptBytes::from(&Script::new(&elements).unwrap()).bytes().to_vec()
+}
+
+/// Returns a script that pushes some data with `OP_PUSHDATA1`.
+fn pushdata1(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x4c_u8, u8::try_from(data.len()).unwrap()];
+
+    bytes.extend_from_slice(data);
+
+    bytes
+}
+
+/// Returns the data pushed by some script element.
+fn pushed_data(script_bytes: &ScriptBytes, n: usize) -> Vec<u8> {
+    match &Script::try_from(script_bytes).unwrap().elements()[n] {
+        Element::Data(data) => data.bytes().to_vec(),
+        _ => panic!("expected a data element"),
+    }
+}
+
+/// Returns a signature (followed by a `SIGHASH` flag byte) with its s-value flipped to `n - s`.
+fn high_s(signature_bytes: &[u8]) -> Vec<u8> {
+    let (sighash_byte, signature_bytes) = signature_bytes.split_last().unwrap();
+
+    let mut signature = Signature::from(SignatureDerFormatBytes::try_of(signature_bytes).unwrap());
+
+    signature.s = ELLIPTIC_CURVE_ORDER - signature.s;
+
+    let mut bytes = SignatureDerFormatBytes::from(&signature).bytes().to_vec();
+
+    bytes.push(*sighash_byte);
+
+    bytes
+}
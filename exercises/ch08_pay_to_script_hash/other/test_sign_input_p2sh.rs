@@ -1,10 +1,12 @@
 use programming_bitcoin_in_rust::*;
 
 use bitcoin::transaction::Transaction;
-use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
 use bitcoin::transaction::Version;
 use bitcoin::transaction::TransactionInput;
 use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
 use bitcoin::transaction::Locktime;
 use bitcoin::script::Script;
 use bitcoin::script::ScriptBytes;
@@ -62,32 +64,29 @@ pub fn run() {
         version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
         inputs: vec![
             TransactionInput::new(
-                funding_transaction.txid(),
-                funding_transaction_utxo_index,
+                OutPoint::new(funding_transaction.txid(), funding_transaction_utxo_index),
                 ScriptBytes::of(&[]),
                 u32::MAX
             ),
         ],
         utxos: vec![
             UnspentTransactionOutput::new(
-                amount_satoshi_lock_by_redeem_script,
+                Amount::from_sat(amount_satoshi_lock_by_redeem_script).unwrap(),
                 ScriptBytes::locking_script_p2sh_redeem_script(&redeem_script_bytes),
             ),
         ],
         locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
     };
 
-    let retrieve_funding_txn = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, funding_transaction.txid());
+    let mut funding_txn_utxos = InMemoryUtxoProvider::new();
 
-        Some(&funding_transaction)
-    };
+    funding_txn_utxos.insert_transaction(&funding_transaction, None);
 
     let input_script = p2sh_locking_transaction.signed_input_bytes_p2pkh(
         0,
         U256::from(secret_e_1),
         SigHashFlag::try_from(0x01_u8).unwrap(),
-        retrieve_funding_txn
+        &funding_txn_utxos
     );
 
     p2sh_locking_transaction.inputs[0].script = input_script.unwrap();
@@ -96,19 +95,18 @@ pub fn run() {
         version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
         inputs: vec![
             TransactionInput::new(
-                p2sh_locking_transaction.txid(),
-                0,
+                OutPoint::new(p2sh_locking_transaction.txid(), 0),
                 ScriptBytes::of(&[]),
                 u32::MAX
             ),
         ],
         utxos: vec![
             UnspentTransactionOutput::new(
-                amount_satoshi_send_to_1,
+                Amount::from_sat(amount_satoshi_send_to_1).unwrap(),
                 ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_1),
             ),
             UnspentTransactionOutput::new(
-                amount_satoshi_send_to_2,
+                Amount::from_sat(amount_satoshi_send_to_2).unwrap(),
                 ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_2),
             ),
         ],
@@ -134,28 +132,20 @@ pub fn run() {
         .concatenate(&Script::try_from(&signature_input_1.concatenate(&signature_input_2)).unwrap())
         .unwrap();
 
-    let retrieve_locking_txn = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, p2sh_locking_transaction.txid());
+    let mut locking_txn_utxos = InMemoryUtxoProvider::new();
 
-        Some(&p2sh_locking_transaction)
-    };
+    locking_txn_utxos.insert_transaction(&p2sh_locking_transaction, None);
 
     let input_script = p2sh_unlocking_transaction.redeem_script_input_bytes_p2sh(
         0,
         Some(&ScriptBytes::from(&reedem_script_arguments)),
         &redeem_script_bytes,
-        retrieve_locking_txn
+        &locking_txn_utxos
     );
 
     p2sh_unlocking_transaction.inputs[0].script = input_script.unwrap();
 
-    let retrieve_locking_txn = |transaction_id: &TransactionId| {
-        assert_eq!(*transaction_id, p2sh_locking_transaction.txid());
-
-        Some(&p2sh_locking_transaction)
-    };
-
-    let result = p2sh_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, retrieve_locking_txn).unwrap();
+    let result = p2sh_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap();
 
     assert!(result);
 }
@@ -0,0 +1,178 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::signing::TransactionSigningError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::transaction::SegWitTransactionError;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::Opcode;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::opcode::ConstantOpcode;
+use bitcoin::script::opcode::CryptographicOpcode;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteVector;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+use crate::util::bitcoin::script::context::*;
+
+pub fn run() {
+    let secret_e_1 = U256::from_be_bytes(hash_256(b"my little secret"));
+    let secret_e_2 = U256::from_be_bytes(hash_256(b"https://www.youtube.com/watch?v=uc6f_2nPSX8&t=60s"));
+    let secret_e_3 = U256::from_be_bytes(hash_256(b"yet another secret"));
+
+    let point_1: EllipticCurvePoint = (secret_e_1 * Secp256k1Point::generator_point()).into();
+    let point_bytes_1 = CompressedPointSecFormatBytes::from(&point_1);
+
+    let point_2: EllipticCurvePoint = (secret_e_2 * Secp256k1Point::generator_point()).into();
+    let point_bytes_2 = CompressedPointSecFormatBytes::from(&point_2);
+
+    let point_3: EllipticCurvePoint = (secret_e_3 * Secp256k1Point::generator_point()).into();
+    let point_bytes_3 = CompressedPointSecFormatBytes::from(&point_3);
+
+    let amount_satoshi_lock = 2500;
+    let amount_satoshi_send = 9000;
+
+    let witness_program_p2wpkh = ScriptBytes::locking_script_p2wpkh_compressed_point(&point_bytes_1);
+
+    let witness_script = Script::new(&[
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Data(DataElement::of(&point_bytes_1.bytes())),
+        Element::Data(DataElement::of(&point_bytes_2.bytes())),
+        Element::Data(DataElement::of(&point_bytes_3.bytes())),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op3)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckMultisig)),
+    ]).unwrap();
+    let witness_script_bytes = ScriptBytes::from(&witness_script);
+
+    let witness_program_p2wsh = ScriptBytes::locking_script_p2wsh_witness_script(&witness_script_bytes);
+
+    // A transaction locking each kind of segregated witness UTXO; its own input is irrelevant.
+    let locking_scripts = [
+        witness_program_p2wpkh.clone(),
+        ScriptBytes::locking_script_p2sh_redeem_script(&witness_program_p2wpkh),
+        witness_program_p2wsh.clone(),
+        ScriptBytes::locking_script_p2sh_redeem_script(&witness_program_p2wsh),
+    ];
+
+    let segwit_locking_transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(
+                OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0),
+                ScriptBytes::of(&[]),
+                u32::MAX
+            ),
+        ],
+        utxos: locking_scripts
+            .iter()
+            .map(|script| UnspentTransactionOutput::new(Amount::from_sat(amount_satoshi_lock).unwrap(), script.clone()))
+            .collect(),
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let mut locking_txn_utxos = InMemoryUtxoProvider::new();
+
+    locking_txn_utxos.insert_transaction(&segwit_locking_transaction, None);
+
+    let unlocking_transaction = Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: (0..4)
+            .map(|n| TransactionInput::new(OutPoint::new(segwit_locking_transaction.txid(), n), ScriptBytes::of(&[]), u32::MAX))
+            .collect(),
+        utxos: vec![
+            UnspentTransactionOutput::new(
+                Amount::from_sat(amount_satoshi_send).unwrap(),
+                ScriptBytes::locking_script_p2wpkh_compressed_point(&point_bytes_2),
+            ),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let sighash = SigHashFlag::try_from(0x01_u8).unwrap();
+
+    let segwit_field_p2wpkh = unlocking_transaction.signed_witness_p2wpkh(0, secret_e_1, sighash, &locking_txn_utxos).unwrap();
+    let segwit_field_p2sh_p2wpkh = unlocking_transaction.signed_witness_p2wpkh(1, secret_e_1, sighash, &locking_txn_utxos).unwrap();
+
+    // Secrets may be provided out of order.
+    let segwit_field_p2wsh = unlocking_transaction
+        .signed_witness_p2wsh_multisig(2, &[secret_e_3, secret_e_1], &witness_script_bytes, sighash, &locking_txn_utxos)
+        .unwrap();
+    let segwit_field_p2sh_p2wsh = unlocking_transaction
+        .signed_witness_p2wsh_multisig(3, &[secret_e_2, secret_e_3], &witness_script_bytes, sighash, &locking_txn_utxos)
+        .unwrap();
+
+    // Exactly two distinct secrets must be provided for a 2-of-3 multisig witness script.
+    let sign_multisig = |secrets_e: &[U256]| unlocking_transaction.signed_witness_p2wsh_multisig(2, secrets_e, &witness_script_bytes, sighash, &locking_txn_utxos);
+
+    assert!(matches!(sign_multisig(&[secret_e_1]), Err(TransactionSigningError::SignatureCountMismatch)));
+    assert!(matches!(sign_multisig(&[secret_e_1, secret_e_2, secret_e_3]), Err(TransactionSigningError::SignatureCountMismatch)));
+    assert!(matches!(sign_multisig(&[secret_e_3, secret_e_3]), Err(TransactionSigningError::DuplicateSecret)));
+    assert!(matches!(sign_multisig(&[secret_e_1, U256::from(1_u8)]), Err(TransactionSigningError::PublicKeyNotFound)));
+
+    let input_script_p2sh_p2wpkh = unlocking_transaction
+        .redeem_script_input_bytes_p2sh(1, None, &witness_program_p2wpkh, &locking_txn_utxos)
+        .unwrap();
+    let input_script_p2sh_p2wsh = unlocking_transaction
+        .redeem_script_input_bytes_p2sh(3, None, &witness_program_p2wsh, &locking_txn_utxos)
+        .unwrap();
+
+    let mut segwit_unlocking_transaction = SegWitTransaction::from_transaction(unlocking_transaction);
+
+    // Without any witness data, the transaction is serialized as a legacy transaction.
+    assert!(!segwit_unlocking_transaction.has_witness());
+    assert_eq!(segwit_unlocking_transaction.bytes(), segwit_unlocking_transaction.stripped().bytes());
+
+    segwit_unlocking_transaction.set_signed_input(0, ScriptBytes::of(&[]), segwit_field_p2wpkh).unwrap();
+    segwit_unlocking_transaction.set_signed_input(1, input_script_p2sh_p2wpkh, segwit_field_p2sh_p2wpkh).unwrap();
+    segwit_unlocking_transaction.set_signed_input(2, ScriptBytes::of(&[]), segwit_field_p2wsh).unwrap();
+    segwit_unlocking_transaction.set_signed_input(3, input_script_p2sh_p2wsh, segwit_field_p2sh_p2wsh).unwrap();
+
+    // There is no fifth transaction input.
+    assert_eq!(
+        segwit_unlocking_transaction.set_signed_input(4, ScriptBytes::of(&[]), SegWitField::new(vec![])),
+        Err(SegWitTransactionError::NoInputFound(4)),
+    );
+    assert_eq!(segwit_unlocking_transaction.set_segwit_field(4, SegWitField::new(vec![])), Err(SegWitTransactionError::NoInputFound(4)));
+    assert!(segwit_unlocking_transaction.segwit_field_mut(4).is_none());
+
+    let (transaction, mut segwit_fields) = segwit_unlocking_transaction.clone().into_parts();
+
+    segwit_fields.pop();
+
+    assert_eq!(SegWitTransaction::new(transaction, segwit_fields).err(), Some(SegWitTransactionError::FieldCountMismatch));
+
+    let result = segwit_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap();
+
+    assert!(result);
+
+    // The fully-signed transaction survives serialization.
+    let parsed_transaction = SegWitTransaction::parse_bytes(&segwit_unlocking_transaction.bytes()).unwrap();
+
+    assert_eq!(parsed_transaction.bytes(), segwit_unlocking_transaction.bytes());
+    assert!(parsed_transaction.wtxid() == segwit_unlocking_transaction.wtxid());
+
+    assert_eq!(parsed_transaction.stripped().bytes(), segwit_unlocking_transaction.stripped().bytes());
+
+    let result = parsed_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap();
+
+    assert!(result);
+}
@@ -0,0 +1,192 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::transaction::verify::signature_inspection::SignatureLocation;
+use bitcoin::transaction::verify::signature_inspection::SignatureInspectionError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::SigHashFlag;
+use crypto::ecdsa::signature::Signature;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use serialization::signature::SignatureDerFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// BIP-143's native P2WPKH example; a P2PK transaction input, and a P2WPKH transaction input.
+const P2WPKH_TRANSACTION: &str = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+const P2WPKH_SPENT_UTXOS: [(&str, i64); 2] = [
+    ("2103c9f4836b9a4f77fc0d81f7bcb01b7f1b35916864b9476c241ce9fc198bd25432ac", 625000000),
+    ("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1", 600000000),
+];
+
+/// BIP-143's P2SH-P2WPKH example.
+const P2SH_P2WPKH_TRANSACTION: &str = "01000000000101db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a5477010000001716001479091972186c449eb1ded22b78e40d009bdf0089feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac02473044022047ac8e878352d3ebbde1c94ce3a10d057c24175747116f8288e5d794d12d482f0220217f36a485cae903c713331d877c1f64677e3622ad4010726870540656fe9dcb012103ad1d8e89212f0b92c74d23bb710c00662ad1470198ac48c43f7d6f93a2a2687392040000";
+const P2SH_P2WPKH_SPENT_UTXO: (&str, i64) = ("a9144733f37cf4db86fbc2efed2500b4f4e49f31202387", 1000000000);
+
+/// BIP-143's P2SH-P2WSH example; a 6-of-6 multisig witness script, with a signature for each
+/// `SIGHASH` flag.
+const MULTISIG_TRANSACTION: &str = "0100000000010136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000023220020a16b5755f7f6f96dbd65f5f0d6ab9418b89af4b1f14a1bb8a09062c35f0dcb54ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac080047304402206ac44d672dac41f9b00e28f4df20c52eeb087207e8d758d76d92c6fab3b73e2b0220367750dbbe19290069cba53d096f44530e4f98acaa594810388cf7409a1870ce01473044022068c7946a43232757cbdf9176f009a928e1cd9a1a8c212f15c1e11ac9f2925d9002205b75f937ff2f9f3c1246e547e54f62e027f64eefa2695578cc6432cdabce271502473044022059ebf56d98010a932cf8ecfec54c48e6139ed6adb0728c09cbe1e4fa0915302e022007cd986c8fa870ff5d2b3a89139c9fe7e499259875357e20fcbb15571c76795403483045022100fbefd94bd0a488d50b79102b5dad4ab6ced30c4069f1eaa69a4b5a763414067e02203156c6a5c9cf88f91265f5a942e96213afae16d83321c8b31bb342142a14d16381483045022100a5263ea0553ba89221984bd7f0b13613db16e7a70c549a86de0cc0444141a407022005c360ef0ae5a5d4f9f2f87a56c1546cc8268cab08c73501d6b3be2e1e1a8a08824730440220525406a1482936d5a21888260dc165497a90a15669636d8edca6b9fe490d309c022032af0c646a34a44d1f4576bf6a4a74b67940f8faa84c7df9abe12a01a11e2b4783cf56210307b8ae49ac90a048e9b53357a2354b3334e9c8bee813ecb98e99a7e07e8c3ba32103b28f0c28bfab54554ae8c658ac5c3e0ce6e79ad336331f78c428dd43eea8449b21034b8113d703413d57761b8b9781957b8c0ac1dfe69f492580ca4195f50376ba4a21033400f6afecb833092a9a21cfdf1ed1376e58c5d1f47de74683123987e967a8f42103a6d48b1131e94ba04d9737d61acdaa1322008af9602b3b14862c07a1789aac162102d8b661b0b3302ee2f162b09e07a55ad5dfbe673a9f01d9f0c19617681024306b56ae00000000";
+const MULTISIG_SPENT_UTXO: (&str, i64) = ("a9149993a429037b5d912407a71c252019287b8d27a587", 987654321);
+
+/// Returns a UTXO provider with the UTXOs spent by each transaction input.
+fn spent_utxo_provider(transaction: &Transaction, spent_utxos: &[(&str, i64)]) -> InMemoryUtxoProvider {
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    for (input, (script, amount)) in transaction.inputs.iter().zip(spent_utxos) {
+        utxo_provider.insert(
+            input.outpoint.clone(),
+            UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(*amount).unwrap(), ScriptBytes::of(&hexadecimal_bytes(script))), None, false),
+        );
+    }
+
+    utxo_provider
+}
+
+pub fn run() {
+    // A legacy signature, and a P2WPKH signature (BIP-143).
+    let transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2WPKH_TRANSACTION)).unwrap();
+    let utxo_provider = spent_utxo_provider(&transaction, &P2WPKH_SPENT_UTXOS);
+
+    let signatures = transaction.signatures(&utxo_provider).unwrap();
+
+    assert_eq!(signatures.len(), 2);
+
+    assert_eq!(signatures[0].input_index, 0);
+    assert_eq!(signatures[0].location, SignatureLocation::Script(0));
+    assert_eq!(signatures[0].valid, Some(true));
+    assert_eq!(signatures[0].public_key, Some(hexadecimal_bytes(&P2WPKH_SPENT_UTXOS[0].0[2..68])));
+
+    assert_eq!(signatures[1].input_index, 1);
+    assert_eq!(signatures[1].location, SignatureLocation::Witness(0));
+    assert_eq!(signatures[1].valid, Some(true));
+    assert_eq!(signatures[1].public_key, Some(hexadecimal_bytes("025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee6357")));
+
+    assert!(signatures.iter().all(|signature| signature.sighash.byte() == 0x01_u8 && !signature.is_malleable()));
+
+    // A P2WPKH signature commits to the amount of the UTXO it spends.
+    let tampered_utxo_provider = spent_utxo_provider(&transaction, &[P2WPKH_SPENT_UTXOS[0], (P2WPKH_SPENT_UTXOS[1].0, 600000001)]);
+
+    let signatures = transaction.input_signatures(1, &tampered_utxo_provider).unwrap();
+
+    assert_eq!(signatures[0].valid, Some(false));
+    assert!(signatures[0].public_key.is_some());
+
+    // A P2SH-P2WPKH signature.
+    let transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2SH_P2WPKH_TRANSACTION)).unwrap();
+    let utxo_provider = spent_utxo_provider(&transaction, &[P2SH_P2WPKH_SPENT_UTXO]);
+
+    let signatures = transaction.signatures(&utxo_provider).unwrap();
+
+    assert_eq!(signatures.len(), 1);
+    assert_eq!(signatures[0].location, SignatureLocation::Witness(0));
+    assert_eq!(signatures[0].valid, Some(true));
+
+    // P2SH-P2WSH multisig signatures, each verified against a different public key of the witness
+    // script, and committing to a different `SIGHASH` flag.
+    let transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(MULTISIG_TRANSACTION)).unwrap();
+    let utxo_provider = spent_utxo_provider(&transaction, &[MULTISIG_SPENT_UTXO]);
+
+    let signatures = transaction.signatures(&utxo_provider).unwrap();
+
+    let witness_script = transaction.segwit_fields()[0].last().unwrap().bytes().to_vec();
+    let witness_script_public_keys: Vec<Vec<u8>> = Script::try_from(&ScriptBytes::of(&witness_script))
+        .unwrap()
+        .elements()
+        .iter()
+        .filter_map(|element| match element {
+            Element::Data(data) => Some(data.bytes().to_vec()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(signatures.len(), 6);
+
+    for (n, signature) in signatures.iter().enumerate() {
+        assert_eq!(signature.location, SignatureLocation::Witness(n + 1));
+        assert_eq!(signature.valid, Some(true));
+        assert_eq!(signature.public_key.as_ref(), Some(&witness_script_public_keys[n]));
+    }
+
+    assert_eq!(
+        signatures.iter().map(|signature| signature.sighash.byte()).collect::<Vec<u8>>(),
+        vec![0x01_u8, 0x02_u8, 0x03_u8, 0x81_u8, 0x82_u8, 0x83_u8]
+    );
+
+    // A transaction input that does not exist.
+    assert!(matches!(transaction.input_signatures(1, &utxo_provider), Err(SignatureInspectionError::NoInputFound(1))));
+    assert!(matches!(transaction.stripped().input_signatures(1, &utxo_provider), Err(SignatureInspectionError::NoInputFound(1))));
+
+    // A non-standard `SIGHASH` flag byte is reported as is; a high s-value is malleable.
+    let secret_e = U256::from_be_bytes(hash_256(b"my little secret"));
+
+    let point: EllipticCurvePoint = (secret_e * Secp256k1Point::generator_point()).into();
+    let point_bytes = CompressedPointSecFormatBytes::from(&point);
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    let outpoint = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+
+    utxo_provider.insert(
+        outpoint.clone(),
+        UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(2500).unwrap(), ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes)), None, false),
+    );
+
+    let mut transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![TransactionInput::new(outpoint, ScriptBytes::of(&[]), u32::MAX)],
+        utxos: vec![UnspentTransactionOutput::new(Amount::from_sat(2000).unwrap(), ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes))],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let input_script = transaction.signed_input_bytes_p2pkh(0, secret_e, SigHashFlag::from(0x84_u32), &utxo_provider).unwrap();
+
+    transaction.inputs[0].script = input_script.clone();
+
+    let signatures = transaction.signatures(&utxo_provider).unwrap();
+
+    assert_eq!(signatures.len(), 1);
+    assert_eq!(signatures[0].sighash.byte(), 0x84_u8);
+    assert_eq!(signatures[0].valid, Some(true));
+    assert_eq!(signatures[0].public_key.as_deref(), Some(point_bytes.bytes()));
+    assert!(!signatures[0].is_malleable());
+
+    let elements = Script::try_from(&input_script).unwrap().elements().to_vec();
+
+    let Element::Data(signature_data) = &elements[0] else { panic!("expected a data element") };
+    let (sighash_byte, signature_bytes) = signature_data.bytes().split_last().unwrap();
+
+    let mut signature = Signature::from(SignatureDerFormatBytes::try_of(signature_bytes).unwrap());
+
+    signature.s = ELLIPTIC_CURVE_ORDER - signature.s;
+
+    let mut high_s_bytes = SignatureDerFormatBytes::from(&signature).bytes().to_vec();
+    high_s_bytes.push(*sighash_byte);
+
+    transaction.inputs[0].script = ScriptBytes::from(&Script::new(&[Element::Data(DataElement::of(&high_s_bytes)), elements[1].clone()]).unwrap());
+
+    let signatures = transaction.signatures(&utxo_provider).unwrap();
+
+    assert!(!signatures[0].low_s);
+    assert!(signatures[0].is_malleable());
+}
@@ -0,0 +1,106 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::size::size_estimation::InputSizeTemplate;
+use bitcoin::transaction::size::size_estimation::SizeEstimationError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// A P2PKH transaction; its signature is 72 bytes long (including its `SIGHASH` flag byte), the
+/// largest size that is estimated.
+const P2PKH_TRANSACTION: &str = "0100000001813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1000000006b483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278afeffffff02a135ef01000000001976a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac99c39800000000001976a9141c4bc762dd5423e332166702cb75f40df79fea1288ac19430600";
+
+/// A 2-of-2 P2SH multisig transaction, with 72 byte signatures.
+const P2SH_MULTISIG_TRANSACTION: &str = "0100000001868278ed6ddfb6c1ed3ad5f8181eb0c7a385aa0836f01d5e4789e6bd304d87221a000000db00483045022100dc92655fe37036f47756db8102e0d7d5e28b3beb83a8fef4f5dc0559bddfb94e02205a36d4e4e6c7fcd16658c50783e00c341609977aed3ad00937bf4ee942a8993701483045022100da6bee3c93766232079a01639d07fa869598749729ae323eab8eef53577d611b02207bef15429dcadce2121ea07f233115c6f09034c0be68db99980b9a6c5e75402201475221022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb702103b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb7152aeffffffff04d3b11400000000001976a914904a49878c0adfc3aa05de7afad2cc15f483a56a88ac7f400900000000001976a914418327e3f3dda4cf5b9089325a4b95abdfa0334088ac722c0c00000000001976a914ba35042cfe9fc66fd35ac2224eebdafd1028ad2788acdc4ace020000000017a91474d691da1574e6b3c192ecfb52cc8984ee7b6c568700000000";
+
+/// BIP-143's native P2WPKH example; the first transaction input spends a P2PK UTXO, and the
+/// second a P2WPKH UTXO (with a 71 byte signature).
+const P2WPKH_TRANSACTION: &str = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+
+/// BIP-143's P2SH-P2WPKH example (with a 71 byte signature).
+const P2SH_P2WPKH_TRANSACTION: &str = "01000000000101db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a5477010000001716001479091972186c449eb1ded22b78e40d009bdf0089feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac02473044022047ac8e878352d3ebbde1c94ce3a10d057c24175747116f8288e5d794d12d482f0220217f36a485cae903c713331d877c1f64677e3622ad4010726870540656fe9dcb012103ad1d8e89212f0b92c74d23bb710c00662ad1470198ac48c43f7d6f93a2a2687392040000";
+
+/// BIP-143's P2SH-P2WSH example; a 6-of-6 multisig witness script, with four 71 byte signatures.
+const P2SH_P2WSH_MULTISIG_TRANSACTION: &str = "0100000000010136641869ca081e70f394c6948e8af409e18b619df2ed74aa106c1ca29787b96e0100000023220020a16b5755f7f6f96dbd65f5f0d6ab9418b89af4b1f14a1bb8a09062c35f0dcb54ffffffff0200e9a435000000001976a914389ffce9cd9ae88dcc0631e88a821ffdbe9bfe2688acc0832f05000000001976a9147480a33f950689af511e6e84c138dbbd3c3ee41588ac080047304402206ac44d672dac41f9b00e28f4df20c52eeb087207e8d758d76d92c6fab3b73e2b0220367750dbbe19290069cba53d096f44530e4f98acaa594810388cf7409a1870ce01473044022068c7946a43232757cbdf9176f009a928e1cd9a1a8c212f15c1e11ac9f2925d9002205b75f937ff2f9f3c1246e547e54f62e027f64eefa2695578cc6432cdabce271502473044022059ebf56d98010a932cf8ecfec54c48e6139ed6adb0728c09cbe1e4fa0915302e022007cd986c8fa870ff5d2b3a89139c9fe7e499259875357e20fcbb15571c76795403483045022100fbefd94bd0a488d50b79102b5dad4ab6ced30c4069f1eaa69a4b5a763414067e02203156c6a5c9cf88f91265f5a942e96213afae16d83321c8b31bb342142a14d16381483045022100a5263ea0553ba89221984bd7f0b13613db16e7a70c549a86de0cc0444141a407022005c360ef0ae5a5d4f9f2f87a56c1546cc8268cab08c73501d6b3be2e1e1a8a08824730440220525406a1482936d5a21888260dc165497a90a15669636d8edca6b9fe490d309c022032af0c646a34a44d1f4576bf6a4a74b67940f8faa84c7df9abe12a01a11e2b4783cf56210307b8ae49ac90a048e9b53357a2354b3334e9c8bee813ecb98e99a7e07e8c3ba32103b28f0c28bfab54554ae8c658ac5c3e0ce6e79ad336331f78c428dd43eea8449b21034b8113d703413d57761b8b9781957b8c0ac1dfe69f492580ca4195f50376ba4a21033400f6afecb833092a9a21cfdf1ed1376e58c5d1f47de74683123987e967a8f42103a6d48b1131e94ba04d9737d61acdaa1322008af9602b3b14862c07a1789aac162102d8b661b0b3302ee2f162b09e07a55ad5dfbe673a9f01d9f0c19617681024306b56ae00000000";
+
+/// A P2TR transaction (see `test_verify_taproot`); its first transaction input is a key path
+/// spend (with a 64 byte signature).
+const P2TR_TRANSACTION: &str = "020000000001044bd6c91048d75a6a9ba5c64d4bd46644b7cfeec58b9090e05884697247fa14f70000000000ffffffff4bd6c91048d75a6a9ba5c64d4bd46644b7cfeec58b9090e05884697247fa14f70100000000ffffffff4bd6c91048d75a6a9ba5c64d4bd46644b7cfeec58b9090e05884697247fa14f70200000000ffffffff4bd6c91048d75a6a9ba5c64d4bd46644b7cfeec58b9090e05884697247fa14f70300000000ffffffff01187301000000000022512097c504629dfbfe0587cf6022e5558f250c3a0c4a40f0b87fbc8a238b4f32f1d50140b6f2ed771e8217622a6a73b0f6adff1f44be9d7a6074f403204bb838a7329a0b296e2157fd7c5e50f47cb74f2a8ed3b532332b79c6d5c821dbb2c1e2971a06db0441f2016acba55d0cae5c50c20050591a301a874ab31024ed2d383fb2274b1e4eb84836182652a2ee8ca00792c45d3120aa94ba1a341bd3c7c1b0ae343c9850d0308122204a1521a0070f2fddb442da66399040bd3c23cd6ba2688316522965378b8173cdac61c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f8655d75c373958e669892b5feea6e33b3888b2f598d2f2a51554d6a379ab6aca0ff933d08853672a2275403f631a185860433b7a30f3dde2a4cbab45ca4cd5b5bf035001020440fea9c8178d77cc31040cd5a0a2fac6be35f78a7541d203586b2bed93ccc05280ac54f1bdf756d64ddce6384eacc5ef70025b34bdb3800228fbcbc66f8e1c7c7d4122bb1cbe4ba65bb694cc06216639de3d854802163256337101192d92a26fa54674bf2371e92f61ec94edd2e137277afc1de8127f2301c704618f5f78a7d64436014620845887703604c8e4ae84560f20e727da81abbe01f42a8d0a9e9488893cd38562ac204a1521a0070f2fddb442da66399040bd3c23cd6ba2688316522965378b8173cdba528761c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f860db55ca6961e05b565437b356256f3a8f76d60160487b90461accd45fa868990f933d08853672a2275403f631a185860433b7a30f3dde2a4cbab45ca4cd5b5bf030101015041c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f86b3e0d7a5db8e0b6a8b2b05bfdc25cda912a08bc81c4d844744b8e75e1cee88f900000000";
+
+/// Returns a transaction with only one of the transaction inputs of another (and its segregated
+/// witness field); e.g.; to measure a transaction input whose template is known.
+fn with_single_input(transaction: &SegWitTransaction, n: usize) -> SegWitTransaction {
+    let (mut transaction, segwit_fields) = transaction.clone().into_parts();
+
+    transaction.inputs = vec![transaction.inputs[n].clone()];
+
+    SegWitTransaction::new(transaction, vec![segwit_fields[n].clone()]).unwrap()
+}
+
+pub fn run() {
+    // Legacy transactions, with the largest estimated signatures, are estimated exactly.
+    let transaction = Transaction::parse_bytes(&hexadecimal_bytes(P2PKH_TRANSACTION)).unwrap();
+
+    assert_eq!(transaction.estimated_weight(&[InputSizeTemplate::P2pkh]), Ok(transaction.weight()));
+    assert_eq!(transaction.estimated_vsize(&[InputSizeTemplate::P2pkh]), Ok(226));
+
+    let transaction = Transaction::parse_bytes(&hexadecimal_bytes(P2SH_MULTISIG_TRANSACTION)).unwrap();
+    let template = InputSizeTemplate::P2shMultisig { m: 2, n: 2 };
+
+    assert_eq!(transaction.estimated_weight(&[template]), Ok(transaction.weight()));
+
+    // Existing input scripts are disregarded.
+    let mut unsigned_transaction = transaction.clone();
+    unsigned_transaction.inputs[0].script = ScriptBytes::of(&[]);
+
+    assert_eq!(unsigned_transaction.estimated_weight(&[template]), Ok(transaction.weight()));
+
+    // Segregated witness transactions; each 71 byte signature is overestimated by one byte,
+    // i.e.; one weight unit.
+    let transaction = with_single_input(&SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2WPKH_TRANSACTION)).unwrap(), 1);
+
+    assert_eq!(transaction.estimated_weight(&[InputSizeTemplate::P2wpkh]), Ok(transaction.weight() + 1));
+
+    let transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2SH_P2WPKH_TRANSACTION)).unwrap();
+
+    assert_eq!(transaction.estimated_weight(&[InputSizeTemplate::P2shP2wpkh]), Ok(transaction.weight() + 1));
+    assert_eq!(transaction.estimated_vsize(&[InputSizeTemplate::P2shP2wpkh]), Ok(transaction.vsize()));
+
+    // A 6-of-6 P2WSH multisig transaction input (ignoring its P2SH input script), with four 71
+    // byte signatures.
+    let mut transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2SH_P2WSH_MULTISIG_TRANSACTION)).unwrap();
+    let segwit_field = transaction.segwit_fields()[0].clone();
+    transaction.set_signed_input(0, ScriptBytes::of(&[]), segwit_field).unwrap();
+
+    assert_eq!(transaction.estimated_weight(&[InputSizeTemplate::P2wshMultisig { m: 6, n: 6 }]), Ok(transaction.weight() + 4));
+
+    // A P2TR key path spend, with the default `SIGHASH` flag, is estimated exactly.
+    let transaction = with_single_input(&SegWitTransaction::parse_bytes(&hexadecimal_bytes(P2TR_TRANSACTION)).unwrap(), 0);
+
+    assert_eq!(transaction.estimated_weight(&[InputSizeTemplate::P2trKeyPath]), Ok(transaction.weight()));
+
+    // Templates inferred from the locking script being spent.
+    let templates = [
+        ("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac", Some(InputSizeTemplate::P2pkh)),
+        ("00147dd65592d0ab2fe0d0257d571abf032cd9db93dc", Some(InputSizeTemplate::P2wpkh)),
+        ("512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343", Some(InputSizeTemplate::P2trKeyPath)),
+        ("a9149993a429037b5d912407a71c252019287b8d27a587", None),
+        ("00205d1b56b63d714eebe542309525f484b7e9d6f686b3781b6f61ef925d66d6f6a0", None),
+    ];
+
+    for (locking_script, template) in templates {
+        assert_eq!(InputSizeTemplate::for_locking_script(&ScriptBytes::of(&hexadecimal_bytes(locking_script))), template);
+    }
+
+    // There must be one template per transaction input.
+    let transaction = Transaction::parse_bytes(&hexadecimal_bytes(P2PKH_TRANSACTION)).unwrap();
+
+    assert_eq!(transaction.estimated_weight(&[]), Err(SizeEstimationError::TemplateCountMismatch));
+    assert_eq!(
+        transaction.estimated_vsize(&[InputSizeTemplate::P2pkh, InputSizeTemplate::P2pkh]),
+        Err(SizeEstimationError::TemplateCountMismatch)
+    );
+}
@@ -0,0 +1,146 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::network::BitcoinNetworkType;
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::segwit::item::SegWitItem;
+use bitcoin::script::ScriptBytes;
+use bitcoin::taproot::tree::TapLeaf;
+use bitcoin::taproot::tree::TapNode;
+use bitcoin::taproot::tree::TapTree;
+use bitcoin::taproot::tree::TapTreeError;
+use bitcoin::taproot::tree::TaprootSpendInfo;
+use serialization::bech32::segwit_address_encode;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+
+use crate::util::bitcoin::script::context::*;
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+fn x_only_key(hexadecimal: &str) -> [u8; 32] {
+    let mut key = [0_u8; 32];
+
+    key.clone_from_slice(&hexadecimal_bytes(hexadecimal));
+
+    key
+}
+
+pub fn run() {
+    // BIP-173 test vector (a version 0 witness program).
+    let address = segwit_address_encode("bc", 0, &hexadecimal_bytes("751e76e8199196d454941c45d1b3a323f1433bd6"));
+
+    assert_eq!(address, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+
+    // BIP-341 wallet test vectors; without a script tree, and with a single leaf script.
+    let internal_key = x_only_key("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+    let spend_info = TaprootSpendInfo::new(internal_key, None).unwrap();
+
+    assert_eq!(spend_info.address(BitcoinNetworkType::Mainnet), "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5");
+
+    let internal_key = x_only_key("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+    let leaf = TapLeaf::new(ScriptBytes::of(&hexadecimal_bytes("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")));
+
+    let tree = TapTree::from_leaves(vec![leaf.clone()]).unwrap();
+    let spend_info = TaprootSpendInfo::new(internal_key, Some(&tree)).unwrap();
+
+    assert_eq!(leaf.hash().to_vec(), hexadecimal_bytes("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"));
+    assert_eq!(spend_info.output_key.to_vec(), hexadecimal_bytes("147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"));
+    assert_eq!(spend_info.address(BitcoinNetworkType::Mainnet), "bc1pz37fc4cn9ah8anwm4xqqhvxygjf9rjf2resrw8h8w4tmvcs0863sa2e586");
+    assert_eq!(
+        spend_info.control_block(&leaf).unwrap().bytes(),
+        hexadecimal_bytes("c1187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27")
+    );
+
+    // A Huffman tree places leaf scripts of greater weight nearer to the merkle root.
+    let leaf_spend = TapLeaf::new(ScriptBytes::of(&[0x51_u8])); // OP_TRUE
+    let leaf_recovery_1 = TapLeaf::new(ScriptBytes::of(&[0x52_u8, 0x87_u8])); // OP_2 OP_EQUAL
+    let leaf_recovery_2 = TapLeaf::new(ScriptBytes::of(&[0x53_u8, 0x87_u8])); // OP_3 OP_EQUAL
+
+    let tree = TapTree::from_weighted_leaves(vec![
+        (1, leaf_recovery_1.clone()),
+        (10, leaf_spend.clone()),
+        (1, leaf_recovery_2.clone()),
+    ]).unwrap();
+
+    let spend_info = TaprootSpendInfo::new(internal_key, Some(&tree)).unwrap();
+
+    assert_eq!(tree.root().depth(), 2);
+    assert_eq!(spend_info.control_block(&leaf_spend).unwrap().merkle_path.len(), 1);
+    assert_eq!(spend_info.control_block(&leaf_recovery_1).unwrap().merkle_path.len(), 2);
+    assert_eq!(spend_info.control_block(&leaf_recovery_2).unwrap().merkle_path.len(), 2);
+    assert!(spend_info.control_block(&leaf).is_none());
+
+    // The merkle root does not depend on the order of a branch's nodes.
+    let tree_reordered = TapTree::new(
+        TapNode::branch(
+            TapNode::branch(TapNode::Leaf(leaf_recovery_2.clone()), TapNode::Leaf(leaf_recovery_1.clone())),
+            TapNode::Leaf(leaf_spend.clone()),
+        )
+    ).unwrap();
+
+    assert_eq!(tree_reordered.merkle_root(), tree.merkle_root());
+
+    assert_eq!(TapTree::from_leaves(vec![]), Err(TapTreeError::NoLeaves));
+    assert_eq!(
+        TapTree::from_leaves(vec![TapLeaf::with_version(0xc1_u8, ScriptBytes::of(&[0x51_u8]))]),
+        Err(TapTreeError::InvalidLeafVersion(0xc1_u8))
+    );
+
+    // Spend a P2TR UTXO along the script path, with a control block for the leaf script.
+    let locking_transaction = Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0), ScriptBytes::of(&[]), u32::MAX),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(Amount::from_sat(10000).unwrap(), spend_info.locking_script()),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let mut locking_txn_utxos = InMemoryUtxoProvider::new();
+
+    locking_txn_utxos.insert_transaction(&locking_transaction, None);
+
+    let unlocking_transaction = Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(OutPoint::new(locking_transaction.txid(), 0), ScriptBytes::of(&[]), u32::MAX),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(Amount::from_sat(9000).unwrap(), spend_info.locking_script()),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let segwit_field = |leaf: &TapLeaf, arguments: &[&[u8]]| {
+        let mut items: Vec<SegWitItem> = arguments.iter().map(|argument| SegWitItem::of(argument)).collect();
+
+        items.push(SegWitItem::of(leaf.script.bytes()));
+        items.push(SegWitItem::of(&spend_info.control_block(leaf).unwrap().bytes()));
+
+        SegWitField::new(items)
+    };
+
+    let mut segwit_unlocking_transaction = SegWitTransaction::from_transaction(unlocking_transaction);
+
+    segwit_unlocking_transaction.set_segwit_field(0, segwit_field(&leaf_spend, &[])).unwrap();
+    assert!(segwit_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap());
+
+    segwit_unlocking_transaction.set_segwit_field(0, segwit_field(&leaf_recovery_2, &[&[0x03_u8]])).unwrap();
+    assert!(segwit_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap());
+
+    segwit_unlocking_transaction.set_segwit_field(0, segwit_field(&leaf_recovery_1, &[&[0x03_u8]])).unwrap();
+    assert!(!segwit_unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap());
+}
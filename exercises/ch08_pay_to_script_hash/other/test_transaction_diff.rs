@@ -0,0 +1,158 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::SEQUENCE_FINAL;
+use bitcoin::transaction::explain::transaction_diff::TransactionChange;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::segwit::item::SegWitItem;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+
+/// Returns an amount of satoshi.
+fn sat(satoshi: i64) -> Amount {
+    Amount::from_sat(satoshi).unwrap()
+}
+
+/// A version 2 transaction spending two outpoints, paying 60000 satoshi to `OP_1` and 39000
+/// satoshi to `OP_2`.
+fn transaction(outpoint_1: OutPoint, outpoint_2: OutPoint) -> Transaction {
+    Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(outpoint_1, ScriptBytes::of(&[]), SEQUENCE_FINAL),
+            TransactionInput::new(outpoint_2, ScriptBytes::of(&[]), SEQUENCE_FINAL),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(sat(60000), ScriptBytes::of(&[0x51_u8])),
+            UnspentTransactionOutput::new(sat(39000), ScriptBytes::of(&[0x52_u8])),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    }
+}
+
+pub fn run() {
+    let outpoint_1 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0);
+    let outpoint_2 = OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 1);
+    let outpoint_3 = OutPoint::new(TransactionId::of(&[0x02_u8; 32]), 0);
+
+    let original = transaction(outpoint_1, outpoint_2);
+
+    assert!(original.diff(&original).is_empty());
+
+    // Version and time lock.
+    let mut changed = original.clone();
+
+    changed.version = Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]);
+    changed.locktime = Locktime::of(&800000_u32.to_le_bytes());
+
+    assert_eq!(original.diff(&changed), vec![
+        TransactionChange::Version { before: 2, after: 1 },
+        TransactionChange::Locktime { before: 0, after: 800000 },
+    ]);
+    assert_eq!(original.diff(&changed)[1].to_string(), "locktime 0 -> 800000");
+
+    // Sequence numbers and scripts, of transaction inputs matched by outpoint.
+    let mut changed = original.clone();
+
+    changed.inputs[0].sequence = 0xfffffffd;
+    changed.inputs[1].script = ScriptBytes::of(&[0x51_u8]);
+
+    assert_eq!(original.diff(&changed), vec![
+        TransactionChange::InputSequence { outpoint: outpoint_1, before: SEQUENCE_FINAL, after: 0xfffffffd },
+        TransactionChange::InputScript { outpoint: outpoint_2, before: ScriptBytes::of(&[]), after: ScriptBytes::of(&[0x51_u8]) },
+    ]);
+    assert_eq!(
+        original.diff(&changed)[0].to_string(),
+        format!("input {} sequence 0xffffffff -> 0xfffffffd", outpoint_1)
+    );
+
+    // Transaction inputs moved, removed, and added.
+    let reordered = transaction(outpoint_2, outpoint_1);
+
+    assert_eq!(original.diff(&reordered), vec![
+        TransactionChange::InputMoved { outpoint: outpoint_1, before: 0, after: 1 },
+        TransactionChange::InputMoved { outpoint: outpoint_2, before: 1, after: 0 },
+    ]);
+
+    let replaced = transaction(outpoint_1, outpoint_3);
+
+    assert_eq!(original.diff(&replaced), vec![
+        TransactionChange::InputRemoved(outpoint_2),
+        TransactionChange::InputAdded(outpoint_3),
+    ]);
+    assert_eq!(original.diff(&replaced)[1].to_string(), format!("+ input {}", outpoint_3));
+
+    // UTXOs, matched by locking script; amounts changed, removed, and added.
+    let mut changed = original.clone();
+
+    changed.utxos[1].amount = sat(38000);
+
+    assert_eq!(original.diff(&changed), vec![
+        TransactionChange::OutputAmount { script: ScriptBytes::of(&[0x52_u8]), before: sat(39000), after: sat(38000) },
+    ]);
+    assert_eq!(original.diff(&changed)[0].to_string(), "output to 2: 0.00039000 BTC -> 0.00038000 BTC");
+
+    let mut changed = original.clone();
+
+    changed.utxos.remove(0);
+    changed.utxos.push(UnspentTransactionOutput::new(sat(59000), ScriptBytes::of(&[0x53_u8])));
+
+    assert_eq!(original.diff(&changed), vec![
+        TransactionChange::OutputRemoved { index: 0, amount: sat(60000), script: ScriptBytes::of(&[0x51_u8]) },
+        TransactionChange::OutputAdded { index: 1, amount: sat(59000), script: ScriptBytes::of(&[0x53_u8]) },
+    ]);
+
+    // Segregated witness fields, of transaction inputs matched by outpoint (even if moved).
+    let unsigned = SegWitTransaction::from_transaction(original.clone());
+
+    let signature = SegWitItem::of(&[0x30_u8, 0x01_u8]);
+    let public_key = SegWitItem::of(&[0x02_u8; 33]);
+
+    let mut signed = unsigned.clone();
+    signed.set_segwit_field(1, SegWitField::new(vec![signature.clone(), public_key.clone()])).unwrap();
+
+    assert!(signed.diff(&signed).is_empty());
+    assert_eq!(unsigned.diff(&signed), vec![
+        TransactionChange::InputWitness {
+            outpoint: outpoint_2,
+            before: SegWitField::new(vec![]),
+            after: SegWitField::new(vec![signature.clone(), public_key.clone()]),
+        },
+    ]);
+    assert_eq!(
+        unsigned.diff(&signed)[0].to_string(),
+        format!("input {} witness [] -> [3001, {}]", outpoint_2, "02".repeat(33))
+    );
+
+    // Witness data is not compared between transactions without segregated witness fields.
+    assert!(original.diff(&signed).is_empty());
+
+    let mut reordered_signed = SegWitTransaction::from_transaction(reordered);
+    reordered_signed.set_segwit_field(0, SegWitField::new(vec![signature.clone(), public_key.clone()])).unwrap();
+
+    assert_eq!(signed.diff(&reordered_signed), vec![
+        TransactionChange::InputMoved { outpoint: outpoint_1, before: 0, after: 1 },
+        TransactionChange::InputMoved { outpoint: outpoint_2, before: 1, after: 0 },
+    ]);
+
+    let mut resigned = signed.clone();
+    resigned.set_segwit_field(1, SegWitField::new(vec![SegWitItem::of(&[0x30_u8, 0x02_u8]), public_key.clone()])).unwrap();
+
+    assert_eq!(signed.diff(&resigned), vec![
+        TransactionChange::InputWitness {
+            outpoint: outpoint_2,
+            before: SegWitField::new(vec![signature, public_key.clone()]),
+            after: SegWitField::new(vec![SegWitItem::of(&[0x30_u8, 0x02_u8]), public_key]),
+        },
+    ]);
+}
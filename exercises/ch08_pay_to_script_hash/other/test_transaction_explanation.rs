@@ -0,0 +1,112 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::RelativeLockTime;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::transaction::explain::transaction_explanation::KnownScripts;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::Opcode;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::opcode::ConstantOpcode;
+use bitcoin::script::opcode::CryptographicOpcode;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::hash_256;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+/// The explanation of a transaction spending a P2PKH UTXO (signed with a non-standard `SIGHASH`
+/// flag), and a 2-of-2 P2SH multisig UTXO (signed by one of its keys so far).
+const P2PKH_P2SH_EXPLANATION: &str = concat!(
+    "transaction 5869cb7bb3ae3516918650c5532891e82a389816ee650890061b339c1a261307\n",
+    "  input 0: 0101010101010101010101010101010101010101010101010101010101010101:0 0.00060000 BTC (pubkeyhash) [ours] signed [ALL|ANYONECANPAY (0x84)]\n",
+    "  input 1: 0202020202020202020202020202020202020202020202020202020202020202:1 0.00040000 BTC (scripthash) [ours] signed [SINGLE|ANYONECANPAY] relative time lock 10 blocks\n",
+    "  output 0: 0.00070000 BTC (pubkeyhash) external\n",
+    "  output 1: 0.00025000 BTC (pubkeyhash) change\n",
+    "  output 2: 0.00000000 BTC (nulldata) null data\n",
+    "  sending 0.00070000 BTC externally, 0.00025000 BTC as change\n",
+    "  fee 0.00005000 BTC (14.084 sat/vB over 355 vB)\n",
+    "  time locked until block height 800000\n",
+    "  replaceable (BIP-125)",
+);
+
+pub fn run() {
+    let secret_e_1 = U256::from_be_bytes(hash_256(b"my little secret"));
+    let secret_e_2 = U256::from_be_bytes(hash_256(b"yet another secret"));
+
+    let point_1: EllipticCurvePoint = (secret_e_1 * Secp256k1Point::generator_point()).into();
+    let point_bytes_1 = CompressedPointSecFormatBytes::from(&point_1);
+
+    let point_2: EllipticCurvePoint = (secret_e_2 * Secp256k1Point::generator_point()).into();
+    let point_bytes_2 = CompressedPointSecFormatBytes::from(&point_2);
+
+    let multisig_redeem_script = ScriptBytes::from(&Script::new(&[
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Data(DataElement::of(point_bytes_1.bytes())),
+        Element::Data(DataElement::of(point_bytes_2.bytes())),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op2)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckMultisig)),
+    ]).unwrap());
+
+    let p2pkh_locking_script = ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_1);
+    let p2sh_locking_script = ScriptBytes::locking_script_p2sh_redeem_script(&multisig_redeem_script);
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    let outpoints = [
+        OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0),
+        OutPoint::new(TransactionId::of(&[0x02_u8; 32]), 1),
+    ];
+
+    utxo_provider.insert(outpoints[0].clone(), UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(60000).unwrap(), p2pkh_locking_script.clone()), None, false));
+    utxo_provider.insert(outpoints[1].clone(), UtxoEntry::new(UnspentTransactionOutput::new(Amount::from_sat(40000).unwrap(), p2sh_locking_script.clone()), None, false));
+
+    let null_data_script = ScriptBytes::from(&Script::new(&[
+        Element::Opcode(Opcode::try_from(0x6a_u8).unwrap()),
+        Element::Data(DataElement::of(b"hello")),
+    ]).unwrap());
+
+    let mut transaction = Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(outpoints[0].clone(), ScriptBytes::of(&[]), 0xfffffffd_u32),
+            TransactionInput::new(outpoints[1].clone(), ScriptBytes::of(&[]), RelativeLockTime::Blocks(10).to_sequence()),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(Amount::from_sat(70000).unwrap(), ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes_2)),
+            UnspentTransactionOutput::new(Amount::from_sat(25000).unwrap(), p2pkh_locking_script.clone()),
+            UnspentTransactionOutput::new(Amount::from_sat(0).unwrap(), null_data_script),
+        ],
+        locktime: Locktime::of(&800000_u32.to_le_bytes())
+    };
+
+    let p2pkh_input_script = transaction.signed_input_bytes_p2pkh(0, secret_e_1, SigHashFlag::from(0x84_u32), &utxo_provider).unwrap();
+    let p2sh_input_script = transaction.signed_input_bytes_p2sh(1, secret_e_2, &multisig_redeem_script, SigHashFlag::try_from(0x83_u8).unwrap()).unwrap();
+
+    transaction.inputs[0].script = p2pkh_input_script;
+    transaction.inputs[1].script = p2sh_input_script;
+
+    let mut known_scripts = KnownScripts::new();
+
+    known_scripts.insert_script(p2pkh_locking_script);
+    known_scripts.insert_script(p2sh_locking_script);
+
+    let explanation = transaction.explain(&utxo_provider, &known_scripts).unwrap();
+
+    assert_eq!(explanation.to_string(), P2PKH_P2SH_EXPLANATION);
+}
@@ -0,0 +1,196 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::verify::verification::TransactionVerificationError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::segwit::item::SegWitItem;
+use bitcoin::taproot::tweak::tweak_public_key;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::TaprootSignatureHashCache;
+use bitcoin::script::taproot_signature_hash;
+use bitcoin::script::SIGHASH_DEFAULT;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+
+use crate::util::bitcoin::script::context::*;
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// An internal key without a script tree, and its output key (BIP-341 wallet test vectors).
+const INTERNAL_KEY: &str = "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d";
+const OUTPUT_KEY: &str = "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343";
+
+/// A transaction locking five P2TR UTXOs to the same output key, committing to four leaf scripts;
+/// `<K2> OP_CHECKSIG`, `<K1> OP_CHECKSIG <K2> OP_CHECKSIGADD OP_2 OP_NUMEQUAL`, `OP_SUCCESS80`, and
+/// `OP_IF <K1> OP_CHECKSIG OP_ELSE <K2> OP_CHECKSIG OP_ENDIF`.
+const LOCKING_TRANSACTION: &str = "020000000101010101010101010101010101010101010101010101010101010101010101010000000000ffffffff051027000000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e2290831961204e000000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e22908319613075000000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e2290831961409c000000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e229083196150c3000000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e229083196100000000";
+
+/// A transaction spending the P2TR UTXOs;
+///
+/// 0. along the key path (`SIGHASH_DEFAULT`),
+/// 1. along the script path, with the first leaf script and an annex (`SIGHASH_ALL|ANYONECANPAY`),
+/// 2. along the script path, with the second leaf script (`SIGHASH_DEFAULT` and `SIGHASH_ALL`),
+/// 3. along the script path, with the third leaf script,
+/// 4. along the script path, with the fourth leaf script (the `OP_ELSE` branch).
+const UNLOCKING_TRANSACTION: &str = "02000000000105bd060f982a7c0674d0b9e64a6e10ad7a353cfd23a42500f61d9381c9db0d1d760000000000ffffffffbd060f982a7c0674d0b9e64a6e10ad7a353cfd23a42500f61d9381c9db0d1d760100000000ffffffffbd060f982a7c0674d0b9e64a6e10ad7a353cfd23a42500f61d9381c9db0d1d760200000000ffffffffbd060f982a7c0674d0b9e64a6e10ad7a353cfd23a42500f61d9381c9db0d1d760300000000ffffffffbd060f982a7c0674d0b9e64a6e10ad7a353cfd23a42500f61d9381c9db0d1d760400000000ffffffff016836020000000000225120fe7d62977b58d343f5798b8066312cb2e2bd830517ee18a9bfcc5e2290831961014095de06af76d47040662a2ba76ea1f422b2bdaaa283a400e0c6c3f4910e6c43ba0ea925841395426e783aa8dff282090e6ec192cd7e3fbecd3c739a6fd4f8d3d90441eec8dc7698538f971c98620e1b0685680fad44fcacb443a8f108f6bbbe470c06dd4afaca7ed7fba4ff416a832f98416053a9f0e51f8151d84459e8049990b9558122204a1521a0070f2fddb442da66399040bd3c23cd6ba2688316522965378b8173cdac61c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f86d059719c1485024db7a5ede087db09e9545d20868efadc59ff82edd5401d4c7d19f464544ebc88d344f0b7c770ddf2c8ba233a16a4a9aafb3db7c50fcf105fb80350010204401e8248c1bafd943af270f6598a4a274210a9c49cd19d5a28794d1d7391e467c00b1f71e087918bd23345797b59f5a663a685736b7e883c98855b0dcbf8249e9a414c43b31d3e3ddc1fe5861bd69147c41a25b27eb84659367b29fefe9c41abea927f7f37bdcdb413cb1ed3ceac94069920033ce7507d931c648778d42573800997014620845887703604c8e4ae84560f20e727da81abbe01f42a8d0a9e9488893cd38562ac204a1521a0070f2fddb442da66399040bd3c23cd6ba2688316522965378b8173cdba529c61c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f860db55ca6961e05b565437b356256f3a8f76d60160487b90461accd45fa86899019f464544ebc88d344f0b7c770ddf2c8ba233a16a4a9aafb3db7c50fcf105fb8030101015061c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f86bda718d5d900f85eb7ef6f52077de383220f16ddf1d85faa61c68054d4a2a17eb866f7f6401a9f92647eee544e097bbdddcd0409ef3c44183ee75d3a05c928770440265b7d1385e47fcc2445bf7eb90ceff310d439286ebc695c0178c031234802dd326d397d0b94e52c6ab13c99d439132153c58dfd5f2d5c6cffd3282a15b65c6300476320845887703604c8e4ae84560f20e727da81abbe01f42a8d0a9e9488893cd38562ac67204a1521a0070f2fddb442da66399040bd3c23cd6ba2688316522965378b8173cdac6861c07590b9f25308cd3952f61f4ff7ea90b84b3339da86a01272d781e40564365f86f933d08853672a2275403f631a185860433b7a30f3dde2a4cbab45ca4cd5b5bfb866f7f6401a9f92647eee544e097bbdddcd0409ef3c44183ee75d3a05c9287700000000";
+
+/// An unsigned transaction spending nine UTXOs, seven of which are P2TR key path spends (BIP-341
+/// wallet test vectors, `keyPathSpending`).
+const KEY_PATH_TRANSACTION: &str = "02000000097de20cbff686da83a54981d2b9bab3586f4ca7e48f57f5b55963115f3b334e9c010000000000000000d7b7cab57b1393ace2d064f4d4a2cb8af6def61273e127517d44759b6dafdd990000000000fffffffff8e1f583384333689228c5d28eac13366be082dc57441760d957275419a418420000000000fffffffff0689180aa63b30cb162a73c6d2a38b7eeda2a83ece74310fda0843ad604853b0100000000feffffffaa5202bdf6d8ccd2ee0f0202afbbb7461d9264a25e5bfd3c5a52ee1239e0ba6c0000000000feffffff956149bdc66faa968eb2be2d2faa29718acbfe3941215893a2a3446d32acd050000000000000000000e664b9773b88c09c32cb70a2a3e4da0ced63b7ba3b22f848531bbb1d5d5f4c94010000000000000000e9aa6b8e6c9de67619e6a3924ae25696bb7b694bb677a632a74ef7eadfd4eabf0000000000ffffffffa778eb6a263dc090464cd125c466b5a99667720b1c110468831d058aa1b82af10100000000ffffffff0200ca9a3b000000001976a91406afd46bcdfd22ef94ac122aa11f241244a37ecc88ac807840cb0000000020ac9a87f5594be208f8532db38cff670c450ed2fea8fcdefcc9a663f78bab962b0065cd1d";
+
+/// The locking script and amount of the UTXO spent by each transaction input.
+const KEY_PATH_SPENT_UTXOS: [(&str, i64); 9] = [
+    ("512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343", 420000000),
+    ("5120147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3", 462000000),
+    ("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac", 294000000),
+    ("5120e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e", 504000000),
+    ("512091b64d5324723a985170e4dc5a0f84c041804f2cd12660fa5dec09fc21783605", 630000000),
+    ("00147dd65592d0ab2fe0d0257d571abf032cd9db93dc", 378000000),
+    ("512075169f4001aa68f15bbed28b218df1d0a62cbbcf1188c6665110c293c907b831", 672000000),
+    ("5120712447206d7a5238acc7ff53fbe94a3b64539ad291c7cdbc490b7577e4b17df5", 546000000),
+    ("512077e30a5522dd9f894c3f8b8bd4c4b2cf82ca7da8a3ea6a239655c39c050ab220", 588000000),
+];
+
+/// The transaction input index, `SIGHASH` flag byte, signature hash and witness signature of each
+/// key path spend.
+const KEY_PATH_SPENDS: [(usize, u8, &str, &str); 7] = [
+    (0, 0x03_u8, "2514a6272f85cfa0f45eb907fcb0d121b808ed37c6ea160a5a9046ed5526d555", "ed7c1647cb97379e76892be0cacff57ec4a7102aa24296ca39af7541246d8ff14d38958d4cc1e2e478e4d4a764bbfd835b16d4e314b72937b29833060b87276c03"),
+    (1, 0x83_u8, "325a644af47e8a5a2591cda0ab0723978537318f10e6a63d4eed783b96a71a4d", "052aedffc554b41f52b521071793a6b88d6dbca9dba94cf34c83696de0c1ec35ca9c5ed4ab28059bd606a4f3a657eec0bb96661d42921b5f50a95ad33675b54f83"),
+    (3, 0x01_u8, "bf013ea93474aa67815b1b6cc441d23b64fa310911d991e713cd34c7f5d46669", "ff45f742a876139946a149ab4d9185574b98dc919d2eb6754f8abaa59d18b025637a3aa043b91817739554f4ed2026cf8022dbd83e351ce1fabc272841d2510a01"),
+    (4, 0x00_u8, "4f900a0bae3f1446fd48490c2958b5a023228f01661cda3496a11da502a7f7ef", "b4010dd48a617db09926f729e79c33ae0b4e94b79f04a1ae93ede6315eb3669de185a17d2b0ac9ee09fd4c64b678a0b61a0a86fa888a273c8511be83bfd6810f"),
+    (6, 0x02_u8, "15f25c298eb5cdc7eb1d638dd2d45c97c4c59dcaec6679cfc16ad84f30876b85", "a3785919a2ce3c4ce26f298c3d51619bc474ae24014bcdd31328cd8cfbab2eff3395fa0a16fe5f486d12f22a9cedded5ae74feb4bbe5351346508c5405bcfee002"),
+    (7, 0x82_u8, "cd292de50313804dabe4685e83f923d2969577191a3e1d2882220dca88cbeb10", "ea0c6ba90763c2d3a296ad82ba45881abb4f426b3f87af162dd24d5109edc1cdd11915095ba47c3a9963dc1e6c432939872bc49212fe34c632cd3ab9fed429c482"),
+    (8, 0x81_u8, "cccb739eca6c13a8a89e6e5cd317ffe55669bbda23f2fd37b0f18755e008edd2", "bbc9584a11074e83bc8c6759ec55401f0ae7b03ef290c3139814f545b58a9f8127258000874f44bc46db7646322107d4d86aec8e73b8719a61fff761d75b5dd981"),
+];
+
+pub fn run() {
+    let mut internal_key = [0_u8; 32];
+    internal_key.clone_from_slice(&hexadecimal_bytes(INTERNAL_KEY));
+
+    let (output_key, _) = tweak_public_key(&internal_key, None).unwrap();
+
+    assert_eq!(output_key.to_vec(), hexadecimal_bytes(OUTPUT_KEY));
+
+    let locking_transaction = Transaction::parse_bytes(&hexadecimal_bytes(LOCKING_TRANSACTION)).unwrap();
+    let unlocking_transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(UNLOCKING_TRANSACTION)).unwrap();
+
+    let mut locking_txn_utxos = InMemoryUtxoProvider::new();
+
+    locking_txn_utxos.insert_transaction(&locking_transaction, None);
+
+    let result = unlocking_transaction.verify(EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap();
+
+    assert!(result);
+
+    // Signatures commit to the UTXOs (except for the OP_SUCCESS leaf script, which is unencumbered).
+    let (mut transaction, segwit_fields) = unlocking_transaction.clone().into_parts();
+
+    transaction.utxos[0].amount = Amount::from_sat(94000).unwrap();
+
+    let tampered_transaction = SegWitTransaction::new(transaction, segwit_fields).unwrap();
+
+    assert!(!tampered_transaction.verify_input(0, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap());
+    assert!(tampered_transaction.verify_input(1, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).is_err());
+    assert!(tampered_transaction.verify_input(2, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).is_err());
+    assert!(tampered_transaction.verify_input(3, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).unwrap());
+    assert!(tampered_transaction.verify_input(4, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).is_err());
+
+    // The argument of `OP_IF` must be empty or exactly `0x01` (MINIMALIF); `0x00` is otherwise
+    // false, and would take the same branch.
+    let mut tampered_transaction = unlocking_transaction.clone();
+    let mut items = tampered_transaction.segwit_fields()[4].to_vec();
+
+    items[1] = SegWitItem::of(&[0x00_u8]);
+    tampered_transaction.set_segwit_field(4, SegWitField::new(items)).unwrap();
+
+    assert!(matches!(
+        tampered_transaction.verify_input(4, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos),
+        Err(TransactionVerificationError::TransactionScriptError(4))
+    ));
+
+    // The `OP_IF` branch requires a signature for `K1`, rather than `K2`.
+    let mut tampered_transaction = unlocking_transaction.clone();
+    let mut items = tampered_transaction.segwit_fields()[4].to_vec();
+
+    items[1] = SegWitItem::of(&[0x01_u8]);
+    tampered_transaction.set_segwit_field(4, SegWitField::new(items)).unwrap();
+
+    assert!(tampered_transaction.verify_input(4, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos).is_err());
+
+    // The control block must prove that the output key commits to the leaf script.
+    let mut tampered_transaction = unlocking_transaction.clone();
+    let mut items = tampered_transaction.segwit_fields()[1].to_vec();
+
+    let mut control_block = items[2].bytes().to_vec();
+    control_block[40] ^= 0x01_u8;
+
+    items[2] = SegWitItem::of(&control_block);
+    tampered_transaction.set_segwit_field(1, SegWitField::new(items)).unwrap();
+
+    assert!(matches!(
+        tampered_transaction.verify_input(1, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &locking_txn_utxos),
+        Err(TransactionVerificationError::TaprootCommitmentMismatch(1))
+    ));
+
+    // Key path spends, for each `SIGHASH` flag (BIP-341 wallet test vectors).
+    let key_path_transaction = Transaction::parse_bytes(&hexadecimal_bytes(KEY_PATH_TRANSACTION)).unwrap();
+
+    let spent_utxos: Vec<UnspentTransactionOutput> = KEY_PATH_SPENT_UTXOS
+        .iter()
+        .map(|(script, amount)| UnspentTransactionOutput::new(Amount::from_sat(*amount).unwrap(), ScriptBytes::of(&hexadecimal_bytes(script))))
+        .collect();
+
+    let cache = TaprootSignatureHashCache::new(&key_path_transaction, spent_utxos.clone()).unwrap();
+
+    assert_eq!(cache.sha_prevouts.to_vec(), hexadecimal_bytes("e3b33bb4ef3a52ad1fffb555c0d82828eb22737036eaeb02a235d82b909c4c3f"));
+    assert_eq!(cache.sha_amounts.to_vec(), hexadecimal_bytes("58a6964a4f5f8f0b642ded0a8a553be7622a719da71d1f5befcefcdee8e0fde6"));
+    assert_eq!(cache.sha_scriptpubkeys.to_vec(), hexadecimal_bytes("23ad0f61ad2bca5ba6a7693f50fce988e17c3780bf2b1e720cfbb38fbdd52e21"));
+    assert_eq!(cache.sha_sequences.to_vec(), hexadecimal_bytes("18959c7221ab5ce9e26c3cd67b22c24f8baa54bac281d8e6b05e400e6c3a957e"));
+    assert_eq!(cache.sha_outputs.to_vec(), hexadecimal_bytes("a2e6dab7c1f0dcd297c8d61647fd17d821541ea69c3cc37dcbad7f90d4eb4bc5"));
+
+    let mut key_path_utxos = InMemoryUtxoProvider::new();
+
+    for (input, utxo) in key_path_transaction.inputs.iter().zip(spent_utxos.clone()) {
+        key_path_utxos.insert(input.outpoint.clone(), UtxoEntry::new(utxo, None, false));
+    }
+
+    let mut signed_transaction = SegWitTransaction::from_transaction(key_path_transaction.clone());
+
+    for (n, hash_type, signature_hash, signature) in KEY_PATH_SPENDS {
+        let digest = taproot_signature_hash(&key_path_transaction, n, hash_type, None, None, &cache).unwrap();
+
+        assert_eq!(digest.to_vec(), hexadecimal_bytes(signature_hash));
+
+        signed_transaction.set_segwit_field(n, SegWitField::new(vec![SegWitItem::of(&hexadecimal_bytes(signature))])).unwrap();
+    }
+
+    for (n, _, _, _) in KEY_PATH_SPENDS {
+        assert!(signed_transaction.verify_input(n, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &key_path_utxos).unwrap());
+    }
+
+    // A signature committing to a different `SIGHASH` flag does not verify.
+    let mut tampered_transaction = signed_transaction.clone();
+    let mut signature = hexadecimal_bytes(KEY_PATH_SPENDS[0].3);
+
+    signature[64] = 0x01_u8;
+
+    tampered_transaction.set_segwit_field(0, SegWitField::new(vec![SegWitItem::of(&signature)])).unwrap();
+
+    assert!(!tampered_transaction.verify_input(0, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &key_path_utxos).unwrap());
+
+    // `SIGHASH_SINGLE` without a UTXO of matching output index.
+    assert!(taproot_signature_hash(&key_path_transaction, 3, 0x03_u8, None, None, &cache).is_none());
+
+    // A transaction input that does not exist.
+    let input_count = key_path_transaction.inputs.len();
+
+    assert!(taproot_signature_hash(&key_path_transaction, input_count, SIGHASH_DEFAULT, None, None, &cache).is_none());
+    assert!(taproot_signature_hash(&key_path_transaction, input_count, 0x81_u8, None, None, &cache).is_none());
+
+    // The UTXO spent by each transaction input must be given.
+    assert!(TaprootSignatureHashCache::new(&key_path_transaction, spent_utxos[1..].to_vec()).is_none());
+}
@@ -0,0 +1,274 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::verify::verification::TransactionVerificationError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::segwit::item::SegWitItem;
+use bitcoin::script::Script;
+use bitcoin::script::ScriptBytes;
+use bitcoin::script::Element;
+use bitcoin::script::DataElement;
+use bitcoin::script::Opcode;
+use bitcoin::script::SigHashFlag;
+use bitcoin::script::opcode::ConstantOpcode;
+use bitcoin::script::opcode::StackOpcode;
+use bitcoin::script::opcode::BitwiseOpcode;
+use bitcoin::script::opcode::CryptographicOpcode;
+use bitcoin::script::opcode::LocktimeOpcode;
+use crypto::secp256k1::Secp256k1Point;
+use crypto::digest::hash_256;
+use crypto::digest::ripemd_160;
+use math::elliptic_curve::point::EllipticCurvePoint;
+use serialization::point::CompressedPointSecFormatBytes;
+use util::byte_string::ByteString;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+use util::number::U256;
+
+use crate::util::bitcoin::script::context::*;
+
+const LOCKTIME: u32 = 800000;
+const SEQUENCE: u32 = 10;
+
+pub fn run() {
+    let secret_e = U256::from_be_bytes(hash_256(b"my little secret"));
+
+    let point: EllipticCurvePoint = (secret_e * Secp256k1Point::generator_point()).into();
+    let point_bytes = CompressedPointSecFormatBytes::from(&point);
+
+    let preimage = b"witness script preimage";
+
+    // Spendable with the preimage, once both time locks have passed.
+    let timelock_script = Script::new(&[
+        Element::Opcode(Opcode::Stack(StackOpcode::OpDup)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpHash256)),
+        Element::Data(DataElement::of(&hash_256(preimage))),
+        Element::Opcode(Opcode::Bitwise(BitwiseOpcode::OpEqualVerify)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpRipemd160)),
+        Element::Data(DataElement::of(&ripemd_160(preimage))),
+        Element::Opcode(Opcode::Bitwise(BitwiseOpcode::OpEqualVerify)),
+        Element::Data(DataElement::from_i64(i64::from(LOCKTIME)).unwrap()),
+        Element::Opcode(Opcode::Locktime(LocktimeOpcode::OpCheckLocktimeVerify)),
+        Element::Opcode(Opcode::Stack(StackOpcode::OpDrop)),
+        Element::Data(DataElement::from_i64(i64::from(SEQUENCE)).unwrap()),
+        Element::Opcode(Opcode::Locktime(LocktimeOpcode::OpCheckSequenceVerify)),
+    ]).unwrap();
+    let timelock_script_bytes = ScriptBytes::from(&timelock_script);
+
+    let multisig_verify_script = Script::new(&[
+        Element::Opcode(Opcode::Constant(ConstantOpcode::OpTrue)),
+        Element::Data(DataElement::of(point_bytes.bytes())),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::OpTrue)),
+        Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCheckMultisigVerify)),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::OpTrue)),
+    ]).unwrap();
+    let multisig_verify_script_bytes = ScriptBytes::from(&multisig_verify_script);
+
+    let witness_program_p2wpkh = ScriptBytes::locking_script_p2wpkh_compressed_point(&point_bytes);
+
+    // A version 0 witness program must be 20 or 32 bytes long.
+    let mut witness_program_wrong_length = vec![0x00_u8, 0x19_u8];
+    witness_program_wrong_length.extend_from_slice(&[0x01_u8; 25]);
+
+    let locking_scripts = [
+        witness_program_p2wpkh.clone(),
+        ScriptBytes::of(&witness_program_wrong_length),
+        ScriptBytes::locking_script_p2pkh_compressed_point(&point_bytes),
+        ScriptBytes::locking_script_p2wsh_witness_script(&timelock_script_bytes),
+        ScriptBytes::locking_script_p2sh_redeem_script(&witness_program_p2wpkh),
+        ScriptBytes::locking_script_p2wsh_witness_script(&multisig_verify_script_bytes),
+    ];
+
+    let locking_transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(
+                OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0),
+                ScriptBytes::of(&[]),
+                u32::MAX
+            ),
+        ],
+        utxos: locking_scripts
+            .iter()
+            .map(|script| UnspentTransactionOutput::new(Amount::from_sat(2500).unwrap(), script.clone()))
+            .collect(),
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8])
+    };
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert_transaction(&locking_transaction, None);
+
+    let sighash = SigHashFlag::try_from(0x01_u8).unwrap();
+
+    let transaction = spending_transaction(&locking_transaction, LOCKTIME, SEQUENCE);
+
+    let segwit_field_p2wpkh = transaction.signed_witness_p2wpkh(0, secret_e, sighash, &utxo_provider).unwrap();
+    let segwit_field_p2sh_p2wpkh = transaction.signed_witness_p2wpkh(4, secret_e, sighash, &utxo_provider).unwrap();
+
+    let multisig_verify_signature = transaction
+        .signed_witness_item_p2wsh(5, secret_e, &multisig_verify_script_bytes, sighash, &utxo_provider)
+        .unwrap();
+    let segwit_field_multisig_verify = transaction
+        .witness_script_field_p2wsh(5, vec![SegWitItem::of(&[]), multisig_verify_signature], &multisig_verify_script_bytes, &utxo_provider)
+        .unwrap();
+
+    let segwit_transaction = SegWitTransaction::from_transaction(transaction);
+
+    let verify = |segwit_transaction: &SegWitTransaction, n: usize| {
+        segwit_transaction.verify_input(n, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider)
+    };
+
+    // P2WPKH.
+    let mut signed_transaction = segwit_transaction.clone();
+    signed_transaction.set_signed_input(0, ScriptBytes::of(&[]), segwit_field_p2wpkh.clone()).unwrap();
+
+    assert!(verify(&signed_transaction, 0).unwrap());
+
+    // The transaction input script of a native witness program must be empty.
+    let mut malleated_transaction = segwit_transaction.clone();
+    malleated_transaction.set_signed_input(0, ScriptBytes::of(&[0x51_u8]), segwit_field_p2wpkh.clone()).unwrap();
+
+    assert!(matches!(verify(&malleated_transaction, 0), Err(TransactionVerificationError::WitnessMalleated(0))));
+
+    // A P2WPKH field has exactly two items.
+    let mut mismatched_transaction = segwit_transaction.clone();
+    mismatched_transaction.set_signed_input(0, ScriptBytes::of(&[]), SegWitField::new(vec![segwit_field_p2wpkh[0].clone()])).unwrap();
+
+    assert!(matches!(verify(&mismatched_transaction, 0), Err(TransactionVerificationError::WitnessProgramMismatch(0))));
+
+    // A malformed signature fails the signature check, rather than the verification.
+    let malformed_signatures: [&[u8]; 4] = [
+        &[0x01_u8],
+        &[0x30_u8, 0xff_u8, 0x02_u8, 0x01_u8, 0x01_u8, 0x02_u8, 0x01_u8, 0x01_u8, 0x01_u8],
+        &[0x30_u8, 0x06_u8, 0x02_u8, 0x05_u8, 0x01_u8, 0x02_u8, 0x01_u8, 0x01_u8, 0x01_u8],
+        &segwit_field_p2wpkh[0].bytes()[1..],
+    ];
+
+    for malformed_signature in malformed_signatures {
+        let mut malformed_transaction = segwit_transaction.clone();
+        let segwit_field = SegWitField::new(vec![SegWitItem::of(malformed_signature), segwit_field_p2wpkh[1].clone()]);
+
+        malformed_transaction.set_signed_input(0, ScriptBytes::of(&[]), segwit_field).unwrap();
+
+        assert!(!verify(&malformed_transaction, 0).unwrap());
+    }
+
+    // A version 0 witness program of another length.
+    let mut wrong_length_transaction = segwit_transaction.clone();
+    wrong_length_transaction.set_segwit_field(1, SegWitField::new(vec![SegWitItem::of(&[0x01_u8])])).unwrap();
+
+    assert!(matches!(verify(&wrong_length_transaction, 1), Err(TransactionVerificationError::WitnessProgramWrongLength(1))));
+
+    // A P2PKH UTXO is not unlocked by a segregated witness field.
+    let mut unexpected_transaction = segwit_transaction.clone();
+    unexpected_transaction.set_segwit_field(2, segwit_field_p2wpkh.clone()).unwrap();
+
+    assert!(matches!(verify(&unexpected_transaction, 2), Err(TransactionVerificationError::WitnessUnexpected(2))));
+
+    // A P2WSH witness script with hash and time lock opcodes.
+    let segwit_field_timelock = SegWitField::new(vec![SegWitItem::of(preimage), SegWitItem::of(timelock_script_bytes.bytes())]);
+
+    let mut timelock_transaction = segwit_transaction.clone();
+    timelock_transaction.set_segwit_field(3, segwit_field_timelock.clone()).unwrap();
+
+    assert!(verify(&timelock_transaction, 3).unwrap());
+
+    // A different witness script does not match the witness program.
+    let mut mismatched_transaction = segwit_transaction.clone();
+    let segwit_field = SegWitField::new(vec![SegWitItem::of(preimage), SegWitItem::of(multisig_verify_script_bytes.bytes())]);
+    mismatched_transaction.set_segwit_field(3, segwit_field).unwrap();
+
+    assert!(matches!(verify(&mismatched_transaction, 3), Err(TransactionVerificationError::WitnessProgramMismatch(3))));
+
+    // The wrong preimage fails `OP_EQUALVERIFY`.
+    let mut wrong_preimage_transaction = segwit_transaction.clone();
+    let segwit_field = SegWitField::new(vec![SegWitItem::of(b"wrong preimage"), SegWitItem::of(timelock_script_bytes.bytes())]);
+    wrong_preimage_transaction.set_segwit_field(3, segwit_field).unwrap();
+
+    assert!(matches!(verify(&wrong_preimage_transaction, 3), Err(TransactionVerificationError::TransactionScriptError(3))));
+
+    // The time locks have not passed; the absolute time lock is a timestamp rather than a block
+    // height; the transaction input is final; the relative time lock is disabled.
+    let unlocked_transactions = [
+        spending_transaction(&locking_transaction, LOCKTIME - 1, SEQUENCE),
+        spending_transaction(&locking_transaction, LOCKTIME, SEQUENCE - 1),
+        spending_transaction(&locking_transaction, 1700000000, SEQUENCE),
+        spending_transaction(&locking_transaction, LOCKTIME, u32::MAX),
+        spending_transaction(&locking_transaction, LOCKTIME, SEQUENCE | (1 << 31)),
+        spending_transaction(&locking_transaction, LOCKTIME, SEQUENCE | (1 << 22)),
+    ];
+
+    for transaction in unlocked_transactions {
+        let mut unlocked_transaction = SegWitTransaction::from_transaction(transaction);
+        unlocked_transaction.set_segwit_field(3, segwit_field_timelock.clone()).unwrap();
+
+        assert!(matches!(verify(&unlocked_transaction, 3), Err(TransactionVerificationError::TransactionScriptError(3))));
+    }
+
+    // Relative time locks require transaction version 2.
+    let mut version_1_transaction = spending_transaction(&locking_transaction, LOCKTIME, SEQUENCE);
+    version_1_transaction.version = Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]);
+
+    let mut version_1_transaction = SegWitTransaction::from_transaction(version_1_transaction);
+    version_1_transaction.set_segwit_field(3, segwit_field_timelock).unwrap();
+
+    assert!(matches!(verify(&version_1_transaction, 3), Err(TransactionVerificationError::TransactionScriptError(3))));
+
+    // P2SH-P2WPKH; the transaction input script must be a single data push of the redeem script.
+    let redeem_script_push = DataElement::of(witness_program_p2wpkh.bytes()).bytes_with_opcode();
+
+    let mut p2sh_transaction = segwit_transaction.clone();
+    p2sh_transaction.set_signed_input(4, ScriptBytes::of(&redeem_script_push), segwit_field_p2sh_p2wpkh.clone()).unwrap();
+
+    assert!(verify(&p2sh_transaction, 4).unwrap());
+
+    let mut malleated_input_script = vec![0x51_u8];
+    malleated_input_script.extend(redeem_script_push);
+
+    let mut malleated_transaction = segwit_transaction.clone();
+    malleated_transaction.set_signed_input(4, ScriptBytes::of(&malleated_input_script), segwit_field_p2sh_p2wpkh).unwrap();
+
+    assert!(matches!(verify(&malleated_transaction, 4), Err(TransactionVerificationError::WitnessMalleated(4))));
+
+    // A P2WSH witness script with `OP_CHECKMULTISIGVERIFY`.
+    let mut multisig_verify_transaction = segwit_transaction.clone();
+    multisig_verify_transaction.set_segwit_field(5, segwit_field_multisig_verify.clone()).unwrap();
+
+    assert!(verify(&multisig_verify_transaction, 5).unwrap());
+
+    let mut invalid_multisig_verify_transaction = segwit_transaction.clone();
+    let segwit_field = SegWitField::new(vec![
+        SegWitItem::of(&[]),
+        segwit_field_p2wpkh[0].clone(),
+        SegWitItem::of(multisig_verify_script_bytes.bytes()),
+    ]);
+    invalid_multisig_verify_transaction.set_segwit_field(5, segwit_field).unwrap();
+
+    assert!(matches!(verify(&invalid_multisig_verify_transaction, 5), Err(TransactionVerificationError::TransactionScriptError(5))));
+}
+
+/// A transaction spending each of the locking transaction's UTXOs, with some time lock and
+/// transaction input sequence number.
+fn spending_transaction(locking_transaction: &Transaction, locktime: u32, sequence: u32) -> Transaction {
+    Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: (0..6)
+            .map(|n| TransactionInput::new(OutPoint::new(locking_transaction.txid(), n), ScriptBytes::of(&[]), sequence))
+            .collect(),
+        utxos: vec![
+            UnspentTransactionOutput::new(Amount::from_sat(9000).unwrap(), ScriptBytes::of(&[0x51_u8])),
+        ],
+        locktime: Locktime::of(&locktime.to_le_bytes())
+    }
+}
@@ -0,0 +1,240 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::transaction::provider::in_memory_provider::InMemoryUtxoProvider;
+use bitcoin::transaction::Version;
+use bitcoin::transaction::TransactionInput;
+use bitcoin::transaction::UnspentTransactionOutput;
+use bitcoin::transaction::OutPoint;
+use bitcoin::transaction::Amount;
+use bitcoin::transaction::Locktime;
+use bitcoin::transaction::verify::verification::TransactionVerificationError;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::field::SegWitField;
+use bitcoin::segwit::item::SegWitItem;
+use bitcoin::script::ScriptBytes;
+use util::byte_string::ByteString;
+use util::byte_value::ByteValue4;
+use util::byte_value::ByteValue32;
+
+use crate::util::bitcoin::script::context::*;
+
+/// Verifies a transaction input spending a P2WSH UTXO with some witness script, given the other
+/// segregated witness items (ordered from bottom to top of the stack).
+fn verify_witness_script(witness_script: &[u8], items: &[&[u8]]) -> Result<bool, TransactionVerificationError> {
+    let witness_script_bytes = ScriptBytes::of(witness_script);
+
+    let locking_transaction = Transaction {
+        version: Version::of(&[0x01_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(OutPoint::new(TransactionId::of(&[0x01_u8; 32]), 0), ScriptBytes::of(&[]), u32::MAX),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(
+                Amount::from_sat(2500).unwrap(),
+                ScriptBytes::locking_script_p2wsh_witness_script(&witness_script_bytes)
+            ),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    };
+
+    let mut utxo_provider = InMemoryUtxoProvider::new();
+
+    utxo_provider.insert_transaction(&locking_transaction, None);
+
+    let mut transaction = SegWitTransaction::from_transaction(Transaction {
+        version: Version::of(&[0x02_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        inputs: vec![
+            TransactionInput::new(OutPoint::new(locking_transaction.txid(), 0), ScriptBytes::of(&[]), u32::MAX),
+        ],
+        utxos: vec![
+            UnspentTransactionOutput::new(Amount::from_sat(2000).unwrap(), ScriptBytes::of(&[0x51_u8])),
+        ],
+        locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+    });
+
+    let mut segwit_items: Vec<SegWitItem> = items.iter().map(|item| SegWitItem::of(item)).collect();
+    segwit_items.push(SegWitItem::of(witness_script));
+
+    transaction.set_segwit_field(0, SegWitField::new(segwit_items)).unwrap();
+
+    transaction.verify_input(0, EXAMPLE_TIMESTAMP, EXAMPLE_BLOCK_HEIGHT, &utxo_provider)
+}
+
+pub fn run() {
+    test_flow_control();
+    test_stack();
+    test_splice();
+    test_arithmetic();
+}
+
+fn test_flow_control() {
+    // `OP_1 OP_IF OP_1 OP_ENDIF`
+    assert!(verify_witness_script(&[0x51_u8, 0x63_u8, 0x51_u8, 0x68_u8], &[]).unwrap());
+
+    // `OP_IF OP_1 OP_ELSE OP_0 OP_ENDIF`; the branch is chosen by the witness item.
+    let if_else_script = [0x63_u8, 0x51_u8, 0x67_u8, 0x00_u8, 0x68_u8];
+
+    assert!(verify_witness_script(&if_else_script, &[&[0x01_u8]]).unwrap());
+    assert!(!verify_witness_script(&if_else_script, &[&[]]).unwrap());
+
+    // Outside of Tapscript leaf scripts, any non-zero argument takes the `OP_IF` branch.
+    assert!(verify_witness_script(&if_else_script, &[&[0x02_u8]]).unwrap());
+
+    // `OP_NOTIF OP_1 OP_ELSE OP_0 OP_ENDIF`
+    let notif_else_script = [0x64_u8, 0x51_u8, 0x67_u8, 0x00_u8, 0x68_u8];
+
+    assert!(verify_witness_script(&notif_else_script, &[&[]]).unwrap());
+    assert!(!verify_witness_script(&notif_else_script, &[&[0x01_u8]]).unwrap());
+
+    // `OP_0 OP_IF OP_RETURN OP_ENDIF OP_1`; opcodes within branches that are not executed are
+    // skipped.
+    assert!(verify_witness_script(&[0x00_u8, 0x63_u8, 0x6a_u8, 0x68_u8, 0x51_u8], &[]).unwrap());
+
+    // `OP_1 OP_IF OP_0 OP_IF OP_RETURN OP_ELSE OP_1 OP_ENDIF OP_ENDIF`; nested conditional blocks.
+    let nested_script = [0x51_u8, 0x63_u8, 0x00_u8, 0x63_u8, 0x6a_u8, 0x67_u8, 0x51_u8, 0x68_u8, 0x68_u8];
+
+    assert!(verify_witness_script(&nested_script, &[]).unwrap());
+
+    // Conditional blocks must begin and end.
+    let mismatched_scripts: [&[u8]; 4] = [
+        &[0x51_u8, 0x63_u8, 0x51_u8],
+        &[0x51_u8, 0x68_u8],
+        &[0x51_u8, 0x67_u8, 0x51_u8],
+        &[0x51_u8, 0x51_u8, 0x63_u8, 0x51_u8, 0x68_u8, 0x68_u8],
+    ];
+
+    for mismatched_script in mismatched_scripts {
+        assert!(matches!(verify_witness_script(mismatched_script, &[]), Err(TransactionVerificationError::TransactionScriptError(0))));
+    }
+
+    // `OP_NOP OP_1`
+    assert!(verify_witness_script(&[0x61_u8, 0x51_u8], &[]).unwrap());
+}
+
+fn test_stack() {
+    // `OP_2 OP_3 OP_2DUP OP_3 OP_EQUALVERIFY OP_2 OP_EQUALVERIFY OP_3 OP_EQUALVERIFY OP_2 OP_EQUAL`;
+    // `x1 x2 OP_2DUP` is `x1 x2 x1 x2`.
+    let two_dup_script = [
+        0x52_u8, 0x53_u8, 0x6e_u8,
+        0x53_u8, 0x88_u8, 0x52_u8, 0x88_u8, 0x53_u8, 0x88_u8, 0x52_u8, 0x87_u8,
+    ];
+
+    assert!(verify_witness_script(&two_dup_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_3 OP_3DUP OP_DEPTH OP_6 OP_NUMEQUALVERIFY OP_3 OP_EQUALVERIFY OP_2
+    // OP_EQUALVERIFY OP_1 OP_EQUALVERIFY OP_2DROP`
+    let three_dup_script = [
+        0x51_u8, 0x52_u8, 0x53_u8, 0x6f_u8, 0x74_u8, 0x56_u8, 0x9d_u8,
+        0x53_u8, 0x88_u8, 0x52_u8, 0x88_u8, 0x51_u8, 0x88_u8, 0x6d_u8,
+    ];
+
+    assert!(verify_witness_script(&three_dup_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_3 OP_ROT OP_1 OP_EQUALVERIFY OP_3 OP_EQUALVERIFY OP_2 OP_EQUAL`
+    let rot_script = [0x51_u8, 0x52_u8, 0x53_u8, 0x7b_u8, 0x51_u8, 0x88_u8, 0x53_u8, 0x88_u8, 0x52_u8, 0x87_u8];
+
+    assert!(verify_witness_script(&rot_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_OVER OP_1 OP_EQUALVERIFY OP_TUCK OP_2DROP OP_2 OP_EQUAL`
+    let over_tuck_script = [0x51_u8, 0x52_u8, 0x78_u8, 0x51_u8, 0x88_u8, 0x7d_u8, 0x6d_u8, 0x52_u8, 0x87_u8];
+
+    assert!(verify_witness_script(&over_tuck_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_3 OP_2 OP_PICK OP_1 OP_EQUALVERIFY OP_2 OP_ROLL OP_1 OP_EQUALVERIFY OP_NIP
+    // OP_3 OP_EQUAL`
+    let pick_roll_script = [
+        0x51_u8, 0x52_u8, 0x53_u8, 0x52_u8, 0x79_u8, 0x51_u8, 0x88_u8,
+        0x52_u8, 0x7a_u8, 0x51_u8, 0x88_u8, 0x77_u8, 0x53_u8, 0x87_u8,
+    ];
+
+    assert!(verify_witness_script(&pick_roll_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_3 OP_4 OP_2SWAP OP_2OVER OP_4 OP_EQUALVERIFY OP_3 OP_EQUALVERIFY OP_2
+    // OP_EQUALVERIFY OP_1 OP_EQUALVERIFY OP_2DROP OP_1`
+    let two_swap_over_script = [
+        0x51_u8, 0x52_u8, 0x53_u8, 0x54_u8, 0x72_u8, 0x70_u8, 0x54_u8, 0x88_u8,
+        0x53_u8, 0x88_u8, 0x52_u8, 0x88_u8, 0x51_u8, 0x88_u8, 0x6d_u8, 0x51_u8,
+    ];
+
+    assert!(verify_witness_script(&two_swap_over_script, &[]).unwrap());
+
+    // `OP_1 OP_2 OP_3 OP_4 OP_5 OP_6 OP_2ROT OP_2 OP_EQUALVERIFY OP_1 OP_EQUALVERIFY OP_6
+    // OP_EQUALVERIFY OP_5 OP_EQUALVERIFY OP_4 OP_EQUALVERIFY OP_3 OP_EQUAL`
+    let two_rot_script = [
+        0x51_u8, 0x52_u8, 0x53_u8, 0x54_u8, 0x55_u8, 0x56_u8, 0x71_u8, 0x52_u8, 0x88_u8, 0x51_u8, 0x88_u8,
+        0x56_u8, 0x88_u8, 0x55_u8, 0x88_u8, 0x54_u8, 0x88_u8, 0x53_u8, 0x87_u8,
+    ];
+
+    assert!(verify_witness_script(&two_rot_script, &[]).unwrap());
+
+    // `OP_2 OP_TOALTSTACK OP_1 OP_FROMALTSTACK OP_2 OP_NUMEQUALVERIFY`
+    assert!(verify_witness_script(&[0x52_u8, 0x6b_u8, 0x51_u8, 0x6c_u8, 0x52_u8, 0x9d_u8], &[]).unwrap());
+
+    // `OP_0 OP_IFDUP OP_DEPTH OP_1 OP_NUMEQUALVERIFY OP_NOT`; `OP_2 OP_IFDUP OP_DEPTH OP_2
+    // OP_NUMEQUALVERIFY OP_2DROP OP_1`
+    assert!(verify_witness_script(&[0x00_u8, 0x73_u8, 0x74_u8, 0x51_u8, 0x9d_u8, 0x91_u8], &[]).unwrap());
+    assert!(verify_witness_script(&[0x52_u8, 0x73_u8, 0x74_u8, 0x52_u8, 0x9d_u8, 0x6d_u8, 0x51_u8], &[]).unwrap());
+
+    // `OP_FROMALTSTACK` with an empty alternative stack; `OP_1 OP_PICK` with a single data element.
+    assert!(matches!(verify_witness_script(&[0x51_u8, 0x6c_u8], &[]), Err(TransactionVerificationError::TransactionScriptError(0))));
+    assert!(matches!(verify_witness_script(&[0x51_u8, 0x51_u8, 0x79_u8], &[]), Err(TransactionVerificationError::TransactionScriptError(0))));
+}
+
+fn test_splice() {
+    // `OP_1 OP_SIZE OP_DROP`
+    assert!(verify_witness_script(&[0x51_u8, 0x82_u8, 0x75_u8], &[]).unwrap());
+
+    // `OP_SIZE OP_3 OP_NUMEQUALVERIFY`
+    assert!(verify_witness_script(&[0x82_u8, 0x53_u8, 0x9d_u8], &[&[0x01_u8, 0x02_u8, 0x03_u8]]).unwrap());
+}
+
+fn test_arithmetic() {
+    // `OP_1 OP_1 OP_NUMEQUAL`
+    assert!(verify_witness_script(&[0x51_u8, 0x51_u8, 0x9c_u8], &[]).unwrap());
+    assert!(!verify_witness_script(&[0x51_u8, 0x52_u8, 0x9c_u8], &[]).unwrap());
+
+    // `OP_1 OP_2 OP_NUMEQUALVERIFY OP_1`
+    assert!(matches!(verify_witness_script(&[0x51_u8, 0x52_u8, 0x9d_u8, 0x51_u8], &[]), Err(TransactionVerificationError::TransactionScriptError(0))));
+
+    // Each script leaves a single true data element (`<a> <b> <opcode> <expected> OP_NUMEQUAL`).
+    let binary_scripts: [[u8; 5]; 12] = [
+        [0x55_u8, 0x53_u8, 0x94_u8, 0x52_u8, 0x9c_u8], // 5 - 3 = 2
+        [0x53_u8, 0x55_u8, 0x94_u8, 0x4f_u8, 0x9f_u8], // 3 - 5 < -1
+        [0x53_u8, 0x55_u8, 0x93_u8, 0x58_u8, 0x9c_u8], // 3 + 5 = 8
+        [0x53_u8, 0x55_u8, 0xa3_u8, 0x53_u8, 0x9c_u8], // min(3, 5) = 3
+        [0x53_u8, 0x55_u8, 0xa4_u8, 0x55_u8, 0x9c_u8], // max(3, 5) = 5
+        [0x51_u8, 0x00_u8, 0x9a_u8, 0x00_u8, 0x9c_u8], // 1 && 0 = 0
+        [0x51_u8, 0x00_u8, 0x9b_u8, 0x51_u8, 0x9c_u8], // 1 || 0 = 1
+        [0x51_u8, 0x52_u8, 0x9e_u8, 0x51_u8, 0x9c_u8], // 1 != 2
+        [0x51_u8, 0x52_u8, 0x9f_u8, 0x51_u8, 0x9c_u8], // 1 < 2
+        [0x51_u8, 0x52_u8, 0xa0_u8, 0x00_u8, 0x9c_u8], // !(1 > 2)
+        [0x52_u8, 0x52_u8, 0xa1_u8, 0x51_u8, 0x9c_u8], // 2 <= 2
+        [0x51_u8, 0x52_u8, 0xa2_u8, 0x00_u8, 0x9c_u8], // !(1 >= 2)
+    ];
+
+    for binary_script in binary_scripts {
+        assert!(verify_witness_script(&binary_script, &[]).unwrap());
+    }
+
+    // Each script leaves a single true data element (`<a> <opcode> <expected> OP_NUMEQUAL`).
+    let unary_scripts: [[u8; 4]; 6] = [
+        [0x52_u8, 0x8b_u8, 0x53_u8, 0x9c_u8], // 2 + 1 = 3
+        [0x52_u8, 0x8c_u8, 0x51_u8, 0x9c_u8], // 2 - 1 = 1
+        [0x51_u8, 0x8f_u8, 0x4f_u8, 0x9c_u8], // -(1) = -1
+        [0x4f_u8, 0x90_u8, 0x51_u8, 0x9c_u8], // |-1| = 1
+        [0x55_u8, 0x92_u8, 0x51_u8, 0x9c_u8], // 5 != 0
+        [0x00_u8, 0x92_u8, 0x00_u8, 0x9c_u8], // !(0 != 0)
+    ];
+
+    for unary_script in unary_scripts {
+        assert!(verify_witness_script(&unary_script, &[]).unwrap());
+    }
+
+    // `<x> OP_2 OP_5 OP_WITHIN`; true when 2 <= x < 5.
+    assert!(!verify_witness_script(&[0x51_u8, 0x52_u8, 0x55_u8, 0xa5_u8], &[]).unwrap());
+    assert!(verify_witness_script(&[0x52_u8, 0x52_u8, 0x55_u8, 0xa5_u8], &[]).unwrap());
+    assert!(verify_witness_script(&[0x54_u8, 0x52_u8, 0x55_u8, 0xa5_u8], &[]).unwrap());
+    assert!(!verify_witness_script(&[0x55_u8, 0x52_u8, 0x55_u8, 0xa5_u8], &[]).unwrap());
+}
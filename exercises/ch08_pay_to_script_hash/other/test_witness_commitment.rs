@@ -0,0 +1,67 @@
+use programming_bitcoin_in_rust::*;
+
+use bitcoin::transaction::Transaction;
+use bitcoin::transaction::TransactionId;
+use bitcoin::segwit::transaction::SegWitTransaction;
+use bitcoin::segwit::commitment::merkle_root;
+use bitcoin::segwit::commitment::block_witness_merkle_root;
+use bitcoin::segwit::commitment::verify_witness_commitment;
+use bitcoin::segwit::commitment::witness_commitment_utxo;
+use bitcoin::segwit::commitment::WitnessCommitmentError;
+use util::byte_string::ByteSlice;
+use util::byte_value::ByteValue32;
+
+use crate::util::hexadecimal::hexadecimal_bytes;
+
+/// Transaction IDs of block 100000, and its merkle root.
+const BLOCK_100000_TXIDS: [&str; 4] = [
+    "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+    "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+    "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+    "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+];
+const BLOCK_100000_MERKLE_ROOT: &str = "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766";
+
+/// The witness commitment UTXO locking script of every mainnet block whose only transaction is its
+/// coinbase transaction (with a witness reserved value of zero); e.g.; blocks mined empty since the
+/// activation of segregated witness.
+const EMPTY_BLOCK_WITNESS_COMMITMENT_SCRIPT: &str = "6a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9";
+
+/// The signed P2SH-P2WPKH transaction from BIP-143's examples.
+const SEGWIT_TRANSACTION: &str = "01000000000101db6b1b20aa0fd7b23880be2ecbd4a98130974cf4748fb66092ac4d3ceb1a5477010000001716001479091972186c449eb1ded22b78e40d009bdf0089feffffff02b8b4eb0b000000001976a914a457b684d7f0d539a46a45bbc043f35b59d0d96388ac0008af2f000000001976a914fd270b1ee6abcaea97fea7ad0402e8bd8ad6d77c88ac02473044022047ac8e878352d3ebbde1c94ce3a10d057c24175747116f8288e5d794d12d482f0220217f36a485cae903c713331d877c1f64677e3622ad4010726870540656fe9dcb012103ad1d8e89212f0b92c74d23bb710c00662ad1470198ac48c43f7d6f93a2a2687392040000";
+
+pub fn run() {
+    let txids: Vec<[u8; 32]> = BLOCK_100000_TXIDS
+        .iter()
+        .map(|txid| txid.parse::<TransactionId>().unwrap().bytes())
+        .collect();
+
+    let expected_merkle_root = BLOCK_100000_MERKLE_ROOT.parse::<TransactionId>().unwrap().bytes();
+
+    assert_eq!(merkle_root(&txids), expected_merkle_root);
+
+    // A block with only a coinbase transaction; its witness merkle root is zero.
+    let coinbase = SegWitTransaction::coinbase_with_witness_commitment(800000, 4, b"", vec![], [0_u8; 32]).unwrap();
+
+    assert_eq!(block_witness_merkle_root(&[coinbase.clone()]), [0_u8; 32]);
+    assert_eq!(coinbase.utxos.last().unwrap().script.bytes(), hexadecimal_bytes(EMPTY_BLOCK_WITNESS_COMMITMENT_SCRIPT));
+    assert_eq!(witness_commitment_utxo([0_u8; 32], [0_u8; 32]).script.bytes(), hexadecimal_bytes(EMPTY_BLOCK_WITNESS_COMMITMENT_SCRIPT));
+    assert_eq!(verify_witness_commitment(&[coinbase]), Ok(()));
+
+    let transaction = SegWitTransaction::parse_bytes(&hexadecimal_bytes(SEGWIT_TRANSACTION)).unwrap();
+
+    // The coinbase transaction's witness transaction ID does not contribute to the commitment.
+    let placeholder_coinbase = SegWitTransaction::coinbase_with_witness_commitment(800000, 4, b"", vec![], [0_u8; 32]).unwrap();
+    let witness_merkle_root = block_witness_merkle_root(&[placeholder_coinbase.clone(), transaction.clone()]);
+
+    let coinbase = SegWitTransaction::coinbase_with_witness_commitment(800000, 4, b"", vec![], witness_merkle_root).unwrap();
+
+    assert_eq!(verify_witness_commitment(&[coinbase.clone(), transaction.clone()]), Ok(()));
+    assert_eq!(verify_witness_commitment(&[placeholder_coinbase, transaction.clone()]), Err(WitnessCommitmentError::Mismatch));
+
+    // Without a commitment, no transaction may have witness data.
+    let coinbase = SegWitTransaction::from_transaction(Transaction::coinbase(800000, 4, b"", vec![]).unwrap());
+
+    assert_eq!(verify_witness_commitment(&[coinbase.clone(), transaction.clone()]), Err(WitnessCommitmentError::UnexpectedWitness));
+    assert_eq!(verify_witness_commitment(&[coinbase, SegWitTransaction::from_transaction(transaction.stripped())]), Ok(()));
+}
@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+
+use programming_bitcoin_in_rust::*;
+
+use util::hexadecimal::hexadecimal_string;
+
+/// Decodes a hexadecimal string (of either case); e.g.; a test vector.
+pub fn hexadecimal_bytes(hexadecimal: &str) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0_u8; hexadecimal.len() / 2];
+
+    hexadecimal_string(&hexadecimal.to_lowercase(), &mut bytes).unwrap();
+
+    bytes
+}
@@ -1,4 +1,5 @@
 pub mod run_exercise;
 pub mod println_exercise;
+pub mod hexadecimal;
 
 pub mod bitcoin;
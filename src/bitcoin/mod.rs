@@ -5,3 +5,4 @@ pub mod transaction;
 pub mod script;
 pub mod script_types;
 pub mod segwit;
+pub mod taproot;
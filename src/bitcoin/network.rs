@@ -1,4 +1,21 @@
 //! Bitcoin network.
+//!
+//! Each network is described by its parameters (`BitcoinNetworkParameters`); i.e.; the encoding of
+//! its addresses and keys, its genesis block, the magic bytes and default port of its peer-to-peer
+//! protocol, and its consensus parameters.
+//!
+//! Testnet (testnet3), testnet4, and signet share address prefixes and human-readable parts;
+//! regtest shares only their Base58Check prefixes. An address (or key) of one of these networks is
+//! therefore also valid on the others (see `BitcoinAddress::require_network`).
+
+use crate::serialization::bitcoin_base58check::BitcoinEncodingPrefix;
+use crate::serialization::bech32::MAINNET_HRP;
+use crate::serialization::bech32::TESTNET_HRP;
+use crate::serialization::bech32::TESTNET4_HRP;
+use crate::serialization::bech32::SIGNET_HRP;
+use crate::serialization::bech32::REGTEST_HRP;
+use crate::crypto::digest::hash_256;
+use crate::util::hexadecimal::hexadecimal_string;
 
 /// Denotes various instances of the Bitcoin network/blockchain.
 #[derive(Debug)]
@@ -7,4 +24,345 @@
 pub enum BitcoinNetworkType {
     Mainnet,
     Testnet,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+/// Parameters that describe a Bitcoin network.
+#[derive(Debug)]
+pub struct BitcoinNetworkParameters {
+    /// The network's name (as used by Bitcoin Core; e.g.; `-chain=signet`).
+    pub name: &'static str,
+
+    /// Message start bytes of the peer-to-peer protocol.
+    pub magic: [u8; 4],
+
+    /// Default port of the peer-to-peer protocol.
+    pub default_port: u16,
+
+    pub p2pkh_address_prefix: BitcoinEncodingPrefix,
+    pub p2sh_address_prefix: BitcoinEncodingPrefix,
+    pub wif_private_key_prefix: BitcoinEncodingPrefix,
+    pub extended_private_key_prefix: BitcoinEncodingPrefix,
+    pub extended_public_key_prefix: BitcoinEncodingPrefix,
+
+    /// Human-readable part of segregated witness (Bech32 and Bech32m) addresses.
+    pub bech32_hrp: &'static str,
+
+    pub genesis_block: GenesisBlock,
+
+    pub consensus: ConsensusParameters,
+}
+
+/// The header of a network's genesis block.
+#[derive(Debug)]
+pub struct GenesisBlock {
+    pub version: u32,
+
+    /// Merkle root (big-endian hexadecimal); i.e.; the `txid` of the coinbase transaction.
+    pub merkle_root: &'static str,
+
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+
+    /// Block hash (big-endian hexadecimal).
+    pub hash: &'static str,
+}
+
+/// Consensus parameters of a network.
+///
+/// Activation heights are those of soft forks whose deployment is buried; i.e.; enforced from a
+/// fixed height.
+#[derive(Debug)]
+pub struct ConsensusParameters {
+    /// Number of blocks after which the block subsidy halves.
+    pub subsidy_halving_interval: u32,
+
+    /// The lowest proof of work difficulty (i.e.; the highest target), in compact format.
+    pub pow_limit_bits: u32,
+
+    /// Target time between blocks (in seconds).
+    pub pow_target_spacing: u32,
+
+    /// Target time of a difficulty adjustment period (in seconds).
+    pub pow_target_timespan: u32,
+
+    /// Allows a block of minimum difficulty if no block was found within twice the target spacing.
+    pub pow_allow_min_difficulty_blocks: bool,
+
+    /// Disables difficulty adjustment.
+    pub pow_no_retargeting: bool,
+
+    /// Enforces the timewarp fix and difficulty adjustment rules of testnet4 (BIP-94).
+    pub enforce_bip94: bool,
+
+    /// Height from which the coinbase transaction commits to the block height.
+    pub bip34_height: u32,
+
+    /// Height from which `OP_CHECKLOCKTIMEVERIFY` is enforced.
+    pub bip65_height: u32,
+
+    /// Height from which strict DER signatures are enforced.
+    pub bip66_height: u32,
+
+    /// Height from which `OP_CHECKSEQUENCEVERIFY` (and relative lock times) are enforced.
+    pub csv_height: u32,
+
+    /// Height from which segregated witness is enforced.
+    pub segwit_height: u32,
+
+    /// Script whose satisfaction signs each block (big-endian hexadecimal), for signet.
+    pub signet_challenge: Option<&'static str>,
+}
+
+const MAINNET_GENESIS_MERKLE_ROOT: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+pub static MAINNET_PARAMETERS: BitcoinNetworkParameters = BitcoinNetworkParameters {
+    name: "main",
+    magic: [0xf9, 0xbe, 0xb4, 0xd9],
+    default_port: 8333,
+    p2pkh_address_prefix: BitcoinEncodingPrefix::MainnetP2pkhAddress,
+    p2sh_address_prefix: BitcoinEncodingPrefix::MainnetP2shAddress,
+    wif_private_key_prefix: BitcoinEncodingPrefix::MainnetWifPrivateKey,
+    extended_private_key_prefix: BitcoinEncodingPrefix::MainnetExtendedPrivateKey,
+    extended_public_key_prefix: BitcoinEncodingPrefix::MainnetExtendedPublicKey,
+    bech32_hrp: MAINNET_HRP,
+    genesis_block: GenesisBlock {
+        version: 1,
+        merkle_root: MAINNET_GENESIS_MERKLE_ROOT,
+        time: 1231006505,
+        bits: 0x1d00ffff,
+        nonce: 2083236893,
+        hash: "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+    },
+    consensus: ConsensusParameters {
+        subsidy_halving_interval: 210000,
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_spacing: 10 * 60,
+        pow_target_timespan: 14 * 24 * 60 * 60,
+        pow_allow_min_difficulty_blocks: false,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        bip34_height: 227931,
+        bip65_height: 388381,
+        bip66_height: 363725,
+        csv_height: 419328,
+        segwit_height: 481824,
+        signet_challenge: None,
+    },
+};
+
+pub static TESTNET_PARAMETERS: BitcoinNetworkParameters = BitcoinNetworkParameters {
+    name: "test",
+    magic: [0x0b, 0x11, 0x09, 0x07],
+    default_port: 18333,
+    p2pkh_address_prefix: BitcoinEncodingPrefix::TestnetP2pkhAddress,
+    p2sh_address_prefix: BitcoinEncodingPrefix::TestnetP2shAddress,
+    wif_private_key_prefix: BitcoinEncodingPrefix::TestnetWifPrivateKey,
+    extended_private_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPrivateKey,
+    extended_public_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPublicKey,
+    bech32_hrp: TESTNET_HRP,
+    genesis_block: GenesisBlock {
+        version: 1,
+        merkle_root: MAINNET_GENESIS_MERKLE_ROOT,
+        time: 1296688602,
+        bits: 0x1d00ffff,
+        nonce: 414098458,
+        hash: "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943",
+    },
+    consensus: ConsensusParameters {
+        subsidy_halving_interval: 210000,
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_spacing: 10 * 60,
+        pow_target_timespan: 14 * 24 * 60 * 60,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        bip34_height: 21111,
+        bip65_height: 581885,
+        bip66_height: 330776,
+        csv_height: 770112,
+        segwit_height: 834624,
+        signet_challenge: None,
+    },
+};
+
+pub static TESTNET4_PARAMETERS: BitcoinNetworkParameters = BitcoinNetworkParameters {
+    name: "testnet4",
+    magic: [0x1c, 0x16, 0x3f, 0x28],
+    default_port: 48333,
+    p2pkh_address_prefix: BitcoinEncodingPrefix::TestnetP2pkhAddress,
+    p2sh_address_prefix: BitcoinEncodingPrefix::TestnetP2shAddress,
+    wif_private_key_prefix: BitcoinEncodingPrefix::TestnetWifPrivateKey,
+    extended_private_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPrivateKey,
+    extended_public_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPublicKey,
+    bech32_hrp: TESTNET4_HRP,
+    genesis_block: GenesisBlock {
+        version: 1,
+        merkle_root: "7aa0a7ae1e223414cb807e40cd57e667b718e42aaf9306db9102fe28912b7b4e",
+        time: 1714777860,
+        bits: 0x1d00ffff,
+        nonce: 393743547,
+        hash: "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043",
+    },
+    consensus: ConsensusParameters {
+        subsidy_halving_interval: 210000,
+        pow_limit_bits: 0x1d00ffff,
+        pow_target_spacing: 10 * 60,
+        pow_target_timespan: 14 * 24 * 60 * 60,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: false,
+        enforce_bip94: true,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 1,
+        signet_challenge: None,
+    },
+};
+
+pub static SIGNET_PARAMETERS: BitcoinNetworkParameters = BitcoinNetworkParameters {
+    name: "signet",
+    magic: [0x0a, 0x03, 0xcf, 0x40],
+    default_port: 38333,
+    p2pkh_address_prefix: BitcoinEncodingPrefix::TestnetP2pkhAddress,
+    p2sh_address_prefix: BitcoinEncodingPrefix::TestnetP2shAddress,
+    wif_private_key_prefix: BitcoinEncodingPrefix::TestnetWifPrivateKey,
+    extended_private_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPrivateKey,
+    extended_public_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPublicKey,
+    bech32_hrp: SIGNET_HRP,
+    genesis_block: GenesisBlock {
+        version: 1,
+        merkle_root: MAINNET_GENESIS_MERKLE_ROOT,
+        time: 1598918400,
+        bits: 0x1e0377ae,
+        nonce: 52613770,
+        hash: "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+    },
+    consensus: ConsensusParameters {
+        subsidy_halving_interval: 210000,
+        pow_limit_bits: 0x1e0377ae,
+        pow_target_spacing: 10 * 60,
+        pow_target_timespan: 14 * 24 * 60 * 60,
+        pow_allow_min_difficulty_blocks: false,
+        pow_no_retargeting: false,
+        enforce_bip94: false,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 1,
+        signet_challenge: Some(
+            "512103ad5e0edad18cb1f0fc0d28a3d4f1f3e445640337489abb10404f2d1e086be430210359ef5021964fe22d6f8e05b2463c9540ce96883fe3b278760f048f5189f2e6c452ae"
+        ),
+    },
+};
+
+pub static REGTEST_PARAMETERS: BitcoinNetworkParameters = BitcoinNetworkParameters {
+    name: "regtest",
+    magic: [0xfa, 0xbf, 0xb5, 0xda],
+    default_port: 18444,
+    p2pkh_address_prefix: BitcoinEncodingPrefix::TestnetP2pkhAddress,
+    p2sh_address_prefix: BitcoinEncodingPrefix::TestnetP2shAddress,
+    wif_private_key_prefix: BitcoinEncodingPrefix::TestnetWifPrivateKey,
+    extended_private_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPrivateKey,
+    extended_public_key_prefix: BitcoinEncodingPrefix::TestnetExtendedPublicKey,
+    bech32_hrp: REGTEST_HRP,
+    genesis_block: GenesisBlock {
+        version: 1,
+        merkle_root: MAINNET_GENESIS_MERKLE_ROOT,
+        time: 1296688602,
+        bits: 0x207fffff,
+        nonce: 2,
+        hash: "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+    },
+    consensus: ConsensusParameters {
+        subsidy_halving_interval: 150,
+        pow_limit_bits: 0x207fffff,
+        pow_target_spacing: 10 * 60,
+        pow_target_timespan: 14 * 24 * 60 * 60,
+        pow_allow_min_difficulty_blocks: true,
+        pow_no_retargeting: true,
+        enforce_bip94: false,
+        bip34_height: 1,
+        bip65_height: 1,
+        bip66_height: 1,
+        csv_height: 1,
+        segwit_height: 0,
+        signet_challenge: None,
+    },
+};
+
+impl BitcoinNetworkType {
+    /// All networks.
+    ///
+    /// Ordered such that (for shared address prefixes and human-readable parts) testnet precedes
+    /// testnet4 and signet.
+    pub const ALL: [Self; 5] = [Self::Mainnet, Self::Testnet, Self::Testnet4, Self::Signet, Self::Regtest];
+
+    /// Returns the network's parameters.
+    pub fn parameters(&self) -> &'static BitcoinNetworkParameters {
+        match self {
+            Self::Mainnet => &MAINNET_PARAMETERS,
+            Self::Testnet => &TESTNET_PARAMETERS,
+            Self::Testnet4 => &TESTNET4_PARAMETERS,
+            Self::Signet => &SIGNET_PARAMETERS,
+            Self::Regtest => &REGTEST_PARAMETERS,
+        }
+    }
+
+    /// Returns the human-readable part of segregated witness (Bech32 and Bech32m) addresses.
+    pub fn bech32_hrp(&self) -> &'static str {
+        self.parameters().bech32_hrp
+    }
+
+    /// Returns the network of some (lower case) segregated witness address human-readable part.
+    ///
+    /// The human-readable part `tb` is shared by testnet, testnet4, and signet; it is attributed
+    /// to testnet.
+    pub fn from_bech32_hrp(hrp: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|network| network.bech32_hrp() == hrp)
+    }
+
+    /// Returns the network of some (Bitcoin Core) network name; e.g.; `signet`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|network| network.parameters().name == name)
+    }
+
+    /// Returns the network of some peer-to-peer protocol message start bytes.
+    pub fn from_magic(magic: [u8; 4]) -> Option<Self> {
+        Self::ALL.into_iter().find(|network| network.parameters().magic == magic)
+    }
+}
+
+impl GenesisBlock {
+    /// Returns the serialized (80 byte) block header.
+    pub fn header_bytes(&self) -> [u8; 80] {
+        let mut bytes = [0_u8; 80];
+        let mut merkle_root = [0_u8; 32];
+
+        hexadecimal_string(self.merkle_root, &mut merkle_root).unwrap();
+        merkle_root.reverse();
+
+        // The genesis block has no previous block; i.e.; its previous block hash is zero.
+        bytes[0..4].clone_from_slice(&self.version.to_le_bytes());
+        bytes[36..68].clone_from_slice(&merkle_root);
+        bytes[68..72].clone_from_slice(&self.time.to_le_bytes());
+        bytes[72..76].clone_from_slice(&self.bits.to_le_bytes());
+        bytes[76..80].clone_from_slice(&self.nonce.to_le_bytes());
+
+        bytes
+    }
+
+    /// Returns the block hash (big-endian), computed from the block header.
+    pub fn block_hash(&self) -> [u8; 32] {
+        let mut hash = hash_256(self.header_bytes());
+
+        hash.reverse();
+
+        hash
+    }
 }
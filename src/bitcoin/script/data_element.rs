@@ -176,6 +176,14 @@ impl DataElement {
         Ok(accumulator)
     }
 
+    /// Returns the data element interpreted as the numeric argument of an opcode (see
+    /// [`DataElement::number`]), where an empty data element (e.g.; pushed by `OP_0`) is zero.
+    pub fn numeric_argument(&self) -> Result<i32, ScriptError> {
+        if self.bytes.is_empty() { return Ok(0) }
+
+        self.number()
+    }
+
     /// Returns whether the data element, if interpreted as the top data element in an exeuction
     /// stack, connotes script success or failure.
     ///
@@ -5,12 +5,17 @@ mod script;
 mod stack;
 mod data_element;
 mod signature_verification;
+mod script_asm;
+mod segwit_signature_hash;
+mod taproot_signature_hash;
 
 pub mod opcode;
 
 pub use script_bytes::ScriptBytes;
 pub use script::Script;
 pub use script::ScriptExecutionContext;
+pub use script::SegWitExecutionContext;
+pub use script::TapscriptExecutionContext;
 pub use script::ScriptCreationError;
 pub use script::ScriptError;
 pub use script::Element;
@@ -26,3 +31,15 @@ pub use signature_verification::TransactionInputCommitment;
 pub use signature_verification::SigHashFlag;
 pub use signature_verification::OpCheckSigDigest;
 pub use signature_verification::DefaultOpCheckSigDigest;
+pub use segwit_signature_hash::segwit_signature_hash;
+pub use segwit_signature_hash::SegWitSignatureHashCache;
+pub use segwit_signature_hash::segwit_signature_verification;
+pub use segwit_signature_hash::segwit_execution_script_code;
+pub use taproot_signature_hash::taproot_signature_hash;
+pub use taproot_signature_hash::is_valid_taproot_sighash;
+pub use taproot_signature_hash::TaprootSignatureHashCache;
+pub use taproot_signature_hash::taproot_signature_verification;
+pub use taproot_signature_hash::tapscript_signature_verification;
+pub use taproot_signature_hash::tapscript_code_separator_position;
+pub use taproot_signature_hash::SIGHASH_DEFAULT;
+pub use taproot_signature_hash::NO_CODE_SEPARATOR_POSITION;
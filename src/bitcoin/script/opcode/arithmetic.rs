@@ -6,32 +6,116 @@ use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::DataElement;
 use crate::bitcoin::script::stack::GetDataElement;
 use crate::bitcoin::script::opcode::ArithmeticOpcode;
+use crate::bitcoin::script::opcode::FlowControlOpcode;
+use crate::bitcoin::script::opcode::opcode_flowcontrol;
 use crate::util::byte_string::ByteString;
 
 pub fn opcode_arithmetic(
     stack: &mut Vec<DataElement>,
-    _script: &Script,
-    _instruction_pointer: usize,
-    _context: &ScriptExecutionContext,
+    script: &Script,
+    instruction_pointer: usize,
+    context: &ScriptExecutionContext,
     opcode: ArithmeticOpcode
 ) -> Result<(), ScriptError> {
     match opcode {
-        ArithmeticOpcode::OpAdd => {
-            let data_1 = stack.get_data_element()?;
-            let data_2 = stack.get_data_element()?;
+        | ArithmeticOpcode::Op1Add
+        | ArithmeticOpcode::Op1Sub
+        | ArithmeticOpcode::OpNegate
+        | ArithmeticOpcode::OpAbs
+        => {
+            let a = i64::from(stack.get_data_element()?.numeric_argument()?);
 
-            stack.push(DataElement::from_i64(i64::from(data_1.number()?) + i64::from(data_2.number()?))?);
+            let result = match opcode {
+                ArithmeticOpcode::Op1Add => a + 1,
+                ArithmeticOpcode::Op1Sub => a - 1,
+                ArithmeticOpcode::OpNegate => -a,
+                ArithmeticOpcode::OpAbs => a.abs(),
+                _ => panic!("unexpected opcode")
+            };
+
+            stack.push(DataElement::from_i64(result)?);
         },
         ArithmeticOpcode::OpNot => {
             let data = stack.get_data_element()?;
 
-            if data.number()? == 0 {
+            if data.numeric_argument()? == 0 {
+                stack.push(DataElement::of(&[1_u8]));
+            } else {
+                stack.push(DataElement::of(&[0_u8]));
+            }
+        },
+        ArithmeticOpcode::Op0NotEqual => {
+            let data = stack.get_data_element()?;
+
+            if data.numeric_argument()? != 0 {
                 stack.push(DataElement::of(&[1_u8]));
             } else {
                 stack.push(DataElement::of(&[0_u8]));
             }
         },
-        _ => todo!(),
+        | ArithmeticOpcode::OpAdd
+        | ArithmeticOpcode::OpSub
+        | ArithmeticOpcode::OpMin
+        | ArithmeticOpcode::OpMax
+        => {
+            // The top data element is the second operand (i.e.; `a b OP_SUB` is `a - b`).
+            let b = i64::from(stack.get_data_element()?.numeric_argument()?);
+            let a = i64::from(stack.get_data_element()?.numeric_argument()?);
+
+            let result = match opcode {
+                ArithmeticOpcode::OpAdd => a + b,
+                ArithmeticOpcode::OpSub => a - b,
+                ArithmeticOpcode::OpMin => a.min(b),
+                ArithmeticOpcode::OpMax => a.max(b),
+                _ => panic!("unexpected opcode")
+            };
+
+            stack.push(DataElement::from_i64(result)?);
+        },
+        | ArithmeticOpcode::OpBoolAnd
+        | ArithmeticOpcode::OpBoolOr
+        | ArithmeticOpcode::OpNumEqual
+        | ArithmeticOpcode::OpNumNotEqual
+        | ArithmeticOpcode::OpLessThan
+        | ArithmeticOpcode::OpGreaterThan
+        | ArithmeticOpcode::OpLessThanOrEqual
+        | ArithmeticOpcode::OpGreaterThanOrEqual
+        => {
+            let b = stack.get_data_element()?.numeric_argument()?;
+            let a = stack.get_data_element()?.numeric_argument()?;
+
+            let result = match opcode {
+                ArithmeticOpcode::OpBoolAnd => (a != 0) && (b != 0),
+                ArithmeticOpcode::OpBoolOr => (a != 0) || (b != 0),
+                ArithmeticOpcode::OpNumEqual => a == b,
+                ArithmeticOpcode::OpNumNotEqual => a != b,
+                ArithmeticOpcode::OpLessThan => a < b,
+                ArithmeticOpcode::OpGreaterThan => a > b,
+                ArithmeticOpcode::OpLessThanOrEqual => a <= b,
+                ArithmeticOpcode::OpGreaterThanOrEqual => a >= b,
+                _ => panic!("unexpected opcode")
+            };
+
+            match result {
+                true => stack.push(DataElement::of(&[1_u8])),
+                false => stack.push(DataElement::of(&[0_u8])),
+            }
+        },
+        ArithmeticOpcode::OpNumEqualVerify => {
+            opcode_arithmetic(stack, script, instruction_pointer, context, ArithmeticOpcode::OpNumEqual)?;
+            opcode_flowcontrol(stack, script, instruction_pointer, context, FlowControlOpcode::OpVerify)?;
+        },
+        ArithmeticOpcode::OpWithin => {
+            // `x min max OP_WITHIN` is true when `min <= x < max`.
+            let max = stack.get_data_element()?.numeric_argument()?;
+            let min = stack.get_data_element()?.numeric_argument()?;
+            let x = stack.get_data_element()?.numeric_argument()?;
+
+            match (min <= x) && (x < max) {
+                true => stack.push(DataElement::of(&[1_u8])),
+                false => stack.push(DataElement::of(&[0_u8])),
+            }
+        },
     }
 
     Ok(())
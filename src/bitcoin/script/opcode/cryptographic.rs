@@ -7,16 +7,23 @@ use crate::bitcoin::script::DataElement;
 use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::TransactionInputCommitment;
 use crate::bitcoin::script::signature_verification;
+use crate::bitcoin::script::segwit_signature_verification;
+use crate::bitcoin::script::segwit_execution_script_code;
+use crate::bitcoin::script::tapscript_signature_verification;
+use crate::bitcoin::script::tapscript_code_separator_position;
 use crate::bitcoin::script::stack::GetDataElement;
 use crate::bitcoin::script::opcode::CryptographicOpcode;
 use crate::bitcoin::script::opcode::FlowControlOpcode;
 use crate::bitcoin::script::opcode::opcode_flowcontrol;
+use crate::crypto::digest::ripemd_160;
 use crate::crypto::digest::sha_1;
 use crate::crypto::digest::sha_256;
 use crate::crypto::digest::hash_160;
+use crate::crypto::digest::hash_256;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 
+const OP_CHECKSIG_ERROR: ScriptError = ScriptError::OpcodeFailed(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig));
 const OP_CHECKMULTISIG_ERROR: ScriptError = ScriptError::OpcodeFailed(Opcode::Cryptographic(CryptographicOpcode::OpCheckMultisig));
 
 pub fn opcode_cryptographic(
@@ -37,11 +44,11 @@ pub fn opcode_cryptographic(
             let mut digest: Vec<u8> = vec![];
 
             match opcode {
-                CryptographicOpcode::OpRipemd160 => todo!(),
+                CryptographicOpcode::OpRipemd160 => digest.extend_from_slice(&ripemd_160(data.bytes())),
                 CryptographicOpcode::OpSha1 => digest.extend_from_slice(&sha_1(data.bytes())),
                 CryptographicOpcode::OpSha256 => digest.extend_from_slice(&sha_256(data.bytes())),
                 CryptographicOpcode::OpHash160 => digest.extend_from_slice(&hash_160(data.bytes())),
-                CryptographicOpcode::OpHash256 => todo!(),
+                CryptographicOpcode::OpHash256 => digest.extend_from_slice(&hash_256(data.bytes())),
                 _ => panic!("unexpected opcode")
             }
 
@@ -56,16 +63,10 @@ pub fn opcode_cryptographic(
                 CryptographicOpcode::OpCheckSig => {
                     let public_key_bytes = stack.get_data_element()?;
                     let signature_bytes = stack.get_data_element()?;
-                    let commitment = TransactionInputCommitment::ScriptCode(script, instruction_pointer);
-
-                    match signature_verification(
-                        &signature_bytes,
-                        &public_key_bytes,
-                        &context.transaction,
-                        context.input_index,
-                        &commitment,
-                        context.checksig_digest,
-                    )? {
+
+                    let signatures = std::slice::from_ref(&signature_bytes);
+
+                    match checksig_verification(&signature_bytes, &public_key_bytes, signatures, script, instruction_pointer, context)? {
                         true => stack.push(DataElement::of(&[1_u8])),
                         false => stack.push(DataElement::of(&[0_u8])),
                     }
@@ -74,10 +75,13 @@ pub fn opcode_cryptographic(
                     opcode_cryptographic(stack, script, instruction_pointer, context, CryptographicOpcode::OpCheckSig)?;
                     opcode_flowcontrol(stack, script, instruction_pointer, context, FlowControlOpcode::OpVerify)?;
                 },
+                // Disabled within Tapscript leaf scripts; see `OP_CHECKSIGADD`.
+                CryptographicOpcode::OpCheckMultisig if context.taproot.is_some() => {
+                    return Err(ScriptError::TapscriptOpcode(Opcode::Cryptographic(opcode)));
+                },
                 CryptographicOpcode::OpCheckMultisig => {
                     let mut public_keys: Vec<DataElement> = vec![];
                     let mut signatures: Vec<DataElement> = vec![];
-                    let commitment = TransactionInputCommitment::ScriptCode(script, instruction_pointer);
 
                     let public_key_count = stack.get_data_element()?.number()?;
 
@@ -102,7 +106,7 @@ pub fn opcode_cryptographic(
 
                     let mut n = 0; // Public key index
 
-                    for signature in signatures {
+                    for signature in &signatures {
                         loop {
                             let Some(public_key) = public_keys.get(n) else {
                                 stack.push(DataElement::of(&[0_u8]));
@@ -112,14 +116,8 @@ pub fn opcode_cryptographic(
 
                             n += 1;
 
-                            if signature_verification(
-                                &signature,
-                                &public_key,
-                                &context.transaction,
-                                context.input_index,
-                                &commitment,
-                                context.checksig_digest,
-                            ).map_err(|_| OP_CHECKMULTISIG_ERROR)? {
+                            if checksig_verification(signature, public_key, &signatures, script, instruction_pointer, context)
+                                .map_err(|_| OP_CHECKMULTISIG_ERROR)? {
                                 break;
                             }
                         }
@@ -127,13 +125,89 @@ pub fn opcode_cryptographic(
 
                     stack.push(DataElement::of(&[1_u8]));
                 },
-                CryptographicOpcode::OpCheckMultisigVerify => todo!(),
+                CryptographicOpcode::OpCheckMultisigVerify if context.taproot.is_some() => {
+                    return Err(ScriptError::TapscriptOpcode(Opcode::Cryptographic(opcode)));
+                },
+                CryptographicOpcode::OpCheckMultisigVerify => {
+                    opcode_cryptographic(stack, script, instruction_pointer, context, CryptographicOpcode::OpCheckMultisig)?;
+                    opcode_flowcontrol(stack, script, instruction_pointer, context, FlowControlOpcode::OpVerify)?;
+                },
                 _ => panic!("unexpected opcode")
             };
         },
+        CryptographicOpcode::OpCheckSigAdd => {
+            // Only enabled within Tapscript leaf scripts (BIP-342).
+            if context.taproot.is_none() {
+                return Err(ScriptError::TapscriptOpcode(Opcode::Cryptographic(opcode)));
+            }
+
+            let public_key_bytes = stack.get_data_element()?;
+            let n = stack.get_data_element()?.numeric_argument()?;
+            let signature_bytes = stack.get_data_element()?;
+
+            match checksig_verification(&signature_bytes, &public_key_bytes, &[], script, instruction_pointer, context)? {
+                true => stack.push(DataElement::from_i64(i64::from(n) + 1)?),
+                false => stack.push(DataElement::from_i64(i64::from(n))?),
+            }
+        },
         CryptographicOpcode::OpCodeSeparator => (),
-        _ => todo!(),
     }
 
     Ok(())
 }
+
+/// Verifies a signature against a public key, for the signature checking opcode at some position
+/// of the executing script.
+///
+/// The legacy signature hash is used, unless executing a segregated witness (version 0) script or
+/// a Tapscript leaf script; the signatures checked by the opcode are then removed from the script
+/// code.
+fn checksig_verification(
+    signature_bytes: &DataElement,
+    public_key_bytes: &DataElement,
+    signatures: &[DataElement],
+    script: &Script,
+    instruction_pointer: usize,
+    context: &ScriptExecutionContext,
+) -> Result<bool, ScriptError> {
+    if let Some(tapscript) = &context.taproot {
+        return tapscript_signature_verification(
+            signature_bytes,
+            public_key_bytes,
+            context.transaction,
+            context.input_index,
+            tapscript_code_separator_position(script, instruction_pointer),
+            tapscript,
+        );
+    }
+
+    match &context.segwit {
+        Some(segwit) => {
+            let script_code = segwit_execution_script_code(script, instruction_pointer)
+                .map_err(|_| OP_CHECKSIG_ERROR)?;
+
+            segwit_signature_verification(
+                signature_bytes,
+                public_key_bytes,
+                context.transaction,
+                context.input_index,
+                &script_code,
+                segwit.amount,
+                segwit.cache,
+                context.checksig_digest,
+            )
+        },
+        None => {
+            let commitment = TransactionInputCommitment::ScriptCode(script, instruction_pointer, signatures);
+
+            signature_verification(
+                signature_bytes,
+                public_key_bytes,
+                context.transaction,
+                context.input_index,
+                &commitment,
+                context.checksig_digest,
+            )
+        },
+    }
+}
@@ -4,6 +4,7 @@ use crate::bitcoin::script::Script;
 use crate::bitcoin::script::ScriptExecutionContext;
 use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::opcode::DataOpcode;
 
 pub fn opcode_data(
@@ -11,7 +12,9 @@ pub fn opcode_data(
     _script: &Script,
     _instruction_pointer: usize,
     _context: &ScriptExecutionContext,
-    _opcode: DataOpcode
+    opcode: DataOpcode
 ) -> Result<(), ScriptError> {
-    todo!()
+    // Data opcodes are parsed along with the data they push (see `Element::Data`); a data opcode
+    // without its data has nothing to push.
+    Err(ScriptError::OpcodeFailed(Opcode::Data(opcode)))
 }
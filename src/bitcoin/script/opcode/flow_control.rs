@@ -16,6 +16,16 @@ pub fn opcode_flowcontrol(
     opcode: FlowControlOpcode
 ) -> Result<(), ScriptError> {
     match opcode {
+        FlowControlOpcode::OpNop => (),
+        // Conditional blocks are evaluated by the stack (see `Stack::evaluate_element`), which
+        // tracks the branches being executed; a conditional opcode cannot be called on its own.
+        | FlowControlOpcode::OpIf
+        | FlowControlOpcode::OpNotIf
+        | FlowControlOpcode::OpElse
+        | FlowControlOpcode::OpEndIf
+        => {
+            return Err(ScriptError::ConditionalBlockMismatched);
+        },
         FlowControlOpcode::OpVerify => {
             let data = stack.get_data_element()?;
 
@@ -26,7 +36,6 @@ pub fn opcode_flowcontrol(
         FlowControlOpcode::OpReturn=> {
             return Err(ScriptError::OpReturn);
         },
-        _ => todo!(),
     }
 
     Ok(())
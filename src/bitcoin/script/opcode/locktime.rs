@@ -4,14 +4,97 @@ use crate::bitcoin::script::Script;
 use crate::bitcoin::script::ScriptExecutionContext;
 use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::opcode::LocktimeOpcode;
+use crate::bitcoin::transaction::LOCKTIME_THRESHOLD;
+use crate::bitcoin::transaction::SEQUENCE_FINAL;
+use crate::bitcoin::transaction::SEQUENCE_LOCKTIME_DISABLE_FLAG;
+use crate::bitcoin::transaction::SEQUENCE_LOCKTIME_TYPE_FLAG;
+use crate::bitcoin::transaction::SEQUENCE_LOCKTIME_MASK;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue4;
 
+/// Time lock operands may be five bytes long, to express values up to `2^39 - 1`.
+const LOCKTIME_OPERAND_SIZE: usize = 5;
+
+/// Verifies a time lock against the spending transaction, leaving the stack unchanged.
+///
+/// - `OP_CHECKLOCKTIMEVERIFY` (BIP-65) compares against the transaction's `nLockTime`.
+/// - `OP_CHECKSEQUENCEVERIFY` (BIP-112) compares against the transaction input's `nSequence`;
+///   the check is skipped if the operand has its disable flag set.
+// The signature is shared by each opcode function; see `call_opcode`.
+#[allow(clippy::ptr_arg)]
 pub fn opcode_locktime(
-    _stack: &mut Vec<DataElement>,
+    stack: &mut Vec<DataElement>,
     _script: &Script,
     _instruction_pointer: usize,
-    _context: &ScriptExecutionContext,
-    _opcode: LocktimeOpcode
+    context: &ScriptExecutionContext,
+    opcode: LocktimeOpcode
 ) -> Result<(), ScriptError> {
-    todo!()
+    let error = ScriptError::OpcodeFailed(Opcode::Locktime(opcode));
+
+    let operand = locktime_operand(stack.last().ok_or(ScriptError::EmptyStack)?)?;
+
+    if operand < 0 { return Err(error) }
+
+    let transaction = context.transaction;
+    let input = transaction.inputs.get(context.input_index).ok_or(error)?;
+
+    match opcode {
+        LocktimeOpcode::OpCheckLocktimeVerify => {
+            let locktime = u32::try_from(operand).map_err(|_| error)?;
+            let transaction_locktime = u32::from_le_bytes(transaction.locktime.bytes());
+
+            // Block heights and timestamps cannot be compared.
+            if (locktime < LOCKTIME_THRESHOLD) != (transaction_locktime < LOCKTIME_THRESHOLD) {
+                return Err(error);
+            }
+
+            if locktime > transaction_locktime { return Err(error) }
+
+            // A final transaction input disables the transaction's time lock.
+            if input.sequence == SEQUENCE_FINAL { return Err(error) }
+        },
+        LocktimeOpcode::OpCheckSequenceVerify => {
+            // Only the lower 32 bits are significant.
+            let sequence = u32::try_from(operand & 0xffffffff_i64).unwrap();
+
+            if sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 { return Ok(()) }
+
+            if transaction.version.value() < 2 { return Err(error) }
+
+            if input.sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 { return Err(error) }
+
+            let mask = SEQUENCE_LOCKTIME_TYPE_FLAG | SEQUENCE_LOCKTIME_MASK;
+
+            let sequence = sequence & mask;
+            let input_sequence = input.sequence & mask;
+
+            // Blocks and seconds cannot be compared.
+            if (sequence < SEQUENCE_LOCKTIME_TYPE_FLAG) != (input_sequence < SEQUENCE_LOCKTIME_TYPE_FLAG) {
+                return Err(error);
+            }
+
+            if sequence > input_sequence { return Err(error) }
+        },
+    }
+
+    Ok(())
+}
+
+/// Returns a time lock operand; i.e.; a little-endian signed integer of up to five bytes.
+fn locktime_operand(data: &DataElement) -> Result<i64, ScriptError> {
+    let bytes = data.bytes();
+
+    if bytes.len() > LOCKTIME_OPERAND_SIZE { return Err(ScriptError::ArithmeticInputOverflow) }
+
+    let Some(most_significant_byte) = bytes.last() else { return Ok(0) };
+
+    let mut buffer = [0_u8; 8];
+    buffer[0..bytes.len()].clone_from_slice(bytes);
+    buffer[bytes.len() - 1] = most_significant_byte & 0x7f_u8;
+
+    let magnitude = i64::from_le_bytes(buffer);
+
+    Ok(if most_significant_byte & 0x80_u8 != 0 { -magnitude } else { magnitude })
 }
@@ -4,14 +4,26 @@ use crate::bitcoin::script::Script;
 use crate::bitcoin::script::ScriptExecutionContext;
 use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::stack::GetDataElement;
 use crate::bitcoin::script::opcode::SpliceOpcode;
+use crate::util::byte_string::ByteSlice;
 
 pub fn opcode_splice(
-    _stack: &mut Vec<DataElement>,
+    stack: &mut Vec<DataElement>,
     _script: &Script,
     _instruction_pointer: usize,
     _context: &ScriptExecutionContext,
-    _opcode: SpliceOpcode
+    opcode: SpliceOpcode
 ) -> Result<(), ScriptError> {
-    todo!()
+    match opcode {
+        SpliceOpcode::OpSize => {
+            let data = stack.get_data_element()?;
+            let size = DataElement::from_i64(data.bytes().len() as i64)?;
+
+            stack.push(data);
+            stack.push(size);
+        },
+    }
+
+    Ok(())
 }
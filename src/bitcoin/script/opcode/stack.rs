@@ -4,6 +4,7 @@ use crate::bitcoin::script::Script;
 use crate::bitcoin::script::ScriptExecutionContext;
 use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::stack::GetDataElement;
 use crate::bitcoin::script::opcode::StackOpcode;
 
@@ -15,30 +16,131 @@ pub fn opcode_stack(
     opcode: StackOpcode
 ) -> Result<(), ScriptError> {
     match opcode {
+        // The alternative stack is held by the stack (see `Stack::evaluate_element`); these
+        // opcodes cannot be called on their own.
+        | StackOpcode::OpToAltStack
+        | StackOpcode::OpFromAltStack
+        => {
+            return Err(ScriptError::OpcodeFailed(Opcode::Stack(opcode)));
+        },
+        StackOpcode::OpIfDup => {
+            let data = stack.get_data_element()?;
+
+            if bool::from(&data) {
+                stack.push(data.clone());
+            }
+
+            stack.push(data);
+        },
+        StackOpcode::OpDepth => {
+            stack.push(DataElement::from_i64(stack.len() as i64)?);
+        },
+        StackOpcode::OpDrop => {
+            stack.get_data_element()?;
+        },
         StackOpcode::OpDup => {
             let data = stack.get_data_element()?;
 
             stack.push(data.clone());
             stack.push(data);
         },
-        StackOpcode::Op2Dup => {
+        StackOpcode::OpNip => {
+            let data_1 = stack.get_data_element()?;
+
+            stack.get_data_element()?;
+            stack.push(data_1);
+        },
+        StackOpcode::OpOver => {
+            let data = depth(stack, 1)?.clone();
+
+            stack.push(data);
+        },
+        | StackOpcode::OpPick
+        | StackOpcode::OpRoll
+        => {
+            let n = stack.get_data_element()?.numeric_argument()?;
+
+            let Ok(n) = usize::try_from(n) else {
+                return Err(ScriptError::OpcodeFailed(Opcode::Stack(opcode)));
+            };
+
+            let data = depth(stack, n)?.clone();
+
+            if opcode == StackOpcode::OpRoll {
+                stack.remove(stack.len() - 1 - n);
+            }
+
+            stack.push(data);
+        },
+        StackOpcode::OpRot => {
+            depth(stack, 2)?;
+
+            let data = stack.remove(stack.len() - 3);
+
+            stack.push(data);
+        },
+        StackOpcode::OpSwap => {
             let data_1 = stack.get_data_element()?;
             let data_2 = stack.get_data_element()?;
 
-            stack.push(data_1.clone());
-            stack.push(data_2.clone());
             stack.push(data_1);
             stack.push(data_2);
         },
-        StackOpcode::OpSwap => {
+        StackOpcode::OpTuck => {
             let data_1 = stack.get_data_element()?;
             let data_2 = stack.get_data_element()?;
 
+            stack.push(data_1.clone());
+            stack.push(data_2);
             stack.push(data_1);
+        },
+        StackOpcode::Op2Drop => {
+            stack.get_data_element()?;
+            stack.get_data_element()?;
+        },
+        StackOpcode::Op2Dup => {
+            let data_1 = stack.get_data_element()?;
+            let data_2 = stack.get_data_element()?;
+
+            stack.push(data_2.clone());
+            stack.push(data_1.clone());
             stack.push(data_2);
+            stack.push(data_1);
+        },
+        StackOpcode::Op3Dup => {
+            depth(stack, 2)?;
+
+            stack.extend_from_within(stack.len() - 3..);
+        },
+        StackOpcode::Op2Over => {
+            depth(stack, 3)?;
+
+            stack.extend_from_within(stack.len() - 4..stack.len() - 2);
+        },
+        StackOpcode::Op2Rot => {
+            depth(stack, 5)?;
+
+            let data: Vec<DataElement> = stack.drain(stack.len() - 6..stack.len() - 4).collect();
+
+            stack.extend(data);
+        },
+        StackOpcode::Op2Swap => {
+            depth(stack, 3)?;
+
+            let data: Vec<DataElement> = stack.drain(stack.len() - 4..stack.len() - 2).collect();
+
+            stack.extend(data);
         },
-        _ => todo!(),
     }
 
     Ok(())
 }
+
+/// Return a reference to the data element at some depth of the stack (where 0 is the top).
+fn depth(stack: &[DataElement], n: usize) -> Result<&DataElement, ScriptError> {
+    if n >= stack.len() {
+        return Err(ScriptError::EmptyStack);
+    }
+
+    Ok(&stack[stack.len() - 1 - n])
+}
@@ -1,12 +1,17 @@
 //! Bitcoin script.
 
+use std::cell::Cell;
+
 use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::opcode::DataOpcode;
 use crate::bitcoin::script::DataElement;
 use crate::bitcoin::script::ScriptBytes;
 use crate::bitcoin::script::signature_verification::OpCheckSigDigest;
 use crate::bitcoin::script::signature_verification::DefaultOpCheckSigDigest;
+use crate::bitcoin::script::segwit_signature_hash::SegWitSignatureHashCache;
+use crate::bitcoin::script::taproot_signature_hash::TaprootSignatureHashCache;
 use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::Amount;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 
@@ -216,6 +221,47 @@ pub struct ScriptExecutionContext<'a> {
     ///
     /// See `OP_CHECKLOCKTIMEVERIFY`.
     pub block_height: u64,
+
+    /// Present when executing a P2WPKH or P2WSH script; signatures then commit to the BIP-143
+    /// signature hash.
+    ///
+    /// See `OP_CHECKSIG`.
+    pub segwit: Option<SegWitExecutionContext<'a>>,
+
+    /// Present when executing a Tapscript leaf script (BIP-342); signatures are then Schnorr
+    /// signatures committing to the BIP-341 signature hash.
+    ///
+    /// See `OP_CHECKSIG`, `OP_CHECKSIGADD`.
+    pub taproot: Option<TapscriptExecutionContext<'a>>,
+}
+
+/// Contains values used by signature checking opcodes when executing a segregated witness (version
+/// 0) script.
+#[derive(Clone, Copy)]
+pub struct SegWitExecutionContext<'a> {
+    /// The amount of the UTXO being spent.
+    pub amount: Amount,
+
+    /// The digests shared by each transaction input's signature hash.
+    pub cache: &'a SegWitSignatureHashCache,
+}
+
+/// Contains values used by signature checking opcodes when executing a Tapscript leaf script
+/// (BIP-342).
+pub struct TapscriptExecutionContext<'a> {
+    /// The digests shared by each transaction input's signature hash, and the UTXOs being spent.
+    pub cache: &'a TaprootSignatureHashCache,
+
+    /// The hash of the executing leaf script.
+    pub leaf_hash: [u8; 32],
+
+    /// The annex of the transaction input's segregated witness field (including its `0x50` tag
+    /// byte), if any.
+    pub annex: Option<&'a [u8]>,
+
+    /// The remaining signature validation budget; each signature checked consumes 50 units, and
+    /// it may not become negative.
+    pub validation_budget: Cell<i64>,
 }
 
 impl <'a> ScriptExecutionContext<'a> {
@@ -231,8 +277,42 @@ impl <'a> ScriptExecutionContext<'a> {
             checksig_digest: &DefaultOpCheckSigDigest {},
             timestamp: timestamp,
             block_height: block_height,
+            segwit: None,
+            taproot: None,
         }
     }
+
+    /// Use the BIP-143 signature hash for signature checking opcodes; i.e.; when executing a
+    /// P2WPKH or P2WSH script.
+    pub fn with_segwit(mut self, amount: Amount, cache: &'a SegWitSignatureHashCache) -> Self {
+        self.segwit = Some(SegWitExecutionContext { amount: amount, cache: cache });
+
+        self
+    }
+
+    /// Use Tapscript rules (BIP-342) and the BIP-341 signature hash for signature checking
+    /// opcodes; i.e.; when executing a Taproot leaf script.
+    ///
+    /// The validation budget is 50 units, plus the serialized size of the transaction input's
+    /// segregated witness field.
+    pub fn with_taproot(
+        mut self,
+        cache: &'a TaprootSignatureHashCache,
+        leaf_hash: [u8; 32],
+        annex: Option<&'a [u8]>,
+        validation_budget: i64,
+    ) -> Self {
+        self.taproot = Some(
+            TapscriptExecutionContext {
+                cache: cache,
+                leaf_hash: leaf_hash,
+                annex: annex,
+                validation_budget: Cell::new(validation_budget),
+            }
+        );
+
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -262,6 +342,17 @@ pub enum ScriptError {
     /// begin and end.
     ConditionalBlockMismatched,
 
+    /// The argument of an `OP_IF` or `OP_NOTIF` within a Tapscript leaf script is neither empty
+    /// nor exactly `0x01` (BIP-342's MINIMALIF rule).
+    MinimalIf,
+
     /// Invalid redeem script.
     InvalidRedeemScript,
+
+    /// The signature validation budget of a Tapscript leaf script was exceeded (BIP-342).
+    ValidationBudgetExceeded,
+
+    /// The opcode is disabled within Tapscript leaf scripts (e.g.; `OP_CHECKMULTISIG`), or is
+    /// only enabled within Tapscript leaf scripts (e.g.; `OP_CHECKSIGADD`).
+    TapscriptOpcode(Opcode),
 }
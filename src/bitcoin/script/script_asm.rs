@@ -0,0 +1,263 @@
+//! Bitcoin script assembly (`asm`) representation.
+//!
+//! Matches the representation given by Bitcoin Core (`ScriptToAsmStr`).
+//!
+//! - Data pushes of up to 4 bytes are displayed as script numbers, in decimal.
+//! - Larger data pushes are displayed in hexadecimal.
+//! - `OP_0`, `OP_1NEGATE` and `OP_1` to `OP_16` are displayed as `0`, `-1` and `1` to `16`.
+//! - Other opcodes are displayed by name; undefined opcodes as `OP_UNKNOWN`.
+//! - A truncated data push ends the representation with `[error]`.
+
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::SigHashFlag;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::util::byte_string::ByteSlice;
+use crate::util::hexadecimal::hexadecimal_encode;
+
+/// The maximum length of a script; longer scripts are unspendable.
+const MAX_SCRIPT_SIZE: usize = 10000;
+
+impl ScriptBytes {
+    /// Returns the assembly representation of the script.
+    pub fn asm(&self) -> String {
+        self.asm_representation(false)
+    }
+
+    /// Returns the assembly representation of the script, decoding the `SIGHASH` flag of data
+    /// pushes that are strictly encoded DER signatures (e.g.; `<signature>[ALL]`).
+    ///
+    /// Intended for transaction input scripts; `SIGHASH` flags are not decoded for unspendable
+    /// scripts.
+    pub fn asm_with_sighash_decode(&self) -> String {
+        self.asm_representation(!self.is_unspendable())
+    }
+
+    /// Indicates that the script is provably unspendable; i.e.; it begins with `OP_RETURN`, or
+    /// is larger than the maximum script size.
+    pub fn is_unspendable(&self) -> bool {
+        let bytes = self.bytes();
+
+        (!bytes.is_empty() && bytes[0] == 0x6a_u8) || bytes.len() > MAX_SCRIPT_SIZE
+    }
+
+    fn asm_representation(&self, sighash_decode: bool) -> String {
+        let bytes = self.bytes();
+        let mut parts: Vec<String> = vec![];
+        let mut index: usize = 0;
+
+        while index < bytes.len() {
+            let opcode = bytes[index];
+            index += 1;
+
+            if opcode > 0x4e_u8 {
+                parts.push(String::from(opcode_name(opcode)));
+                continue;
+            }
+
+            // Data push opcodes; read the data length.
+            let length_bytes: usize = match opcode {
+                0x4c_u8 => 1,
+                0x4d_u8 => 2,
+                0x4e_u8 => 4,
+                _ => 0,
+            };
+
+            if index + length_bytes > bytes.len() {
+                parts.push(String::from("[error]"));
+                break;
+            }
+
+            let mut length_buffer = [0_u8; 4];
+            length_buffer[0..length_bytes].clone_from_slice(&bytes[index..(index + length_bytes)]);
+            index += length_bytes;
+
+            let length = if length_bytes == 0 { usize::from(opcode) } else { usize::try_from(u32::from_le_bytes(length_buffer)).unwrap() };
+
+            if bytes.len() - index < length {
+                parts.push(String::from("[error]"));
+                break;
+            }
+
+            let data = &bytes[index..(index + length)];
+            index += length;
+
+            if data.len() <= 4 {
+                parts.push(script_number(data).to_string());
+            } else {
+                parts.push(data_asm(data, sighash_decode));
+            }
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// Interprets up to 4 bytes as a little-endian script number, whose most significant bit is its
+/// sign.
+fn script_number(data: &[u8]) -> i64 {
+    if data.is_empty() { return 0 }
+
+    let mut value: i64 = 0;
+
+    for (i, byte) in data.iter().enumerate() {
+        value |= i64::from(*byte) << (8 * i);
+    }
+
+    let sign_bit: i64 = 0x80_i64 << (8 * (data.len() - 1));
+
+    if value & sign_bit != 0 {
+        -(value & !sign_bit)
+    } else {
+        value
+    }
+}
+
+fn data_asm(data: &[u8], sighash_decode: bool) -> String {
+    let (data, suffix) = match data.split_last() {
+        Some((sighash_byte, signature)) if sighash_decode && SignatureDerFormatBytes::is_strict_encoding(signature) => {
+            match SigHashFlag::try_from(*sighash_byte) {
+                Ok(sighash) => (signature, format!("[{}]", sighash)),
+                Err(_) => (data, String::new()),
+            }
+        },
+        _ => (data, String::new()),
+    };
+
+    let mut buffer = vec![0_u8; data.len() * 2];
+
+    hexadecimal_encode(data, &mut buffer).unwrap();
+
+    format!("{}{}", std::str::from_utf8(&buffer).unwrap(), suffix)
+}
+
+/// Returns the name of a (non data push) opcode.
+fn opcode_name(opcode: u8) -> &'static str {
+    match opcode {
+        0x4f => "-1",
+        0x50 => "OP_RESERVED",
+        0x51 => "1",
+        0x52 => "2",
+        0x53 => "3",
+        0x54 => "4",
+        0x55 => "5",
+        0x56 => "6",
+        0x57 => "7",
+        0x58 => "8",
+        0x59 => "9",
+        0x5a => "10",
+        0x5b => "11",
+        0x5c => "12",
+        0x5d => "13",
+        0x5e => "14",
+        0x5f => "15",
+        0x60 => "16",
+
+        // Flow control.
+        0x61 => "OP_NOP",
+        0x62 => "OP_VER",
+        0x63 => "OP_IF",
+        0x64 => "OP_NOTIF",
+        0x65 => "OP_VERIF",
+        0x66 => "OP_VERNOTIF",
+        0x67 => "OP_ELSE",
+        0x68 => "OP_ENDIF",
+        0x69 => "OP_VERIFY",
+        0x6a => "OP_RETURN",
+
+        // Stack.
+        0x6b => "OP_TOALTSTACK",
+        0x6c => "OP_FROMALTSTACK",
+        0x6d => "OP_2DROP",
+        0x6e => "OP_2DUP",
+        0x6f => "OP_3DUP",
+        0x70 => "OP_2OVER",
+        0x71 => "OP_2ROT",
+        0x72 => "OP_2SWAP",
+        0x73 => "OP_IFDUP",
+        0x74 => "OP_DEPTH",
+        0x75 => "OP_DROP",
+        0x76 => "OP_DUP",
+        0x77 => "OP_NIP",
+        0x78 => "OP_OVER",
+        0x79 => "OP_PICK",
+        0x7a => "OP_ROLL",
+        0x7b => "OP_ROT",
+        0x7c => "OP_SWAP",
+        0x7d => "OP_TUCK",
+
+        // Splice.
+        0x7e => "OP_CAT",
+        0x7f => "OP_SUBSTR",
+        0x80 => "OP_LEFT",
+        0x81 => "OP_RIGHT",
+        0x82 => "OP_SIZE",
+
+        // Bitwise logic.
+        0x83 => "OP_INVERT",
+        0x84 => "OP_AND",
+        0x85 => "OP_OR",
+        0x86 => "OP_XOR",
+        0x87 => "OP_EQUAL",
+        0x88 => "OP_EQUALVERIFY",
+        0x89 => "OP_RESERVED1",
+        0x8a => "OP_RESERVED2",
+
+        // Arithmetic.
+        0x8b => "OP_1ADD",
+        0x8c => "OP_1SUB",
+        0x8d => "OP_2MUL",
+        0x8e => "OP_2DIV",
+        0x8f => "OP_NEGATE",
+        0x90 => "OP_ABS",
+        0x91 => "OP_NOT",
+        0x92 => "OP_0NOTEQUAL",
+        0x93 => "OP_ADD",
+        0x94 => "OP_SUB",
+        0x95 => "OP_MUL",
+        0x96 => "OP_DIV",
+        0x97 => "OP_MOD",
+        0x98 => "OP_LSHIFT",
+        0x99 => "OP_RSHIFT",
+        0x9a => "OP_BOOLAND",
+        0x9b => "OP_BOOLOR",
+        0x9c => "OP_NUMEQUAL",
+        0x9d => "OP_NUMEQUALVERIFY",
+        0x9e => "OP_NUMNOTEQUAL",
+        0x9f => "OP_LESSTHAN",
+        0xa0 => "OP_GREATERTHAN",
+        0xa1 => "OP_LESSTHANOREQUAL",
+        0xa2 => "OP_GREATERTHANOREQUAL",
+        0xa3 => "OP_MIN",
+        0xa4 => "OP_MAX",
+        0xa5 => "OP_WITHIN",
+
+        // Cryptographic.
+        0xa6 => "OP_RIPEMD160",
+        0xa7 => "OP_SHA1",
+        0xa8 => "OP_SHA256",
+        0xa9 => "OP_HASH160",
+        0xaa => "OP_HASH256",
+        0xab => "OP_CODESEPARATOR",
+        0xac => "OP_CHECKSIG",
+        0xad => "OP_CHECKSIGVERIFY",
+        0xae => "OP_CHECKMULTISIG",
+        0xaf => "OP_CHECKMULTISIGVERIFY",
+
+        // Expansion.
+        0xb0 => "OP_NOP1",
+        0xb1 => "OP_CHECKLOCKTIMEVERIFY",
+        0xb2 => "OP_CHECKSEQUENCEVERIFY",
+        0xb3 => "OP_NOP4",
+        0xb4 => "OP_NOP5",
+        0xb5 => "OP_NOP6",
+        0xb6 => "OP_NOP7",
+        0xb7 => "OP_NOP8",
+        0xb8 => "OP_NOP9",
+        0xb9 => "OP_NOP10",
+        0xba => "OP_CHECKSIGADD",
+
+        0xff => "OP_INVALIDOPCODE",
+
+        _ => "OP_UNKNOWN",
+    }
+}
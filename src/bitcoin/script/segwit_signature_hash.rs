@@ -0,0 +1,197 @@
+//! Segregated witness (version 0) transaction signing (BIP-143).
+//!
+//! The legacy signature hash rehashes the entire (modified) transaction for each transaction
+//! input, and so its cost grows quadratically with the number of transaction inputs. BIP-143
+//! instead commits to three digests shared by every transaction input ...
+//!
+//! - `hashPrevouts`; the outpoints of all transaction inputs.
+//! - `hashSequence`; the sequence numbers of all transaction inputs.
+//! - `hashOutputs`; all UTXOs.
+//!
+//! ... which are computed once per transaction, and then reused.
+//!
+//! The signing transaction input also commits to the amount of the UTXO it spends.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
+use crate::bitcoin::script::ScriptError;
+use crate::bitcoin::script::ScriptCreationError;
+use crate::bitcoin::script::OpCheckSigDigest;
+use crate::bitcoin::script::opcode::CryptographicOpcode;
+use crate::bitcoin::script::signature_verification::SigHashFlag;
+use crate::bitcoin::script::signature_verification::SigHashType;
+use crate::bitcoin::script::signature_verification::signature_digest_verification;
+use crate::crypto::digest::hash_256;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteVector;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue4;
+use crate::util::byte_value::ByteValue8;
+use crate::util::varint::varint_u64;
+
+/// The digests shared by each transaction input's signature hash (BIP-143); i.e.; the
+/// `hashPrevouts`, `hashSequence`, and `hashOutputs` midstates.
+///
+/// These are computed once for a transaction, and are used for any transaction input and
+/// `SIGHASH` flag; they must not be reused after the transaction's inputs or UTXOs change.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct SegWitSignatureHashCache {
+    /// `HASH_256` of the outpoints of all transaction inputs.
+    pub hash_prevouts: [u8; 32],
+
+    /// `HASH_256` of the sequence numbers of all transaction inputs.
+    pub hash_sequence: [u8; 32],
+
+    /// `HASH_256` of all UTXOs.
+    pub hash_outputs: [u8; 32],
+}
+
+impl SegWitSignatureHashCache {
+    /// Compute the shared digests for a transaction.
+    pub fn new(transaction: &Transaction) -> Self {
+        let mut prevouts: Vec<u8> = Vec::with_capacity(transaction.inputs.len() * 36);
+        let mut sequences: Vec<u8> = Vec::with_capacity(transaction.inputs.len() * 4);
+        let mut outputs: Vec<u8> = vec![];
+
+        for input in &transaction.inputs {
+            prevouts.extend_from_slice(&input.outpoint.bytes());
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        for utxo in &transaction.utxos {
+            outputs.extend_from_slice(&utxo.bytes());
+        }
+
+        Self {
+            hash_prevouts: hash_256(prevouts),
+            hash_sequence: hash_256(sequences),
+            hash_outputs: hash_256(outputs),
+        }
+    }
+}
+
+/// Provides the digest for segregated witness (version 0) signature checking opcodes to verify
+/// against, and for transaction input signing (BIP-143).
+///
+/// - `script_code` is the P2WPKH script code (see `ScriptBytes::segwit_script_code`), or the
+///   P2WSH witness script (following the last executed `OP_CODESEPARATOR`, if any).
+/// - `amount` is the amount of the UTXO spent by the signing transaction input.
+///
+/// Unlike the legacy signature hash, there is no "`SIGHASH_SINGLE` bug"; if there is no UTXO with
+/// a matching output index, no UTXOs are committed to.
+///
+/// Returns nothing if the transaction input does not exist.
+pub fn segwit_signature_hash(
+    transaction: &Transaction,
+    input_index: usize,
+    script_code: &ScriptBytes,
+    amount: Amount,
+    sighash: SigHashFlag,
+    cache: &SegWitSignatureHashCache,
+    checksig_digest: &dyn OpCheckSigDigest,
+) -> Option<[u8; 32]> {
+    let input = transaction.inputs.get(input_index)?;
+    let sighash_type = sighash.sighash_type();
+
+    let hash_prevouts = if sighash.anyonecanpay() { [0_u8; 32] } else { cache.hash_prevouts };
+
+    let hash_sequence = match sighash_type {
+        SigHashType::SigHashAll if !sighash.anyonecanpay() => cache.hash_sequence,
+        _ => [0_u8; 32],
+    };
+
+    let hash_outputs = match sighash_type {
+        SigHashType::SigHashAll => cache.hash_outputs,
+        SigHashType::SigHashSingle if input_index < transaction.utxos.len() => hash_256(transaction.utxos[input_index].bytes()),
+        _ => [0_u8; 32],
+    };
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(156 + script_code.bytes().len());
+
+    bytes.extend_from_slice(&transaction.version.bytes());
+    bytes.extend_from_slice(&hash_prevouts);
+    bytes.extend_from_slice(&hash_sequence);
+    bytes.extend_from_slice(&input.outpoint.bytes());
+
+    let (varint_bytes, varint_length) = varint_u64(u64::try_from(script_code.bytes().len()).unwrap());
+
+    bytes.extend_from_slice(&varint_bytes[0..varint_length]);
+    bytes.extend_from_slice(script_code.bytes());
+    bytes.extend_from_slice(&amount.bytes());
+    bytes.extend_from_slice(&input.sequence.to_le_bytes());
+    bytes.extend_from_slice(&hash_outputs);
+    bytes.extend_from_slice(&transaction.locktime.bytes());
+    bytes.extend_from_slice(&sighash.to_le_bytes());
+
+    Some(checksig_digest.digest(&bytes))
+}
+
+/// Verifies a commitment claimed by a segregated witness (version 0) transaction input over the
+/// UTXO it spends (BIP-143).
+///
+/// i.e.; `OP_CHECKSIG`, within a P2WPKH or P2WSH script.
+#[allow(clippy::too_many_arguments)]
+pub fn segwit_signature_verification(
+    signature_bytes: &DataElement,
+    public_key_bytes: &DataElement,
+    transaction: &Transaction,
+    input_index: usize,
+    script_code: &ScriptBytes,
+    amount: Amount,
+    cache: &SegWitSignatureHashCache,
+    checksig_digest: &dyn OpCheckSigDigest,
+) -> Result<bool, ScriptError> {
+    signature_digest_verification(signature_bytes, public_key_bytes, |sighash_flag| {
+        segwit_signature_hash(transaction, input_index, script_code, amount, sighash_flag, cache, checksig_digest)
+            .ok_or_else(opchecksig_error)
+    })
+}
+
+/// Returns the script code for a signature checking opcode within a P2WPKH or P2WSH script; i.e.;
+/// the script following the last executed `OP_CODESEPARATOR`, if any.
+///
+/// Unlike the legacy signature hash, any other `OP_CODESEPARATOR` opcodes are not removed.
+pub fn segwit_execution_script_code(script: &Script, instruction_pointer: usize) -> Result<ScriptBytes, ScriptCreationError> {
+    let is_code_separator = |element: &Element| {
+        matches!(element, Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)))
+    };
+
+    let elements = script.elements();
+
+    match elements.iter().take(instruction_pointer).rposition(is_code_separator) {
+        Some(n) => Ok(ScriptBytes::from(&Script::new(&elements[(n + 1)..])?)),
+        None => Ok(ScriptBytes::from(script)),
+    }
+}
+
+impl ScriptBytes {
+    /// Returns the script code that a segregated witness (version 0) transaction input commits
+    /// to, given the UTXO's locking script (or P2SH redeem script) and the witness script.
+    ///
+    /// - P2WPKH; `OP_DUP OP_HASH160 <public key hash> OP_EQUALVERIFY OP_CHECKSIG`.
+    /// - P2WSH; the witness script.
+    pub fn segwit_script_code(&self, witness_script: Option<&ScriptBytes>) -> Option<ScriptBytes> {
+        match self.witness_program() {
+            Some((0, public_key_hash)) if public_key_hash.len() == 20 => {
+                let mut bytes: Vec<u8> = vec![0x76_u8, 0xa9_u8, 0x14_u8];
+
+                bytes.extend_from_slice(public_key_hash);
+                bytes.extend_from_slice(&[0x88_u8, 0xac_u8]);
+
+                Some(ScriptBytes::of(&bytes))
+            },
+            Some((0, script_hash)) if script_hash.len() == 32 => witness_script.cloned(),
+            _ => None,
+        }
+    }
+}
+
+fn opchecksig_error() -> ScriptError {
+    ScriptError::OpcodeFailed(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig))
+}
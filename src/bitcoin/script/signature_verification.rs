@@ -32,18 +32,44 @@ pub fn signature_verification(
     commitment: &TransactionInputCommitment,
     checksig_digest: &dyn OpCheckSigDigest,
 ) -> Result<bool, ScriptError> {
+    signature_digest_verification(signature_bytes, public_key_bytes, |sighash_flag| {
+        signature_verification_hash(
+            transaction,
+            input_index,
+            commitment,
+            sighash_flag,
+            checksig_digest,
+        ).map_err(|_| opchecksig_error())
+    })
+}
+
+/// Verifies a signature (DER format, followed by a `SIGHASH` flag byte) against a public key (SEC
+/// format), over the digest provided for its `SIGHASH` flag.
+pub(crate) fn signature_digest_verification<F>(
+    signature_bytes: &DataElement,
+    public_key_bytes: &DataElement,
+    digest: F,
+) -> Result<bool, ScriptError>
+where F: FnOnce(SigHashFlag) -> Result<[u8; 32], ScriptError> {
     let signature_bytes = signature_bytes.bytes();
     let public_key_bytes = public_key_bytes.bytes();
 
     let sighash_byte = *signature_bytes.last()
        .ok_or(opchecksig_error())?;
-    let sighash_flag = SigHashFlag::try_from(sighash_byte)
-       .map_err(|_| opchecksig_error())?;
 
-    let signature_format = SignatureDerFormatBytes::of(&signature_bytes[0..(signature_bytes.len() - 1)]);
-    let signature = Signature::from(signature_format.clone());
+    // Consensus rules accept any `SIGHASH` flag byte; non-standard flags commit as `SIGHASH_ALL`.
+    let sighash_flag = SigHashFlag::from(u32::from(sighash_byte));
+
+    // A signature that cannot be parsed fails the check, as an invalid signature does.
+    let Some(signature_format) = SignatureDerFormatBytes::try_of(&signature_bytes[0..(signature_bytes.len() - 1)]) else {
+        return Ok(false);
+    };
+
+    let signature = Signature::from(signature_format);
 
     let point = match public_key_bytes.first().ok_or(opchecksig_error())? {
+        0x02_u8 | 0x03_u8 if public_key_bytes.len() != 33 => return Err(opchecksig_error()),
+        0x04_u8 if public_key_bytes.len() != 65 => return Err(opchecksig_error()),
         0x02_u8 | 0x03_u8 => {
             CompressedPointSecFormatBytes::of(&public_key_bytes).elliptic_curve_point_secp256k1().map_err(|_| opchecksig_error())?
         },
@@ -53,15 +79,7 @@ pub fn signature_verification(
         _ => return Err(opchecksig_error()),
     };
 
-    let digest = signature_verification_hash(
-        transaction,
-        input_index,
-        commitment,
-        sighash_flag,
-        checksig_digest,
-    ).map_err(|_| opchecksig_error())?;
-
-    let data = U256::from_be_bytes(digest);
+    let data = U256::from_be_bytes(digest(sighash_flag)?);
 
     Ok(signature.verify_point_secp256k1(data, point))
 }
@@ -85,8 +103,9 @@ impl OpCheckSigDigest for DefaultOpCheckSigDigest {
 pub enum TransactionInputCommitment<'a> {
     /// Parses the current script in the context of an actual execution.
     ///
-    /// Applies `OP_CODESEPARATOR` truncation for already executed segments.
-    ScriptCode(&'a Script, usize),
+    /// Applies `OP_CODESEPARATOR` truncation for already executed segments, and removes any data
+    /// push of the given signatures from the script (Bitcoin Core's `FindAndDelete`).
+    ScriptCode(&'a Script, usize, &'a [DataElement]),
 
     /// The commitment is made against a UTXO's P2PKH locking script.
     P2pkhLockingScript(ScriptBytes),
@@ -115,47 +134,44 @@ pub fn signature_verification_hash(
     sighash: SigHashFlag,
     checksig_digest: &dyn OpCheckSigDigest,
 ) -> Result<[u8; 32], ScriptCreationError> {
-    // Return default hash on erroneous input index.
-    if input_index > transaction.inputs.len() {
-        return Ok(checksig_digest.digest(&U256::from(1).to_le_bytes::<32>()));
+    // Return the "one" hash on erroneous input index.
+    if input_index >= transaction.inputs.len() {
+        return Ok(sighash_one());
     }
 
-    // Perform "OP_CODESEPARATOR" truncation, if necessary.
-    let is_code_separator = |element: &Element| {
-        match element {
-            Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)) => true,
-            _ => false
-        }
-    };
+    // "SIGHASH_SINGLE" bug; return the "one" hash if there is no UTXO with a matching output index.
+    if sighash.sighash_type() == SigHashType::SigHashSingle && input_index >= transaction.utxos.len() {
+        return Ok(sighash_one());
+    }
 
     // Commitment bytes, used to overwrite the transaction input script when signing against a
     // transaction input.
-    let (commitment_script_bytes, skip_elements) = match commitment {
-        TransactionInputCommitment::ScriptCode(execution_script, instruction_pointer) => {
-            if let Some(n) = execution_script.elements().iter().take(*instruction_pointer).rposition(is_code_separator) {
-                (ScriptBytes::from(*execution_script), n + 1)
-            } else {
-                (ScriptBytes::from(*execution_script), 0)
-            }
+    let commitment_script_bytes = match commitment {
+        TransactionInputCommitment::ScriptCode(execution_script, instruction_pointer, signatures) => {
+            // Perform "OP_CODESEPARATOR" truncation, if necessary.
+            let is_code_separator = |element: &Element| {
+                matches!(element, Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)))
+            };
+
+            let elements = execution_script.elements();
+
+            let script_code = match elements.iter().take(*instruction_pointer).rposition(is_code_separator) {
+                Some(n) => ScriptBytes::from(&Script::new(&elements[(n + 1)..])?),
+                None => ScriptBytes::from(*execution_script),
+            };
+
+            // A signature cannot commit to itself; remove it, as pushed by a script.
+            let bytes = signatures.iter().fold(script_code.bytes().to_vec(), |bytes, signature| {
+                find_and_delete(&bytes, &DataElement::of(signature.bytes()).bytes_with_opcode())
+            });
+
+            ScriptBytes::of(&bytes)
         },
-        TransactionInputCommitment::P2pkhLockingScript(script_bytes) => (script_bytes.clone(), 0),
-        TransactionInputCommitment::RedeemScript(script_bytes) => (script_bytes.clone(), 0),
+        TransactionInputCommitment::P2pkhLockingScript(script_bytes) => script_bytes.clone(),
+        TransactionInputCommitment::RedeemScript(script_bytes) => script_bytes.clone(),
     };
 
-    let commitment_script = Script::try_from(&commitment_script_bytes)?;
-    let commitment_script_without_code_separators = commitment_script
-        .elements()
-        .iter()
-        .skip(skip_elements)
-        .filter_map(|element| if is_code_separator(element) { None } else { Some(element) });
-
-    let mut commitment_elements = vec![];
-
-    for element in commitment_script_without_code_separators {
-        commitment_elements.push(element.clone());
-    }
-
-    let commitment_bytes = ScriptBytes::from(&Script::new(&commitment_elements)?);
+    let commitment_bytes = ScriptBytes::of(&without_code_separators(commitment_script_bytes.bytes()));
 
     // Initalize a copy of the transaction input(s).
     let sighash_inputs: Vec<TransactionInput> = if sighash.anyonecanpay() {
@@ -206,18 +222,11 @@ pub fn signature_verification_hash(
         SigHashType::SigHashNone => vec![], // "SIGHASH_NONE" -- commit none.
         SigHashType::SigHashSingle => {
             // "SIGHASH_SINGLE" -- commit UTXO with matching signing transaction input index.
-            if input_index > transaction.utxos.len() {
-                return Ok(checksig_digest.digest(&U256::from(1).to_le_bytes::<32>()));
-            }
-
             let mut utxos = transaction.utxos[0..=input_index].to_vec();
 
             // For non-signing transaction inputs, set amount to -1 and assign an empty script.
             for utxo in utxos[0..input_index].iter_mut() {
-                *utxo = utxo.clone();
-
-                (*utxo).amount = -1;
-                (*utxo).script = ScriptBytes::of(&[]);
+                *utxo = UnspentTransactionOutput::null();
             }
 
             utxos
@@ -234,7 +243,7 @@ pub fn signature_verification_hash(
 
     let mut bytes = transaction_copy.bytes();
 
-    // Append the four byte little-endian "SIGHASH".
+    // Append the four byte little-endian "SIGHASH" (its raw value, if non-standard).
     bytes.extend_from_slice(&sighash.to_le_bytes());
 
     Ok(checksig_digest.digest(&bytes))
@@ -269,20 +278,106 @@ pub fn signature_signing_hash(
     )
 }
 
+/// The "one" hash; i.e.; the little-endian 256 bit integer `1`, returned (not digested) in place of
+/// a signature hash when the transaction input or `SIGHASH_SINGLE` UTXO does not exist.
+///
+/// Any signature for this hash (and public key) is valid for every such transaction input.
+fn sighash_one() -> [u8; 32] {
+    U256::from(1).to_le_bytes::<32>()
+}
+
+/// Removes every `OP_CODESEPARATOR` from script bytes, as Bitcoin Core does when serializing the
+/// script code; i.e.; without reencoding data pushes.
+///
+/// A truncated data push at the end of the script is kept as is.
+fn without_code_separators(bytes: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i: usize = 0;
+
+    while i < bytes.len() {
+        let Some(end) = opcode_end(bytes, i) else {
+            result.extend_from_slice(&bytes[i..]);
+
+            break;
+        };
+
+        if bytes[i] != 0xab_u8 { result.extend_from_slice(&bytes[i..end]) }
+
+        i = end;
+    }
+
+    result
+}
+
+/// Removes every occurrence of a pattern that begins at an opcode boundary from script bytes, as
+/// Bitcoin Core's `FindAndDelete` does; consecutive occurrences are all removed.
+///
+/// A truncated data push at the end of the script is kept as is.
+fn find_and_delete(bytes: &[u8], pattern: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i: usize = 0;
+
+    loop {
+        while !pattern.is_empty() && bytes[i..].starts_with(pattern) { i += pattern.len() }
+
+        let Some(end) = opcode_end(bytes, i) else {
+            result.extend_from_slice(&bytes[i..]);
+
+            break;
+        };
+
+        result.extend_from_slice(&bytes[i..end]);
+
+        i = end;
+    }
+
+    result
+}
+
+/// Returns the end of the opcode (including any data it pushes) at some position of script bytes;
+/// or nothing, if there is no opcode, or its data push is truncated.
+fn opcode_end(bytes: &[u8], i: usize) -> Option<usize> {
+    let opcode = *bytes.get(i)?;
+
+    let (header, length) = match opcode {
+        0x00_u8..=0x4b_u8 => (1, usize::from(opcode)),
+        0x4c_u8 => (2, usize::from(*bytes.get(i + 1)?)),
+        0x4d_u8 => {
+            let length = bytes.get((i + 1)..(i + 3))?;
+
+            (3, usize::from(u16::from_le_bytes([length[0], length[1]])))
+        },
+        0x4e_u8 => {
+            let length = bytes.get((i + 1)..(i + 5))?;
+
+            (5, usize::try_from(u32::from_le_bytes([length[0], length[1], length[2], length[3]])).ok()?)
+        },
+        _ => (1, 0),
+    };
+
+    let end = i.checked_add(header)?.checked_add(length)?;
+
+    if end <= bytes.len() { Some(end) } else { None }
+}
+
 fn opchecksig_error() -> ScriptError {
     ScriptError::OpcodeFailed(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig))
 }
 
-/// A `SIGHASH` flag, represented as a byte.
+/// A `SIGHASH` flag.
+///
+/// Signatures carry the flag as a single byte, but the signature hash commits to a four byte
+/// value (Bitcoin Core's `nHashType`); non-standard values are preserved as is.
 #[derive(Debug)]
 #[derive(Clone, Copy)]
 pub struct SigHashFlag {
-    byte: u8
+    value: u32
 }
 
 /// `SIGHASH` types.
 #[derive(Debug)]
 #[derive(Clone, Copy)]
+#[derive(PartialEq)]
 pub enum SigHashType {
     /// `0x01`: `ALL`
     SigHashAll,
@@ -309,33 +404,71 @@ impl TryFrom<u8> for SigHashFlag {
         // 0x7c = 0b01111100
         if byte & 0x7c_u8 > 0x00_u8 { return Err(SigHashFlagError) }
 
-        Ok(Self { byte: byte })
+        Ok(Self { value: u32::from(byte) })
+    }
+}
+
+impl From<u32> for SigHashFlag {
+    /// Create a `SIGHASH` flag from any four byte value, as Bitcoin Core's consensus rules permit.
+    ///
+    /// Values whose lower five bits are neither `0x02` (`NONE`) nor `0x03` (`SINGLE`) commit as
+    /// `ALL`.
+    fn from(value: u32) -> Self {
+        Self { value: value }
     }
 }
 
 impl SigHashFlag {
     /// Returns the byte of the `SIGHASH` flag.
     pub fn byte(&self) -> u8 {
-        self.byte
+        self.value.to_le_bytes()[0]
+    }
+
+    /// Returns the four byte value of the `SIGHASH` flag.
+    pub fn value(&self) -> u32 {
+        self.value
     }
 
     /// Returns the little-endian four byte represenation of the `SIGHASH` flag.
     pub fn to_le_bytes(&self) -> [u8; 4] {
-        [self.byte, 0_u8, 0_u8, 0_u8]
+        self.value.to_le_bytes()
     }
 
     /// Returns the `SigHashType` for a given `SigHashFlag`.
     pub fn sighash_type(&self) -> SigHashType {
-        match self.byte & 0x03_u8 {
-            0x01_u8 => SigHashType::SigHashAll,
-            0x02_u8 => SigHashType::SigHashNone,
-            0x03_u8 => SigHashType::SigHashSingle,
-            _ => panic!("unexpected bits")
+        match self.value & 0x1f_u32 {
+            0x02_u32 => SigHashType::SigHashNone,
+            0x03_u32 => SigHashType::SigHashSingle,
+            _ => SigHashType::SigHashAll,
         }
     }
 
     /// Returns a boolean denoting if modifier `ANYONECANPAY` is set or not.
     pub fn anyonecanpay(&self) -> bool {
-        self.byte & 0x80_u8 == 0x80_u8
+        self.value & 0x80_u32 == 0x80_u32
+    }
+}
+
+impl std::fmt::Display for SigHashFlag {
+    /// Displays the `SIGHASH` flag (e.g.; `ALL`, `SINGLE|ANYONECANPAY`); a non-standard value is
+    /// followed by its raw value (e.g.; `ALL (0x00)`).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let sighash_type = match self.sighash_type() {
+            SigHashType::SigHashAll => "ALL",
+            SigHashType::SigHashNone => "NONE",
+            SigHashType::SigHashSingle => "SINGLE",
+        };
+
+        if self.anyonecanpay() {
+            write!(f, "{}|ANYONECANPAY", sighash_type)?;
+        } else {
+            write!(f, "{}", sighash_type)?;
+        }
+
+        let is_standard = u8::try_from(self.value).is_ok_and(|byte| Self::try_from(byte).is_ok());
+
+        if !is_standard { write!(f, " ({:#04x})", self.value)? }
+
+        Ok(())
     }
 }
@@ -7,6 +7,9 @@ use crate::bitcoin::script::Element;
 use crate::bitcoin::script::DataElement;
 use crate::bitcoin::script::Opcode;
 use crate::bitcoin::script::call_opcode;
+use crate::bitcoin::script::opcode::FlowControlOpcode;
+use crate::bitcoin::script::opcode::StackOpcode;
+use crate::util::byte_string::ByteSlice;
 
 /// Bitcoin script stack.
 ///
@@ -16,9 +19,12 @@ pub struct Stack<'a> {
     stack: Vec<DataElement>,
 
     /// Alternative stack (`OP_TOALTSTACK`, `OP_FROMALTSTACK`).
-    #[allow(dead_code)]
     altstack: Vec<DataElement>,
 
+    /// Conditional blocks (`OP_IF`, `OP_NOTIF`) the instruction pointer is within, innermost
+    /// last; each denotes whether its current branch is executed.
+    conditions: Vec<bool>,
+
     /// A reference to the script being processed.
     script: &'a Script,
 
@@ -41,6 +47,22 @@ impl <'a> Stack<'a> {
         Self {
             stack: vec![],
             altstack: vec![],
+            conditions: vec![],
+            script: script,
+            n: 0,
+            end: false,
+            error: None,
+            context: context,
+        }
+    }
+
+    /// Create a new stack for a given script, initially containing some data elements (ordered
+    /// from bottom to top); e.g.; segregated witness items.
+    pub fn with_data_elements(script: &'a Script, context: &'a ScriptExecutionContext, data_elements: Vec<DataElement>) -> Self {
+        Self {
+            stack: data_elements,
+            altstack: vec![],
+            conditions: vec![],
             script: script,
             n: 0,
             end: false,
@@ -58,6 +80,7 @@ impl <'a> Stack<'a> {
             Self {
                 stack: self.stack.clone(),
                 altstack: self.altstack.clone(),
+                conditions: vec![],
                 script: script,
                 n: 0,
                 end: false,
@@ -104,21 +127,42 @@ impl <'a> Stack<'a> {
     }
 
     /// Process script elements and call a single opcode from the script sequence.
+    ///
+    /// Data elements and opcodes within a branch of a conditional block that is not executed are
+    /// skipped.
     pub fn evaluate_element(&mut self) -> Option<Result<Opcode, ScriptError>> {
         if self.end { return None }
 
         loop {
             let element = self.script.get(self.n);
+            let executing = self.conditions.iter().all(|condition| *condition);
 
             match element {
                 Some(Element::Opcode(opcode)) => {
-                    let result = match call_opcode(
-                        &mut self.stack,
-                        self.script,
-                        self.n,
-                        &self.context,
-                        *opcode
-                    ) {
+                    let result = match opcode {
+                        | Opcode::FlowControl(FlowControlOpcode::OpIf)
+                        | Opcode::FlowControl(FlowControlOpcode::OpNotIf)
+                        | Opcode::FlowControl(FlowControlOpcode::OpElse)
+                        | Opcode::FlowControl(FlowControlOpcode::OpEndIf)
+                        => self.call_conditional(*opcode, executing),
+                        _ if !executing => {
+                            self.n += 1;
+
+                            continue;
+                        },
+                        | Opcode::Stack(StackOpcode::OpToAltStack)
+                        | Opcode::Stack(StackOpcode::OpFromAltStack)
+                        => self.call_altstack(*opcode),
+                        _ => call_opcode(
+                            &mut self.stack,
+                            self.script,
+                            self.n,
+                            self.context,
+                            *opcode
+                        ),
+                    };
+
+                    let result = match result {
                         Ok(_) => Some(Ok(*opcode)),
                         Err(error) => {
                             self.end_error(error);
@@ -132,9 +176,17 @@ impl <'a> Stack<'a> {
                     return result;
                 },
                 Some(Element::Data(data_element)) => {
-                    self.stack.push(data_element.clone());
+                    if executing {
+                        self.stack.push(data_element.clone());
+                    }
                 },
                 None => {
+                    if !self.conditions.is_empty() {
+                        self.end_error(ScriptError::ConditionalBlockMismatched);
+
+                        return Some(Err(ScriptError::ConditionalBlockMismatched));
+                    }
+
                     self.end();
 
                     return None;
@@ -145,6 +197,63 @@ impl <'a> Stack<'a> {
         }
     }
 
+    /// Begin, switch the branch of, or end a conditional block.
+    ///
+    /// `OP_IF` and `OP_NOTIF` only consume their argument when executed; within a Tapscript leaf
+    /// script, the argument must be empty or exactly `0x01` (MINIMALIF).
+    fn call_conditional(&mut self, opcode: Opcode, executing: bool) -> Result<(), ScriptError> {
+        match opcode {
+            | Opcode::FlowControl(FlowControlOpcode::OpIf)
+            | Opcode::FlowControl(FlowControlOpcode::OpNotIf)
+            => {
+                let mut condition = false;
+
+                if executing {
+                    let data = self.stack.pop().ok_or(ScriptError::EmptyStack)?;
+
+                    if self.context.taproot.is_some() && !(data.bytes().is_empty() || data.bytes() == [0x01_u8]) {
+                        return Err(ScriptError::MinimalIf);
+                    }
+
+                    condition = bool::from(&data) == (opcode == Opcode::FlowControl(FlowControlOpcode::OpIf));
+                }
+
+                self.conditions.push(condition);
+            },
+            Opcode::FlowControl(FlowControlOpcode::OpElse) => {
+                let condition = self.conditions.last_mut().ok_or(ScriptError::ConditionalBlockMismatched)?;
+
+                *condition = !*condition;
+            },
+            Opcode::FlowControl(FlowControlOpcode::OpEndIf) => {
+                self.conditions.pop().ok_or(ScriptError::ConditionalBlockMismatched)?;
+            },
+            _ => panic!("unexpected opcode")
+        }
+
+        Ok(())
+    }
+
+    /// Move the data element at the top of the stack to the top of the alternative stack, or
+    /// vice versa.
+    fn call_altstack(&mut self, opcode: Opcode) -> Result<(), ScriptError> {
+        match opcode {
+            Opcode::Stack(StackOpcode::OpToAltStack) => {
+                let data = self.stack.pop().ok_or(ScriptError::EmptyStack)?;
+
+                self.altstack.push(data);
+            },
+            Opcode::Stack(StackOpcode::OpFromAltStack) => {
+                let data = self.altstack.pop().ok_or(ScriptError::OpcodeFailed(opcode))?;
+
+                self.stack.push(data);
+            },
+            _ => panic!("unexpected opcode")
+        }
+
+        Ok(())
+    }
+
     /// Return a reference the data element at the top of the stack, if any.
     pub fn peek(&self) -> Option<&DataElement> {
         self.stack.last()
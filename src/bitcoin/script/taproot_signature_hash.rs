@@ -0,0 +1,315 @@
+//! Taproot (segregated witness version 1) transaction signing (BIP-341).
+//!
+//! Like BIP-143, each transaction input's signature hash commits to digests shared by every
+//! transaction input, computed once per transaction. Unlike BIP-143, the digests are single
+//! SHA-256 digests, and every transaction input commits to the amounts and locking scripts of all
+//! UTXOs being spent (not only its own).
+//!
+//! - `sha_prevouts`; the outpoints of all transaction inputs.
+//! - `sha_amounts`; the amounts of all UTXOs being spent.
+//! - `sha_scriptpubkeys`; the locking scripts of all UTXOs being spent.
+//! - `sha_sequences`; the sequence numbers of all transaction inputs.
+//! - `sha_outputs`; all UTXOs.
+//!
+//! The signature message is hashed with `tagged_hash("TapSighash", 0x00 || SigMsg)`.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
+use crate::bitcoin::script::ScriptError;
+use crate::bitcoin::script::TapscriptExecutionContext;
+use crate::bitcoin::script::opcode::CryptographicOpcode;
+use crate::crypto::schnorr::signature::SchnorrSignature;
+use crate::crypto::digest::sha_256;
+use crate::crypto::digest::tagged::TAP_SIGHASH;
+use crate::util::byte_string::ByteVector;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue4;
+use crate::util::byte_value::ByteValue8;
+use crate::util::varint::varint_u64;
+
+/// The `SIGHASH` flag byte of a 64 byte Schnorr signature; commits as `SIGHASH_ALL`.
+pub const SIGHASH_DEFAULT: u8 = 0x00_u8;
+
+/// The `OP_CODESEPARATOR` position committed to when none has been executed.
+pub const NO_CODE_SEPARATOR_POSITION: u32 = u32::MAX;
+
+/// The digests shared by each transaction input's signature hash (BIP-341), along with the UTXOs
+/// being spent.
+///
+/// These are computed once for a transaction, and are used for any transaction input and
+/// `SIGHASH` flag; they must not be reused after the transaction's inputs or UTXOs change.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct TaprootSignatureHashCache {
+    /// The UTXOs spent by each transaction input.
+    pub spent_utxos: Vec<UnspentTransactionOutput>,
+
+    /// SHA-256 of the outpoints of all transaction inputs.
+    pub sha_prevouts: [u8; 32],
+
+    /// SHA-256 of the amounts of all UTXOs being spent.
+    pub sha_amounts: [u8; 32],
+
+    /// SHA-256 of the locking scripts (each prefixed by its length) of all UTXOs being spent.
+    pub sha_scriptpubkeys: [u8; 32],
+
+    /// SHA-256 of the sequence numbers of all transaction inputs.
+    pub sha_sequences: [u8; 32],
+
+    /// SHA-256 of all UTXOs.
+    pub sha_outputs: [u8; 32],
+}
+
+impl TaprootSignatureHashCache {
+    /// Compute the shared digests for a transaction, given the UTXO spent by each transaction
+    /// input.
+    ///
+    /// Returns nothing if the number of UTXOs differs from the number of transaction inputs.
+    pub fn new(transaction: &Transaction, spent_utxos: Vec<UnspentTransactionOutput>) -> Option<Self> {
+        if transaction.inputs.len() != spent_utxos.len() { return None }
+
+        Some(Self::of_spent_utxos(transaction, spent_utxos))
+    }
+
+    /// Compute the shared digests for a transaction, retrieving the UTXO spent by each transaction
+    /// input.
+    pub fn from_provider<P>(transaction: &Transaction, utxo_provider: &P) -> Result<Self, UtxoProviderError>
+    where P: UtxoProvider + ?Sized {
+        let spent_utxos = transaction.inputs
+            .iter()
+            .map(|input| Ok(utxo_provider.utxo(&input.outpoint)?.utxo))
+            .collect::<Result<Vec<UnspentTransactionOutput>, UtxoProviderError>>()?;
+
+        Ok(Self::of_spent_utxos(transaction, spent_utxos))
+    }
+
+    /// Compute the shared digests for a transaction, given (exactly) the UTXO spent by each
+    /// transaction input.
+    fn of_spent_utxos(transaction: &Transaction, spent_utxos: Vec<UnspentTransactionOutput>) -> Self {
+        let mut prevouts: Vec<u8> = Vec::with_capacity(transaction.inputs.len() * 36);
+        let mut amounts: Vec<u8> = Vec::with_capacity(spent_utxos.len() * 8);
+        let mut scriptpubkeys: Vec<u8> = vec![];
+        let mut sequences: Vec<u8> = Vec::with_capacity(transaction.inputs.len() * 4);
+        let mut outputs: Vec<u8> = vec![];
+
+        for input in &transaction.inputs {
+            prevouts.extend_from_slice(&input.outpoint.bytes());
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        for utxo in &spent_utxos {
+            amounts.extend_from_slice(&utxo.amount.bytes());
+            scriptpubkeys.extend_from_slice(&utxo.bytes()[8..]);
+        }
+
+        for utxo in &transaction.utxos {
+            outputs.extend_from_slice(&utxo.bytes());
+        }
+
+        Self {
+            spent_utxos: spent_utxos,
+            sha_prevouts: sha_256(prevouts),
+            sha_amounts: sha_256(amounts),
+            sha_scriptpubkeys: sha_256(scriptpubkeys),
+            sha_sequences: sha_256(sequences),
+            sha_outputs: sha_256(outputs),
+        }
+    }
+}
+
+/// Indicates that a `SIGHASH` flag byte is valid for a Taproot signature; i.e.; `0x00` (default),
+/// `0x01` to `0x03`, or `0x81` to `0x83` (`ANYONECANPAY`).
+pub fn is_valid_taproot_sighash(hash_type: u8) -> bool {
+    matches!(hash_type, 0x00_u8..=0x03_u8 | 0x81_u8..=0x83_u8)
+}
+
+/// Provides the digest for Taproot signature checking (key path spends, and Tapscript signature
+/// checking opcodes), and for transaction input signing (BIP-341).
+///
+/// - `annex` is the annex of the signing transaction input's segregated witness field (including
+///   its `0x50` tag byte), if any.
+/// - `leaf` is the hash of the executing leaf script, and the position of the last executed
+///   `OP_CODESEPARATOR` (or `NO_CODE_SEPARATOR_POSITION`), for a script path spend (BIP-342).
+///
+/// Returns nothing if the `SIGHASH` flag byte is invalid, if the transaction input (or the UTXO it
+/// spends) does not exist, or if `SIGHASH_SINGLE` is used without a UTXO of matching output index.
+pub fn taproot_signature_hash(
+    transaction: &Transaction,
+    input_index: usize,
+    hash_type: u8,
+    annex: Option<&[u8]>,
+    leaf: Option<([u8; 32], u32)>,
+    cache: &TaprootSignatureHashCache,
+) -> Option<[u8; 32]> {
+    if !is_valid_taproot_sighash(hash_type) { return None }
+
+    let input = transaction.inputs.get(input_index)?;
+    let spent_utxo = cache.spent_utxos.get(input_index)?;
+
+    let output_type = hash_type & 0x03_u8;
+    let anyonecanpay = (hash_type & 0x80_u8) == 0x80_u8;
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(256);
+
+    // Epoch.
+    bytes.push(0x00_u8);
+
+    bytes.push(hash_type);
+    bytes.extend_from_slice(&transaction.version.bytes());
+    bytes.extend_from_slice(&transaction.locktime.bytes());
+
+    if !anyonecanpay {
+        bytes.extend_from_slice(&cache.sha_prevouts);
+        bytes.extend_from_slice(&cache.sha_amounts);
+        bytes.extend_from_slice(&cache.sha_scriptpubkeys);
+        bytes.extend_from_slice(&cache.sha_sequences);
+    }
+
+    // "SIGHASH_ALL" (or the default) -- commit all UTXOs.
+    if output_type == 0x00_u8 || output_type == 0x01_u8 {
+        bytes.extend_from_slice(&cache.sha_outputs);
+    }
+
+    let extension_flag = if leaf.is_some() { 1_u8 } else { 0_u8 };
+
+    bytes.push((extension_flag * 2) + u8::from(annex.is_some()));
+
+    if anyonecanpay {
+        bytes.extend_from_slice(&input.outpoint.bytes());
+        bytes.extend_from_slice(&spent_utxo.bytes());
+        bytes.extend_from_slice(&input.sequence.to_le_bytes());
+    } else {
+        bytes.extend_from_slice(&u32::try_from(input_index).unwrap().to_le_bytes());
+    }
+
+    if let Some(annex) = annex {
+        let mut annex_bytes: Vec<u8> = Vec::with_capacity(9 + annex.len());
+
+        let (varint_bytes, varint_length) = varint_u64(u64::try_from(annex.len()).unwrap());
+
+        annex_bytes.extend_from_slice(&varint_bytes[0..varint_length]);
+        annex_bytes.extend_from_slice(annex);
+
+        bytes.extend_from_slice(&sha_256(annex_bytes));
+    }
+
+    // "SIGHASH_SINGLE" -- commit the UTXO with matching signing transaction input index.
+    if output_type == 0x03_u8 {
+        let utxo = transaction.utxos.get(input_index)?;
+
+        bytes.extend_from_slice(&sha_256(utxo.bytes()));
+    }
+
+    if let Some((leaf_hash, code_separator_position)) = leaf {
+        bytes.extend_from_slice(&leaf_hash);
+
+        // Key version.
+        bytes.push(0x00_u8);
+
+        bytes.extend_from_slice(&code_separator_position.to_le_bytes());
+    }
+
+    Some(TAP_SIGHASH.hash(bytes))
+}
+
+/// Verifies a Schnorr signature (64 bytes, or 65 bytes followed by a non-default `SIGHASH` flag
+/// byte) against an x-only public key, over the BIP-341 signature hash.
+///
+/// i.e.; a key path spend (without `leaf`), or a Tapscript signature checking opcode.
+pub fn taproot_signature_verification(
+    signature_bytes: &[u8],
+    public_key: &[u8; 32],
+    transaction: &Transaction,
+    input_index: usize,
+    annex: Option<&[u8]>,
+    leaf: Option<([u8; 32], u32)>,
+    cache: &TaprootSignatureHashCache,
+) -> bool {
+    let hash_type = match signature_bytes.len() {
+        64 => SIGHASH_DEFAULT,
+        // The default `SIGHASH` flag must be implied, not explicit.
+        65 if signature_bytes[64] != SIGHASH_DEFAULT => signature_bytes[64],
+        _ => return false,
+    };
+
+    let Some(signature) = SchnorrSignature::parse_bytes(&signature_bytes[0..64]) else { return false };
+    let Some(digest) = taproot_signature_hash(transaction, input_index, hash_type, annex, leaf, cache) else { return false };
+
+    signature.verify_secp256k1(&digest, public_key)
+}
+
+/// Verifies a signature against a public key for a Tapscript signature checking opcode (BIP-342).
+///
+/// - An empty public key fails the script.
+/// - An empty signature does not verify, and is not an error (e.g.; to skip a key within an
+///   `OP_CHECKSIGADD` threshold script).
+/// - Otherwise, 50 units of the validation budget are consumed.
+/// - A 32 byte public key is an x-only public key; a signature that does not verify fails the
+///   script.
+/// - Other public keys are of some future (undefined) key type, and are unencumbered.
+pub fn tapscript_signature_verification(
+    signature_bytes: &DataElement,
+    public_key_bytes: &DataElement,
+    transaction: &Transaction,
+    input_index: usize,
+    code_separator_position: u32,
+    tapscript: &TapscriptExecutionContext,
+) -> Result<bool, ScriptError> {
+    let signature_bytes = signature_bytes.bytes();
+    let public_key_bytes = public_key_bytes.bytes();
+
+    if public_key_bytes.is_empty() { return Err(opchecksig_error()) }
+    if signature_bytes.is_empty() { return Ok(false) }
+
+    let validation_budget = tapscript.validation_budget.get() - 50;
+
+    tapscript.validation_budget.set(validation_budget);
+
+    if validation_budget < 0 { return Err(ScriptError::ValidationBudgetExceeded) }
+
+    if public_key_bytes.len() == 32 {
+        let mut public_key = [0_u8; 32];
+
+        public_key.clone_from_slice(public_key_bytes);
+
+        let verified = taproot_signature_verification(
+            signature_bytes,
+            &public_key,
+            transaction,
+            input_index,
+            tapscript.annex,
+            Some((tapscript.leaf_hash, code_separator_position)),
+            tapscript.cache,
+        );
+
+        if !verified { return Err(opchecksig_error()) }
+    }
+
+    Ok(true)
+}
+
+/// Returns the opcode position of the last `OP_CODESEPARATOR` preceding a signature checking
+/// opcode of a Tapscript leaf script (or `NO_CODE_SEPARATOR_POSITION`), as committed to by its
+/// signature hash.
+///
+/// Each script element (including data pushes) counts as one opcode.
+pub fn tapscript_code_separator_position(script: &Script, instruction_pointer: usize) -> u32 {
+    let is_code_separator = |element: &Element| {
+        matches!(element, Element::Opcode(Opcode::Cryptographic(CryptographicOpcode::OpCodeSeparator)))
+    };
+
+    match script.elements().iter().take(instruction_pointer).rposition(is_code_separator) {
+        Some(n) => u32::try_from(n).unwrap(),
+        None => NO_CODE_SEPARATOR_POSITION,
+    }
+}
+
+fn opchecksig_error() -> ScriptError {
+    ScriptError::OpcodeFailed(Opcode::Cryptographic(CryptographicOpcode::OpCheckSig))
+}
@@ -4,5 +4,8 @@ mod script_types;
 
 pub mod p2pkh;
 pub mod p2sh;
+pub mod p2wpkh;
+pub mod p2wsh;
+pub mod p2tr;
 
 pub use script_types::BitcoinTransactionType;
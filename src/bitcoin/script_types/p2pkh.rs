@@ -18,7 +18,7 @@
 //! - `OP_CHECKSIG`
 
 use crate::bitcoin::transaction::Transaction;
-use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
 use crate::bitcoin::transaction::signing::TransactionSigningError;
 use crate::bitcoin::script::signature_signing_hash;
 use crate::bitcoin::script::TransactionInputCommitment;
@@ -35,7 +35,6 @@ use crate::crypto::digest::hash_160;
 use crate::serialization::signature::SignatureDerFormatBytes;
 use crate::serialization::point::CompressedPointSecFormatBytes;
 use crate::serialization::point::UncompressedPointSecFormatBytes;
-use crate::serialization::bitcoin_address::BitcoinAddress;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 use crate::util::number::U256;
@@ -84,40 +83,22 @@ impl ScriptBytes {
 
         ScriptBytes::of(&bytes)
     }
-
-    /// Creates a locking script for a P2PKH UTXO (given a P2PKH address).
-    pub fn locking_script_p2pkh_address(address: &BitcoinAddress) -> Self {
-        assert_eq!(address.transaction_type(), BitcoinTransactionType::P2pkh);
-
-        let mut bytes = [0_u8; 25];
-
-        bytes[0] = 0x76_u8;
-        bytes[1] = 0xa9_u8;
-        bytes[2] = 0x14_u8;
-        bytes[3..=22].clone_from_slice(address.hash_bytes());
-        bytes[23] = 0x88_u8;
-        bytes[24] = 0xac_u8;
-
-        ScriptBytes::of(&bytes)
-    }
 }
 
 impl Transaction {
     /// Sign a transaction input against a P2PKH UTXO.
-    pub fn signed_input_bytes_p2pkh<'a, F>(
-        &'a self,
+    pub fn signed_input_bytes_p2pkh<P>(
+        &self,
         n: usize,
         secret_e: U256,
         sighash: SigHashFlag,
-        transaction_retrieval: F
+        utxo_provider: &P
     ) -> Result<ScriptBytes, TransactionSigningError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
+    where P: UtxoProvider + ?Sized {
         let input = &self.inputs[n];
 
-        let prev_txn = transaction_retrieval(&input.txid).ok_or(TransactionSigningError::NoPreviousTransactionFound(input.txid))?;
-        let utxo_index = usize::try_from(input.utxo_index)?;
-
-        let utxo_script_bytes = &prev_txn.utxos.get(utxo_index).ok_or(TransactionSigningError::NoInputFound(input.txid, utxo_index))?.script;
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+        let utxo_script_bytes = &entry.utxo.script;
 
         if !utxo_script_bytes.is_p2pkh_locking() { return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::P2pkh)) }
 
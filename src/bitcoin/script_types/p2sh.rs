@@ -28,7 +28,7 @@
 //! - `OP_EQUAL`
 
 use crate::bitcoin::transaction::Transaction;
-use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
 use crate::bitcoin::transaction::signing::TransactionSigningError;
 use crate::bitcoin::script::signature_signing_hash;
 use crate::bitcoin::script::TransactionInputCommitment;
@@ -42,7 +42,6 @@ use crate::bitcoin::script_types::BitcoinTransactionType;
 use crate::crypto::ecdsa::signature::Signature;
 use crate::crypto::digest::hash_160;
 use crate::serialization::signature::SignatureDerFormatBytes;
-use crate::serialization::bitcoin_address::BitcoinAddress;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 use crate::util::number::U256;
@@ -73,20 +72,6 @@ impl ScriptBytes {
 
         ScriptBytes::of(&bytes)
     }
-
-    /// Creates a locking script for a P2SH UTXO (given a P2SH address).
-    pub fn locking_script_p2sh_address(address: &BitcoinAddress) -> Self {
-        assert_eq!(address.transaction_type(), BitcoinTransactionType::P2sh);
-
-        let mut bytes = [0_u8; 23];
-
-        bytes[0] = 0xa9_u8;
-        bytes[1] = 0x14_u8;
-        bytes[1..=20].clone_from_slice(address.hash_bytes());
-        bytes[21] = 0x87_u8;
-
-        ScriptBytes::of(&bytes)
-    }
 }
 
 impl Transaction {
@@ -97,20 +82,18 @@ impl Transaction {
     ///
     /// Additional script (expressed as bytes) may also be provided, which will be placed before
     /// the redeem script data element in the returned signing transaction input script.
-    pub fn redeem_script_input_bytes_p2sh<'a, F>(
-        &'a self,
+    pub fn redeem_script_input_bytes_p2sh<P>(
+        &self,
         n: usize,
         additional_script: Option<&ScriptBytes>,
         redeem_script: &ScriptBytes,
-        transaction_retrieval: F
+        utxo_provider: &P
     ) -> Result<ScriptBytes, TransactionSigningError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
+    where P: UtxoProvider + ?Sized {
         let input = &self.inputs[n];
 
-        let prev_txn = transaction_retrieval(&input.txid).ok_or(TransactionSigningError::NoPreviousTransactionFound(input.txid))?;
-        let utxo_index = usize::try_from(input.utxo_index)?;
-
-        let utxo_script_bytes = &prev_txn.utxos.get(utxo_index).ok_or(TransactionSigningError::NoInputFound(input.txid, utxo_index))?.script;
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+        let utxo_script_bytes = &entry.utxo.script;
 
         if !utxo_script_bytes.is_p2sh_locking() { return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::P2sh)) }
 
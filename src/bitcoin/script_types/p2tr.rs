@@ -0,0 +1,60 @@
+//! P2TR (pay to Taproot).
+//!
+//! A version 1 witness program (BIP-341) of a 32 byte (x-only) output key. The transaction input
+//! script is empty; the UTXO is unlocked by the segregated witness field, along either the key
+//! path or the script path (see `bitcoin::taproot`).
+//!
+//! Unlike version 0 witness programs, P2TR may not be wrapped in P2SH.
+//!
+//! # Segregated witness field pattern (key path)
+//!
+//! - **`<Schnorr signature bytes>`** (64 bytes, or 65 bytes with a `SIGHASH` flag byte)
+//! - _`<annex>`_ (optional; begins with `0x50`)
+//!
+//! # Segregated witness field pattern (script path)
+//!
+//! - **`<leaf script arguments...>`**
+//! - **`<leaf script>`**
+//! - **`<control block>`**
+//! - _`<annex>`_ (optional; begins with `0x50`)
+//!
+//! # Locking script pattern
+//!
+//! - `OP_1`
+//! - _`0x20`_
+//! - **`<32 byte output key>`**
+
+use crate::bitcoin::script::ScriptBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+
+impl ScriptBytes {
+    /// Indicates that these script bytes match a P2TR locking script template.
+    pub fn is_p2tr_locking(&self) -> bool {
+        let bytes = self.bytes();
+
+        (bytes.len() == 34) && (bytes[0] == 0x51_u8) && (bytes[1] == 0x20_u8)
+    }
+
+    /// Returns the (x-only) output key that this locking script references.
+    pub fn locking_taproot_output_key(&self) -> [u8; 32] {
+        assert!(self.is_p2tr_locking());
+
+        let mut output_key = [0_u8; 32];
+
+        output_key.clone_from_slice(&self.bytes()[2..34]);
+
+        output_key
+    }
+
+    /// Creates a locking script for a P2TR UTXO (given an x-only output key).
+    pub fn locking_script_p2tr_output_key(output_key: &[u8; 32]) -> Self {
+        let mut bytes = [0_u8; 34];
+
+        bytes[0] = 0x51_u8;
+        bytes[1] = 0x20_u8;
+        bytes[2..=33].clone_from_slice(output_key);
+
+        ScriptBytes::of(&bytes)
+    }
+}
@@ -0,0 +1,128 @@
+//! P2WPKH (pay to witness public key hash).
+//!
+//! A version 0 witness program (BIP-141) committing to a 20 byte public key hash. The transaction
+//! input script is empty; the signature and public key are instead provided by the segregated
+//! witness field, and the signature commits to the BIP-143 signature hash.
+//!
+//! The witness program may also be used as a P2SH redeem script (P2SH-P2WPKH); the transaction
+//! input script is then a single data push of the redeem script (see
+//! `Transaction::redeem_script_input_bytes_p2sh`).
+//!
+//! # Segregated witness field pattern
+//!
+//! - **`<DER signature format bytes>`**
+//! - **`<SEC point format bytes>`** (compressed)
+//!
+//! # Locking script pattern
+//!
+//! - `OP_0`
+//! - _`0x14`_
+//! - **`<20 byte public key hash>`**
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::signing::TransactionSigningError;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::segwit::item::SegWitItem;
+use crate::bitcoin::script::segwit_signature_hash;
+use crate::bitcoin::script::SegWitSignatureHashCache;
+use crate::bitcoin::script::DefaultOpCheckSigDigest;
+use crate::bitcoin::script::SigHashFlag;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::crypto::ecdsa::signature::Signature;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::digest::hash_160;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::serialization::point::CompressedPointSecFormatBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+use crate::util::number::U256;
+
+impl ScriptBytes {
+    /// Indicates that these script bytes match a P2WPKH locking script template.
+    pub fn is_p2wpkh_locking(&self) -> bool {
+        let bytes = self.bytes();
+
+        (bytes.len() == 22) && (bytes[0] == 0x00_u8) && (bytes[1] == 0x14_u8)
+    }
+
+    /// Returns the public key hash (20 byte `OP_HASH160`) of a compressed elliptic curve point
+    /// byte representation that this locking script references.
+    pub fn locking_witness_public_key_hash(&self) -> &[u8] {
+        assert!(self.is_p2wpkh_locking());
+
+        &self.bytes()[2..22]
+    }
+
+    /// Creates a locking script for a P2WPKH UTXO (given a compressed point byte representation).
+    ///
+    /// This is also the redeem script of a P2SH-P2WPKH UTXO.
+    pub fn locking_script_p2wpkh_compressed_point(point_bytes: &CompressedPointSecFormatBytes) -> Self {
+        let mut bytes = [0_u8; 22];
+
+        bytes[0] = 0x00_u8;
+        bytes[1] = 0x14_u8;
+        bytes[2..=21].clone_from_slice(&hash_160(point_bytes.bytes()));
+
+        ScriptBytes::of(&bytes)
+    }
+
+    /// Indicates that this locking script is a witness program, or a P2SH locking script whose
+    /// redeem script is the witness program.
+    pub(crate) fn is_witness_program_locking(&self, witness_program: &ScriptBytes) -> bool {
+        if self == witness_program { return true }
+
+        self.is_p2sh_locking() && self.locking_redeem_script_hash() == hash_160(witness_program.bytes())
+    }
+}
+
+impl Transaction {
+    /// Sign a transaction input against a P2WPKH (or P2SH-P2WPKH) UTXO.
+    ///
+    /// Returns the segregated witness field, containing the signature and the compressed public
+    /// key.
+    pub fn signed_witness_p2wpkh<P>(
+        &self,
+        n: usize,
+        secret_e: U256,
+        sighash: SigHashFlag,
+        utxo_provider: &P
+    ) -> Result<SegWitField, TransactionSigningError>
+    where P: UtxoProvider + ?Sized {
+        let input = self.inputs.get(n).ok_or(TransactionSigningError::InputNotFound(n))?;
+
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+        let utxo_script_bytes = &entry.utxo.script;
+
+        let point = secret_e * Secp256k1Point::generator_point();
+        let point_bytes = CompressedPointSecFormatBytes::from(&point.into());
+
+        let witness_program = ScriptBytes::locking_script_p2wpkh_compressed_point(&point_bytes);
+
+        if !utxo_script_bytes.is_witness_program_locking(&witness_program) {
+            return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::P2wpkh));
+        }
+
+        let script_code = witness_program.segwit_script_code(None).unwrap();
+
+        let digest = segwit_signature_hash(
+            self,
+            n,
+            &script_code,
+            entry.utxo.amount,
+            sighash,
+            &SegWitSignatureHashCache::new(self),
+            &DefaultOpCheckSigDigest {},
+        ).ok_or(TransactionSigningError::InputNotFound(n))?;
+
+        let signature = Signature::sign_secp256k1(U256::from_be_bytes(digest), secret_e);
+        let signature_bytes = SignatureDerFormatBytes::from(&signature);
+
+        let mut signature_bytes = signature_bytes.bytes().to_vec();
+
+        signature_bytes.push(sighash.byte());
+
+        Ok(SegWitField::new(vec![SegWitItem::of(&signature_bytes), SegWitItem::of(point_bytes.bytes())]))
+    }
+}
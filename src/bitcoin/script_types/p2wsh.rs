@@ -0,0 +1,241 @@
+//! P2WSH (pay to witness script hash).
+//!
+//! A version 0 witness program (BIP-141) committing to the 32 byte SHA-256 digest of a witness
+//! script. The transaction input script is empty; the segregated witness field provides the
+//! witness script (as its last item) and its arguments, and signatures commit to the BIP-143
+//! signature hash.
+//!
+//! Unlike P2SH, the witness script is not pushed by a script; it is executed with the remaining
+//! segregated witness items as its initial stack.
+//!
+//! The witness program may also be used as a P2SH redeem script (P2SH-P2WSH); the transaction
+//! input script is then a single data push of the redeem script (see
+//! `Transaction::redeem_script_input_bytes_p2sh`).
+//!
+//! # Segregated witness field pattern
+//!
+//! - `[<...>]`
+//! - **`<witness script bytes>`**
+//!
+//! # Locking script pattern
+//!
+//! - `OP_0`
+//! - _`0x20`_
+//! - **`<32 byte witness script hash>`**
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::signing::TransactionSigningError;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::segwit::item::SegWitItem;
+use crate::bitcoin::script::segwit_signature_hash;
+use crate::bitcoin::script::SegWitSignatureHashCache;
+use crate::bitcoin::script::DefaultOpCheckSigDigest;
+use crate::bitcoin::script::SigHashFlag;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::crypto::ecdsa::signature::Signature;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::digest::sha_256;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::serialization::point::CompressedPointSecFormatBytes;
+use crate::serialization::point::UncompressedPointSecFormatBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+use crate::util::number::U256;
+
+impl ScriptBytes {
+    /// Indicates that these script bytes match a P2WSH locking script template.
+    pub fn is_p2wsh_locking(&self) -> bool {
+        let bytes = self.bytes();
+
+        (bytes.len() == 34) && (bytes[0] == 0x00_u8) && (bytes[1] == 0x20_u8)
+    }
+
+    /// Returns the witness script hash (32 byte SHA-256) that this locking script references.
+    pub fn locking_witness_script_hash(&self) -> &[u8] {
+        assert!(self.is_p2wsh_locking());
+
+        &self.bytes()[2..34]
+    }
+
+    /// Creates a locking script for a P2WSH UTXO.
+    ///
+    /// This is also the redeem script of a P2SH-P2WSH UTXO.
+    pub fn locking_script_p2wsh_witness_script(witness_script_bytes: &ScriptBytes) -> Self {
+        let mut bytes = [0_u8; 34];
+
+        bytes[0] = 0x00_u8;
+        bytes[1] = 0x20_u8;
+        bytes[2..=33].clone_from_slice(&sha_256(witness_script_bytes.bytes()));
+
+        ScriptBytes::of(&bytes)
+    }
+}
+
+impl Transaction {
+    /// Creates the segregated witness field of a transaction input spending a P2WSH (or
+    /// P2SH-P2WSH) UTXO.
+    ///
+    /// Requires the witness script (expressed as bytes) to be provided, which will be placed as
+    /// the last item.
+    ///
+    /// Arguments to the witness script (e.g.; signatures) are placed before the witness script, in
+    /// order.
+    pub fn witness_script_field_p2wsh<P>(
+        &self,
+        n: usize,
+        arguments: Vec<SegWitItem>,
+        witness_script: &ScriptBytes,
+        utxo_provider: &P
+    ) -> Result<SegWitField, TransactionSigningError>
+    where P: UtxoProvider + ?Sized {
+        let input = self.inputs.get(n).ok_or(TransactionSigningError::InputNotFound(n))?;
+
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+        let witness_program = ScriptBytes::locking_script_p2wsh_witness_script(witness_script);
+
+        if !entry.utxo.script.is_witness_program_locking(&witness_program) {
+            return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::P2wsh));
+        }
+
+        let mut items = arguments;
+
+        items.push(SegWitItem::of(witness_script.bytes()));
+
+        Ok(SegWitField::new(items))
+    }
+
+    /// Sign a transaction input against a P2WSH (or P2SH-P2WSH) UTXO.
+    ///
+    /// Creates a signature against a particular witness script.
+    ///
+    /// Returns a segregated witness item containing the signature.
+    pub fn signed_witness_item_p2wsh<P>(
+        &self,
+        n: usize,
+        secret_e: U256,
+        witness_script: &ScriptBytes,
+        sighash: SigHashFlag,
+        utxo_provider: &P
+    ) -> Result<SegWitItem, TransactionSigningError>
+    where P: UtxoProvider + ?Sized {
+        let cache = SegWitSignatureHashCache::new(self);
+
+        self.signed_witness_item_p2wsh_cached(n, secret_e, witness_script, sighash, &cache, utxo_provider)
+    }
+
+    /// Sign a transaction input against a P2WSH (or P2SH-P2WSH) UTXO, whose witness script is a
+    /// multisig locking script (`OP_CHECKMULTISIG`).
+    ///
+    /// Exactly `m` distinct secrets must be given, in any order; their signatures are ordered as
+    /// their public keys appear within the witness script. Any other number of signatures would
+    /// leave the stack unclean, or fail `OP_CHECKMULTISIG`.
+    ///
+    /// Returns the complete segregated witness field; the empty dummy element consumed by
+    /// `OP_CHECKMULTISIG`, the signatures, and the witness script.
+    pub fn signed_witness_p2wsh_multisig<P>(
+        &self,
+        n: usize,
+        secrets_e: &[U256],
+        witness_script: &ScriptBytes,
+        sighash: SigHashFlag,
+        utxo_provider: &P
+    ) -> Result<SegWitField, TransactionSigningError>
+    where P: UtxoProvider + ?Sized {
+        if !witness_script.is_multisig_locking() {
+            return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::Multisig));
+        }
+
+        // The number of signatures required (`m`) is the first opcode of the witness script.
+        let m = usize::from(witness_script.bytes()[0] - 0x50_u8);
+
+        if secrets_e.len() != m { return Err(TransactionSigningError::SignatureCountMismatch) }
+
+        for (i, secret_e) in secrets_e.iter().enumerate() {
+            if secrets_e[..i].contains(secret_e) { return Err(TransactionSigningError::DuplicateSecret) }
+        }
+
+        let public_keys: Vec<Vec<u8>> = Script::try_from(witness_script)?
+            .elements()
+            .iter()
+            .filter_map(|element| match element {
+                Element::Data(data) => Some(data.bytes().to_vec()),
+                _ => None,
+            })
+            .collect();
+
+        // Order the secrets by the position of their public key within the witness script.
+        let mut ordered_secrets: Vec<(usize, U256)> = Vec::with_capacity(secrets_e.len());
+
+        for secret_e in secrets_e {
+            let point = (*secret_e) * Secp256k1Point::generator_point();
+
+            let compressed = CompressedPointSecFormatBytes::from(&point.into());
+            let uncompressed = UncompressedPointSecFormatBytes::from(&point.into());
+
+            let position = public_keys
+                .iter()
+                .position(|public_key| public_key == compressed.bytes() || public_key == uncompressed.bytes())
+                .ok_or(TransactionSigningError::PublicKeyNotFound)?;
+
+            ordered_secrets.push((position, *secret_e));
+        }
+
+        ordered_secrets.sort_by_key(|(position, _)| *position);
+
+        let cache = SegWitSignatureHashCache::new(self);
+
+        // The dummy element consumed by "OP_CHECKMULTISIG" must be empty (BIP-147).
+        let mut arguments: Vec<SegWitItem> = vec![SegWitItem::of(&[])];
+
+        for (_, secret_e) in ordered_secrets {
+            arguments.push(self.signed_witness_item_p2wsh_cached(n, secret_e, witness_script, sighash, &cache, utxo_provider)?);
+        }
+
+        self.witness_script_field_p2wsh(n, arguments, witness_script, utxo_provider)
+    }
+
+    /// Sign a transaction input against a P2WSH (or P2SH-P2WSH) UTXO, reusing the digests shared
+    /// by each signature hash.
+    fn signed_witness_item_p2wsh_cached<P>(
+        &self,
+        n: usize,
+        secret_e: U256,
+        witness_script: &ScriptBytes,
+        sighash: SigHashFlag,
+        cache: &SegWitSignatureHashCache,
+        utxo_provider: &P
+    ) -> Result<SegWitItem, TransactionSigningError>
+    where P: UtxoProvider + ?Sized {
+        let input = self.inputs.get(n).ok_or(TransactionSigningError::InputNotFound(n))?;
+
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+        let witness_program = ScriptBytes::locking_script_p2wsh_witness_script(witness_script);
+
+        if !entry.utxo.script.is_witness_program_locking(&witness_program) {
+            return Err(TransactionSigningError::LockingScriptNotFound(BitcoinTransactionType::P2wsh));
+        }
+
+        let digest = segwit_signature_hash(
+            self,
+            n,
+            witness_script,
+            entry.utxo.amount,
+            sighash,
+            cache,
+            &DefaultOpCheckSigDigest {},
+        ).ok_or(TransactionSigningError::InputNotFound(n))?;
+
+        let signature = Signature::sign_secp256k1(U256::from_be_bytes(digest), secret_e);
+        let signature_bytes = SignatureDerFormatBytes::from(&signature);
+
+        let mut signature_bytes = signature_bytes.bytes().to_vec();
+
+        signature_bytes.push(sighash.byte());
+
+        Ok(SegWitItem::of(&signature_bytes))
+    }
+}
@@ -1,6 +1,7 @@
 //! Bitcoin transaction script types.
 
 use crate::bitcoin::script::ScriptBytes;
+use crate::util::byte_string::ByteSlice;
 
 /// Represents a Bitcoin transaction script type.
 #[derive(Debug)]
@@ -10,11 +11,50 @@ pub enum BitcoinTransactionType {
     /// Unknown transaction type.
     Unknown,
 
+    /// P2PK (pay to public key).
+    P2pk,
+
     /// P2PKH (pay to public key hash).
     P2pkh,
 
     /// P2SH (pay to script hash).
     P2sh,
+
+    /// Bare multisig (`m <public keys...> n OP_CHECKMULTISIG`).
+    Multisig,
+
+    /// Null data (`OP_RETURN`, followed only by data pushes).
+    NullData,
+
+    /// P2WPKH (pay to witness public key hash); version 0 witness program, 20 bytes.
+    P2wpkh,
+
+    /// P2WSH (pay to witness script hash); version 0 witness program, 32 bytes.
+    P2wsh,
+
+    /// P2TR (pay to Taproot); version 1 witness program, 32 bytes.
+    P2tr,
+
+    /// Witness program of some future (undefined) witness version.
+    WitnessUnknown,
+}
+
+impl BitcoinTransactionType {
+    /// Returns the name given to the script type by Bitcoin Core (e.g.; `pubkeyhash`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unknown => "nonstandard",
+            Self::P2pk => "pubkey",
+            Self::P2pkh => "pubkeyhash",
+            Self::P2sh => "scripthash",
+            Self::Multisig => "multisig",
+            Self::NullData => "nulldata",
+            Self::P2wpkh => "witness_v0_keyhash",
+            Self::P2wsh => "witness_v0_scripthash",
+            Self::P2tr => "witness_v1_taproot",
+            Self::WitnessUnknown => "witness_unknown",
+        }
+    }
 }
 
 impl ScriptBytes {
@@ -23,6 +63,127 @@ impl ScriptBytes {
         if self.is_p2pkh_locking() { return BitcoinTransactionType::P2pkh }
         if self.is_p2sh_locking() { return BitcoinTransactionType::P2sh }
 
+        if let Some((version, program)) = self.witness_program() {
+            return match (version, program.len()) {
+                (0, 20) => BitcoinTransactionType::P2wpkh,
+                (0, 32) => BitcoinTransactionType::P2wsh,
+                (0, _) => BitcoinTransactionType::Unknown,
+                (1, 32) => BitcoinTransactionType::P2tr,
+                _ => BitcoinTransactionType::WitnessUnknown,
+            };
+        }
+
+        if self.is_null_data() { return BitcoinTransactionType::NullData }
+        if self.is_p2pk_locking() { return BitcoinTransactionType::P2pk }
+        if self.is_multisig_locking() { return BitcoinTransactionType::Multisig }
+
         return BitcoinTransactionType::Unknown;
     }
+
+    /// Returns the witness version and witness program, if these script bytes are a witness
+    /// program (BIP-141); i.e.; a version opcode (`OP_0`, `OP_1` to `OP_16`) followed by a single
+    /// data push of 2 to 40 bytes.
+    pub fn witness_program(&self) -> Option<(u8, &[u8])> {
+        let bytes = self.bytes();
+
+        if !(4..=42).contains(&bytes.len()) { return None }
+        if usize::from(bytes[1]) + 2 != bytes.len() { return None }
+
+        match bytes[0] {
+            0x00_u8 => Some((0, &bytes[2..])),
+            version @ 0x51_u8..=0x60_u8 => Some((version - 0x50_u8, &bytes[2..])),
+            _ => None,
+        }
+    }
+
+    /// Indicates that these script bytes match a P2PK locking script template
+    /// (`<public key> OP_CHECKSIG`).
+    pub fn is_p2pk_locking(&self) -> bool {
+        let bytes = self.bytes();
+
+        match bytes.split_last() {
+            Some((0xac_u8, push)) => single_public_key_push(push),
+            _ => false,
+        }
+    }
+
+    /// Indicates that these script bytes match a bare multisig locking script template
+    /// (`m <public keys...> n OP_CHECKMULTISIG`).
+    pub fn is_multisig_locking(&self) -> bool {
+        let bytes = self.bytes();
+
+        if bytes.len() < 3 || bytes[bytes.len() - 1] != 0xae_u8 { return false }
+
+        let small_integer = |opcode: u8| match opcode {
+            0x51_u8..=0x60_u8 => Some(opcode - 0x50_u8),
+            _ => None,
+        };
+
+        let (Some(m), Some(n)) = (small_integer(bytes[0]), small_integer(bytes[bytes.len() - 2])) else { return false };
+
+        let mut index: usize = 1;
+        let mut keys: u8 = 0;
+
+        while index < bytes.len() - 2 {
+            let length = usize::from(bytes[index]);
+
+            if index + 1 + length > bytes.len() - 2 { return false }
+            if !single_public_key_push(&bytes[index..(index + 1 + length)]) { return false }
+
+            index += 1 + length;
+            keys += 1;
+
+            // At most 16 public keys are expressible with a small integer.
+            if keys > 16 { return false }
+        }
+
+        keys == n && m <= n
+    }
+
+    /// Indicates that these script bytes are a null data script (`OP_RETURN`, followed only by
+    /// data pushes).
+    pub fn is_null_data(&self) -> bool {
+        let bytes = self.bytes();
+
+        if bytes.is_empty() || bytes[0] != 0x6a_u8 { return false }
+
+        let mut index: usize = 1;
+
+        while index < bytes.len() {
+            let opcode = bytes[index];
+            index += 1;
+
+            let length = match opcode {
+                0x00_u8..=0x4b_u8 => usize::from(opcode),
+                0x4c_u8..=0x4e_u8 => {
+                    let length_bytes: usize = match opcode { 0x4c_u8 => 1, 0x4d_u8 => 2, _ => 4 };
+
+                    if index + length_bytes > bytes.len() { return false }
+
+                    let mut buffer = [0_u8; 4];
+                    buffer[0..length_bytes].clone_from_slice(&bytes[index..(index + length_bytes)]);
+                    index += length_bytes;
+
+                    usize::try_from(u32::from_le_bytes(buffer)).unwrap()
+                },
+                0x4f_u8..=0x60_u8 => 0,
+                _ => return false,
+            };
+
+            if bytes.len() - index < length { return false }
+
+            index += length;
+        }
+
+        true
+    }
+}
+
+/// Indicates that some bytes are a single data push of a (compressed or uncompressed) public key.
+fn single_public_key_push(bytes: &[u8]) -> bool {
+    match bytes.split_first() {
+        Some((0x21_u8, key)) => key.len() == 33 && (key[0] == 0x02_u8 || key[0] == 0x03_u8),
+        Some((0x41_u8, key)) => key.len() == 65 && (key[0] == 0x04_u8 || key[0] == 0x06_u8 || key[0] == 0x07_u8),
+        _ => false,
+    }
 }
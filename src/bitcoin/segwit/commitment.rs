@@ -0,0 +1,128 @@
+//! Segregated witness block commitments (BIP-141).
+//!
+//! A block's merkle root commits only to its transaction IDs, and so not to any witness data. A
+//! block containing segregated witness transactions instead commits to the merkle root of its
+//! witness transaction IDs, within a UTXO of its coinbase transaction.
+//!
+//! - The coinbase transaction's witness transaction ID is taken to be zero (as it cannot commit
+//!   to itself).
+//! - The commitment is `HASH_256(witness merkle root || witness reserved value)`, where the
+//!   witness reserved value is the single 32 byte item of the coinbase transaction's segregated
+//!   witness field.
+//! - If no commitment is present, then no transaction within the block may have witness data.
+
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::coinbase::witness_commitment_script;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::wtxid::WitnessTransactionId;
+use crate::crypto::digest::hash_256;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue32;
+
+/// Errors thrown when validating a block's witness commitment.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum WitnessCommitmentError {
+    /// The block has no transactions, or its first transaction is not a coinbase transaction.
+    CoinbaseNotFound,
+
+    /// The coinbase transaction's segregated witness field is not a single 32 byte witness
+    /// reserved value.
+    WitnessReservedValueSize,
+
+    /// The witness commitment does not match the block's transactions.
+    Mismatch,
+
+    /// The block has no witness commitment, yet a transaction has witness data.
+    UnexpectedWitness,
+}
+
+/// Compute the merkle root of some (little-endian) hashes, as Bitcoin does; i.e.; each level
+/// pairs and concatenates hashes (duplicating the last hash of an odd level), and hashes each pair
+/// with `HASH_256`.
+///
+/// The merkle root of no hashes is zero.
+pub fn merkle_root(hashes: &[[u8; 32]]) -> [u8; 32] {
+    if hashes.is_empty() { return [0_u8; 32] }
+
+    let mut level: Vec<[u8; 32]> = hashes.to_vec();
+
+    while level.len() > 1 {
+        if level.len() % 2 == 1 { level.push(*level.last().unwrap()) }
+
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let mut preimage = [0_u8; 64];
+
+                preimage[0..32].clone_from_slice(&pair[0]);
+                preimage[32..64].clone_from_slice(&pair[1]);
+
+                hash_256(preimage)
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+/// Compute the witness merkle root of some witness transaction IDs.
+///
+/// The first witness transaction ID (the coinbase transaction's) is taken to be zero, whatever it
+/// is given as.
+pub fn witness_merkle_root(wtxids: &[WitnessTransactionId]) -> [u8; 32] {
+    let hashes: Vec<[u8; 32]> = wtxids
+        .iter()
+        .enumerate()
+        .map(|(i, wtxid)| if i == 0 { [0_u8; 32] } else { wtxid.bytes() })
+        .collect();
+
+    merkle_root(&hashes)
+}
+
+/// Compute the witness merkle root of a block's transactions (the coinbase transaction first).
+pub fn block_witness_merkle_root(transactions: &[SegWitTransaction]) -> [u8; 32] {
+    let wtxids: Vec<WitnessTransactionId> = transactions.iter().map(|transaction| transaction.wtxid()).collect();
+
+    witness_merkle_root(&wtxids)
+}
+
+/// Creates a witness commitment UTXO, to be appended to a coinbase transaction's UTXOs.
+pub fn witness_commitment_utxo(witness_merkle_root: [u8; 32], witness_reserved_value: [u8; 32]) -> UnspentTransactionOutput {
+    UnspentTransactionOutput::new(Amount::ZERO, witness_commitment_script(witness_merkle_root, witness_reserved_value))
+}
+
+/// Validates that a block's coinbase transaction commits to the witness data of the block's
+/// transactions (the coinbase transaction first).
+///
+/// A block without a witness commitment is valid only if no transaction has witness data.
+pub fn verify_witness_commitment(transactions: &[SegWitTransaction]) -> Result<(), WitnessCommitmentError> {
+    let coinbase = match transactions.first() {
+        Some(transaction) if transaction.is_coinbase() => transaction,
+        _ => return Err(WitnessCommitmentError::CoinbaseNotFound),
+    };
+
+    let Some(commitment) = coinbase.witness_commitment() else {
+        if transactions.iter().any(|transaction| transaction.has_witness()) {
+            return Err(WitnessCommitmentError::UnexpectedWitness);
+        }
+
+        return Ok(());
+    };
+
+    let segwit_field = &coinbase.segwit_fields()[0];
+
+    if segwit_field.len() != 1 || segwit_field[0].bytes().len() != 32 {
+        return Err(WitnessCommitmentError::WitnessReservedValueSize);
+    }
+
+    let mut preimage = [0_u8; 64];
+
+    preimage[0..32].clone_from_slice(&block_witness_merkle_root(transactions));
+    preimage[32..64].clone_from_slice(segwit_field[0].bytes());
+
+    if hash_256(preimage) != commitment { return Err(WitnessCommitmentError::Mismatch) }
+
+    Ok(())
+}
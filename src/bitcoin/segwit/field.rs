@@ -10,6 +10,7 @@ use crate::util::varint::varint_u64;
 
 #[derive(Debug)]
 #[derive(Clone)]
+#[derive(PartialEq)]
 pub struct SegWitField {
     items: Vec<SegWitItem>
 }
@@ -22,7 +23,18 @@ impl std::ops::Deref for SegWitField {
     }
 }
 
+impl std::ops::DerefMut for SegWitField {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
+
 impl SegWitField {
+    /// Create a segregated witness field consisting of some items.
+    pub fn new(items: Vec<SegWitItem>) -> Self {
+        Self { items: items }
+    }
+
     /// Parse segregated witness fields and items from a sequence of bytes.
     pub fn parse_bytes(bytes: &[u8]) -> Result<(Self, usize), TransactionParsingError> {
         let mut field: Vec<SegWitItem> = vec![];
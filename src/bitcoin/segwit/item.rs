@@ -5,6 +5,7 @@ use crate::util::byte_string::ByteSlice;
 use crate::util::hexadecimal::hexadecimal_encode;
 
 #[derive(Clone)]
+#[derive(PartialEq)]
 pub struct SegWitItem {
     bytes: Vec<u8>
 }
@@ -4,3 +4,4 @@ pub mod transaction;
 pub mod wtxid;
 pub mod field;
 pub mod item;
+pub mod commitment;
@@ -8,6 +8,7 @@ use crate::bitcoin::transaction::UnspentTransactionOutput;
 use crate::bitcoin::transaction::Locktime;
 use crate::bitcoin::segwit::wtxid::WitnessTransactionId;
 use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::script::ScriptBytes;
 use crate::crypto::digest::hash_256;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteVector;
@@ -16,6 +17,17 @@ use crate::util::byte_value::ByteValue4;
 use crate::util::varint::read_varint_u64;
 use crate::util::varint::varint_u64;
 
+/// Errors thrown when constructing (or modifying) a segregated witness transaction.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum SegWitTransactionError {
+    /// The number of segregated witness fields differs from the number of transaction inputs.
+    FieldCountMismatch,
+
+    /// No transaction input exists at some index.
+    NoInputFound(usize),
+}
+
 /// A Bitcoin transaction, with witness data.
 #[derive(Debug)]
 #[derive(Clone)]
@@ -25,6 +37,84 @@ pub struct SegWitTransaction {
 }
 
 impl SegWitTransaction {
+    /// Create a segregated witness transaction, given a segregated witness field for each
+    /// transaction input.
+    pub fn new(transaction: Transaction, segwit_fields: Vec<SegWitField>) -> Result<Self, SegWitTransactionError> {
+        if transaction.inputs.len() != segwit_fields.len() { return Err(SegWitTransactionError::FieldCountMismatch) }
+
+        Ok(
+            Self {
+                transaction: transaction,
+                segwit_fields: segwit_fields
+            }
+        )
+    }
+
+    /// Create a segregated witness transaction, with an empty segregated witness field for each
+    /// transaction input.
+    pub fn from_transaction(transaction: Transaction) -> Self {
+        let segwit_fields = vec![SegWitField::new(vec![]); transaction.inputs.len()];
+
+        Self {
+            transaction: transaction,
+            segwit_fields: segwit_fields
+        }
+    }
+
+    /// Returns the segregated witness fields, one for each transaction input.
+    pub fn segwit_fields(&self) -> &[SegWitField] {
+        &self.segwit_fields
+    }
+
+    /// Returns the segregated witness fields (mutably), one for each transaction input.
+    pub fn segwit_fields_mut(&mut self) -> &mut [SegWitField] {
+        &mut self.segwit_fields
+    }
+
+    /// Returns a transaction input's segregated witness field (mutably), if it exists.
+    pub fn segwit_field_mut(&mut self, n: usize) -> Option<&mut SegWitField> {
+        self.segwit_fields.get_mut(n)
+    }
+
+    /// Replaces a transaction input's segregated witness field, if the transaction input exists.
+    pub fn set_segwit_field(&mut self, n: usize, segwit_field: SegWitField) -> Result<(), SegWitTransactionError> {
+        *self.segwit_field_mut(n).ok_or(SegWitTransactionError::NoInputFound(n))? = segwit_field;
+
+        Ok(())
+    }
+
+    /// Indicates that any transaction input's segregated witness field is not empty; i.e.; that
+    /// the transaction is serialized with the segregated witness marker and flag.
+    pub fn has_witness(&self) -> bool {
+        self.segwit_fields.iter().any(|field| !field.is_empty())
+    }
+
+    /// Returns the transaction without its segregated witness data (a.k.a. the "stripped"
+    /// transaction).
+    pub fn stripped(&self) -> Transaction {
+        self.transaction.clone()
+    }
+
+    /// Separates the transaction from its segregated witness fields.
+    pub fn into_parts(self) -> (Transaction, Vec<SegWitField>) {
+        (self.transaction, self.segwit_fields)
+    }
+
+    /// Sets a transaction input's script and segregated witness field; i.e.; once it has been
+    /// signed.
+    ///
+    /// The script is empty for a native witness program, and is a data push of the witness
+    /// program for a P2SH-wrapped witness program (see
+    /// `Transaction::redeem_script_input_bytes_p2sh`).
+    pub fn set_signed_input(&mut self, n: usize, script: ScriptBytes, segwit_field: SegWitField) -> Result<(), SegWitTransactionError> {
+        if n >= self.transaction.inputs.len() { return Err(SegWitTransactionError::NoInputFound(n)) }
+
+        self.transaction.inputs[n].script = script;
+        self.segwit_fields[n] = segwit_field;
+
+        Ok(())
+    }
+
     /// Compute the witness transaction ID (`wtxid`).
     ///
     /// The witness transaction ID is the "double SHA-256" digest of the transaction byte
@@ -33,7 +123,7 @@ impl SegWitTransaction {
     /// If all transaction inputs' segregated witness fields are empty, then the normal transaction
     /// ID (`txid`) is returned.
     pub fn wtxid(&self) -> WitnessTransactionId {
-        if self.segwit_fields.iter().all(|field| field.is_empty()) {
+        if !self.has_witness() {
             return WitnessTransactionId::of(&self.txid().bytes());
         }
 
@@ -41,10 +131,17 @@ impl SegWitTransaction {
     }
 
     /// Parse a byte string for a segregated witness transaction.
+    ///
+    /// A transaction serialized without the segregated witness marker and flag is parsed with an
+    /// empty segregated witness field for each transaction input.
     pub fn parse_bytes(bytes: &[u8]) -> Result<Self, TransactionParsingError> {
         // The overall length of the byte slice.
         let bytes_length = bytes.len();
 
+        // Without the segregated witness marker, this is a transaction without witness data.
+        if bytes_length < 5 { return Err(TransactionParsingError::UnexpectedByteLength) }
+        if bytes[4] != 0x00_u8 { return Ok(Self::from_transaction(Transaction::parse_bytes(bytes)?)) }
+
         // There should be at least enough bytes to read the version bytes, segregated witness
         // marker byte, segregated witness flag byte, and the beginning of the transaction input
         // count variable integer.
@@ -53,8 +150,7 @@ impl SegWitTransaction {
         // Read the version bytes.
         let version_bytes = Version::of(&bytes[0..4]);
 
-        assert_eq!(bytes[4], 0x00_u8);
-        assert_eq!(bytes[5], 0x01_u8);
+        if bytes[5] != 0x01_u8 { return Err(TransactionParsingError::UnexpectedSegWitFlag) }
 
         // Parse the variable integer, given the leading byte.
         let (mut variable_count, mut skip_bytes) =
@@ -115,6 +211,9 @@ impl SegWitTransaction {
         // There should be a remainder of four bytes, for the locktime.
         if bytes_length > cursor_index + 4 { return Err(TransactionParsingError::UnexpectedByteLength) }
 
+        // The segregated witness marker and flag must be omitted if there is no witness data.
+        if segwit_fields.iter().all(|field| field.is_empty()) { return Err(TransactionParsingError::SuperfluousSegWitFields) }
+
         let locktime = Locktime::of(&bytes[cursor_index..]);
 
         let transaction = Transaction { version: version_bytes, inputs: transaction_inputs, utxos: utxos, locktime: locktime };
@@ -136,6 +235,18 @@ impl std::ops::Deref for SegWitTransaction {
     }
 }
 
+impl From<Transaction> for SegWitTransaction {
+    fn from(transaction: Transaction) -> Self {
+        Self::from_transaction(transaction)
+    }
+}
+
+impl From<SegWitTransaction> for Transaction {
+    fn from(transaction: SegWitTransaction) -> Self {
+        transaction.transaction
+    }
+}
+
 impl ByteString for SegWitTransaction {
     /// Parse a byte string for a transaction.
     fn of(bytes: &[u8]) -> Self {
@@ -145,7 +256,12 @@ impl ByteString for SegWitTransaction {
 
 impl ByteVector for SegWitTransaction {
     /// Return the sequence of bytes representing this transaction.
+    ///
+    /// If all transaction inputs' segregated witness fields are empty, then the transaction is
+    /// serialized without the segregated witness marker and flag.
     fn bytes(&self) -> Vec<u8> {
+        if !self.has_witness() { return self.transaction.bytes() }
+
         let mut bytes: Vec<u8> = vec![];
 
         // Version bytes.
@@ -156,22 +272,22 @@ impl ByteVector for SegWitTransaction {
         bytes.push(0x01_u8);
 
         // Number of inputs.
-        let (varint_bytes, varint_length) = varint_u64(u64::try_from((*self).inputs.len()).unwrap());
+        let (varint_bytes, varint_length) = varint_u64(u64::try_from(self.inputs.len()).unwrap());
 
         bytes.extend_from_slice(&varint_bytes[0..varint_length]);
 
         // Transaction inputs.
-        for input in &(*self).inputs {
+        for input in &self.inputs {
             bytes.extend_from_slice(&input.bytes());
         }
 
         // Number of UTXOs.
-        let (varint_bytes, varint_length) = varint_u64(u64::try_from((*self).utxos.len()).unwrap());
+        let (varint_bytes, varint_length) = varint_u64(u64::try_from(self.utxos.len()).unwrap());
 
         bytes.extend_from_slice(&varint_bytes[0..varint_length]);
 
         // UTXOs.
-        for utxo in &(*self).utxos {
+        for utxo in &self.utxos {
             bytes.extend_from_slice(&utxo.bytes());
         }
 
@@ -181,7 +297,7 @@ impl ByteVector for SegWitTransaction {
         }
 
         // Locktime.
-        bytes.extend_from_slice(&(*self).locktime.bytes());
+        bytes.extend_from_slice(&self.locktime.bytes());
 
         bytes
     }
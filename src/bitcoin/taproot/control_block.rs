@@ -0,0 +1,100 @@
+//! Taproot control blocks (BIP-341).
+//!
+//! The last segregated witness item (excluding any annex) of a script path spend.
+//!
+//! - **`<leaf version | output key parity>`** (1 byte)
+//! - **`<internal key>`** (32 bytes)
+//! - **`<merkle path>`** (32 bytes per node, at most 128 nodes)
+
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::taproot::TAPROOT_LEAF_MASK;
+use crate::bitcoin::taproot::hashes::tapleaf_hash;
+use crate::bitcoin::taproot::hashes::tapbranch_hash;
+use crate::bitcoin::taproot::tweak::tweak_public_key;
+
+/// The size of a control block without any merkle path nodes.
+pub const TAPROOT_CONTROL_BASE_SIZE: usize = 33;
+
+/// The size of each merkle path node of a control block.
+pub const TAPROOT_CONTROL_NODE_SIZE: usize = 32;
+
+/// The maximum number of merkle path nodes of a control block.
+pub const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+
+/// A Taproot control block.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct ControlBlock {
+    /// The leaf version of the spent leaf script.
+    pub leaf_version: u8,
+
+    /// Indicates that the output key's `y` coordinate is odd.
+    pub output_key_parity: bool,
+
+    /// The (x-only) internal key.
+    pub internal_key: [u8; 32],
+
+    /// The nodes hashed with the leaf, from the leaf to the merkle root.
+    pub merkle_path: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    /// Parse a control block; its size must be `33 + 32 * m` bytes, for `0 <= m <= 128`.
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < TAPROOT_CONTROL_BASE_SIZE { return None }
+        if !(bytes.len() - TAPROOT_CONTROL_BASE_SIZE).is_multiple_of(TAPROOT_CONTROL_NODE_SIZE) { return None }
+        if (bytes.len() - TAPROOT_CONTROL_BASE_SIZE) / TAPROOT_CONTROL_NODE_SIZE > TAPROOT_CONTROL_MAX_NODE_COUNT { return None }
+
+        let mut internal_key = [0_u8; 32];
+        internal_key.clone_from_slice(&bytes[1..33]);
+
+        let merkle_path = bytes[TAPROOT_CONTROL_BASE_SIZE..]
+            .chunks(TAPROOT_CONTROL_NODE_SIZE)
+            .map(|node| {
+                let mut hash = [0_u8; 32];
+                hash.clone_from_slice(node);
+
+                hash
+            })
+            .collect();
+
+        Some(
+            Self {
+                leaf_version: bytes[0] & TAPROOT_LEAF_MASK,
+                output_key_parity: (bytes[0] & 0x01_u8) == 0x01_u8,
+                internal_key: internal_key,
+                merkle_path: merkle_path,
+            }
+        )
+    }
+
+    /// Returns the byte representation of the control block.
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(TAPROOT_CONTROL_BASE_SIZE + TAPROOT_CONTROL_NODE_SIZE * self.merkle_path.len());
+
+        bytes.push(self.leaf_version | u8::from(self.output_key_parity));
+        bytes.extend_from_slice(&self.internal_key);
+
+        for node in &self.merkle_path {
+            bytes.extend_from_slice(node);
+        }
+
+        bytes
+    }
+
+    /// Compute the merkle root committed to, given the hash of the spent leaf.
+    pub fn merkle_root(&self, leaf_hash: &[u8; 32]) -> [u8; 32] {
+        self.merkle_path.iter().fold(*leaf_hash, |node, sibling| tapbranch_hash(&node, sibling))
+    }
+
+    /// Verifies that an output key commits to a leaf script, as proven by this control block.
+    pub fn verify_taproot_commitment(&self, output_key: &[u8; 32], script: &ScriptBytes) -> bool {
+        let merkle_root = self.merkle_root(&tapleaf_hash(self.leaf_version, script));
+
+        match tweak_public_key(&self.internal_key, Some(&merkle_root)) {
+            Some((tweaked_key, parity)) => &tweaked_key == output_key && parity == self.output_key_parity,
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Taproot tagged hashes (BIP-341).
+
+use crate::bitcoin::script::ScriptBytes;
+use crate::crypto::digest::tagged::TAP_LEAF;
+use crate::crypto::digest::tagged::TAP_BRANCH;
+use crate::crypto::digest::tagged::TAP_TWEAK;
+use crate::util::byte_string::ByteSlice;
+use crate::util::varint::varint_u64;
+
+/// Compute the hash of a leaf script; i.e.;
+/// `tagged_hash("TapLeaf", leaf version || compact size(script) || script)`.
+pub fn tapleaf_hash(leaf_version: u8, script: &ScriptBytes) -> [u8; 32] {
+    let script_bytes = script.bytes();
+
+    let mut preimage: Vec<u8> = Vec::with_capacity(10 + script_bytes.len());
+
+    let (varint_bytes, varint_length) = varint_u64(u64::try_from(script_bytes.len()).unwrap());
+
+    preimage.push(leaf_version);
+    preimage.extend_from_slice(&varint_bytes[0..varint_length]);
+    preimage.extend_from_slice(script_bytes);
+
+    TAP_LEAF.hash(preimage)
+}
+
+/// Compute the hash of a branch of two nodes; i.e.; `tagged_hash("TapBranch", a || b)`, where the
+/// nodes are ordered lexicographically.
+pub fn tapbranch_hash(node_1: &[u8; 32], node_2: &[u8; 32]) -> [u8; 32] {
+    let (a, b) = if node_1 <= node_2 { (node_1, node_2) } else { (node_2, node_1) };
+
+    let mut preimage = [0_u8; 64];
+
+    preimage[0..32].clone_from_slice(a);
+    preimage[32..64].clone_from_slice(b);
+
+    TAP_BRANCH.hash(preimage)
+}
+
+/// Compute the tweak of an internal key; i.e.; `tagged_hash("TapTweak", P || merkle root)`.
+///
+/// Without a script tree, the tweak commits only to the internal key.
+pub fn taptweak_hash(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> [u8; 32] {
+    let mut preimage: Vec<u8> = Vec::with_capacity(64);
+
+    preimage.extend_from_slice(internal_key);
+
+    if let Some(merkle_root) = merkle_root {
+        preimage.extend_from_slice(merkle_root);
+    }
+
+    TAP_TWEAK.hash(preimage)
+}
@@ -0,0 +1,25 @@
+//! Taproot (BIP-341).
+//!
+//! A version 1, 32 byte witness program is a Taproot output key `Q`; an internal key `P`, tweaked
+//! to commit to an (optional) merkle tree of scripts.
+//!
+//! - `Q = P + int(tagged_hash("TapTweak", P || merkle root)) * G`
+//!
+//! The UTXO may be spent along the key path (a Schnorr signature for `Q`), or along the script
+//! path (a leaf script, its arguments, and a control block proving its inclusion in the tree).
+
+pub mod hashes;
+pub mod tweak;
+pub mod control_block;
+pub mod tapscript;
+pub mod tree;
+
+/// The mask applied to the first byte of a control block, to obtain the leaf version.
+pub const TAPROOT_LEAF_MASK: u8 = 0xfe_u8;
+
+/// The leaf version of Tapscript (BIP-342) leaf scripts.
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0_u8;
+
+/// The first byte of an annex; i.e.; the last segregated witness item of a Taproot spend, if there
+/// are at least two items.
+pub const ANNEX_TAG: u8 = 0x50_u8;
@@ -0,0 +1,66 @@
+//! Tapscript (BIP-342) leaf script rules.
+//!
+//! - `OP_SUCCESSx`; certain unassigned opcodes make a leaf script unconditionally valid, so that
+//!   future upgrades may assign them any behaviour. A leaf script is scanned for these before
+//!   execution; execution never reaches them.
+//! - `OP_CHECKMULTISIG` and `OP_CHECKMULTISIGVERIFY` are disabled; see `OP_CHECKSIGADD`.
+//! - Signature checking opcodes consume a validation budget.
+
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::ScriptCreationError;
+use crate::util::byte_string::ByteSlice;
+
+/// The maximum number of stack elements at the start of (and during) leaf script execution.
+pub const MAX_STACK_SIZE: usize = 1000;
+
+/// The validation budget of a leaf script, in addition to the serialized size of the segregated
+/// witness field.
+pub const VALIDATION_BUDGET_OFFSET: i64 = 50;
+
+/// Indicates that an opcode byte is an `OP_SUCCESSx` opcode within Tapscript leaf scripts.
+pub fn is_op_success(byte: u8) -> bool {
+    matches!(
+        byte,
+        0x50_u8 | 0x62_u8 | 0x7e_u8..=0x81_u8 | 0x83_u8..=0x86_u8 | 0x89_u8..=0x8a_u8 |
+        0x8d_u8..=0x8e_u8 | 0x95_u8..=0x99_u8 | 0xbb_u8..=0xfe_u8
+    )
+}
+
+impl ScriptBytes {
+    /// Indicates that a Tapscript leaf script contains an `OP_SUCCESSx` opcode (outside of any
+    /// data push).
+    ///
+    /// Returns an error if a data push is truncated before any `OP_SUCCESSx` opcode is found.
+    pub fn contains_op_success(&self) -> Result<bool, ScriptCreationError> {
+        let bytes = self.bytes();
+        let mut i: usize = 0;
+
+        while let Some(byte) = bytes.get(i) {
+            i += 1;
+
+            let (length_size, literal_length) = match byte {
+                0x01_u8..=0x4b_u8 => (0, usize::from(*byte)),
+                0x4c_u8 => (1, 0),
+                0x4d_u8 => (2, 0),
+                0x4e_u8 => (4, 0),
+                byte if is_op_success(*byte) => return Ok(true),
+                _ => continue,
+            };
+
+            if i + length_size > bytes.len() { return Err(ScriptCreationError) }
+
+            let mut length_bytes = [0_u8; 4];
+            length_bytes[0..length_size].clone_from_slice(&bytes[i..(i + length_size)]);
+
+            let length = literal_length + usize::try_from(u32::from_le_bytes(length_bytes)).map_err(|_| ScriptCreationError)?;
+
+            i += length_size;
+
+            if i + length > bytes.len() { return Err(ScriptCreationError) }
+
+            i += length;
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,287 @@
+//! Taproot script trees (BIP-341).
+//!
+//! An output key may commit to a binary tree of leaf scripts; each leaf script may then be used to
+//! spend the UTXO (along the script path), revealing only that leaf script and the hashes of the
+//! nodes along its path to the merkle root.
+//!
+//! Leaf scripts that are more likely to be used should be nearer to the merkle root, as their
+//! control blocks are smaller. Given the relative likelihood (weight) of each leaf script, a
+//! Huffman tree minimizes the expected control block size.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::bitcoin::network::BitcoinNetworkType;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::taproot::ANNEX_TAG;
+use crate::bitcoin::taproot::TAPROOT_LEAF_MASK;
+use crate::bitcoin::taproot::TAPROOT_LEAF_TAPSCRIPT;
+use crate::bitcoin::taproot::hashes::tapleaf_hash;
+use crate::bitcoin::taproot::hashes::tapbranch_hash;
+use crate::bitcoin::taproot::tweak::tweak_public_key;
+use crate::bitcoin::taproot::control_block::ControlBlock;
+use crate::bitcoin::taproot::control_block::TAPROOT_CONTROL_MAX_NODE_COUNT;
+use crate::serialization::bech32::segwit_address_encode;
+
+/// Errors thrown when constructing a Taproot script tree or output.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum TapTreeError {
+    /// No leaf scripts were given.
+    NoLeaves,
+
+    /// A leaf script is deeper than 128 nodes; its control block would be invalid.
+    DepthExceeded,
+
+    /// A leaf version is odd, or is the annex tag (`0x50`).
+    InvalidLeafVersion(u8),
+
+    /// The internal key is not a valid x-only public key, or its tweak is invalid.
+    InvalidInternalKey,
+}
+
+/// A leaf script of a Taproot script tree.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct TapLeaf {
+    /// The leaf version; e.g.; `TAPROOT_LEAF_TAPSCRIPT`.
+    pub leaf_version: u8,
+
+    /// The leaf script.
+    pub script: ScriptBytes,
+}
+
+impl TapLeaf {
+    /// Create a Tapscript leaf script (BIP-342).
+    pub fn new(script: ScriptBytes) -> Self {
+        Self::with_version(TAPROOT_LEAF_TAPSCRIPT, script)
+    }
+
+    /// Create a leaf script of some leaf version.
+    pub fn with_version(leaf_version: u8, script: ScriptBytes) -> Self {
+        Self {
+            leaf_version: leaf_version,
+            script: script,
+        }
+    }
+
+    /// Compute the hash of the leaf script.
+    pub fn hash(&self) -> [u8; 32] {
+        tapleaf_hash(self.leaf_version, &self.script)
+    }
+}
+
+/// A node of a Taproot script tree.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum TapNode {
+    /// A leaf script.
+    Leaf(TapLeaf),
+
+    /// A branch of two nodes.
+    Branch(Box<TapNode>, Box<TapNode>),
+}
+
+impl TapNode {
+    /// Create a branch of two nodes.
+    pub fn branch(node_1: TapNode, node_2: TapNode) -> Self {
+        Self::Branch(Box::new(node_1), Box::new(node_2))
+    }
+
+    /// Compute the hash of the node.
+    pub fn hash(&self) -> [u8; 32] {
+        match self {
+            Self::Leaf(leaf) => leaf.hash(),
+            Self::Branch(node_1, node_2) => tapbranch_hash(&node_1.hash(), &node_2.hash()),
+        }
+    }
+
+    /// Returns the depth of the deepest leaf script below this node (zero for a leaf script).
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Leaf(_) => 0,
+            Self::Branch(node_1, node_2) => 1 + node_1.depth().max(node_2.depth()),
+        }
+    }
+
+    /// Returns the leaf scripts below this node, each with the hashes of the nodes along its path
+    /// (ordered from the leaf script upwards).
+    fn leaves_with_merkle_paths(&self) -> Vec<(TapLeaf, Vec<[u8; 32]>)> {
+        match self {
+            Self::Leaf(leaf) => vec![(leaf.clone(), vec![])],
+            Self::Branch(node_1, node_2) => {
+                let (hash_1, hash_2) = (node_1.hash(), node_2.hash());
+
+                let mut leaves = node_1.leaves_with_merkle_paths();
+
+                for (_, merkle_path) in &mut leaves { merkle_path.push(hash_2) }
+
+                for (leaf, mut merkle_path) in node_2.leaves_with_merkle_paths() {
+                    merkle_path.push(hash_1);
+                    leaves.push((leaf, merkle_path));
+                }
+
+                leaves
+            },
+        }
+    }
+
+    fn leaf_versions(&self) -> Vec<u8> {
+        match self {
+            Self::Leaf(leaf) => vec![leaf.leaf_version],
+            Self::Branch(node_1, node_2) => [node_1.leaf_versions(), node_2.leaf_versions()].concat(),
+        }
+    }
+}
+
+/// A Taproot script tree.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct TapTree {
+    root: TapNode,
+}
+
+impl TapTree {
+    /// Create a script tree, given its root node.
+    pub fn new(root: TapNode) -> Result<Self, TapTreeError> {
+        if root.depth() > TAPROOT_CONTROL_MAX_NODE_COUNT { return Err(TapTreeError::DepthExceeded) }
+
+        for leaf_version in root.leaf_versions() {
+            if (leaf_version & TAPROOT_LEAF_MASK) != leaf_version || leaf_version == ANNEX_TAG {
+                return Err(TapTreeError::InvalidLeafVersion(leaf_version));
+            }
+        }
+
+        Ok(Self { root: root })
+    }
+
+    /// Create a Huffman script tree, given leaf scripts and their weights; i.e.; the two nodes of
+    /// least weight are repeatedly joined by a branch (whose weight is their sum).
+    ///
+    /// Nodes of equal weight are joined in the order they were given (and created).
+    pub fn from_weighted_leaves(leaves: Vec<(u64, TapLeaf)>) -> Result<Self, TapTreeError> {
+        if leaves.is_empty() { return Err(TapTreeError::NoLeaves) }
+
+        let mut nodes: Vec<Option<TapNode>> = vec![];
+        let mut heap: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+
+        for (weight, leaf) in leaves {
+            heap.push(Reverse((weight, nodes.len())));
+            nodes.push(Some(TapNode::Leaf(leaf)));
+        }
+
+        while heap.len() > 1 {
+            let Reverse((weight_1, i)) = heap.pop().unwrap();
+            let Reverse((weight_2, j)) = heap.pop().unwrap();
+
+            let branch = TapNode::branch(nodes[i].take().unwrap(), nodes[j].take().unwrap());
+
+            heap.push(Reverse((weight_1.saturating_add(weight_2), nodes.len())));
+            nodes.push(Some(branch));
+        }
+
+        let Reverse((_, i)) = heap.pop().unwrap();
+
+        Self::new(nodes[i].take().unwrap())
+    }
+
+    /// Create a balanced script tree, given leaf scripts of equal weight.
+    pub fn from_leaves(leaves: Vec<TapLeaf>) -> Result<Self, TapTreeError> {
+        Self::from_weighted_leaves(leaves.into_iter().map(|leaf| (1, leaf)).collect())
+    }
+
+    /// Returns the root node.
+    pub fn root(&self) -> &TapNode {
+        &self.root
+    }
+
+    /// Compute the merkle root.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.root.hash()
+    }
+
+    /// Returns the leaf scripts, each with the hashes of the nodes along its path to the merkle
+    /// root (ordered from the leaf script upwards).
+    pub fn leaves(&self) -> Vec<(TapLeaf, Vec<[u8; 32]>)> {
+        self.root.leaves_with_merkle_paths()
+    }
+}
+
+/// A Taproot output; i.e.; an internal key, tweaked to commit to an (optional) script tree.
+///
+/// Provides the output key (and its locking script and address), and the control block for each
+/// leaf script.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct TaprootSpendInfo {
+    /// The (x-only) internal key.
+    pub internal_key: [u8; 32],
+
+    /// The merkle root of the script tree, if any.
+    pub merkle_root: Option<[u8; 32]>,
+
+    /// The (x-only) output key.
+    pub output_key: [u8; 32],
+
+    /// Indicates that the output key's `y` coordinate is odd.
+    pub output_key_parity: bool,
+
+    leaves: Vec<(TapLeaf, Vec<[u8; 32]>)>,
+}
+
+impl TaprootSpendInfo {
+    /// Tweak an (x-only) internal key to commit to a script tree (if any).
+    ///
+    /// Without a script tree, the UTXO may only be spent along the key path. To allow only script
+    /// path spends, use an internal key without a known secret (e.g.; the BIP-341 "NUMS" point).
+    pub fn new(internal_key: [u8; 32], tree: Option<&TapTree>) -> Result<Self, TapTreeError> {
+        let merkle_root = tree.map(|tree| tree.merkle_root());
+
+        let (output_key, output_key_parity) = tweak_public_key(&internal_key, merkle_root.as_ref())
+            .ok_or(TapTreeError::InvalidInternalKey)?;
+
+        Ok(
+            Self {
+                internal_key: internal_key,
+                merkle_root: merkle_root,
+                output_key: output_key,
+                output_key_parity: output_key_parity,
+                leaves: tree.map(|tree| tree.leaves()).unwrap_or_default(),
+            }
+        )
+    }
+
+    /// Returns the leaf scripts, each with the hashes of the nodes along its path to the merkle
+    /// root.
+    pub fn leaves(&self) -> &[(TapLeaf, Vec<[u8; 32]>)] {
+        &self.leaves
+    }
+
+    /// Returns the control block for spending along the script path with some leaf script, if it
+    /// is within the script tree.
+    pub fn control_block(&self, leaf: &TapLeaf) -> Option<ControlBlock> {
+        let (leaf, merkle_path) = self.leaves.iter().find(|(tree_leaf, _)| tree_leaf == leaf)?;
+
+        Some(
+            ControlBlock {
+                leaf_version: leaf.leaf_version,
+                output_key_parity: self.output_key_parity,
+                internal_key: self.internal_key,
+                merkle_path: merkle_path.clone(),
+            }
+        )
+    }
+
+    /// Creates the P2TR locking script.
+    pub fn locking_script(&self) -> ScriptBytes {
+        ScriptBytes::locking_script_p2tr_output_key(&self.output_key)
+    }
+
+    /// Returns the (Bech32m) P2TR address.
+    pub fn address(&self, network: BitcoinNetworkType) -> String {
+        segwit_address_encode(network.bech32_hrp(), 1, &self.output_key)
+    }
+}
@@ -0,0 +1,23 @@
+//! Taproot internal key tweaking (BIP-341).
+
+use crate::bitcoin::taproot::hashes::taptweak_hash;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::util::number::U256;
+
+/// Tweak an (x-only) internal key to commit to a merkle root (if any).
+///
+/// Returns the (x-only) output key, and whether its `y` coordinate is odd.
+///
+/// Returns nothing if the internal key is not a valid x-only public key, the tweak is not less
+/// than the elliptic curve order, or the output key is the point at infinity.
+pub fn tweak_public_key(internal_key: &[u8; 32], merkle_root: Option<&[u8; 32]>) -> Option<([u8; 32], bool)> {
+    let point = Secp256k1Point::lift_x(U256::from_be_bytes(*internal_key))?;
+    let tweak = U256::from_be_bytes(taptweak_hash(internal_key, merkle_root));
+
+    if tweak >= ELLIPTIC_CURVE_ORDER { return None }
+
+    let (x, y) = (point + (tweak * Secp256k1Point::generator_point())).coordinates()?;
+
+    Some((x.to_be_bytes::<32>(), y.bit(0)))
+}
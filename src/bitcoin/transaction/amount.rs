@@ -0,0 +1,229 @@
+//! Bitcoin amounts.
+
+use crate::util::byte_value::ByteValue8;
+use crate::util::byte_value::i64_little_endian;
+
+/// The amount of satoshi in one bitcoin.
+pub const COIN: i64 = 100_000_000;
+
+/// The maximum amount of satoshi that may ever exist (`MAX_MONEY`).
+///
+/// This is a consensus rule, which limits the amounts of individual UTXOs as well as sums of
+/// UTXO amounts.
+pub const MAX_MONEY: i64 = 21_000_000 * COIN;
+
+/// An amount of satoshi.
+///
+/// Amounts created from checked constructors and arithmetic are always within `0..=MAX_MONEY`.
+/// Amounts parsed from transaction bytes are not checked (see `Amount::is_money_range`), as the
+/// transaction itself may be invalid.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount {
+    /// Amount of satoshi.
+    satoshi: i64,
+}
+
+/// Denominations of an amount.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum AmountUnit {
+    /// `BTC`: 100,000,000 satoshi.
+    Bitcoin,
+
+    /// `mBTC`: 100,000 satoshi.
+    MilliBitcoin,
+
+    /// `sat`: 1 satoshi.
+    Satoshi,
+}
+
+/// Errors thrown when creating an amount.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum AmountError {
+    /// The amount is negative, or above `MAX_MONEY`.
+    OutOfRange,
+
+    /// The amount has more decimal places than its denomination allows.
+    TooPrecise,
+
+    /// The denomination is not recognized.
+    UnknownUnit,
+
+    /// The amount could not be parsed as a decimal number.
+    InvalidFormat,
+}
+
+impl AmountUnit {
+    /// Returns the number of decimal places the denomination allows.
+    pub fn decimals(&self) -> u32 {
+        match self {
+            Self::Bitcoin => 8,
+            Self::MilliBitcoin => 5,
+            Self::Satoshi => 0,
+        }
+    }
+
+    /// Returns the symbol for the denomination.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Bitcoin => "BTC",
+            Self::MilliBitcoin => "mBTC",
+            Self::Satoshi => "sat",
+        }
+    }
+}
+
+impl std::str::FromStr for AmountUnit {
+    type Err = AmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BTC" | "btc" => Ok(Self::Bitcoin),
+            "mBTC" | "mbtc" => Ok(Self::MilliBitcoin),
+            "sat" | "sats" | "satoshi" => Ok(Self::Satoshi),
+            _ => Err(AmountError::UnknownUnit),
+        }
+    }
+}
+
+impl Amount {
+    /// Zero satoshi.
+    pub const ZERO: Self = Self { satoshi: 0 };
+
+    /// `MAX_MONEY`.
+    pub const MAX_MONEY: Self = Self { satoshi: MAX_MONEY };
+
+    /// The "null" amount (`-1`), used for transaction outputs that are blanked out when computing
+    /// `SIGHASH_SINGLE` signature digests.
+    pub const NULL: Self = Self { satoshi: -1 };
+
+    /// Create an amount of satoshi.
+    ///
+    /// The amount must be within `0..=MAX_MONEY`.
+    pub fn from_sat(satoshi: i64) -> Result<Self, AmountError> {
+        let amount = Self { satoshi: satoshi };
+
+        if !amount.is_money_range() { return Err(AmountError::OutOfRange) }
+
+        Ok(amount)
+    }
+
+    /// Returns the amount of satoshi.
+    pub fn to_sat(&self) -> i64 {
+        self.satoshi
+    }
+
+    /// Indicates that the amount is within `0..=MAX_MONEY`.
+    pub fn is_money_range(&self) -> bool {
+        (0..=MAX_MONEY).contains(&self.satoshi)
+    }
+
+    /// Adds another amount, returning nothing if the sum would fall outside of `0..=MAX_MONEY`.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::from_sat(self.satoshi.checked_add(rhs.satoshi)?).ok()
+    }
+
+    /// Subtracts another amount, returning nothing if the difference would fall outside of
+    /// `0..=MAX_MONEY`.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::from_sat(self.satoshi.checked_sub(rhs.satoshi)?).ok()
+    }
+
+    /// Sums a series of amounts, returning nothing if any amount or partial sum falls outside of
+    /// `0..=MAX_MONEY`.
+    pub fn checked_sum(amounts: impl Iterator<Item = Self>) -> Option<Self> {
+        let mut sum = Self::ZERO;
+
+        for amount in amounts {
+            if !amount.is_money_range() { return None }
+
+            sum = sum.checked_add(amount)?;
+        }
+
+        Some(sum)
+    }
+
+    /// Parse an amount expressed as a decimal number in some denomination (e.g.; `"0.0015"`
+    /// bitcoin).
+    pub fn parse_in(s: &str, unit: AmountUnit) -> Result<Self, AmountError> {
+        let decimals = unit.decimals();
+
+        let (integer, fraction) = match s.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (s, ""),
+        };
+
+        if integer.is_empty() && fraction.is_empty() { return Err(AmountError::InvalidFormat) }
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) { return Err(AmountError::InvalidFormat) }
+
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.len() > usize::try_from(decimals).unwrap() { return Err(AmountError::TooPrecise) }
+
+        let scale = 10_i64.pow(decimals);
+
+        let integer: i64 = if integer.is_empty() { 0 } else { integer.parse().map_err(|_| AmountError::OutOfRange)? };
+        let fraction: i64 = if fraction.is_empty() { 0 } else {
+            fraction.parse::<i64>().unwrap() * 10_i64.pow(decimals - u32::try_from(fraction.len()).unwrap())
+        };
+
+        let satoshi = integer
+            .checked_mul(scale)
+            .and_then(|satoshi| satoshi.checked_add(fraction))
+            .ok_or(AmountError::OutOfRange)?;
+
+        Self::from_sat(satoshi)
+    }
+
+    /// Formats the amount as a decimal number in some denomination, without a symbol.
+    pub fn format_in(&self, unit: AmountUnit) -> String {
+        let decimals = unit.decimals();
+        let scale = 10_i64.pow(decimals);
+
+        let sign = if self.satoshi < 0 { "-" } else { "" };
+        let integer = (self.satoshi / scale).abs();
+        let fraction = (self.satoshi % scale).abs();
+
+        if decimals == 0 {
+            format!("{}{}", sign, integer)
+        } else {
+            format!("{}{}.{:0width$}", sign, integer, fraction, width = usize::try_from(decimals).unwrap())
+        }
+    }
+}
+
+impl ByteValue8 for Amount {
+    /// Initialize a little-endian signed 8 byte integer.
+    ///
+    /// The amount is not checked to be within `0..=MAX_MONEY`.
+    fn of(bytes: &[u8]) -> Self {
+        Self { satoshi: i64_little_endian(bytes) }
+    }
+
+    /// Return a little-endian signed 8 byte integer.
+    fn bytes(&self) -> [u8; 8] {
+        self.satoshi.to_le_bytes()
+    }
+}
+
+impl std::fmt::Display for Amount {
+    /// Displays the amount in bitcoin (e.g.; `0.00150000 BTC`).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.format_in(AmountUnit::Bitcoin), AmountUnit::Bitcoin.symbol())
+    }
+}
+
+impl std::str::FromStr for Amount {
+    type Err = AmountError;
+
+    /// Parse an amount with its denomination (e.g.; `0.0015 BTC`, `1.5 mBTC`, `150000 sat`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, unit) = s.trim().split_once(' ').ok_or(AmountError::UnknownUnit)?;
+
+        Self::parse_in(number.trim(), unit.trim().parse()?)
+    }
+}
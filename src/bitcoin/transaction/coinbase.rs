@@ -0,0 +1,257 @@
+//! Bitcoin coinbase transactions.
+//!
+//! The first transaction of each block is its coinbase transaction, which claims the block
+//! subsidy and the fees of the block's other transactions.
+//!
+//! - It has a single transaction input, which spends the null outpoint.
+//! - Its transaction input script is arbitrary data (2 to 100 bytes), which must begin with the
+//!   block height (BIP-34). Miners typically reserve some of the remaining space as an "extra
+//!   nonce", to vary the block's merkle root.
+//! - If the block contains segregated witness transactions, a UTXO commits to the witness merkle
+//!   root (BIP-141); the coinbase transaction's segregated witness field provides a 32 byte
+//!   "witness reserved value" to the commitment.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::TransactionInput;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Version;
+use crate::bitcoin::transaction::Locktime;
+use crate::bitcoin::transaction::SEQUENCE_FINAL;
+use crate::bitcoin::transaction::verify::check_transaction::MIN_COINBASE_SCRIPT_SIZE;
+use crate::bitcoin::transaction::verify::check_transaction::MAX_COINBASE_SCRIPT_SIZE;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::segwit::item::SegWitItem;
+use crate::bitcoin::segwit::commitment::witness_commitment_utxo;
+use crate::bitcoin::script::ScriptBytes;
+use crate::crypto::digest::hash_256;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue4;
+
+/// The header of a witness commitment, following `OP_RETURN` and a 36 byte data push.
+pub const WITNESS_COMMITMENT_HEADER: [u8; 4] = [0xaa_u8, 0x21_u8, 0xa9_u8, 0xed_u8];
+
+/// The transaction version used for constructed coinbase transactions.
+const COINBASE_TRANSACTION_VERSION: u32 = 2;
+
+/// Errors thrown when constructing a coinbase transaction.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum CoinbaseError {
+    /// The coinbase transaction input script would not be between 2 and 100 bytes long.
+    ScriptSize,
+
+    /// The extra nonce does not have the size reserved for it.
+    ExtraNonceSize,
+
+    /// The transaction is not a coinbase transaction, or has no extra nonce.
+    NotFound,
+}
+
+impl Transaction {
+    /// Indicates that this is a coinbase transaction; i.e.; it has a single transaction input,
+    /// which spends the null outpoint.
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.len() == 1 && self.inputs[0].outpoint.is_null()
+    }
+
+    /// Returns the block height committed to by a coinbase transaction (BIP-34); i.e.; the number
+    /// at the beginning of its transaction input script.
+    pub fn coinbase_height(&self) -> Option<u32> {
+        if !self.is_coinbase() { return None }
+
+        let bytes = self.inputs[0].script.bytes();
+
+        match *bytes.first()? {
+            0x00_u8 => Some(0),
+            opcode @ 0x51_u8..=0x60_u8 => Some(u32::from(opcode - 0x50_u8)),
+            length @ 0x01_u8..=0x04_u8 => {
+                let number = bytes.get(1..=usize::from(length))?;
+
+                // Block heights are non-negative.
+                if number[number.len() - 1] & 0x80_u8 != 0 { return None }
+
+                let mut buffer = [0_u8; 4];
+                buffer[0..number.len()].clone_from_slice(number);
+
+                Some(u32::from_le_bytes(buffer))
+            },
+            _ => None,
+        }
+    }
+
+    /// Create a coinbase transaction, for a block at some height.
+    ///
+    /// The transaction input script consists of the block height (BIP-34), a zeroed extra nonce
+    /// of some size, and some arbitrary data (if not empty).
+    pub fn coinbase(
+        block_height: u32,
+        extra_nonce_size: usize,
+        coinbase_data: &[u8],
+        utxos: Vec<UnspentTransactionOutput>
+    ) -> Result<Self, CoinbaseError> {
+        let mut script: Vec<u8> = script_number_push(block_height);
+
+        if extra_nonce_size > 0 { script.extend(data_push(&vec![0_u8; extra_nonce_size])?) }
+        if !coinbase_data.is_empty() { script.extend(data_push(coinbase_data)?) }
+
+        if !(MIN_COINBASE_SCRIPT_SIZE..=MAX_COINBASE_SCRIPT_SIZE).contains(&script.len()) {
+            return Err(CoinbaseError::ScriptSize);
+        }
+
+        Ok(
+            Self {
+                version: Version::of(&COINBASE_TRANSACTION_VERSION.to_le_bytes()),
+                inputs: vec![TransactionInput::new(OutPoint::null(), ScriptBytes::of(&script), SEQUENCE_FINAL)],
+                utxos: utxos,
+                locktime: Locktime::of(&[0_u8; 4]),
+            }
+        )
+    }
+
+    /// Returns the extra nonce of a coinbase transaction; i.e.; the data element following the
+    /// block height.
+    pub fn coinbase_extra_nonce(&self) -> Option<&[u8]> {
+        let (start, end) = self.coinbase_extra_nonce_range()?;
+
+        Some(&self.inputs[0].script.bytes()[start..end])
+    }
+
+    /// Overwrites the extra nonce of a coinbase transaction.
+    ///
+    /// The extra nonce must have the size reserved for it, so that the transaction size is
+    /// unchanged.
+    pub fn set_coinbase_extra_nonce(&mut self, extra_nonce: &[u8]) -> Result<(), CoinbaseError> {
+        let (start, end) = self.coinbase_extra_nonce_range().ok_or(CoinbaseError::NotFound)?;
+
+        if end - start != extra_nonce.len() { return Err(CoinbaseError::ExtraNonceSize) }
+
+        let mut bytes = self.inputs[0].script.bytes().to_vec();
+        bytes[start..end].clone_from_slice(extra_nonce);
+
+        self.inputs[0].script = ScriptBytes::of(&bytes);
+
+        Ok(())
+    }
+
+    /// Returns the witness commitment (BIP-141) of a coinbase transaction; i.e.; the commitment
+    /// of the last UTXO whose locking script matches the witness commitment pattern.
+    pub fn witness_commitment(&self) -> Option<[u8; 32]> {
+        let utxo = self.utxos.iter().rev().find(|utxo| is_witness_commitment_script(&utxo.script))?;
+
+        let mut commitment = [0_u8; 32];
+        commitment.clone_from_slice(&utxo.script.bytes()[6..38]);
+
+        Some(commitment)
+    }
+
+    /// Returns the byte range of the extra nonce within the coinbase transaction input script.
+    fn coinbase_extra_nonce_range(&self) -> Option<(usize, usize)> {
+        self.coinbase_height()?;
+
+        let bytes = self.inputs[0].script.bytes();
+
+        // Skip the block height.
+        let start = match bytes[0] {
+            length @ 0x01_u8..=0x04_u8 => 1 + usize::from(length),
+            _ => 1,
+        };
+
+        // The extra nonce is pushed directly, or (if longer than 75 bytes) with `OP_PUSHDATA1`.
+        let (start, length) = match *bytes.get(start)? {
+            length @ 0x01_u8..=0x4b_u8 => (start + 1, usize::from(length)),
+            0x4c_u8 => (start + 2, usize::from(*bytes.get(start + 1)?)),
+            _ => return None,
+        };
+
+        if start + length > bytes.len() { return None }
+
+        Some((start, start + length))
+    }
+}
+
+impl SegWitTransaction {
+    /// Create a coinbase transaction, for a block at some height, that commits to the block's
+    /// witness merkle root (BIP-141).
+    ///
+    /// A witness commitment UTXO is appended to the given UTXOs, and the segregated witness field
+    /// provides a witness reserved value of zero.
+    pub fn coinbase_with_witness_commitment(
+        block_height: u32,
+        extra_nonce_size: usize,
+        coinbase_data: &[u8],
+        utxos: Vec<UnspentTransactionOutput>,
+        witness_merkle_root: [u8; 32],
+    ) -> Result<Self, CoinbaseError> {
+        let witness_reserved_value = [0_u8; 32];
+
+        let mut transaction = Transaction::coinbase(block_height, extra_nonce_size, coinbase_data, utxos)?;
+
+        transaction.utxos.push(witness_commitment_utxo(witness_merkle_root, witness_reserved_value));
+
+        let segwit_field = SegWitField::new(vec![SegWitItem::of(&witness_reserved_value)]);
+
+        // A coinbase transaction has a single transaction input.
+        Ok(SegWitTransaction::new(transaction, vec![segwit_field]).unwrap())
+    }
+}
+
+/// Creates the locking script of a witness commitment UTXO.
+///
+/// `OP_RETURN <0xaa21a9ed || HASH_256(witness merkle root || witness reserved value)>`
+pub fn witness_commitment_script(witness_merkle_root: [u8; 32], witness_reserved_value: [u8; 32]) -> ScriptBytes {
+    let mut preimage = [0_u8; 64];
+
+    preimage[0..32].clone_from_slice(&witness_merkle_root);
+    preimage[32..64].clone_from_slice(&witness_reserved_value);
+
+    let mut bytes: Vec<u8> = vec![0x6a_u8, 0x24_u8];
+
+    bytes.extend_from_slice(&WITNESS_COMMITMENT_HEADER);
+    bytes.extend_from_slice(&hash_256(preimage));
+
+    ScriptBytes::of(&bytes)
+}
+
+/// Indicates that a locking script matches the witness commitment pattern; additional bytes may
+/// follow the commitment.
+fn is_witness_commitment_script(script: &ScriptBytes) -> bool {
+    let bytes = script.bytes();
+
+    bytes.len() >= 38 && bytes[0] == 0x6a_u8 && bytes[1] == 0x24_u8 && bytes[2..6] == WITNESS_COMMITMENT_HEADER
+}
+
+/// Returns the script bytes that push a number, as Bitcoin Core's `CScript() << n` does; i.e.;
+/// `OP_0`, `OP_1` to `OP_16`, or a minimal little-endian script number.
+fn script_number_push(n: u32) -> Vec<u8> {
+    match n {
+        0 => vec![0x00_u8],
+        1..=16 => vec![0x50_u8 + u8::try_from(n).unwrap()],
+        _ => {
+            let mut number: Vec<u8> = n.to_le_bytes().to_vec();
+
+            while number.last() == Some(&0x00_u8) { number.pop(); }
+
+            // Keep the number positive.
+            if number.last().is_some_and(|byte| byte & 0x80_u8 != 0) { number.push(0x00_u8) }
+
+            let mut bytes = vec![u8::try_from(number.len()).unwrap()];
+            bytes.extend(number);
+
+            bytes
+        },
+    }
+}
+
+/// Returns the script bytes that push some data, for data that fits within a coinbase
+/// transaction input script.
+fn data_push(data: &[u8]) -> Result<Vec<u8>, CoinbaseError> {
+    let length = u8::try_from(data.len()).map_err(|_| CoinbaseError::ScriptSize)?;
+
+    let mut bytes: Vec<u8> = if length <= 0x4b_u8 { vec![length] } else { vec![0x4c_u8, length] };
+    bytes.extend_from_slice(data);
+
+    Ok(bytes)
+}
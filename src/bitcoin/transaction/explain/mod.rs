@@ -0,0 +1,4 @@
+//! Bitcoin transaction explanations and differences.
+
+pub mod transaction_explanation;
+pub mod transaction_diff;
@@ -0,0 +1,178 @@
+//! Bitcoin transaction differences.
+//!
+//! Compares two versions of a transaction (e.g.; as proposed, and as returned by a co-signer, or
+//! an original and its replacement).
+//!
+//! Transaction inputs are matched by their outpoint. UTXOs are matched by their locking script,
+//! in order of appearance; a UTXO whose amount changed is reported as such, rather than as a
+//! removal and an addition.
+//!
+//! Segregated witness fields are compared per matched transaction input, when comparing two
+//! versions of a segregated witness transaction.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::script::ScriptBytes;
+use crate::util::byte_value::ByteValue4;
+
+/// A difference between two versions of a transaction.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum TransactionChange {
+    /// The transaction version changed.
+    Version { before: u32, after: u32 },
+
+    /// The time lock (`nLockTime`) changed.
+    Locktime { before: u32, after: u32 },
+
+    /// A transaction input was added.
+    InputAdded(OutPoint),
+
+    /// A transaction input was removed.
+    InputRemoved(OutPoint),
+
+    /// A transaction input moved to another position.
+    InputMoved { outpoint: OutPoint, before: usize, after: usize },
+
+    /// A transaction input's sequence number changed.
+    InputSequence { outpoint: OutPoint, before: u32, after: u32 },
+
+    /// A transaction input's script changed (e.g.; a signature was added).
+    InputScript { outpoint: OutPoint, before: ScriptBytes, after: ScriptBytes },
+
+    /// A transaction input's segregated witness field changed (e.g.; a signature was added).
+    InputWitness { outpoint: OutPoint, before: SegWitField, after: SegWitField },
+
+    /// A UTXO was added, at some output index.
+    OutputAdded { index: usize, amount: Amount, script: ScriptBytes },
+
+    /// A UTXO was removed, from some output index.
+    OutputRemoved { index: usize, amount: Amount, script: ScriptBytes },
+
+    /// A UTXO's amount changed.
+    OutputAmount { script: ScriptBytes, before: Amount, after: Amount },
+}
+
+impl Transaction {
+    /// Returns the differences between this transaction and another version of it.
+    ///
+    /// Segregated witness data is not compared (see `SegWitTransaction::diff`).
+    pub fn diff(&self, other: &Transaction) -> Vec<TransactionChange> {
+        diff_transactions(self, None, other, None)
+    }
+}
+
+impl SegWitTransaction {
+    /// Returns the differences between this transaction and another version of it, including
+    /// changes to the segregated witness field of each transaction input.
+    pub fn diff(&self, other: &SegWitTransaction) -> Vec<TransactionChange> {
+        diff_transactions(self, Some(self.segwit_fields()), other, Some(other.segwit_fields()))
+    }
+}
+
+fn diff_transactions(
+    transaction: &Transaction,
+    segwit_fields: Option<&[SegWitField]>,
+    other: &Transaction,
+    other_segwit_fields: Option<&[SegWitField]>
+) -> Vec<TransactionChange> {
+    let mut changes: Vec<TransactionChange> = vec![];
+
+    if transaction.version.value() != other.version.value() {
+        changes.push(TransactionChange::Version { before: transaction.version.value(), after: other.version.value() });
+    }
+
+    let (locktime_before, locktime_after) = (u32::from_le_bytes(transaction.locktime.bytes()), u32::from_le_bytes(other.locktime.bytes()));
+
+    if locktime_before != locktime_after {
+        changes.push(TransactionChange::Locktime { before: locktime_before, after: locktime_after });
+    }
+
+    // Transaction inputs, matched by outpoint.
+    for (i, input) in transaction.inputs.iter().enumerate() {
+        let Some((j, other_input)) = other.inputs.iter().enumerate().find(|(_, other_input)| other_input.outpoint == input.outpoint) else {
+            changes.push(TransactionChange::InputRemoved(input.outpoint));
+            continue;
+        };
+
+        if i != j {
+            changes.push(TransactionChange::InputMoved { outpoint: input.outpoint, before: i, after: j });
+        }
+
+        if input.sequence != other_input.sequence {
+            changes.push(TransactionChange::InputSequence { outpoint: input.outpoint, before: input.sequence, after: other_input.sequence });
+        }
+
+        if input.script != other_input.script {
+            changes.push(TransactionChange::InputScript { outpoint: input.outpoint, before: input.script.clone(), after: other_input.script.clone() });
+        }
+
+        if let (Some(segwit_fields), Some(other_segwit_fields)) = (segwit_fields, other_segwit_fields) {
+            let empty_field = SegWitField::new(vec![]);
+
+            let field = segwit_fields.get(i).unwrap_or(&empty_field);
+            let other_field = other_segwit_fields.get(j).unwrap_or(&empty_field);
+
+            if field != other_field {
+                changes.push(TransactionChange::InputWitness { outpoint: input.outpoint, before: field.clone(), after: other_field.clone() });
+            }
+        }
+    }
+
+    for other_input in &other.inputs {
+        if !transaction.inputs.iter().any(|input| input.outpoint == other_input.outpoint) {
+            changes.push(TransactionChange::InputAdded(other_input.outpoint));
+        }
+    }
+
+    // UTXOs, matched by locking script.
+    let mut matched = vec![false; other.utxos.len()];
+
+    for (i, utxo) in transaction.utxos.iter().enumerate() {
+        let other_index = (0..other.utxos.len()).find(|j| !matched[*j] && other.utxos[*j].script == utxo.script);
+
+        match other_index {
+            Some(j) => {
+                matched[j] = true;
+
+                if utxo.amount != other.utxos[j].amount {
+                    changes.push(TransactionChange::OutputAmount { script: utxo.script.clone(), before: utxo.amount, after: other.utxos[j].amount });
+                }
+            },
+            None => {
+                changes.push(TransactionChange::OutputRemoved { index: i, amount: utxo.amount, script: utxo.script.clone() });
+            },
+        }
+    }
+
+    for (j, other_utxo) in other.utxos.iter().enumerate() {
+        if !matched[j] {
+            changes.push(TransactionChange::OutputAdded { index: j, amount: other_utxo.amount, script: other_utxo.script.clone() });
+        }
+    }
+
+    changes
+}
+
+impl std::fmt::Display for TransactionChange {
+    /// Displays the difference.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Version { before, after } => write!(f, "version {} -> {}", before, after),
+            Self::Locktime { before, after } => write!(f, "locktime {} -> {}", before, after),
+            Self::InputAdded(outpoint) => write!(f, "+ input {}", outpoint),
+            Self::InputRemoved(outpoint) => write!(f, "- input {}", outpoint),
+            Self::InputMoved { outpoint, before, after } => write!(f, "input {} moved {} -> {}", outpoint, before, after),
+            Self::InputSequence { outpoint, before, after } => write!(f, "input {} sequence {:#010x} -> {:#010x}", outpoint, before, after),
+            Self::InputScript { outpoint, before, after } => write!(f, "input {} script {:?} -> {:?}", outpoint, before, after),
+            Self::InputWitness { outpoint, before, after } => write!(f, "input {} witness {:?} -> {:?}", outpoint, before.to_vec(), after.to_vec()),
+            Self::OutputAdded { index, amount, script } => write!(f, "+ output {}: {} to {}", index, amount, script.asm()),
+            Self::OutputRemoved { index, amount, script } => write!(f, "- output {}: {} to {}", index, amount, script.asm()),
+            Self::OutputAmount { script, before, after } => write!(f, "output to {}: {} -> {}", script.asm(), before, after),
+        }
+    }
+}
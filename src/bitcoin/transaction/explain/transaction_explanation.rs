@@ -0,0 +1,333 @@
+//! Bitcoin transaction explanations.
+//!
+//! Summarizes what a transaction does, from the perspective of a wallet that knows some set of
+//! its own locking scripts (or addresses); e.g.; when reviewing a transaction before co-signing.
+//!
+//! - Transaction inputs; the UTXOs spent, their amounts and script types, and the `SIGHASH`
+//!   flags of any signatures already present.
+//! - UTXOs; classified as external payments, change (to a known locking script), or null data.
+//! - The fee and fee rate.
+//! - Time locks, and replaceability (BIP-125).
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::AbsoluteLockTime;
+use crate::bitcoin::transaction::RelativeLockTime;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeRate;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::SigHashFlag;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::serialization::bitcoin_address::BitcoinAddress;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::util::byte_string::ByteSlice;
+
+/// A set of locking scripts (and addresses) known to belong to a wallet.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct KnownScripts {
+    scripts: Vec<ScriptBytes>,
+    addresses: Vec<BitcoinAddress>,
+}
+
+impl KnownScripts {
+    /// Create an empty set of known locking scripts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a known locking script.
+    pub fn insert_script(&mut self, script: ScriptBytes) {
+        self.scripts.push(script);
+    }
+
+    /// Adds a known address; i.e.; its locking script.
+    pub fn insert_address(&mut self, address: BitcoinAddress) {
+        self.addresses.push(address);
+    }
+
+    /// Indicates that a locking script is known.
+    pub fn contains(&self, script: &ScriptBytes) -> bool {
+        if self.scripts.contains(script) { return true }
+
+        self.addresses.iter().any(|address| address.script_pubkey() == *script)
+    }
+}
+
+/// Classification of a UTXO.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum OutputKind {
+    /// A payment to a locking script that is not known.
+    External,
+
+    /// A payment to a known locking script.
+    Change,
+
+    /// A null data (`OP_RETURN`) UTXO.
+    NullData,
+}
+
+/// Explanation of a transaction input.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct InputExplanation {
+    /// The UTXO being spent.
+    pub outpoint: OutPoint,
+
+    /// Amount of the UTXO being spent.
+    pub amount: Amount,
+
+    /// Script type of the UTXO being spent.
+    pub script_type: BitcoinTransactionType,
+
+    /// Indicates that the UTXO being spent has a known locking script.
+    pub known: bool,
+
+    /// Sequence number (`nSequence`).
+    pub sequence: u32,
+
+    /// The relative time lock (BIP-68), if any and if enforced (transaction version 2 and above).
+    pub relative_locktime: Option<RelativeLockTime>,
+
+    /// `SIGHASH` flags of the signatures present in the input script and segregated witness
+    /// field, in order.
+    pub sighash_flags: Vec<SigHashFlag>,
+}
+
+/// Explanation of a UTXO.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct OutputExplanation {
+    /// Output index.
+    pub index: usize,
+
+    /// UTXO amount.
+    pub amount: Amount,
+
+    /// Script type of the UTXO.
+    pub script_type: BitcoinTransactionType,
+
+    /// Classification of the UTXO.
+    pub kind: OutputKind,
+}
+
+/// Explanation of a transaction.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct TransactionExplanation {
+    /// Transaction ID.
+    pub txid: TransactionId,
+
+    /// Transaction inputs.
+    pub inputs: Vec<InputExplanation>,
+
+    /// UTXOs.
+    pub outputs: Vec<OutputExplanation>,
+
+    /// Sum of the amounts of the UTXOs being spent.
+    pub input_amount: Amount,
+
+    /// Sum of the amounts of the UTXOs.
+    pub output_amount: Amount,
+
+    /// Sum of the amounts paid to locking scripts that are not known.
+    pub external_amount: Amount,
+
+    /// Sum of the amounts paid to known locking scripts.
+    pub change_amount: Amount,
+
+    /// Transaction fee.
+    pub fee: Amount,
+
+    /// Transaction virtual size (in virtual bytes).
+    pub vsize: usize,
+
+    /// Transaction fee rate.
+    pub feerate: FeeRate,
+
+    /// The absolute time lock, if any and if enforced (i.e.; not all transaction inputs are
+    /// final).
+    pub locktime: Option<AbsoluteLockTime>,
+
+    /// Indicates that the transaction signals replaceability (BIP-125).
+    pub replaceable: bool,
+}
+
+impl Transaction {
+    /// Explains the transaction, given the UTXOs it spends and a set of known locking scripts.
+    pub fn explain<P>(&self, utxo_provider: &P, known_scripts: &KnownScripts) -> Result<TransactionExplanation, FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        explain_transaction(self, None, self.vsize(), utxo_provider, known_scripts)
+    }
+}
+
+impl SegWitTransaction {
+    /// Explains the transaction, given the UTXOs it spends and a set of known locking scripts.
+    ///
+    /// Witness data is discounted, per the transaction virtual size.
+    pub fn explain<P>(&self, utxo_provider: &P, known_scripts: &KnownScripts) -> Result<TransactionExplanation, FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        explain_transaction(self, Some(self.segwit_fields()), self.vsize(), utxo_provider, known_scripts)
+    }
+}
+
+fn explain_transaction<P>(
+    transaction: &Transaction,
+    segwit_fields: Option<&[SegWitField]>,
+    vsize: usize,
+    utxo_provider: &P,
+    known_scripts: &KnownScripts
+) -> Result<TransactionExplanation, FeeCalculationError>
+where P: UtxoProvider + ?Sized {
+    let mut inputs: Vec<InputExplanation> = Vec::with_capacity(transaction.inputs.len());
+
+    for (i, input) in transaction.inputs.iter().enumerate() {
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+
+        let mut sighash_flags = signature_sighash_flags(&input.script);
+
+        if let Some(field) = segwit_fields.and_then(|fields| fields.get(i)) {
+            for item in field.iter() {
+                sighash_flags.extend(signature_sighash_flag(item.bytes()));
+            }
+        }
+
+        let relative_locktime = if transaction.version.value() >= 2 { input.relative_locktime() } else { None };
+
+        inputs.push(
+            InputExplanation {
+                outpoint: input.outpoint,
+                amount: entry.utxo.amount,
+                script_type: entry.utxo.script.locking_script_type(),
+                known: known_scripts.contains(&entry.utxo.script),
+                sequence: input.sequence,
+                relative_locktime: relative_locktime,
+                sighash_flags: sighash_flags,
+            }
+        );
+    }
+
+    let outputs: Vec<OutputExplanation> = transaction.utxos.iter().enumerate().map(|(index, utxo)| {
+        let script_type = utxo.script.locking_script_type();
+
+        let kind = if script_type == BitcoinTransactionType::NullData {
+            OutputKind::NullData
+        } else if known_scripts.contains(&utxo.script) {
+            OutputKind::Change
+        } else {
+            OutputKind::External
+        };
+
+        OutputExplanation {
+            index: index,
+            amount: utxo.amount,
+            script_type: script_type,
+            kind: kind,
+        }
+    }).collect();
+
+    let output_sum = |kind: Option<OutputKind>| {
+        let amounts = outputs.iter().filter(|output| kind.is_none_or(|kind| output.kind == kind)).map(|output| output.amount);
+
+        Amount::checked_sum(amounts).ok_or(FeeCalculationError::AmountOutOfRange)
+    };
+
+    let input_amount = Amount::checked_sum(inputs.iter().map(|input| input.amount)).ok_or(FeeCalculationError::AmountOutOfRange)?;
+    let output_amount = output_sum(None)?;
+    let external_amount = output_sum(Some(OutputKind::External))?;
+    let change_amount = output_sum(Some(OutputKind::Change))?;
+
+    let fee = input_amount.checked_sub(output_amount).ok_or(FeeCalculationError::NegativeFee)?;
+
+    let locktime_enforced = !transaction.locktime.is_zero() && !transaction.inputs.iter().all(|input| input.is_final());
+
+    Ok(
+        TransactionExplanation {
+            txid: transaction.txid(),
+            inputs: inputs,
+            outputs: outputs,
+            input_amount: input_amount,
+            output_amount: output_amount,
+            external_amount: external_amount,
+            change_amount: change_amount,
+            fee: fee,
+            vsize: vsize,
+            feerate: FeeRate::from_fee(fee, vsize),
+            locktime: if locktime_enforced { Some(transaction.locktime.absolute()) } else { None },
+            replaceable: transaction.signals_replaceability(),
+        }
+    )
+}
+
+/// Returns the `SIGHASH` flags of the signatures pushed by a script.
+///
+/// Scripts that cannot be parsed are considered to have no signatures.
+fn signature_sighash_flags(script_bytes: &ScriptBytes) -> Vec<SigHashFlag> {
+    let Ok(script) = Script::try_from(script_bytes) else { return vec![] };
+
+    script.elements().iter().filter_map(|element| match element {
+        Element::Data(data) => signature_sighash_flag(data.bytes()),
+        _ => None,
+    }).collect()
+}
+
+/// Returns the `SIGHASH` flag of some bytes, if they are a strictly encoded DER signature followed
+/// by a `SIGHASH` flag byte.
+///
+/// Any `SIGHASH` flag byte is accepted, as consensus rules permit.
+fn signature_sighash_flag(bytes: &[u8]) -> Option<SigHashFlag> {
+    let (sighash_byte, signature) = bytes.split_last()?;
+
+    if !SignatureDerFormatBytes::is_strict_encoding(signature) { return None }
+
+    Some(SigHashFlag::from(u32::from(*sighash_byte)))
+}
+
+impl std::fmt::Display for TransactionExplanation {
+    /// Displays the explanation, over several lines.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "transaction {}", self.txid)?;
+
+        for (i, input) in self.inputs.iter().enumerate() {
+            let sighash_flags: Vec<String> = input.sighash_flags.iter().map(|flag| flag.to_string()).collect();
+
+            write!(f, "  input {}: {} {} ({})", i, input.outpoint, input.amount, input.script_type.name())?;
+
+            if input.known { write!(f, " [ours]")? }
+            if !sighash_flags.is_empty() { write!(f, " signed [{}]", sighash_flags.join(", "))? }
+            if let Some(relative_locktime) = input.relative_locktime { write!(f, " relative time lock {}", relative_locktime)? }
+
+            writeln!(f)?;
+        }
+
+        for output in &self.outputs {
+            let kind = match output.kind {
+                OutputKind::External => "external",
+                OutputKind::Change => "change",
+                OutputKind::NullData => "null data",
+            };
+
+            writeln!(f, "  output {}: {} ({}) {}", output.index, output.amount, output.script_type.name(), kind)?;
+        }
+
+        writeln!(f, "  sending {} externally, {} as change", self.external_amount, self.change_amount)?;
+        writeln!(f, "  fee {} ({} over {} vB)", self.fee, self.feerate, self.vsize)?;
+
+        match self.locktime {
+            Some(locktime) => writeln!(f, "  time locked until {}", locktime)?,
+            None => writeln!(f, "  not time locked")?,
+        }
+
+        write!(f, "  {}", if self.replaceable { "replaceable (BIP-125)" } else { "not replaceable" })
+    }
+}
@@ -0,0 +1,77 @@
+//! Child-pays-for-parent (CPFP).
+//!
+//! An unconfirmed transaction paying too low a fee may be accelerated by spending one of its
+//! UTXOs in a child transaction that pays a higher fee. Miners evaluate the parent and child
+//! together, as a package whose fee rate is the sum of their fees over the sum of their virtual
+//! sizes.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::TransactionInput;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::Locktime;
+use crate::bitcoin::transaction::fee::transaction_fee::DUST_THRESHOLD;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeRate;
+use crate::bitcoin::transaction::fee::transaction_fee::MIN_RELAY_FEERATE;
+use crate::bitcoin::transaction::fee::replace_by_fee::FeeBumpError;
+use crate::bitcoin::transaction::fee::replace_by_fee::MAX_BIP125_RBF_SEQUENCE;
+use crate::bitcoin::transaction::size::size_estimation::InputSizeTemplate;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::script::ScriptBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_value::ByteValue4;
+
+impl Transaction {
+    /// Creates a child transaction which spends the UTXO at `output_index` to a single output
+    /// (locked by `locking_script`), paying a fee such that the package (this transaction and
+    /// the child) meets some target fee rate.
+    ///
+    /// The size of the child's signed transaction input is estimated from `template`, which
+    /// should correspond to the spent UTXO's locking script.
+    ///
+    /// The child's transaction input signals replaceability, and has an empty script; the child
+    /// must be signed before it is broadcast.
+    pub fn child_pays_for_parent<P>(
+        &self,
+        output_index: usize,
+        locking_script: ScriptBytes,
+        template: InputSizeTemplate,
+        package_feerate: FeeRate,
+        utxo_provider: &P
+    ) -> Result<Transaction, FeeBumpError>
+    where P: UtxoProvider + ?Sized {
+        let utxo = self.utxos.get(output_index).ok_or(FeeBumpError::OutputNotFound(output_index))?;
+        let outpoint_index = u32::try_from(output_index).map_err(|_| FeeBumpError::OutputNotFound(output_index))?;
+
+        let parent_fee = self.fee(utxo_provider)?;
+        let parent_vsize = self.vsize();
+
+        let mut child = Transaction {
+            version: self.version,
+            inputs: vec![
+                TransactionInput::new(OutPoint::new(self.txid(), outpoint_index), ScriptBytes::of(&[]), MAX_BIP125_RBF_SEQUENCE),
+            ],
+            utxos: vec![
+                UnspentTransactionOutput::new(utxo.amount, locking_script),
+            ],
+            locktime: Locktime::of(&[0x00_u8, 0x00_u8, 0x00_u8, 0x00_u8]),
+        };
+
+        let child_vsize = child.estimated_vsize(&[template])?;
+
+        let package_fee = package_feerate.fee(parent_vsize + child_vsize).ok_or(FeeCalculationError::AmountOutOfRange)?;
+        let minimum_fee = MIN_RELAY_FEERATE.fee(child_vsize).ok_or(FeeCalculationError::AmountOutOfRange)?;
+
+        // The parent may already pay for (part of) the package.
+        let child_fee = std::cmp::max(package_fee.checked_sub(parent_fee).unwrap_or(Amount::ZERO), minimum_fee);
+
+        child.utxos[0].amount = utxo.amount
+            .checked_sub(child_fee)
+            .filter(|amount| amount.to_sat() >= DUST_THRESHOLD)
+            .ok_or(FeeBumpError::InsufficientOutputAmount(output_index))?;
+
+        Ok(child)
+    }
+}
@@ -1,3 +1,5 @@
 //! Bitcoin transaction fees.
 
 pub mod transaction_fee;
+pub mod replace_by_fee;
+pub mod child_pays_for_parent;
@@ -0,0 +1,264 @@
+//! Replace-by-fee (BIP-125).
+//!
+//! An unconfirmed transaction may be replaced by another transaction which spends (some of) the
+//! same UTXOs, provided that the original transaction signals replaceability and the replacement
+//! pays a sufficiently higher fee. Nodes enforce the following rules.
+//!
+//! 1. The original transaction signals replaceability; one of its transaction inputs has a
+//!    sequence number (`nSequence`) less than `0xfffffffe`.
+//! 2. The replacement only includes an unconfirmed transaction input if that input was included
+//!    in the original transaction.
+//! 3. The replacement pays an absolute fee of at least the sum paid by the original transaction
+//!    (and all of its descendants, which are evicted alongside it).
+//! 4. The replacement pays for its own bandwidth, at or above the incremental relay fee rate; the
+//!    fee increase must be at least `incremental relay fee rate * replacement virtual size`.
+//! 5. At most 100 transactions are replaced (the original, plus its descendants).
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeRate;
+use crate::bitcoin::transaction::fee::transaction_fee::DUST_THRESHOLD;
+use crate::bitcoin::transaction::size::size_estimation::SizeEstimationError;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::script::ScriptBytes;
+use crate::util::byte_string::ByteString;
+
+/// The largest sequence number (`nSequence`) which signals replaceability.
+pub const MAX_BIP125_RBF_SEQUENCE: u32 = 0xfffffffd;
+
+/// The incremental relay fee rate (1 satoshi per virtual byte) that a replacement must pay for its
+/// own bandwidth.
+pub const INCREMENTAL_RELAY_FEERATE: FeeRate = FeeRate::from_sat_per_kvb(1000);
+
+/// The maximum number of transactions that a replacement may evict.
+pub const MAX_REPLACEMENT_CANDIDATES: usize = 100;
+
+/// Errors thrown when bumping transaction fees.
+#[derive(Debug)]
+pub enum FeeBumpError {
+    FeeCalculationError(FeeCalculationError),
+    SizeEstimationError(SizeEstimationError),
+
+    /// BIP-125 rule 1; the original transaction does not signal replaceability.
+    NotReplaceable,
+
+    /// BIP-125 rule 2; the replacement spends an unconfirmed UTXO that the original did not.
+    NewUnconfirmedInput(OutPoint),
+
+    /// BIP-125 rule 3; the replacement pays a lower absolute fee than the replaced transactions.
+    InsufficientAbsoluteFee,
+
+    /// BIP-125 rule 4; the replacement does not pay for its own bandwidth.
+    InsufficientFeeIncrement,
+
+    /// BIP-125 rule 5; too many transactions would be replaced.
+    TooManyReplacements,
+
+    /// The transaction output to draw the fee from does not exist.
+    OutputNotFound(usize),
+
+    /// The transaction output to draw the fee from cannot cover the fee (without becoming dust).
+    InsufficientOutputAmount(usize),
+}
+
+impl From<FeeCalculationError> for FeeBumpError {
+    fn from(error: FeeCalculationError) -> Self {
+        Self::FeeCalculationError(error)
+    }
+}
+
+impl From<SizeEstimationError> for FeeBumpError {
+    fn from(error: SizeEstimationError) -> Self {
+        Self::SizeEstimationError(error)
+    }
+}
+
+impl From<UtxoProviderError> for FeeBumpError {
+    fn from(error: UtxoProviderError) -> Self {
+        Self::FeeCalculationError(FeeCalculationError::UtxoProviderError(error))
+    }
+}
+
+impl Transaction {
+    /// Indicates that the transaction signals replaceability (BIP-125); i.e.; that any of its
+    /// transaction inputs have a sequence number of at most `0xfffffffd`.
+    ///
+    /// Replaceability inherited from unconfirmed ancestors is not considered.
+    pub fn signals_replaceability(&self) -> bool {
+        self.inputs.iter().any(|input| input.sequence <= MAX_BIP125_RBF_SEQUENCE)
+    }
+
+    /// Creates a replacement transaction that pays a higher fee, targeting some fee rate.
+    ///
+    /// The fee increase is drawn from the UTXO at `change_index`, whose amount is reduced. The
+    /// resulting fee satisfies both the target fee rate, and BIP-125 rules 3 and 4.
+    ///
+    /// `descendants` are the unconfirmed transactions which spend this transaction's UTXOs
+    /// (directly or indirectly), and would be evicted alongside it; the replacement also pays for
+    /// their fees.
+    ///
+    /// The replacement is assumed to have the same virtual size as this (signed) transaction.
+    ///
+    /// Every transaction input signals replaceability, and its script is cleared; the
+    /// replacement must be signed again before it is broadcast.
+    pub fn replace_by_fee<P>(
+        &self,
+        change_index: usize,
+        target_feerate: FeeRate,
+        descendants: &[Transaction],
+        utxo_provider: &P
+    ) -> Result<Transaction, FeeBumpError>
+    where P: UtxoProvider + ?Sized {
+        replace_transaction_by_fee(self, self.vsize(), change_index, target_feerate, descendants, utxo_provider)
+    }
+
+    /// Validates this transaction as a replacement of an `original` transaction, per BIP-125
+    /// rules 1 through 5.
+    ///
+    /// `descendants` are the unconfirmed transactions which spend the original's UTXOs (directly
+    /// or indirectly), and would be evicted alongside it.
+    ///
+    /// The UTXO provider must provide the UTXOs spent by this transaction, the original, and
+    /// its descendants; UTXOs without a confirmation height are considered unconfirmed.
+    pub fn validate_replacement<P>(
+        &self,
+        original: &Transaction,
+        descendants: &[Transaction],
+        utxo_provider: &P
+    ) -> Result<(), FeeBumpError>
+    where P: UtxoProvider + ?Sized {
+        validate_transaction_replacement(self, self.vsize(), original, descendants, utxo_provider)
+    }
+}
+
+impl SegWitTransaction {
+    /// Creates a replacement transaction that pays a higher fee, targeting some fee rate (see
+    /// `Transaction::replace_by_fee`).
+    ///
+    /// Witness data is discounted, per the transaction virtual size. Segregated witness fields
+    /// are cleared, alongside transaction input scripts.
+    pub fn replace_by_fee<P>(
+        &self,
+        change_index: usize,
+        target_feerate: FeeRate,
+        descendants: &[Transaction],
+        utxo_provider: &P
+    ) -> Result<SegWitTransaction, FeeBumpError>
+    where P: UtxoProvider + ?Sized {
+        let replacement = replace_transaction_by_fee(self, self.vsize(), change_index, target_feerate, descendants, utxo_provider)?;
+
+        Ok(SegWitTransaction::from_transaction(replacement))
+    }
+
+    /// Validates this transaction as a replacement of an `original` transaction, per BIP-125
+    /// rules 1 through 5 (see `Transaction::validate_replacement`).
+    ///
+    /// Witness data is discounted, per the transaction virtual size.
+    pub fn validate_replacement<P>(
+        &self,
+        original: &Transaction,
+        descendants: &[Transaction],
+        utxo_provider: &P
+    ) -> Result<(), FeeBumpError>
+    where P: UtxoProvider + ?Sized {
+        validate_transaction_replacement(self, self.vsize(), original, descendants, utxo_provider)
+    }
+}
+
+/// Sums the fees of an original transaction and its descendants, all of which a replacement
+/// evicts (BIP-125 rule 3).
+fn replaced_fees<P>(original: &Transaction, descendants: &[Transaction], utxo_provider: &P) -> Result<Amount, FeeBumpError>
+where P: UtxoProvider + ?Sized {
+    let mut replaced_fees = original.fee(utxo_provider)?;
+
+    for descendant in descendants {
+        replaced_fees = replaced_fees
+            .checked_add(descendant.fee(utxo_provider)?)
+            .ok_or(FeeCalculationError::AmountOutOfRange)?;
+    }
+
+    Ok(replaced_fees)
+}
+
+fn replace_transaction_by_fee<P>(
+    transaction: &Transaction,
+    vsize: usize,
+    change_index: usize,
+    target_feerate: FeeRate,
+    descendants: &[Transaction],
+    utxo_provider: &P
+) -> Result<Transaction, FeeBumpError>
+where P: UtxoProvider + ?Sized {
+    if !transaction.signals_replaceability() { return Err(FeeBumpError::NotReplaceable) }
+
+    let original_fee = transaction.fee(utxo_provider)?;
+    let replaced_fees = replaced_fees(transaction, descendants, utxo_provider)?;
+
+    let target_fee = target_feerate.fee(vsize).ok_or(FeeCalculationError::AmountOutOfRange)?;
+    let minimum_fee = INCREMENTAL_RELAY_FEERATE.fee(vsize)
+        .and_then(|increment| replaced_fees.checked_add(increment))
+        .ok_or(FeeCalculationError::AmountOutOfRange)?;
+
+    let fee_increase = std::cmp::max(target_fee, minimum_fee)
+        .checked_sub(original_fee)
+        .ok_or(FeeCalculationError::AmountOutOfRange)?;
+
+    let mut replacement = transaction.clone();
+
+    let change = replacement.utxos.get_mut(change_index).ok_or(FeeBumpError::OutputNotFound(change_index))?;
+
+    change.amount = change.amount
+        .checked_sub(fee_increase)
+        .filter(|amount| amount.to_sat() >= DUST_THRESHOLD)
+        .ok_or(FeeBumpError::InsufficientOutputAmount(change_index))?;
+
+    for input in replacement.inputs.iter_mut() {
+        input.script = ScriptBytes::of(&[]);
+        input.sequence = std::cmp::min(input.sequence, MAX_BIP125_RBF_SEQUENCE);
+    }
+
+    Ok(replacement)
+}
+
+fn validate_transaction_replacement<P>(
+    replacement: &Transaction,
+    vsize: usize,
+    original: &Transaction,
+    descendants: &[Transaction],
+    utxo_provider: &P
+) -> Result<(), FeeBumpError>
+where P: UtxoProvider + ?Sized {
+    // Rule 1.
+    if !original.signals_replaceability() { return Err(FeeBumpError::NotReplaceable) }
+
+    // Rule 2.
+    for input in &replacement.inputs {
+        let spent_by_original = original.inputs.iter().any(|original_input| original_input.outpoint == input.outpoint);
+
+        if !spent_by_original && utxo_provider.utxo(&input.outpoint)?.is_unconfirmed() {
+            return Err(FeeBumpError::NewUnconfirmedInput(input.outpoint));
+        }
+    }
+
+    // Rule 5.
+    if 1 + descendants.len() > MAX_REPLACEMENT_CANDIDATES { return Err(FeeBumpError::TooManyReplacements) }
+
+    // Rule 3.
+    let replaced_fees = replaced_fees(original, descendants, utxo_provider)?;
+    let replacement_fee = replacement.fee(utxo_provider)?;
+
+    if replacement_fee < replaced_fees { return Err(FeeBumpError::InsufficientAbsoluteFee) }
+
+    // Rule 4.
+    let minimum_fee = INCREMENTAL_RELAY_FEERATE.fee(vsize)
+        .and_then(|increment| replaced_fees.checked_add(increment))
+        .ok_or(FeeCalculationError::AmountOutOfRange)?;
+
+    if replacement_fee < minimum_fee { return Err(FeeBumpError::InsufficientFeeIncrement) }
+
+    Ok(())
+}
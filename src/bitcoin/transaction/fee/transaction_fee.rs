@@ -1,14 +1,94 @@
 //! Bitcoin transaction fees.
 
 use crate::bitcoin::transaction::Transaction;
-use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+
+/// The minimum relay fee rate (1 satoshi per virtual byte).
+pub const MIN_RELAY_FEERATE: FeeRate = FeeRate::from_sat_per_kvb(1000);
+
+/// The amount (of satoshi) below which a UTXO is considered dust, and is not relayed.
+///
+/// Based on the cost of spending a P2PKH UTXO at the default dust relay fee rate.
+pub const DUST_THRESHOLD: i64 = 546;
 
 /// Errors thrown when calculating transaction fees.
 #[derive(Debug)]
 pub enum FeeCalculationError {
     NegativeFee,
-    TransactionNotFound,
-    UtxoNotFound,
+    UtxoProviderError(UtxoProviderError),
+
+    /// A UTXO amount, or the sum of UTXO amounts, is outside of `0..=MAX_MONEY`.
+    AmountOutOfRange,
+}
+
+/// A fee rate, in satoshi per 1000 virtual bytes (sat/kvB).
+///
+/// Fee rates are integers, as in Bitcoin Core; fees calculated from a fee rate are rounded up to
+/// the next satoshi.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeeRate {
+    /// Satoshi per 1000 virtual bytes.
+    sat_per_kvb: u64,
+}
+
+impl FeeRate {
+    /// Zero satoshi per virtual byte.
+    pub const ZERO: Self = Self { sat_per_kvb: 0 };
+
+    /// Create a fee rate of some satoshi per 1000 virtual bytes.
+    pub const fn from_sat_per_kvb(sat_per_kvb: u64) -> Self {
+        Self { sat_per_kvb: sat_per_kvb }
+    }
+
+    /// Create a fee rate of some (whole) satoshi per virtual byte.
+    pub const fn from_sat_per_vb(sat_per_vb: u64) -> Self {
+        Self { sat_per_kvb: sat_per_vb.saturating_mul(1000) }
+    }
+
+    /// Create the fee rate of a fee paid over some virtual size (in virtual bytes), rounding
+    /// down.
+    ///
+    /// A virtual size of zero has a fee rate of zero.
+    pub fn from_fee(fee: Amount, vsize: usize) -> Self {
+        let fee = u64::try_from(fee.to_sat()).unwrap_or(0);
+        let vsize = u64::try_from(vsize).unwrap();
+
+        if vsize == 0 { return Self::ZERO }
+
+        Self { sat_per_kvb: fee.saturating_mul(1000) / vsize }
+    }
+
+    /// Returns the fee rate in satoshi per 1000 virtual bytes.
+    pub fn to_sat_per_kvb(&self) -> u64 {
+        self.sat_per_kvb
+    }
+
+    /// Calculates the fee paid over some virtual size (in virtual bytes) at this fee rate,
+    /// rounding up.
+    ///
+    /// Returns nothing if the fee would fall outside of `0..=MAX_MONEY`.
+    pub fn fee(&self, vsize: usize) -> Option<Amount> {
+        let fee = self.sat_per_kvb.checked_mul(u64::try_from(vsize).ok()?)?.div_ceil(1000);
+
+        Amount::from_sat(i64::try_from(fee).ok()?).ok()
+    }
+}
+
+impl std::fmt::Display for FeeRate {
+    /// Displays the fee rate in satoshi per virtual byte (e.g.; `14.084 sat/vB`).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{:03} sat/vB", self.sat_per_kvb / 1000, self.sat_per_kvb % 1000)
+    }
+}
+
+impl From<UtxoProviderError> for FeeCalculationError {
+    fn from(error: UtxoProviderError) -> Self {
+        Self::UtxoProviderError(error)
+    }
 }
 
 impl Transaction {
@@ -16,24 +96,21 @@ impl Transaction {
     ///
     /// The transaction fee is the difference between the sum amounts of the set of transaction
     /// inputs and the set of UTXOs.
-    pub fn fee<'a, F>(&'a self, transaction_retrieval: F) -> Result<i64, FeeCalculationError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
-        let mut input_amount: i64 = 0;
+    ///
+    /// Sums are checked; amounts outside of `0..=MAX_MONEY` are rejected rather than overflowing.
+    pub fn fee<P>(&self, utxo_provider: &P) -> Result<Amount, FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        let mut input_amounts: Vec<Amount> = Vec::with_capacity(self.inputs.len());
 
         for input in &self.inputs {
-            let prev_txn = transaction_retrieval(&input.txid).ok_or(FeeCalculationError::TransactionNotFound)?;
+            let entry = utxo_provider.utxo(&input.outpoint)?;
 
-            let utxo = &prev_txn.utxos.get(usize::try_from(input.utxo_index).unwrap()).ok_or(FeeCalculationError::UtxoNotFound)?;
-
-            input_amount += utxo.amount;
+            input_amounts.push(entry.utxo.amount);
         }
 
-        let output_amount: i64 = self.utxos.iter().map(|utxo| utxo.amount).sum();
+        let input_amount = Amount::checked_sum(input_amounts.into_iter()).ok_or(FeeCalculationError::AmountOutOfRange)?;
+        let output_amount = Amount::checked_sum(self.utxos.iter().map(|utxo| utxo.amount)).ok_or(FeeCalculationError::AmountOutOfRange)?;
 
-        if input_amount >= output_amount {
-            Ok(input_amount - output_amount)
-        } else {
-            Err(FeeCalculationError::NegativeFee)
-        }
+        input_amount.checked_sub(output_amount).ok_or(FeeCalculationError::NegativeFee)
     }
 }
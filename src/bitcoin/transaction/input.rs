@@ -1,6 +1,9 @@
 //! Transaction input structure.
 
 use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::RelativeLockTime;
+use crate::bitcoin::transaction::SEQUENCE_FINAL;
 use crate::bitcoin::transaction::transaction::TransactionParsingError;
 use crate::bitcoin::script::ScriptBytes;
 use crate::util::byte_string::ByteString;
@@ -15,11 +18,8 @@ use crate::util::varint::varint_u64;
 #[derive(Debug)]
 #[derive(Clone)]
 pub struct TransactionInput {
-    /// The Transaction ID of a previous transaction.
-    pub txid: TransactionId,
-
-    /// Output index of the previous transaction's UTXO (unspent transaction output).
-    pub utxo_index: u32,
+    /// The previous transaction's UTXO (unspent transaction output) being spent.
+    pub outpoint: OutPoint,
 
     /// Script bytes.
     pub script: ScriptBytes,
@@ -31,7 +31,7 @@ pub struct TransactionInput {
 impl std::fmt::Display for TransactionInput {
     /// Displays the transaction input.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "<transaction input [{}:{}] [{}] [{}]>", self.txid, self.utxo_index, self.script, self.sequence)
+        write!(f, "<transaction input [{}] [{}] [{}]>", self.outpoint, self.script, self.sequence)
     }
 }
 
@@ -48,8 +48,7 @@ impl ByteVector for TransactionInput {
         let mut bytes: Vec<u8> = vec![];
 
         // Previous transaction ID and previous output index.
-        bytes.extend_from_slice(&self.txid.bytes());
-        bytes.extend_from_slice(&self.utxo_index.to_le_bytes());
+        bytes.extend_from_slice(&self.outpoint.bytes());
 
         // Variable integer for script length.
         let (varint_bytes, varint_length) = varint_u64(u64::try_from(self.script.bytes().len()).unwrap());
@@ -68,15 +67,26 @@ impl ByteVector for TransactionInput {
 
 impl TransactionInput {
     /// Create a transaction input.
-    pub fn new(txid: TransactionId, utxo_index: u32, script: ScriptBytes, sequence: u32) -> Self {
+    pub fn new(outpoint: OutPoint, script: ScriptBytes, sequence: u32) -> Self {
         Self {
-            txid: txid,
-            utxo_index: utxo_index,
+            outpoint: outpoint,
             script: script,
             sequence: sequence
         }
     }
 
+    /// Indicates that the transaction input is final; i.e.; its sequence number is `0xffffffff`.
+    pub fn is_final(&self) -> bool {
+        self.sequence == SEQUENCE_FINAL
+    }
+
+    /// Returns the relative time lock (BIP-68) encoded by the sequence number, unless disabled.
+    ///
+    /// Relative time locks are only enforced for transaction versions 2 and above.
+    pub fn relative_locktime(&self) -> Option<RelativeLockTime> {
+        RelativeLockTime::from_sequence(self.sequence)
+    }
+
     /// Parse a transaction input from a sequence of bytes.
     pub fn parse_bytes(bytes: &[u8]) -> Result<(Self, usize), TransactionParsingError> {
         // The overall length of the byte slice.
@@ -118,6 +128,6 @@ impl TransactionInput {
         // Read the last 4 bytes as the sequence integer.
         let sequence = u32_little_endian(&bytes[sequence_byte_index..bytes_read]);
 
-        Ok((Self::new(txid, prev_output_index, ScriptBytes::of(script_bytes), sequence), bytes_read))
+        Ok((Self::new(OutPoint::new(txid, prev_output_index), ScriptBytes::of(script_bytes), sequence), bytes_read))
     }
 }
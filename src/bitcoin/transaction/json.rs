@@ -0,0 +1,310 @@
+//! Bitcoin transaction JSON encoding.
+//!
+//! Transactions are represented in the format given by Bitcoin Core's `decoderawtransaction`.
+//!
+//! ```text
+//! {
+//!   "txid": "<hex>",
+//!   "hash": "<hex>",          (wtxid)
+//!   "version": n,
+//!   "size": n,
+//!   "vsize": n,
+//!   "weight": n,
+//!   "locktime": n,
+//!   "vin": [
+//!     {
+//!       "txid": "<hex>",
+//!       "vout": n,
+//!       "scriptSig": { "asm": "<asm>", "hex": "<hex>" },
+//!       "txinwitness": [ "<hex>", ... ],    (if any)
+//!       "sequence": n
+//!     },
+//!     ...
+//!   ],
+//!   "vout": [
+//!     {
+//!       "value": x.xxxxxxxx,               (in bitcoin)
+//!       "n": n,
+//!       "scriptPubKey": { "asm": "<asm>", "hex": "<hex>", "address": "<address>", "type": "<type>" }
+//!     },
+//!     ...
+//!   ]
+//! }
+//! ```
+//!
+//! Coinbase transaction inputs instead give their input script as `"coinbase": "<hex>"`.
+//!
+//! Output descriptors (`"desc"`) are not produced. Addresses are produced for locking scripts that
+//! have one (see `BitcoinAddress::from_script`); i.e.; P2PKH, P2SH, and segregated witness
+//! (including P2TR) locking scripts.
+//!
+//! When parsing, only the fields needed to reconstruct the transaction are read; `"txid"` and
+//! `"hash"` are checked against the reconstructed transaction, if present.
+
+use crate::bitcoin::network::BitcoinNetworkType;
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::TransactionInput;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::AmountUnit;
+use crate::bitcoin::transaction::Version;
+use crate::bitcoin::transaction::Locktime;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::segwit::item::SegWitItem;
+use crate::bitcoin::script::ScriptBytes;
+use crate::serialization::bitcoin_address::BitcoinAddress;
+use crate::serialization::json::JsonValue;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_value::ByteValue4;
+use crate::util::hexadecimal::hexadecimal_encode;
+use crate::util::hexadecimal::hexadecimal_string;
+
+/// Errors thrown when reading a transaction from JSON.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum TransactionJsonError {
+    /// A required field is missing.
+    MissingField(&'static str),
+
+    /// A field has an unexpected type or value.
+    InvalidField(&'static str),
+
+    /// The transaction ID (`"txid"`) does not match the transaction.
+    TransactionIdMismatch,
+
+    /// The witness transaction ID (`"hash"`) does not match the transaction.
+    WitnessTransactionIdMismatch,
+
+    /// A transaction input has segregated witness data, which a transaction without witness
+    /// data cannot represent.
+    UnexpectedWitness,
+}
+
+impl Transaction {
+    /// Represents the transaction as JSON, in the format of `decoderawtransaction`.
+    ///
+    /// The network determines the encoding of addresses.
+    pub fn to_json(&self, network: BitcoinNetworkType) -> JsonValue {
+        transaction_json(self, None, self.txid().to_string(), self.base_size(), self.vsize(), self.weight(), network)
+    }
+
+    /// Reads a transaction from JSON, in the format of `decoderawtransaction`.
+    ///
+    /// Transactions with segregated witness data should be read as `SegWitTransaction`.
+    pub fn from_json(json: &JsonValue) -> Result<Self, TransactionJsonError> {
+        let (transaction, segwit_fields) = parse_transaction_json(json)?;
+
+        if segwit_fields.iter().any(|field| !field.is_empty()) { return Err(TransactionJsonError::UnexpectedWitness) }
+
+        Ok(transaction)
+    }
+}
+
+impl SegWitTransaction {
+    /// Represents the transaction as JSON, in the format of `decoderawtransaction`.
+    ///
+    /// The network determines the encoding of addresses.
+    pub fn to_json(&self, network: BitcoinNetworkType) -> JsonValue {
+        transaction_json(self, Some(self.segwit_fields()), self.wtxid().to_string(), self.total_size(), self.vsize(), self.weight(), network)
+    }
+
+    /// Reads a segregated witness transaction from JSON, in the format of `decoderawtransaction`.
+    pub fn from_json(json: &JsonValue) -> Result<Self, TransactionJsonError> {
+        let (transaction, segwit_fields) = parse_transaction_json(json)?;
+        let transaction = SegWitTransaction::new(transaction, segwit_fields)
+            .map_err(|_| TransactionJsonError::InvalidField("txinwitness"))?;
+
+        if let Some(hash) = json.get("hash") {
+            let hash = hash.as_str().ok_or(TransactionJsonError::InvalidField("hash"))?;
+
+            if hash != transaction.wtxid().to_string() { return Err(TransactionJsonError::WitnessTransactionIdMismatch) }
+        }
+
+        Ok(transaction)
+    }
+}
+
+fn transaction_json(
+    transaction: &Transaction,
+    segwit_fields: Option<&[SegWitField]>,
+    hash: String,
+    size: usize,
+    vsize: usize,
+    weight: usize,
+    network: BitcoinNetworkType,
+) -> JsonValue {
+    let is_coinbase = transaction.is_coinbase();
+
+    let vin = transaction.inputs.iter().enumerate().map(|(i, input)| {
+        let mut members: Vec<(String, JsonValue)> = vec![];
+
+        if is_coinbase {
+            members.push((String::from("coinbase"), JsonValue::string(hexadecimal(input.script.bytes()))));
+        } else {
+            members.push((String::from("txid"), JsonValue::string(input.outpoint.txid.to_string())));
+            members.push((String::from("vout"), JsonValue::integer(input.outpoint.index)));
+            members.push((
+                String::from("scriptSig"),
+                JsonValue::Object(vec![
+                    (String::from("asm"), JsonValue::string(input.script.asm_with_sighash_decode())),
+                    (String::from("hex"), JsonValue::string(hexadecimal(input.script.bytes()))),
+                ])
+            ));
+        }
+
+        if let Some(field) = segwit_fields.map(|fields| &fields[i]).filter(|field| !field.is_empty()) {
+            let items = field.iter().map(|item| JsonValue::string(hexadecimal(item.bytes()))).collect();
+
+            members.push((String::from("txinwitness"), JsonValue::Array(items)));
+        }
+
+        members.push((String::from("sequence"), JsonValue::integer(input.sequence)));
+
+        JsonValue::Object(members)
+    }).collect();
+
+    let vout = transaction.utxos.iter().enumerate().map(|(n, utxo)| {
+        let script_type = utxo.script.locking_script_type();
+
+        let mut script_pubkey: Vec<(String, JsonValue)> = vec![
+            (String::from("asm"), JsonValue::string(utxo.script.asm())),
+            (String::from("hex"), JsonValue::string(hexadecimal(utxo.script.bytes()))),
+        ];
+
+        if let Some(address) = BitcoinAddress::from_script(&utxo.script, network) {
+            script_pubkey.push((String::from("address"), JsonValue::string(address.to_string())));
+        }
+
+        script_pubkey.push((String::from("type"), JsonValue::string(script_type.name())));
+
+        JsonValue::Object(vec![
+            (String::from("value"), JsonValue::Number(utxo.amount.format_in(AmountUnit::Bitcoin))),
+            (String::from("n"), JsonValue::integer(u64::try_from(n).unwrap())),
+            (String::from("scriptPubKey"), JsonValue::Object(script_pubkey)),
+        ])
+    }).collect();
+
+    JsonValue::Object(vec![
+        (String::from("txid"), JsonValue::string(transaction.txid().to_string())),
+        (String::from("hash"), JsonValue::string(hash)),
+        (String::from("version"), JsonValue::integer(i32::from_le_bytes(transaction.version.bytes()))),
+        (String::from("size"), JsonValue::integer(u64::try_from(size).unwrap())),
+        (String::from("vsize"), JsonValue::integer(u64::try_from(vsize).unwrap())),
+        (String::from("weight"), JsonValue::integer(u64::try_from(weight).unwrap())),
+        (String::from("locktime"), JsonValue::integer(u32::from_le_bytes(transaction.locktime.bytes()))),
+        (String::from("vin"), JsonValue::Array(vin)),
+        (String::from("vout"), JsonValue::Array(vout)),
+    ])
+}
+
+fn parse_transaction_json(json: &JsonValue) -> Result<(Transaction, Vec<SegWitField>), TransactionJsonError> {
+    // Version numbers are given as signed integers, though are also accepted as unsigned.
+    let version = field(json, "version")?.as_i64()
+        .and_then(|version| i32::try_from(version).map(|version| version.to_le_bytes()).ok().or(u32::try_from(version).map(|version| version.to_le_bytes()).ok()))
+        .ok_or(TransactionJsonError::InvalidField("version"))?;
+
+    let locktime = field(json, "locktime")?.as_u64()
+        .and_then(|locktime| u32::try_from(locktime).ok())
+        .ok_or(TransactionJsonError::InvalidField("locktime"))?;
+
+    let vin = field(json, "vin")?.as_array().ok_or(TransactionJsonError::InvalidField("vin"))?;
+    let vout = field(json, "vout")?.as_array().ok_or(TransactionJsonError::InvalidField("vout"))?;
+
+    let mut inputs: Vec<TransactionInput> = Vec::with_capacity(vin.len());
+    let mut segwit_fields: Vec<SegWitField> = Vec::with_capacity(vin.len());
+
+    for input in vin {
+        let (outpoint, script) = match input.get("coinbase") {
+            Some(coinbase) => {
+                let coinbase = coinbase.as_str().ok_or(TransactionJsonError::InvalidField("coinbase"))?;
+
+                (OutPoint::null(), ScriptBytes::of(&from_hexadecimal(coinbase, "coinbase")?))
+            },
+            None => {
+                let txid: TransactionId = field(input, "txid")?.as_str()
+                    .and_then(|txid| txid.parse().ok())
+                    .ok_or(TransactionJsonError::InvalidField("txid"))?;
+
+                let index = field(input, "vout")?.as_u64()
+                    .and_then(|index| u32::try_from(index).ok())
+                    .ok_or(TransactionJsonError::InvalidField("vout"))?;
+
+                let script = field(field(input, "scriptSig")?, "hex")?.as_str()
+                    .ok_or(TransactionJsonError::InvalidField("scriptSig"))?;
+
+                (OutPoint::new(txid, index), ScriptBytes::of(&from_hexadecimal(script, "scriptSig")?))
+            },
+        };
+
+        let sequence = field(input, "sequence")?.as_u64()
+            .and_then(|sequence| u32::try_from(sequence).ok())
+            .ok_or(TransactionJsonError::InvalidField("sequence"))?;
+
+        let mut items: Vec<SegWitItem> = vec![];
+
+        if let Some(witness) = input.get("txinwitness") {
+            for item in witness.as_array().ok_or(TransactionJsonError::InvalidField("txinwitness"))? {
+                let item = item.as_str().ok_or(TransactionJsonError::InvalidField("txinwitness"))?;
+
+                items.push(SegWitItem::of(&from_hexadecimal(item, "txinwitness")?));
+            }
+        }
+
+        inputs.push(TransactionInput::new(outpoint, script, sequence));
+        segwit_fields.push(SegWitField::new(items));
+    }
+
+    let mut utxos: Vec<UnspentTransactionOutput> = Vec::with_capacity(vout.len());
+
+    for utxo in vout {
+        let amount = field(utxo, "value")?.as_number()
+            .and_then(|value| Amount::parse_in(value, AmountUnit::Bitcoin).ok())
+            .ok_or(TransactionJsonError::InvalidField("value"))?;
+
+        let script = field(field(utxo, "scriptPubKey")?, "hex")?.as_str()
+            .ok_or(TransactionJsonError::InvalidField("scriptPubKey"))?;
+
+        utxos.push(UnspentTransactionOutput::new(amount, ScriptBytes::of(&from_hexadecimal(script, "scriptPubKey")?)));
+    }
+
+    let transaction = Transaction {
+        version: Version::of(&version),
+        inputs: inputs,
+        utxos: utxos,
+        locktime: Locktime::of(&locktime.to_le_bytes()),
+    };
+
+    if let Some(txid) = json.get("txid") {
+        let txid = txid.as_str().ok_or(TransactionJsonError::InvalidField("txid"))?;
+
+        if txid != transaction.txid().to_string() { return Err(TransactionJsonError::TransactionIdMismatch) }
+    }
+
+    Ok((transaction, segwit_fields))
+}
+
+fn field<'a>(json: &'a JsonValue, key: &'static str) -> Result<&'a JsonValue, TransactionJsonError> {
+    json.get(key).ok_or(TransactionJsonError::MissingField(key))
+}
+
+fn hexadecimal(bytes: &[u8]) -> String {
+    let mut buffer = vec![0_u8; bytes.len() * 2];
+
+    hexadecimal_encode(bytes, &mut buffer).unwrap();
+
+    String::from_utf8(buffer).unwrap()
+}
+
+fn from_hexadecimal(s: &str, key: &'static str) -> Result<Vec<u8>, TransactionJsonError> {
+    if !s.len().is_multiple_of(2) { return Err(TransactionJsonError::InvalidField(key)) }
+
+    let mut buffer = vec![0_u8; s.len() / 2];
+
+    hexadecimal_string(&s.to_ascii_lowercase(), &mut buffer).map_err(|_| TransactionJsonError::InvalidField(key))?;
+
+    Ok(buffer)
+}
@@ -3,6 +3,9 @@
 use crate::util::byte_value::ByteValue4;
 use crate::util::byte_value::u32_little_endian;
 
+/// Time lock values below this threshold are block heights; otherwise, they are Unix timestamps.
+pub const LOCKTIME_THRESHOLD: u32 = 500000000;
+
 /// Bitcoin transaction time lock (`nLockTime`).
 #[derive(Debug)]
 #[derive(Clone, Copy)]
@@ -49,7 +52,7 @@ impl Locktime {
     /// Returns the interpretation of the time lock value.
     pub fn interpretation(&self) -> LocktimeType {
         match self.value {
-            value if value < 500000000_u32 => LocktimeType::BlockHeight,
+            value if value < LOCKTIME_THRESHOLD => LocktimeType::BlockHeight,
             _ => LocktimeType::UnixTimestamp,
         }
     }
@@ -68,3 +71,91 @@ impl Locktime {
         self.value
     }
 }
+
+impl Locktime {
+    /// Create a time lock from an absolute time lock.
+    pub fn new(absolute: AbsoluteLockTime) -> Self {
+        Self { value: absolute.value() }
+    }
+
+    /// Returns the time lock as an absolute time lock.
+    pub fn absolute(&self) -> AbsoluteLockTime {
+        AbsoluteLockTime::from_consensus(self.value)
+    }
+
+    /// Indicates that the time lock is zero; i.e.; the transaction is not time locked.
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+/// An absolute time lock, either at some block height or some Unix timestamp.
+///
+/// Used by transactions (`nLockTime`) and `OP_CHECKLOCKTIMEVERIFY` (BIP-65).
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub enum AbsoluteLockTime {
+    /// Locked until the block height is greater than this value.
+    Blocks(u32),
+
+    /// Locked until the median time past is greater than this Unix timestamp.
+    Seconds(u32),
+}
+
+impl AbsoluteLockTime {
+    /// Interpret a consensus value (`nLockTime`) as an absolute time lock.
+    pub fn from_consensus(value: u32) -> Self {
+        match value {
+            value if value < LOCKTIME_THRESHOLD => Self::Blocks(value),
+            value => Self::Seconds(value),
+        }
+    }
+
+    /// Create an absolute time lock at some block height.
+    ///
+    /// Returns nothing if the height would be interpreted as a timestamp.
+    pub fn from_height(height: u32) -> Option<Self> {
+        if height < LOCKTIME_THRESHOLD { Some(Self::Blocks(height)) } else { None }
+    }
+
+    /// Create an absolute time lock at some Unix timestamp.
+    ///
+    /// Returns nothing if the timestamp would be interpreted as a block height.
+    pub fn from_timestamp(timestamp: u32) -> Option<Self> {
+        if timestamp >= LOCKTIME_THRESHOLD { Some(Self::Seconds(timestamp)) } else { None }
+    }
+
+    /// Returns the consensus value (`nLockTime`).
+    pub fn value(&self) -> u32 {
+        match *self {
+            Self::Blocks(value) | Self::Seconds(value) => value,
+        }
+    }
+
+    /// Indicates that both time locks are measured in the same unit (block height, or time).
+    pub fn is_same_unit(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Blocks(_), Self::Blocks(_)) | (Self::Seconds(_), Self::Seconds(_))
+        )
+    }
+
+    /// Indicates that the time lock has passed, for a transaction included in a block at some
+    /// height, with some median time past (BIP-113).
+    pub fn is_satisfied_by(&self, block_height: u32, median_time_past: u32) -> bool {
+        match *self {
+            Self::Blocks(height) => height < block_height,
+            Self::Seconds(timestamp) => timestamp < median_time_past,
+        }
+    }
+}
+
+impl std::fmt::Display for AbsoluteLockTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Blocks(height) => write!(f, "block height {}", height),
+            Self::Seconds(timestamp) => write!(f, "timestamp {}", timestamp),
+        }
+    }
+}
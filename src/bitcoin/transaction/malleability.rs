@@ -0,0 +1,194 @@
+//! Bitcoin transaction malleability normalization.
+//!
+//! The transaction ID of a legacy transaction commits to its transaction input scripts, which
+//! are not themselves covered by signatures. A third party may therefore rewrite them, without
+//! invalidating the transaction, to produce a variant with a different transaction ID.
+//!
+//! - Flipping a signature's s-value to `n - s` (a "high" s-value, see BIP-146).
+//! - Encoding a signature in non-strict DER (e.g.; with superfluous padding bytes, see BIP-66).
+//! - Pushing data with a non-minimal data opcode (e.g.; `OP_PUSHDATA1` for 20 bytes).
+//! - Adding data elements before those consumed by the locking script.
+//! - Using a non-empty dummy element for `OP_CHECKMULTISIG` (see BIP-147).
+//!
+//! Normalization undoes these, producing a canonical transaction that is equally valid. All
+//! variants of a transaction share the same normalized transaction ID.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Opcode;
+use crate::bitcoin::script::SigHashFlag;
+use crate::bitcoin::script::opcode::ConstantOpcode;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::crypto::ecdsa::signature::Signature;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+
+/// A normalized transaction.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct NormalizedTransaction {
+    /// The normalized transaction.
+    pub transaction: Transaction,
+
+    /// Indices of the transaction inputs whose scripts were rewritten.
+    pub normalized_inputs: Vec<usize>,
+
+    /// Indicates that the transaction ID changed; i.e.; the original transaction was not in
+    /// canonical form.
+    pub txid_changed: bool,
+}
+
+impl Transaction {
+    /// Rewrites the transaction input scripts into canonical form.
+    ///
+    /// - Signatures are given a low s-value, and strict DER encoding.
+    /// - Data is pushed with minimal data opcodes.
+    /// - Data elements not consumed by P2PK, P2PKH, multisig, or P2SH (multisig or nested
+    ///   segregated witness) locking scripts are removed; the `OP_CHECKMULTISIG` dummy element is
+    ///   made empty.
+    ///
+    /// Transaction input scripts that contain opcodes other than data pushes, or that unlock other
+    /// locking scripts (e.g.; P2SH with another redeem script), are not rewritten.
+    pub fn normalize<P>(&self, utxo_provider: &P) -> Result<NormalizedTransaction, UtxoProviderError>
+    where P: UtxoProvider + ?Sized {
+        let mut transaction = self.clone();
+        let mut normalized_inputs: Vec<usize> = vec![];
+
+        for (i, input) in transaction.inputs.iter_mut().enumerate() {
+            let entry = utxo_provider.utxo(&input.outpoint)?;
+
+            let Some(script) = normalized_input_script(&input.script, &entry.utxo.script) else { continue };
+
+            if script != input.script {
+                input.script = script;
+                normalized_inputs.push(i);
+            }
+        }
+
+        Ok(
+            NormalizedTransaction {
+                txid_changed: !normalized_inputs.is_empty(),
+                transaction: transaction,
+                normalized_inputs: normalized_inputs,
+            }
+        )
+    }
+
+    /// Returns the transaction ID of the normalized transaction.
+    ///
+    /// Malleated variants of a transaction share the same normalized transaction ID.
+    pub fn normalized_txid<P>(&self, utxo_provider: &P) -> Result<TransactionId, UtxoProviderError>
+    where P: UtxoProvider + ?Sized {
+        Ok(self.normalize(utxo_provider)?.transaction.txid())
+    }
+}
+
+/// Returns the canonical form of a transaction input script, given the locking script it
+/// unlocks; or nothing, if the transaction input script is not push-only, the locking script (or
+/// P2SH redeem script) is not recognized, or too few data elements are pushed to satisfy it.
+///
+/// Only data elements known to be signatures are normalized as signatures.
+fn normalized_input_script(script_bytes: &ScriptBytes, locking_script: &ScriptBytes) -> Option<ScriptBytes> {
+    let script = Script::try_from(script_bytes).ok()?;
+
+    // Data pushed by the script, in order.
+    let mut pushes: Vec<Vec<u8>> = Vec::with_capacity(script.elements().len());
+
+    for element in script.elements() {
+        pushes.push(pushed_data(element)?);
+    }
+
+    // The number of data elements consumed, the positions of the signatures among them, and
+    // whether the first of these is an `OP_CHECKMULTISIG` dummy element.
+    let (consumed, signatures, multisig_dummy) = match locking_script.locking_script_type() {
+        BitcoinTransactionType::P2pk => (1, 0..1, false),
+        BitcoinTransactionType::P2pkh => (2, 0..1, false),
+        BitcoinTransactionType::Multisig => {
+            let m = multisig_threshold(locking_script);
+
+            (m + 1, 1..(m + 1), true)
+        },
+        BitcoinTransactionType::P2sh => {
+            let redeem_script = ScriptBytes::of(pushes.last()?);
+
+            match redeem_script.locking_script_type() {
+                BitcoinTransactionType::Multisig => {
+                    let m = multisig_threshold(&redeem_script);
+
+                    (m + 2, 1..(m + 1), true)
+                },
+                BitcoinTransactionType::P2wpkh | BitcoinTransactionType::P2wsh => (1, 0..0, false),
+                _ => return None,
+            }
+        },
+        _ => return None,
+    };
+
+    if pushes.len() < consumed { return None }
+
+    pushes.drain(0..(pushes.len() - consumed));
+
+    if multisig_dummy { pushes[0].clear() }
+
+    for data in &mut pushes[signatures] {
+        if let Some(signature) = normalized_signature(data) { *data = signature }
+    }
+
+    let elements: Vec<Element> = pushes.iter().map(|data| minimal_push(data)).collect();
+
+    Some(ScriptBytes::from(&Script::new(&elements).ok()?))
+}
+
+/// Returns the data pushed by a script element, if it is a data push.
+fn pushed_data(element: &Element) -> Option<Vec<u8>> {
+    match element {
+        Element::Data(data) => Some(data.bytes().to_vec()),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::OpFalse)) => Some(vec![]),
+        Element::Opcode(Opcode::Constant(ConstantOpcode::Op1Negate)) => Some(vec![0x81_u8]),
+        Element::Opcode(opcode @ Opcode::Constant(_)) => Some(vec![u8::from(*opcode) - 0x50_u8]),
+        _ => None,
+    }
+}
+
+/// Returns the script element that pushes some data with the minimal data opcode (BIP-62).
+fn minimal_push(data: &[u8]) -> Element {
+    match data {
+        [] => Element::Opcode(Opcode::Constant(ConstantOpcode::OpFalse)),
+        [n @ 0x01_u8..=0x10_u8] => Element::Opcode(Opcode::try_from(0x50_u8 + n).unwrap()),
+        [0x81_u8] => Element::Opcode(Opcode::Constant(ConstantOpcode::Op1Negate)),
+        _ => Element::Data(DataElement::of(data)),
+    }
+}
+
+/// Returns the canonical form of a DER format signature followed by a `SIGHASH` flag byte; or
+/// nothing, if the data is not a signature.
+fn normalized_signature(data: &[u8]) -> Option<Vec<u8>> {
+    let (sighash_byte, signature_bytes) = data.split_last()?;
+
+    SigHashFlag::try_from(*sighash_byte).ok()?;
+
+    let mut signature = Signature::from(SignatureDerFormatBytes::try_of(signature_bytes)?);
+
+    if !signature.is_low_s_secp256k1() {
+        signature.s = ELLIPTIC_CURVE_ORDER - signature.s;
+    }
+
+    let mut bytes = SignatureDerFormatBytes::from(&signature).bytes().to_vec();
+
+    bytes.push(*sighash_byte);
+
+    Some(bytes)
+}
+
+/// Returns the number of signatures (`m`) required by a multisig locking script.
+fn multisig_threshold(locking_script: &ScriptBytes) -> usize {
+    usize::from(locking_script.bytes()[0] - 0x50_u8)
+}
@@ -2,20 +2,45 @@
 
 mod transaction;
 mod txid;
+mod outpoint;
+mod amount;
 mod version;
 mod locktime;
+mod sequence;
 mod input;
 mod utxo;
 
 pub mod fee;
+pub mod size;
 pub mod verify;
 pub mod signing;
+pub mod provider;
+pub mod json;
+pub mod explain;
+pub mod malleability;
+pub mod coinbase;
 
 pub use transaction::Transaction;
 pub use transaction::TransactionParsingError;
 pub use txid::TransactionId;
+pub use txid::TransactionIdParsingError;
+pub use outpoint::OutPoint;
+pub use outpoint::OutPointParsingError;
+pub use amount::Amount;
+pub use amount::AmountUnit;
+pub use amount::AmountError;
+pub use amount::COIN;
+pub use amount::MAX_MONEY;
 pub use version::Version;
 pub use locktime::Locktime;
 pub use locktime::LocktimeType;
+pub use locktime::AbsoluteLockTime;
+pub use locktime::LOCKTIME_THRESHOLD;
+pub use sequence::RelativeLockTime;
+pub use sequence::SEQUENCE_FINAL;
+pub use sequence::SEQUENCE_LOCKTIME_DISABLE_FLAG;
+pub use sequence::SEQUENCE_LOCKTIME_TYPE_FLAG;
+pub use sequence::SEQUENCE_LOCKTIME_MASK;
+pub use sequence::SEQUENCE_LOCKTIME_GRANULARITY;
 pub use input::TransactionInput;
 pub use utxo::UnspentTransactionOutput;
@@ -0,0 +1,92 @@
+//! Transaction outpoints.
+
+use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::txid::TransactionIdParsingError;
+use crate::util::byte_string::ByteVector;
+use crate::util::byte_value::ByteValue32;
+
+/// A reference to a previous transaction's UTXO (unspent transaction output), given by its
+/// transaction ID and output index.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct OutPoint {
+    /// The transaction ID of a previous transaction.
+    pub txid: TransactionId,
+
+    /// Output index of the previous transaction's UTXO.
+    pub index: u32,
+}
+
+impl OutPoint {
+    /// Create an outpoint.
+    pub fn new(txid: TransactionId, index: u32) -> Self {
+        Self {
+            txid: txid,
+            index: index,
+        }
+    }
+
+    /// Create the "null" outpoint, referenced by coinbase transaction inputs.
+    ///
+    /// The null outpoint has a transaction ID of zero, and an output index of `0xffffffff`.
+    pub fn null() -> Self {
+        Self::new(TransactionId::of(&[0_u8; 32]), u32::MAX)
+    }
+
+    /// Indicates that this is the "null" outpoint.
+    pub fn is_null(&self) -> bool {
+        *self == Self::null()
+    }
+}
+
+impl ByteVector for OutPoint {
+    /// Return the sequence of bytes representing this outpoint.
+    ///
+    /// The little-endian transaction ID is followed by the little-endian output index.
+    fn bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(36);
+
+        bytes.extend_from_slice(&self.txid.bytes());
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+
+        bytes
+    }
+}
+
+impl std::fmt::Display for OutPoint {
+    /// Displays the outpoint (`txid:vout`).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.txid, self.index)
+    }
+}
+
+#[derive(Debug)]
+pub enum OutPointParsingError {
+    MissingSeparator,
+    TransactionIdParsingError(TransactionIdParsingError),
+    ParseIntError(std::num::ParseIntError),
+}
+
+impl From<TransactionIdParsingError> for OutPointParsingError {
+    fn from(error: TransactionIdParsingError) -> Self {
+        Self::TransactionIdParsingError(error)
+    }
+}
+
+impl From<std::num::ParseIntError> for OutPointParsingError {
+    fn from(error: std::num::ParseIntError) -> Self {
+        Self::ParseIntError(error)
+    }
+}
+
+impl std::str::FromStr for OutPoint {
+    type Err = OutPointParsingError;
+
+    /// Parse an outpoint from its `txid:vout` representation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (txid, index) = s.split_once(':').ok_or(OutPointParsingError::MissingSeparator)?;
+
+        Ok(Self::new(txid.parse()?, index.parse()?))
+    }
+}
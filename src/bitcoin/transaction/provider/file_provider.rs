@@ -0,0 +1,129 @@
+//! File-backed UTXO (unspent transaction output) provider.
+//!
+//! UTXOs are appended to a file as a sequence of records, each consisting of;
+//!
+//! - the outpoint (36 bytes; the little-endian transaction ID and output index),
+//! - the confirmation block height (little-endian 4 bytes; `0xffffffff` if unconfirmed),
+//! - the coinbase flag (1 byte),
+//! - and the UTXO (amount, and length-prefixed locking script).
+//!
+//! Only an index of record locations is held in memory; UTXOs are read from the file on demand.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::TransactionParsingError;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use crate::util::byte_string::ByteVector;
+use crate::util::byte_value::ByteValue32;
+use crate::util::byte_value::u32_little_endian;
+
+/// Length of a record, preceding the UTXO.
+const RECORD_HEADER_LENGTH: usize = 36 + 4 + 1;
+
+/// Height recorded for unconfirmed UTXOs.
+const UNCONFIRMED_HEIGHT: u32 = u32::MAX;
+
+/// Provides UTXOs stored in a file.
+#[derive(Debug)]
+pub struct FileUtxoProvider {
+    /// The backing file.
+    file: std::fs::File,
+
+    /// Location (offset and length) of each record, by outpoint.
+    index: HashMap<OutPoint, (u64, usize)>,
+}
+
+impl FileUtxoProvider {
+    /// Open a file of UTXO records, creating it if it does not exist, and index its records.
+    ///
+    /// Records appended later for the same outpoint take precedence over earlier records.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, UtxoProviderError> {
+        let mut file = std::fs::OpenOptions::new().read(true).append(true).create(true).open(path)?;
+
+        let mut bytes: Vec<u8> = vec![];
+
+        file.read_to_end(&mut bytes)?;
+
+        let mut index = HashMap::new();
+        let mut cursor_index: usize = 0;
+
+        while cursor_index < bytes.len() {
+            let (outpoint, _, record_length) = Self::parse_record(&bytes[cursor_index..])?;
+
+            index.insert(outpoint, (u64::try_from(cursor_index).unwrap(), record_length));
+
+            cursor_index += record_length;
+        }
+
+        Ok(Self { file: file, index: index })
+    }
+
+    /// Append a UTXO record to the file.
+    pub fn append(&mut self, outpoint: OutPoint, entry: &UtxoEntry) -> Result<(), UtxoProviderError> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(RECORD_HEADER_LENGTH);
+
+        bytes.extend_from_slice(&outpoint.bytes());
+        bytes.extend_from_slice(&entry.height.unwrap_or(UNCONFIRMED_HEIGHT).to_le_bytes());
+        bytes.push(u8::from(entry.coinbase));
+        bytes.extend_from_slice(&entry.utxo.bytes());
+
+        let offset = self.file.seek(SeekFrom::End(0))?;
+
+        self.file.write_all(&bytes)?;
+
+        self.index.insert(outpoint, (offset, bytes.len()));
+
+        Ok(())
+    }
+
+    /// Returns the number of indexed UTXOs.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Indicates that there are no indexed UTXOs.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Parse the outpoint and UTXO entry of a record, along with the length of the record.
+    fn parse_record(bytes: &[u8]) -> Result<(OutPoint, UtxoEntry, usize), UtxoProviderError> {
+        if bytes.len() <= RECORD_HEADER_LENGTH { return Err(TransactionParsingError::UnexpectedByteLength.into()) }
+
+        let outpoint = OutPoint::new(TransactionId::of(&bytes[0..32]), u32_little_endian(&bytes[32..36]));
+
+        let height = match u32_little_endian(&bytes[36..40]) {
+            UNCONFIRMED_HEIGHT => None,
+            height => Some(height),
+        };
+
+        let coinbase = bytes[40] != 0;
+
+        let (utxo, utxo_length) = UnspentTransactionOutput::parse_bytes(&bytes[RECORD_HEADER_LENGTH..])?;
+
+        Ok((outpoint, UtxoEntry::new(utxo, height, coinbase), RECORD_HEADER_LENGTH + utxo_length))
+    }
+}
+
+impl UtxoProvider for FileUtxoProvider {
+    fn utxo(&self, outpoint: &OutPoint) -> Result<UtxoEntry, UtxoProviderError> {
+        let (offset, length) = *self.index.get(outpoint).ok_or(UtxoProviderError::UtxoNotFound(*outpoint))?;
+
+        let mut file = &self.file;
+        let mut bytes = vec![0_u8; length];
+
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+
+        Ok(Self::parse_record(&bytes)?.1)
+    }
+}
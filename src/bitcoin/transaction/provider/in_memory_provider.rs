@@ -0,0 +1,63 @@
+//! In-memory UTXO (unspent transaction output) provider.
+
+use std::collections::HashMap;
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoEntry;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+
+/// Provides UTXOs held in memory.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Default)]
+pub struct InMemoryUtxoProvider {
+    /// UTXOs, by outpoint.
+    utxos: HashMap<OutPoint, UtxoEntry>,
+}
+
+impl InMemoryUtxoProvider {
+    /// Create an empty UTXO provider.
+    pub fn new() -> Self {
+        Self { utxos: HashMap::new() }
+    }
+
+    /// Add a UTXO.
+    pub fn insert(&mut self, outpoint: OutPoint, entry: UtxoEntry) {
+        self.utxos.insert(outpoint, entry);
+    }
+
+    /// Add every UTXO of a transaction, confirmed at some block height (if confirmed).
+    pub fn insert_transaction(&mut self, transaction: &Transaction, height: Option<u32>) {
+        let txid = transaction.txid();
+        let coinbase = transaction.is_coinbase();
+
+        for (i, utxo) in transaction.utxos.iter().enumerate() {
+            let outpoint = OutPoint::new(txid, u32::try_from(i).unwrap());
+
+            self.insert(outpoint, UtxoEntry::new(utxo.clone(), height, coinbase));
+        }
+    }
+
+    /// Remove a (spent) UTXO.
+    pub fn remove(&mut self, outpoint: &OutPoint) -> Option<UtxoEntry> {
+        self.utxos.remove(outpoint)
+    }
+
+    /// Returns the number of UTXOs.
+    pub fn len(&self) -> usize {
+        self.utxos.len()
+    }
+
+    /// Indicates that there are no UTXOs.
+    pub fn is_empty(&self) -> bool {
+        self.utxos.is_empty()
+    }
+}
+
+impl UtxoProvider for InMemoryUtxoProvider {
+    fn utxo(&self, outpoint: &OutPoint) -> Result<UtxoEntry, UtxoProviderError> {
+        self.utxos.get(outpoint).cloned().ok_or(UtxoProviderError::UtxoNotFound(*outpoint))
+    }
+}
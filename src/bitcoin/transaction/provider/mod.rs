@@ -0,0 +1,5 @@
+//! Providers of previous transactions' UTXOs (unspent transaction outputs).
+
+pub mod utxo_provider;
+pub mod in_memory_provider;
+pub mod file_provider;
@@ -0,0 +1,69 @@
+//! UTXO (unspent transaction output) provider interface.
+//!
+//! Fee calculation, verification and signing only require the UTXOs being spent by a
+//! transaction's inputs (their amounts and locking scripts); not the whole previous transactions.
+
+use crate::bitcoin::transaction::OutPoint;
+use crate::bitcoin::transaction::UnspentTransactionOutput;
+use crate::bitcoin::transaction::TransactionParsingError;
+
+/// A UTXO, along with the context of its confirmation.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct UtxoEntry {
+    /// The UTXO.
+    pub utxo: UnspentTransactionOutput,
+
+    /// The block height at which the UTXO was confirmed, if confirmed.
+    pub height: Option<u32>,
+
+    /// Indicates that the UTXO was created by a coinbase transaction.
+    pub coinbase: bool,
+}
+
+impl UtxoEntry {
+    /// Create a UTXO entry.
+    pub fn new(utxo: UnspentTransactionOutput, height: Option<u32>, coinbase: bool) -> Self {
+        Self {
+            utxo: utxo,
+            height: height,
+            coinbase: coinbase,
+        }
+    }
+
+    /// Indicates that the UTXO has yet to be confirmed.
+    pub fn is_unconfirmed(&self) -> bool {
+        self.height.is_none()
+    }
+}
+
+/// Errors thrown when retrieving UTXOs.
+#[derive(Debug)]
+pub enum UtxoProviderError {
+    /// No UTXO exists for the outpoint (it may not exist, or may have been spent).
+    UtxoNotFound(OutPoint),
+
+    /// The UTXO could not be read from its backing store.
+    IoError(std::io::Error),
+
+    /// The UTXO could not be parsed from its backing store.
+    TransactionParsingError(TransactionParsingError),
+}
+
+impl From<std::io::Error> for UtxoProviderError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+impl From<TransactionParsingError> for UtxoProviderError {
+    fn from(error: TransactionParsingError) -> Self {
+        Self::TransactionParsingError(error)
+    }
+}
+
+/// Provides the UTXOs spent by transaction inputs, by outpoint.
+pub trait UtxoProvider {
+    /// Retrieve the UTXO referenced by an outpoint.
+    fn utxo(&self, outpoint: &OutPoint) -> Result<UtxoEntry, UtxoProviderError>;
+}
@@ -0,0 +1,84 @@
+//! Bitcoin transaction input sequence numbers (`nSequence`) and relative time locks (BIP-68).
+
+/// The sequence number of a final transaction input, which disables the transaction's time lock
+/// (`nLockTime`) if all transaction inputs are final.
+pub const SEQUENCE_FINAL: u32 = 0xffffffff;
+
+/// If set, the sequence number is not interpreted as a relative time lock.
+pub const SEQUENCE_LOCKTIME_DISABLE_FLAG: u32 = 1 << 31;
+
+/// If set, the relative time lock is measured in units of 512 seconds; otherwise, in blocks.
+pub const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+
+/// The bits of the sequence number that hold the relative time lock value.
+pub const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000ffff;
+
+/// Relative time locks measured in time have a granularity of `2 ^ 9 = 512` seconds.
+pub const SEQUENCE_LOCKTIME_GRANULARITY: u32 = 9;
+
+/// A relative time lock (BIP-68), measured from the confirmation of the UTXO being spent.
+///
+/// Used by transaction inputs (`nSequence`, for transaction versions 2 and above) and
+/// `OP_CHECKSEQUENCEVERIFY` (BIP-112).
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub enum RelativeLockTime {
+    /// Locked for some number of blocks.
+    Blocks(u16),
+
+    /// Locked for some number of 512 second intervals.
+    Time(u16),
+}
+
+impl RelativeLockTime {
+    /// Interpret a sequence number as a relative time lock.
+    ///
+    /// Returns nothing if the sequence number sets the disable flag.
+    pub fn from_sequence(sequence: u32) -> Option<Self> {
+        if sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 { return None }
+
+        let value = u16::try_from(sequence & SEQUENCE_LOCKTIME_MASK).unwrap();
+
+        if sequence & SEQUENCE_LOCKTIME_TYPE_FLAG != 0 {
+            Some(Self::Time(value))
+        } else {
+            Some(Self::Blocks(value))
+        }
+    }
+
+    /// Create a relative time lock of at least some number of seconds, rounded up to the next
+    /// 512 second interval.
+    ///
+    /// Returns nothing if the duration exceeds the largest relative time lock.
+    pub fn from_seconds_ceil(seconds: u32) -> Option<Self> {
+        let intervals = seconds.div_ceil(1 << SEQUENCE_LOCKTIME_GRANULARITY);
+
+        u16::try_from(intervals).ok().map(Self::Time)
+    }
+
+    /// Returns the sequence number encoding the relative time lock.
+    pub fn to_sequence(&self) -> u32 {
+        match *self {
+            Self::Blocks(blocks) => u32::from(blocks),
+            Self::Time(intervals) => SEQUENCE_LOCKTIME_TYPE_FLAG | u32::from(intervals),
+        }
+    }
+
+    /// Indicates that both time locks are measured in the same unit (blocks, or time).
+    pub fn is_same_unit(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Blocks(_), Self::Blocks(_)) | (Self::Time(_), Self::Time(_))
+        )
+    }
+}
+
+impl std::fmt::Display for RelativeLockTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Blocks(blocks) => write!(f, "{} blocks", blocks),
+            Self::Time(intervals) => write!(f, "{} seconds", u32::from(*intervals) << SEQUENCE_LOCKTIME_GRANULARITY),
+        }
+    }
+}
@@ -2,15 +2,25 @@
 
 use crate::bitcoin::script::ScriptCreationError;
 use crate::bitcoin::script_types::BitcoinTransactionType;
-use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
 
 #[derive(Debug)]
 pub enum TransactionSigningError {
-    NoPreviousTransactionFound(TransactionId),
-    NoInputFound(TransactionId, usize),
+    UtxoProviderError(UtxoProviderError),
+
+    /// The transaction input to sign does not exist.
+    InputNotFound(usize),
 
     LockingScriptNotFound(BitcoinTransactionType),
 
+    PublicKeyNotFound,
+
+    /// The number of secrets differs from the number of signatures required by a multisig script.
+    SignatureCountMismatch,
+
+    /// The same secret is given more than once.
+    DuplicateSecret,
+
     ScriptCreationError(ScriptCreationError),
 
     TryFromIntError(std::num::TryFromIntError),
@@ -22,6 +32,12 @@ impl From<std::num::TryFromIntError> for TransactionSigningError {
     }
 }
 
+impl From<UtxoProviderError> for TransactionSigningError {
+    fn from(error: UtxoProviderError) -> Self {
+        Self::UtxoProviderError(error)
+    }
+}
+
 impl From<ScriptCreationError> for TransactionSigningError {
     fn from(error: ScriptCreationError) -> Self {
         Self::ScriptCreationError(error)
@@ -0,0 +1,4 @@
+//! Bitcoin transaction sizes (BIP-141 weight and virtual size).
+
+pub mod transaction_size;
+pub mod size_estimation;
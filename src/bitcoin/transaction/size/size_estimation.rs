@@ -0,0 +1,176 @@
+//! Bitcoin transaction size estimation, prior to signing.
+//!
+//! An unsigned transaction's inputs have empty scripts (and witnesses), which are later replaced
+//! by signatures and other unlocking data. The eventual size of that data is estimated from the
+//! template of the UTXO being spent, so that a fee may be chosen to target some fee rate.
+//!
+//! Estimates assume the largest possible DER signature produced by this crate (71 bytes; a low
+//! s-value never requires a padding byte) plus its one byte `SIGHASH` flag, and are therefore
+//! conservative by at most a few bytes per signature.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::size::transaction_size::WITNESS_SCALE_FACTOR;
+use crate::bitcoin::transaction::size::transaction_size::weight_to_vsize;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::util::byte_string::ByteSlice;
+use crate::util::varint::varint_u64;
+
+/// Maximum size of a DER signature with an appended `SIGHASH` flag byte.
+const SIGNATURE_SIZE: usize = 72;
+
+/// Size of a compressed SEC format point.
+const COMPRESSED_POINT_SIZE: usize = 33;
+
+/// Size of an uncompressed SEC format point.
+const UNCOMPRESSED_POINT_SIZE: usize = 65;
+
+/// Size of a BIP-340 Schnorr signature, using the default `SIGHASH` flag (no flag byte).
+const SCHNORR_SIGNATURE_SIZE: usize = 64;
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum SizeEstimationError {
+    /// There is not exactly one template per transaction input.
+    TemplateCountMismatch,
+}
+
+/// Templates for the unlocking data of a transaction input, used for size estimation.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum InputSizeTemplate {
+    /// P2PKH, with a compressed public key.
+    P2pkh,
+
+    /// P2PKH, with an uncompressed public key.
+    P2pkhUncompressed,
+
+    /// P2SH, with an `m`-of-`n` `OP_CHECKMULTISIG` redeem script (of compressed public keys).
+    P2shMultisig { m: usize, n: usize },
+
+    /// P2WPKH (native segregated witness).
+    P2wpkh,
+
+    /// P2WPKH, nested within P2SH.
+    P2shP2wpkh,
+
+    /// P2WSH (native segregated witness), with an `m`-of-`n` `OP_CHECKMULTISIG` witness script
+    /// (of compressed public keys).
+    P2wshMultisig { m: usize, n: usize },
+
+    /// P2TR key path spend.
+    P2trKeyPath,
+}
+
+impl InputSizeTemplate {
+    /// Heuristically determines a template from the locking script being spent.
+    ///
+    /// - P2PKH is assumed to use a compressed public key.
+    /// - P2TR is assumed to be spent along the key path, with the default `SIGHASH` flag.
+    ///
+    /// Returns nothing if the unlocking data cannot be inferred from the locking script alone
+    /// (e.g.; P2SH or P2WSH, whose redeem script or witness script is not known).
+    pub fn for_locking_script(script_bytes: &ScriptBytes) -> Option<Self> {
+        match script_bytes.locking_script_type() {
+            BitcoinTransactionType::P2pkh => Some(Self::P2pkh),
+            BitcoinTransactionType::P2wpkh => Some(Self::P2wpkh),
+            BitcoinTransactionType::P2tr => Some(Self::P2trKeyPath),
+            _ => None,
+        }
+    }
+
+    /// Returns the estimated size of the unlocking script (`scriptSig`), excluding its length
+    /// prefix.
+    pub fn script_size(&self) -> usize {
+        match *self {
+            Self::P2pkh => push_size(SIGNATURE_SIZE) + push_size(COMPRESSED_POINT_SIZE),
+            Self::P2pkhUncompressed => push_size(SIGNATURE_SIZE) + push_size(UNCOMPRESSED_POINT_SIZE),
+            Self::P2shMultisig { m, n } => 1 + (m * push_size(SIGNATURE_SIZE)) + push_size(multisig_script_size(n)),
+            Self::P2wpkh => 0,
+            Self::P2shP2wpkh => push_size(22),
+            Self::P2wshMultisig { .. } => 0,
+            Self::P2trKeyPath => 0,
+        }
+    }
+
+    /// Returns the estimated size of the segregated witness field, including its item count.
+    ///
+    /// Returns nothing for templates which do not use segregated witness.
+    pub fn witness_size(&self) -> Option<usize> {
+        match *self {
+            Self::P2pkh | Self::P2pkhUncompressed | Self::P2shMultisig { .. } => None,
+            Self::P2wpkh | Self::P2shP2wpkh => {
+                Some(varint_size(2) + item_size(SIGNATURE_SIZE) + item_size(COMPRESSED_POINT_SIZE))
+            },
+            Self::P2wshMultisig { m, n } => {
+                Some(varint_size(m + 2) + item_size(0) + (m * item_size(SIGNATURE_SIZE)) + item_size(multisig_script_size(n)))
+            },
+            Self::P2trKeyPath => Some(varint_size(1) + item_size(SCHNORR_SIGNATURE_SIZE)),
+        }
+    }
+}
+
+impl Transaction {
+    /// Estimates the weight of the transaction once its inputs are signed, given a template for
+    /// each transaction input.
+    ///
+    /// Existing input scripts are disregarded, in favor of the templates.
+    pub fn estimated_weight(&self, templates: &[InputSizeTemplate]) -> Result<usize, SizeEstimationError> {
+        if templates.len() != self.inputs.len() { return Err(SizeEstimationError::TemplateCountMismatch) }
+
+        let mut base_size = self.base_size();
+
+        for (input, template) in self.inputs.iter().zip(templates) {
+            let script_length = input.script.bytes().len();
+
+            base_size -= varint_size(script_length) + script_length;
+            base_size += varint_size(template.script_size()) + template.script_size();
+        }
+
+        let witness_sizes: Vec<Option<usize>> = templates.iter().map(|template| template.witness_size()).collect();
+
+        let witness_size: usize = if witness_sizes.iter().any(|size| size.is_some()) {
+            // Segregated witness marker and flag, followed by a witness field for every input
+            // (empty witness fields consist of a zero item count).
+            2 + witness_sizes.iter().map(|size| size.unwrap_or(varint_size(0))).sum::<usize>()
+        } else {
+            0
+        };
+
+        Ok((base_size * WITNESS_SCALE_FACTOR) + witness_size)
+    }
+
+    /// Estimates the virtual size of the transaction once its inputs are signed, given a template
+    /// for each transaction input.
+    pub fn estimated_vsize(&self, templates: &[InputSizeTemplate]) -> Result<usize, SizeEstimationError> {
+        Ok(weight_to_vsize(self.estimated_weight(templates)?))
+    }
+}
+
+/// Size of a script data element with some length, including its data opcode.
+fn push_size(length: usize) -> usize {
+    match length {
+        0..=75 => 1 + length,
+        76..=0xff => 2 + length,
+        0x100..=0xffff => 3 + length,
+        _ => 5 + length,
+    }
+}
+
+/// Size of a segregated witness item with some length, including its length prefix.
+fn item_size(length: usize) -> usize {
+    varint_size(length) + length
+}
+
+/// Size of a variable integer.
+fn varint_size(value: usize) -> usize {
+    varint_u64(u64::try_from(value).unwrap()).1
+}
+
+/// Size of an `m`-of-`n` `OP_CHECKMULTISIG` script of compressed public keys.
+///
+/// `OP_m <public key> ... <public key> OP_n OP_CHECKMULTISIG`
+fn multisig_script_size(n: usize) -> usize {
+    1 + (n * push_size(COMPRESSED_POINT_SIZE)) + 1 + 1
+}
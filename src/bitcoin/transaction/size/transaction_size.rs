@@ -0,0 +1,90 @@
+//! Bitcoin transaction sizes (BIP-141 weight and virtual size).
+//!
+//! Serialized sizes are measured in bytes, and come in two variants.
+//!
+//! - Base size (a.k.a. "stripped size"): the transaction serialized without any segregated
+//!   witness marker, flag, or witness fields; i.e.; the serialization used to compute the `txid`.
+//! - Total size: the transaction serialized with segregated witness data, if any.
+//!
+//! Weight is computed as `(base size * 3) + total size`, in weight units. Virtual size is the
+//! weight divided by four (rounded up), in virtual bytes.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use crate::bitcoin::transaction::fee::transaction_fee::FeeRate;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::util::byte_string::ByteVector;
+
+/// The factor by which non-witness bytes are weighed, relative to witness bytes.
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+
+/// Converts a weight (in weight units) to a virtual size (in virtual bytes), rounding up.
+pub fn weight_to_vsize(weight: usize) -> usize {
+    weight.div_ceil(WITNESS_SCALE_FACTOR)
+}
+
+impl Transaction {
+    /// Returns the serialized size of the transaction, excluding any segregated witness data.
+    pub fn base_size(&self) -> usize {
+        self.bytes().len()
+    }
+
+    /// Returns the serialized size of the transaction.
+    ///
+    /// A transaction without segregated witness data has a total size equal to its base size.
+    pub fn total_size(&self) -> usize {
+        self.base_size()
+    }
+
+    /// Returns the transaction weight (in weight units).
+    pub fn weight(&self) -> usize {
+        (self.base_size() * (WITNESS_SCALE_FACTOR - 1)) + self.total_size()
+    }
+
+    /// Returns the transaction virtual size (in virtual bytes).
+    pub fn vsize(&self) -> usize {
+        weight_to_vsize(self.weight())
+    }
+
+    /// Calculates the transaction fee rate.
+    pub fn feerate<P>(&self, utxo_provider: &P) -> Result<FeeRate, FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        let fee = self.fee(utxo_provider)?;
+
+        Ok(FeeRate::from_fee(fee, self.vsize()))
+    }
+}
+
+impl SegWitTransaction {
+    /// Returns the serialized size of the transaction, excluding any segregated witness data
+    /// (a.k.a. the "stripped size").
+    pub fn base_size(&self) -> usize {
+        (**self).base_size()
+    }
+
+    /// Returns the serialized size of the transaction, including segregated witness data.
+    pub fn total_size(&self) -> usize {
+        self.bytes().len()
+    }
+
+    /// Returns the transaction weight (in weight units).
+    pub fn weight(&self) -> usize {
+        (self.base_size() * (WITNESS_SCALE_FACTOR - 1)) + self.total_size()
+    }
+
+    /// Returns the transaction virtual size (in virtual bytes).
+    pub fn vsize(&self) -> usize {
+        weight_to_vsize(self.weight())
+    }
+
+    /// Calculates the transaction fee rate.
+    ///
+    /// Witness data is discounted, per the transaction virtual size.
+    pub fn feerate<P>(&self, utxo_provider: &P) -> Result<FeeRate, FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        let fee = self.fee(utxo_provider)?;
+
+        Ok(FeeRate::from_fee(fee, self.vsize()))
+    }
+}
@@ -35,6 +35,8 @@ pub enum TransactionParsingError {
     UnexpectedByteLength,
     TryFromIntError(std::num::TryFromIntError),
     VariableIntegerError,
+    UnexpectedSegWitFlag,
+    SuperfluousSegWitFields,
 }
 
 impl From<std::num::TryFromIntError> for TransactionParsingError {
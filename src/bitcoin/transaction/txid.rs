@@ -2,10 +2,11 @@
 
 use crate::util::byte_value::ByteValue32;
 use crate::util::hexadecimal::hexadecimal_encode;
+use crate::util::hexadecimal::hexadecimal_string;
 
 /// Transaction ID (`txid`).
 #[derive(Clone, Copy)]
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, Hash)]
 pub struct TransactionId {
     /// Little-endian transaction ID bytes.
     bytes: [u8; 32]
@@ -69,3 +70,21 @@ impl TransactionId {
         buffer
     }
 }
+
+#[derive(Debug)]
+pub struct TransactionIdParsingError;
+
+impl std::str::FromStr for TransactionId {
+    type Err = TransactionIdParsingError;
+
+    /// Parse a transaction ID from its (big-endian) hexadecimal representation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes: [u8; 32] = [0_u8; 32];
+
+        if s.len() != 64 { return Err(TransactionIdParsingError) }
+
+        hexadecimal_string(&s.to_ascii_lowercase(), &mut bytes).map_err(|_| TransactionIdParsingError)?;
+
+        Ok(Self::new(&bytes))
+    }
+}
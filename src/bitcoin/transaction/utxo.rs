@@ -1,11 +1,12 @@
 //! UTXOs (unspent transaction outputs).
 
+use crate::bitcoin::transaction::Amount;
 use crate::bitcoin::transaction::transaction::TransactionParsingError;
 use crate::bitcoin::script::ScriptBytes;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 use crate::util::byte_string::ByteVector;
-use crate::util::byte_value::i64_little_endian;
+use crate::util::byte_value::ByteValue8;
 use crate::util::varint::read_varint_u64;
 use crate::util::varint::varint_u64;
 
@@ -14,7 +15,7 @@ use crate::util::varint::varint_u64;
 #[derive(Clone)]
 pub struct UnspentTransactionOutput {
     /// UTXO amount (of satoshi).
-    pub amount: i64,
+    pub amount: Amount,
 
     /// Script bytes.
     pub script: ScriptBytes,
@@ -40,7 +41,7 @@ impl ByteVector for UnspentTransactionOutput {
         let mut bytes: Vec<u8> = vec![];
 
         // Amount field, little-endian.
-        bytes.extend_from_slice(&self.amount.to_le_bytes());
+        bytes.extend_from_slice(&self.amount.bytes());
 
         // Variable integer for script length.
         let (varint_bytes, varint_length) = varint_u64(u64::try_from(self.script.bytes().len()).unwrap());
@@ -55,13 +56,20 @@ impl ByteVector for UnspentTransactionOutput {
 }
 
 impl UnspentTransactionOutput {
-    pub fn new(amount: i64, script: ScriptBytes) -> Self {
+    pub fn new(amount: Amount, script: ScriptBytes) -> Self {
         Self {
             amount: amount,
             script: script,
         }
     }
 
+    /// Create a "null" UTXO, with an amount of `-1` and an empty script.
+    ///
+    /// Used to blank out transaction outputs when computing `SIGHASH_SINGLE` signature digests.
+    pub fn null() -> Self {
+        Self::new(Amount::NULL, ScriptBytes::of(&[]))
+    }
+
     /// Parse a byte string for a UTXO.
     pub fn parse_bytes(bytes: &[u8]) -> Result<(Self, usize), TransactionParsingError> {
         // The overall length of the byte slice.
@@ -72,7 +80,7 @@ impl UnspentTransactionOutput {
         if bytes_length < 9 { return Err(TransactionParsingError::UnexpectedByteLength) }
 
         // Parse the amount field.
-        let amount = i64_little_endian(&bytes[0..8]);
+        let amount = Amount::of(&bytes[0..8]);
 
         // Parse the next bytes as a variable integer denoting the script length.
         let (script_length, skip_bytes) =
@@ -85,6 +93,9 @@ impl UnspentTransactionOutput {
         // Calculate the overall length of the UTXO.
         let bytes_read = script_byte_index + usize::try_from(script_length)?;
 
+        // There should be at least enough bytes to read the script in whole, given its length.
+        if bytes_length < bytes_read { return Err(TransactionParsingError::UnexpectedByteLength) }
+
         Ok((Self { amount: amount, script: ScriptBytes::of(&bytes[script_byte_index..bytes_read]) }, bytes_read))
     }
 }
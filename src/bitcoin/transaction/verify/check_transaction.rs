@@ -0,0 +1,70 @@
+//! Context-free Bitcoin transaction checks (`CheckTransaction`).
+//!
+//! These checks depend only on the transaction itself; not on its previous transactions, its
+//! scripts, or the state of the blockchain.
+
+use std::collections::HashSet;
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::verify::verification::TransactionVerificationError;
+use crate::bitcoin::transaction::size::transaction_size::WITNESS_SCALE_FACTOR;
+use crate::util::byte_string::ByteSlice;
+
+/// The maximum weight of a block, and therefore of any transaction within it (BIP-141).
+pub const MAX_BLOCK_WEIGHT: usize = 4000000;
+
+/// The minimum length of a coinbase transaction input script.
+pub const MIN_COINBASE_SCRIPT_SIZE: usize = 2;
+
+/// The maximum length of a coinbase transaction input script.
+pub const MAX_COINBASE_SCRIPT_SIZE: usize = 100;
+
+impl Transaction {
+    /// Performs context-free checks of the transaction (`CheckTransaction`).
+    ///
+    /// - There is at least one transaction input, and at least one UTXO.
+    /// - The transaction, excluding witness data, does not exceed the maximum block weight.
+    /// - Each UTXO amount, and their sum, is within `0..=MAX_MONEY`.
+    /// - No two transaction inputs spend the same outpoint.
+    /// - A coinbase transaction input script is between 2 and 100 bytes long; other transaction
+    ///   inputs do not spend the null outpoint.
+    pub fn check_transaction(&self) -> Result<(), TransactionVerificationError> {
+        if self.inputs.is_empty() { return Err(TransactionVerificationError::EmptyInputs) }
+        if self.utxos.is_empty() { return Err(TransactionVerificationError::EmptyOutputs) }
+
+        if self.base_size() * WITNESS_SCALE_FACTOR > MAX_BLOCK_WEIGHT {
+            return Err(TransactionVerificationError::Oversized);
+        }
+
+        let mut total = Amount::ZERO;
+
+        for (i, utxo) in self.utxos.iter().enumerate() {
+            if !utxo.amount.is_money_range() { return Err(TransactionVerificationError::OutputAmountOutOfRange(i)) }
+
+            total = total.checked_add(utxo.amount).ok_or(TransactionVerificationError::TotalOutputAmountOutOfRange)?;
+        }
+
+        let mut outpoints = HashSet::with_capacity(self.inputs.len());
+
+        for input in &self.inputs {
+            if !outpoints.insert(input.outpoint) {
+                return Err(TransactionVerificationError::DuplicateInput(input.outpoint));
+            }
+        }
+
+        if self.is_coinbase() {
+            let script_size = self.inputs[0].script.bytes().len();
+
+            if !(MIN_COINBASE_SCRIPT_SIZE..=MAX_COINBASE_SCRIPT_SIZE).contains(&script_size) {
+                return Err(TransactionVerificationError::CoinbaseScriptSize);
+            }
+        } else {
+            for (i, input) in self.inputs.iter().enumerate() {
+                if input.outpoint.is_null() { return Err(TransactionVerificationError::NullPrevout(i)) }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
 //! Bitcoin transaction verification.
 
 pub mod verification;
+pub mod check_transaction;
+pub mod timelock;
+pub mod signature_inspection;
+pub mod witness_verification;
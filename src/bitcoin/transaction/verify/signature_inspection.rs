@@ -0,0 +1,320 @@
+//! Inspection of the signatures present in a Bitcoin transaction.
+//!
+//! Walks each transaction input script (and segregated witness field), finding data elements
+//! that are DER format signatures followed by a `SIGHASH` flag byte.
+//!
+//! Each signature is checked against the public keys found in the transaction input script and
+//! the script it commits to (the UTXO's locking script, or the P2SH redeem script); e.g.; to
+//! audit which co-signers of a multisig transaction input have signed.
+//!
+//! Signatures within a P2WPKH or P2WSH segregated witness field (native, or P2SH-wrapped) are
+//! checked against the BIP-143 digest, and the public keys found in the segregated witness field
+//! and witness script.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::script::signature_verification;
+use crate::bitcoin::script::segwit_signature_verification;
+use crate::bitcoin::script::SegWitSignatureHashCache;
+use crate::bitcoin::script::TransactionInputCommitment;
+use crate::bitcoin::script::DefaultOpCheckSigDigest;
+use crate::bitcoin::script::SigHashFlag;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script_types::BitcoinTransactionType;
+use crate::crypto::ecdsa::signature::Signature;
+use crate::crypto::digest::sha_256;
+use crate::serialization::signature::SignatureDerFormatBytes;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+
+#[derive(Debug)]
+pub enum SignatureInspectionError {
+    NoInputFound(usize),
+
+    UtxoProviderError(UtxoProviderError),
+}
+
+impl From<UtxoProviderError> for SignatureInspectionError {
+    fn from(error: UtxoProviderError) -> Self {
+        Self::UtxoProviderError(error)
+    }
+}
+
+/// Where a signature was found within a transaction input.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum SignatureLocation {
+    /// The element index within the transaction input script.
+    Script(usize),
+
+    /// The item index within the segregated witness field.
+    Witness(usize),
+}
+
+/// A signature present in a transaction input.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct InputSignature {
+    /// Index of the transaction input.
+    pub input_index: usize,
+
+    /// Where the signature was found.
+    pub location: SignatureLocation,
+
+    /// The ECDSA signature.
+    pub signature: Signature,
+
+    /// The `SIGHASH` flag, as the byte following the signature; non-standard values (e.g.; `0x00`,
+    /// `0x84`) are preserved as is.
+    pub sighash: SigHashFlag,
+
+    /// The public key (SEC format) that the signature is checked against; i.e.; the public key
+    /// it verifies against, or the only candidate public key.
+    pub public_key: Option<Vec<u8>>,
+
+    /// Indicates that the signature verifies against `public_key`, if it could be checked.
+    pub valid: Option<bool>,
+
+    /// Indicates that the s-value is in the lower half of the elliptic curve order (BIP-146).
+    pub low_s: bool,
+
+    /// Indicates that the signature is a strictly encoded DER signature (BIP-66).
+    pub strict_der: bool,
+}
+
+impl InputSignature {
+    /// Indicates that the signature could be replaced by a different, equally valid encoding;
+    /// i.e.; it has a high s-value, or is not strictly DER encoded.
+    pub fn is_malleable(&self) -> bool {
+        !self.low_s || !self.strict_der
+    }
+}
+
+impl Transaction {
+    /// Returns the signatures present in a transaction input, checked against the public keys
+    /// found in its script and the script it commits to.
+    pub fn input_signatures<P>(&self, n: usize, utxo_provider: &P) -> Result<Vec<InputSignature>, SignatureInspectionError>
+    where P: UtxoProvider + ?Sized {
+        input_signatures(self, n, None, utxo_provider)
+    }
+
+    /// Returns the signatures present in all transaction inputs.
+    pub fn signatures<P>(&self, utxo_provider: &P) -> Result<Vec<InputSignature>, SignatureInspectionError>
+    where P: UtxoProvider + ?Sized {
+        let mut signatures: Vec<InputSignature> = vec![];
+
+        for n in 0..self.inputs.len() {
+            signatures.extend(self.input_signatures(n, utxo_provider)?);
+        }
+
+        Ok(signatures)
+    }
+}
+
+impl SegWitTransaction {
+    /// Returns the signatures present in a transaction input, including its segregated witness
+    /// field.
+    pub fn input_signatures<P>(&self, n: usize, utxo_provider: &P) -> Result<Vec<InputSignature>, SignatureInspectionError>
+    where P: UtxoProvider + ?Sized {
+        let segwit_field = self.segwit_fields().get(n).ok_or(SignatureInspectionError::NoInputFound(n))?;
+
+        input_signatures(self, n, Some(segwit_field), utxo_provider)
+    }
+
+    /// Returns the signatures present in all transaction inputs, including their segregated
+    /// witness fields.
+    pub fn signatures<P>(&self, utxo_provider: &P) -> Result<Vec<InputSignature>, SignatureInspectionError>
+    where P: UtxoProvider + ?Sized {
+        let mut signatures: Vec<InputSignature> = vec![];
+
+        for n in 0..self.inputs.len() {
+            signatures.extend(self.input_signatures(n, utxo_provider)?);
+        }
+
+        Ok(signatures)
+    }
+}
+
+fn input_signatures<P>(
+    transaction: &Transaction,
+    n: usize,
+    segwit_field: Option<&SegWitField>,
+    utxo_provider: &P
+) -> Result<Vec<InputSignature>, SignatureInspectionError>
+where P: UtxoProvider + ?Sized {
+    let input = transaction.inputs.get(n).ok_or(SignatureInspectionError::NoInputFound(n))?;
+    let entry = utxo_provider.utxo(&input.outpoint)?;
+    let locking_script = &entry.utxo.script;
+
+    // Data elements of the transaction input script (and their element indices); unparseable
+    // scripts have none.
+    let data_elements: Vec<(usize, DataElement)> = match Script::try_from(&input.script) {
+        Ok(script) => script.elements().iter().enumerate().filter_map(|(i, element)| match element {
+            Element::Data(data) => Some((i, data.clone())),
+            _ => None,
+        }).collect(),
+        Err(_) => vec![],
+    };
+
+    // The script that signatures commit to; the redeem script for P2SH.
+    let committed_script_bytes = match locking_script.locking_script_type() {
+        BitcoinTransactionType::P2sh => data_elements.last().map(|(_, data)| ScriptBytes::of(data.bytes())),
+        _ => Some(locking_script.clone()),
+    };
+
+    let committed_script = committed_script_bytes.as_ref().and_then(|script_bytes| Script::try_from(script_bytes).ok());
+
+    // Candidate public keys, from both the transaction input script and the committed script.
+    let mut public_keys: Vec<DataElement> = data_elements.iter().map(|(_, data)| data.clone()).collect();
+
+    if let Some(script) = &committed_script {
+        for element in script.elements() {
+            if let Element::Data(data) = element { public_keys.push(data.clone()) }
+        }
+    }
+
+    public_keys.retain(|data| is_public_key(data.bytes()));
+
+    let mut signatures: Vec<InputSignature> = vec![];
+
+    for (i, data) in &data_elements {
+        let Some((signature, sighash, strict_der)) = parse_signature(data.bytes()) else { continue };
+
+        let (public_key, valid) = match &committed_script {
+            Some(script) => {
+                let commitment = TransactionInputCommitment::ScriptCode(script, 0, std::slice::from_ref(data));
+
+                check_public_keys(&public_keys, |public_key| {
+                    signature_verification(data, public_key, transaction, n, &commitment, &DefaultOpCheckSigDigest {}).unwrap_or(false)
+                })
+            },
+            None => (None, None),
+        };
+
+        signatures.push(
+            InputSignature {
+                input_index: n,
+                location: SignatureLocation::Script(*i),
+                signature: signature,
+                sighash: sighash,
+                public_key: public_key,
+                valid: valid,
+                low_s: signature.is_low_s_secp256k1(),
+                strict_der: strict_der,
+            }
+        );
+    }
+
+    if let Some(field) = segwit_field {
+        let witness_program = committed_script_bytes.as_ref().and_then(|script_bytes| script_bytes.witness_program());
+
+        // The script code signatures commit to (BIP-143), the candidate public keys, and the items
+        // that may be signatures; i.e.; excluding a P2WSH witness script.
+        let (script_code, witness_public_keys, items) = match (witness_program, field.split_last()) {
+            // A P2WPKH segregated witness field is a signature and a public key.
+            (Some((0, program)), _) if program.len() == 20 => {
+                let public_keys = match field.len() {
+                    2 => vec![DataElement::of(field[1].bytes())],
+                    _ => vec![],
+                };
+
+                (committed_script_bytes.as_ref().and_then(|script_bytes| script_bytes.segwit_script_code(None)), public_keys, &field[..])
+            },
+            // A P2WSH segregated witness field ends with the witness script.
+            (Some((0, program)), Some((witness_script, items))) if program.len() == 32 && sha_256(witness_script.bytes()) == program => {
+                let script_code = ScriptBytes::of(witness_script.bytes());
+
+                let mut public_keys: Vec<DataElement> = items.iter().map(|item| DataElement::of(item.bytes())).collect();
+
+                if let Ok(script) = Script::try_from(&script_code) {
+                    for element in script.elements() {
+                        if let Element::Data(data) = element { public_keys.push(data.clone()) }
+                    }
+                }
+
+                (Some(script_code), public_keys, items)
+            },
+            _ => (None, vec![], &field[..]),
+        };
+
+        let witness_public_keys: Vec<DataElement> = witness_public_keys
+            .into_iter()
+            .filter(|data| is_public_key(data.bytes()))
+            .collect();
+
+        let cache = SegWitSignatureHashCache::new(transaction);
+
+        for (i, item) in items.iter().enumerate() {
+            let Some((signature, sighash, strict_der)) = parse_signature(item.bytes()) else { continue };
+
+            let (public_key, valid) = match &script_code {
+                Some(script_code) => {
+                    let data = DataElement::of(item.bytes());
+
+                    check_public_keys(&witness_public_keys, |public_key| {
+                        segwit_signature_verification(&data, public_key, transaction, n, script_code, entry.utxo.amount, &cache, &DefaultOpCheckSigDigest {})
+                            .unwrap_or(false)
+                    })
+                },
+                None => (None, None),
+            };
+
+            signatures.push(
+                InputSignature {
+                    input_index: n,
+                    location: SignatureLocation::Witness(i),
+                    signature: signature,
+                    sighash: sighash,
+                    public_key: public_key,
+                    valid: valid,
+                    low_s: signature.is_low_s_secp256k1(),
+                    strict_der: strict_der,
+                }
+            );
+        }
+    }
+
+    Ok(signatures)
+}
+
+/// Returns the public key that a signature verifies against, or the only candidate public key, and
+/// whether the signature verifies; nothing can be checked without candidate public keys.
+fn check_public_keys<F>(public_keys: &[DataElement], verify: F) -> (Option<Vec<u8>>, Option<bool>)
+where F: Fn(&DataElement) -> bool {
+    if public_keys.is_empty() { return (None, None) }
+
+    match public_keys.iter().find(|public_key| verify(public_key)) {
+        Some(public_key) => (Some(public_key.bytes().to_vec()), Some(true)),
+        None if public_keys.len() == 1 => (Some(public_keys[0].bytes().to_vec()), Some(false)),
+        None => (None, Some(false)),
+    }
+}
+
+/// Parses a DER format signature followed by a `SIGHASH` flag byte, also indicating whether it is
+/// strictly encoded.
+///
+/// Any `SIGHASH` flag byte is accepted, as consensus rules permit.
+fn parse_signature(bytes: &[u8]) -> Option<(Signature, SigHashFlag, bool)> {
+    let (sighash_byte, signature_bytes) = bytes.split_last()?;
+
+    let sighash = SigHashFlag::from(u32::from(*sighash_byte));
+    let signature_format = SignatureDerFormatBytes::try_of(signature_bytes)?;
+
+    Some((Signature::from(signature_format), sighash, SignatureDerFormatBytes::is_strict_encoding(signature_bytes)))
+}
+
+/// Indicates that some bytes are a compressed or uncompressed SEC format public key.
+fn is_public_key(bytes: &[u8]) -> bool {
+    match bytes.first() {
+        Some(0x02_u8 | 0x03_u8) => bytes.len() == 33,
+        Some(0x04_u8) => bytes.len() == 65,
+        _ => false,
+    }
+}
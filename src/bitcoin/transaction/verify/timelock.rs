@@ -0,0 +1,107 @@
+//! Bitcoin transaction time lock verification.
+//!
+//! A transaction may not be included in a block until its time locks have passed.
+//!
+//! - Absolute time lock (`nLockTime`); the transaction is final once the block height (or the
+//!   median time past, per BIP-113) exceeds its time lock, or if all of its transaction inputs
+//!   are final.
+//! - Relative time locks (`nSequence`, BIP-68); for transaction versions 2 and above, each
+//!   transaction input may require some number of blocks (or 512 second intervals) to pass after
+//!   the confirmation of the UTXO it spends.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::RelativeLockTime;
+use crate::bitcoin::transaction::SEQUENCE_LOCKTIME_GRANULARITY;
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum SequenceLocksError {
+    /// `prev_heights` does not have one block height per transaction input.
+    PrevHeightsMismatch,
+
+    /// `prev_mtps` does not have one median time past per transaction input.
+    PrevMtpsMismatch,
+}
+
+/// The last block height and median time past at which a transaction's relative time locks have
+/// yet to pass.
+///
+/// A value of `-1` indicates that the transaction has no such relative time lock.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+pub struct SequenceLocks {
+    /// Last block height at which the transaction is relative time locked.
+    pub min_height: i64,
+
+    /// Last median time past at which the transaction is relative time locked.
+    pub min_time: i64,
+}
+
+impl SequenceLocks {
+    /// Indicates that the relative time locks have passed, for a transaction included in a block
+    /// at some height, whose previous block has some median time past.
+    pub fn is_satisfied_by(&self, block_height: u32, median_time_past: u32) -> bool {
+        self.min_height < i64::from(block_height) && self.min_time < i64::from(median_time_past)
+    }
+}
+
+impl Transaction {
+    /// Indicates that the transaction is final (`IsFinalTx`); i.e.; that its absolute time lock
+    /// does not prevent its inclusion in a block at some height, whose previous block has some
+    /// median time past.
+    pub fn is_final(&self, block_height: u32, median_time_past: u32) -> bool {
+        if self.locktime.is_zero() { return true }
+
+        if self.locktime.absolute().is_satisfied_by(block_height, median_time_past) { return true }
+
+        self.inputs.iter().all(|input| input.is_final())
+    }
+
+    /// Calculates the relative time locks of the transaction (`CalculateSequenceLocks`).
+    ///
+    /// For each transaction input, `prev_heights` gives the block height at which its UTXO was
+    /// confirmed, and `prev_mtps` gives the median time past of the block preceding that block.
+    pub fn sequence_locks(&self, prev_heights: &[u32], prev_mtps: &[u32]) -> Result<SequenceLocks, SequenceLocksError> {
+        if prev_heights.len() != self.inputs.len() { return Err(SequenceLocksError::PrevHeightsMismatch) }
+        if prev_mtps.len() != self.inputs.len() { return Err(SequenceLocksError::PrevMtpsMismatch) }
+
+        let mut sequence_locks = SequenceLocks { min_height: -1, min_time: -1 };
+
+        // Relative time locks are not enforced for transaction versions below 2.
+        if self.version.value() < 2 { return Ok(sequence_locks) }
+
+        for ((input, prev_height), prev_mtp) in self.inputs.iter().zip(prev_heights).zip(prev_mtps) {
+            match input.relative_locktime() {
+                None => (),
+                Some(RelativeLockTime::Blocks(blocks)) => {
+                    let min_height = i64::from(*prev_height) + i64::from(blocks) - 1;
+
+                    sequence_locks.min_height = std::cmp::max(sequence_locks.min_height, min_height);
+                },
+                Some(RelativeLockTime::Time(intervals)) => {
+                    let min_time = i64::from(*prev_mtp) + (i64::from(intervals) << SEQUENCE_LOCKTIME_GRANULARITY) - 1;
+
+                    sequence_locks.min_time = std::cmp::max(sequence_locks.min_time, min_time);
+                },
+            }
+        }
+
+        Ok(sequence_locks)
+    }
+
+    /// Indicates that the relative time locks of the transaction have passed
+    /// (`SequenceLocks`), for its inclusion in a block at some height, whose previous block has
+    /// some median time past.
+    ///
+    /// See `Transaction::sequence_locks` for `prev_heights` and `prev_mtps`.
+    pub fn sequence_locks_satisfied(
+        &self,
+        prev_heights: &[u32],
+        prev_mtps: &[u32],
+        block_height: u32,
+        median_time_past: u32
+    ) -> Result<bool, SequenceLocksError> {
+        Ok(self.sequence_locks(prev_heights, prev_mtps)?.is_satisfied_by(block_height, median_time_past))
+    }
+}
@@ -2,24 +2,84 @@
 
 use crate::bitcoin::transaction::Transaction;
 use crate::bitcoin::transaction::TransactionId;
+use crate::bitcoin::transaction::OutPoint;
 use crate::bitcoin::transaction::fee::transaction_fee::FeeCalculationError;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProviderError;
 use crate::bitcoin::script::Script;
 use crate::bitcoin::script::ScriptBytes;
 use crate::bitcoin::script::ScriptExecutionContext;
 use crate::bitcoin::script::ScriptError;
 use crate::bitcoin::script::Stack;
+use crate::bitcoin::script::Element;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::SegWitSignatureHashCache;
+use crate::bitcoin::script::TaprootSignatureHashCache;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::crypto::digest::hash_160;
 use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 
+/// The number of blocks that must pass before a coinbase transaction's UTXOs may be spent.
+pub const COINBASE_MATURITY: u64 = 100;
+
 #[derive(Debug)]
 pub enum TransactionVerificationError {
-    NoPreviousTransactionFound(TransactionId),
     NoInputFound(TransactionId, usize),
 
+    UtxoProviderError(UtxoProviderError),
+
+    /// A transaction input spends a coinbase transaction's UTXO before it has matured.
+    PrematureCoinbaseSpend(usize),
+
+    /// The transaction has no transaction inputs.
+    EmptyInputs,
+
+    /// The transaction has no UTXOs.
+    EmptyOutputs,
+
+    /// The transaction (without witness data) exceeds the maximum block weight.
+    Oversized,
+
+    /// A UTXO amount is outside of `0..=MAX_MONEY`.
+    OutputAmountOutOfRange(usize),
+
+    /// The sum of UTXO amounts is outside of `0..=MAX_MONEY`.
+    TotalOutputAmountOutOfRange,
+
+    /// Multiple transaction inputs spend the same outpoint.
+    DuplicateInput(OutPoint),
+
+    /// The coinbase transaction input script is not between 2 and 100 bytes long.
+    CoinbaseScriptSize,
+
+    /// A non-coinbase transaction input spends the null outpoint.
+    NullPrevout(usize),
+
     FeeCalculationError(FeeCalculationError),
 
     TransactionScriptError(usize),
 
+    /// A transaction input's segregated witness field does not satisfy the witness program it
+    /// spends; e.g.; a P2WPKH field without exactly two items, or a P2WSH witness script whose
+    /// SHA-256 digest differs (BIP-141).
+    WitnessProgramMismatch(usize),
+
+    /// A transaction input spends a version 0 witness program that is neither 20 nor 32 bytes long.
+    WitnessProgramWrongLength(usize),
+
+    /// A transaction input spending a witness program has a script other than an empty script (or,
+    /// if P2SH-wrapped, a single data push of the redeem script).
+    WitnessMalleated(usize),
+
+    /// A transaction input that does not spend a witness program has a non-empty segregated
+    /// witness field.
+    WitnessUnexpected(usize),
+
+    /// A Taproot script path spend's control block is malformed, or does not prove that the
+    /// output key commits to the leaf script (BIP-341).
+    TaprootCommitmentMismatch(usize),
+
     TryFromIntError(std::num::TryFromIntError),
 }
 
@@ -29,6 +89,12 @@ impl From<std::num::TryFromIntError> for TransactionVerificationError {
     }
 }
 
+impl From<UtxoProviderError> for TransactionVerificationError {
+    fn from(error: UtxoProviderError) -> Self {
+        Self::UtxoProviderError(error)
+    }
+}
+
 impl From<FeeCalculationError> for TransactionVerificationError {
     fn from(error: FeeCalculationError) -> Self {
         Self::FeeCalculationError(error)
@@ -37,17 +103,24 @@ impl From<FeeCalculationError> for TransactionVerificationError {
 
 impl Transaction {
     /// Verifies the transaction.
-    pub fn verify<'a, F>(
-        &'a self,
+    ///
+    /// A coinbase transaction has no UTXO being spent, and therefore no fee or scripts to verify;
+    /// only its context-free checks are performed.
+    pub fn verify<P>(
+        &self,
         timestamp: u64,
         block_height: u64,
-        transaction_retrieval: F
+        utxo_provider: &P
     ) -> Result<bool, TransactionVerificationError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
-        self.verify_fee(&transaction_retrieval)?;
+    where P: UtxoProvider + ?Sized {
+        self.check_transaction()?;
+
+        if self.is_coinbase() { return Ok(true) }
+
+        self.verify_fee(utxo_provider)?;
 
         for i in 0..self.inputs.len() {
-            let verified = self.verify_input(i, timestamp, block_height, &transaction_retrieval)?;
+            let verified = self.verify_input(i, timestamp, block_height, utxo_provider)?;
 
             if !verified { return Ok(false) }
         }
@@ -56,30 +129,100 @@ impl Transaction {
     }
 
     /// Verifies the fees for a transaction.
-    pub fn verify_fee<'a, F>(&'a self, transaction_retrieval: F) -> Result<(), FeeCalculationError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
-        self.fee(transaction_retrieval)?;
+    pub fn verify_fee<P>(&self, utxo_provider: &P) -> Result<(), FeeCalculationError>
+    where P: UtxoProvider + ?Sized {
+        self.fee(utxo_provider)?;
 
         Ok(())
     }
 
     /// Verifies a transaction input.
-    pub fn verify_input<'a, F>(
-        &'a self,
+    ///
+    /// A transaction input may not spend a coinbase transaction's UTXO until `COINBASE_MATURITY`
+    /// blocks have passed since its confirmation.
+    ///
+    /// A coinbase transaction input spends the null outpoint; it has no script to evaluate.
+    ///
+    /// The transaction has no witness data; a transaction input that spends a witness program
+    /// (native or P2SH-wrapped) does not verify. See `SegWitTransaction::verify_input`.
+    pub fn verify_input<P>(
+        &self,
+        n: usize,
+        timestamp: u64,
+        block_height: u64,
+        utxo_provider: &P
+    ) -> Result<bool, TransactionVerificationError>
+    where P: UtxoProvider + ?Sized {
+        self.verify_input_with_witness(n, None, None, None, timestamp, block_height, utxo_provider)
+    }
+
+    /// Verifies a transaction input, given its segregated witness field (if any) and the digests
+    /// shared by each transaction input's BIP-143 and BIP-341 signature hashes (computed if not
+    /// given).
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn verify_input_with_witness<P>(
+        &self,
         n: usize,
+        segwit_field: Option<&SegWitField>,
+        cache: Option<&SegWitSignatureHashCache>,
+        taproot_cache: Option<&TaprootSignatureHashCache>,
         timestamp: u64,
         block_height: u64,
-        transaction_retrieval: F
+        utxo_provider: &P
     ) -> Result<bool, TransactionVerificationError>
-    where F: Fn(&'a TransactionId) -> Option<&'a Transaction> {
+    where P: UtxoProvider + ?Sized {
         let input = self.inputs.get(n).ok_or(TransactionVerificationError::NoInputFound(self.txid(), n))?;
 
-        let prev_txn = transaction_retrieval(&input.txid).ok_or(TransactionVerificationError::NoPreviousTransactionFound(input.txid))?;
-        let utxo_index = usize::try_from(input.utxo_index)?;
+        if self.is_coinbase() { return Ok(true) }
+
+        let entry = utxo_provider.utxo(&input.outpoint)?;
+
+        if entry.coinbase {
+            let confirmed_height = entry.height.map(u64::from).ok_or(TransactionVerificationError::PrematureCoinbaseSpend(n))?;
+
+            if block_height < confirmed_height + COINBASE_MATURITY {
+                return Err(TransactionVerificationError::PrematureCoinbaseSpend(n));
+            }
+        }
 
-        let utxo_script_bytes = &prev_txn.utxos.get(utxo_index).ok_or(TransactionVerificationError::NoInputFound(input.txid, utxo_index))?.script;
+        let empty_segwit_field = SegWitField::new(vec![]);
+        let segwit_field = segwit_field.unwrap_or(&empty_segwit_field);
+
+        let utxo_script_bytes = &entry.utxo.script;
         let input_script_bytes = &input.script;
 
+        // A native witness program; the transaction input script must be empty.
+        if let Some((version, program)) = utxo_script_bytes.witness_program() {
+            if !input_script_bytes.bytes().is_empty() { return Err(TransactionVerificationError::WitnessMalleated(n)) }
+
+            // Taproot; not applicable to P2SH-wrapped witness programs.
+            if version == 1 && program.len() == 32 {
+                let computed_cache;
+                let taproot_cache = match taproot_cache {
+                    Some(taproot_cache) => taproot_cache,
+                    None => {
+                        computed_cache = TaprootSignatureHashCache::from_provider(self, utxo_provider)?;
+
+                        &computed_cache
+                    },
+                };
+
+                return self.verify_taproot_program(n, program, segwit_field, taproot_cache, timestamp, block_height);
+            }
+
+            return self.verify_witness_program(n, version, program, segwit_field, entry.utxo.amount, cache, timestamp, block_height);
+        }
+
+        // A P2SH-wrapped witness program; the transaction input script must only push the redeem
+        // script.
+        if let Some(redeem_script_bytes) = p2sh_witness_program(n, input_script_bytes, utxo_script_bytes)? {
+            let (version, program) = redeem_script_bytes.witness_program().unwrap();
+
+            return self.verify_witness_program(n, version, program, segwit_field, entry.utxo.amount, cache, timestamp, block_height);
+        }
+
+        if !segwit_field.is_empty() { return Err(TransactionVerificationError::WitnessUnexpected(n)) }
+
         let input_script = Script::try_from(input_script_bytes).map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
         let utxo_script = Script::try_from(utxo_script_bytes).map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
 
@@ -133,3 +276,31 @@ impl Transaction {
         }
     }
 }
+
+/// Returns the redeem script of a transaction input that spends a P2SH-wrapped witness program;
+/// i.e.; the last data element pushed by its script is a witness program, whose `HASH_160` digest
+/// matches the P2SH locking script.
+///
+/// The transaction input script must be nothing other than a single data push of the redeem
+/// script (BIP-141).
+fn p2sh_witness_program(
+    n: usize,
+    input_script_bytes: &ScriptBytes,
+    utxo_script_bytes: &ScriptBytes
+) -> Result<Option<ScriptBytes>, TransactionVerificationError> {
+    if !utxo_script_bytes.is_p2sh_locking() { return Ok(None) }
+
+    let Ok(input_script) = Script::try_from(input_script_bytes) else { return Ok(None) };
+    let Some(Element::Data(redeem_script)) = input_script.elements().last() else { return Ok(None) };
+
+    let redeem_script_bytes = ScriptBytes::of(redeem_script.bytes());
+
+    if redeem_script_bytes.witness_program().is_none() { return Ok(None) }
+    if hash_160(redeem_script.bytes()).as_slice() != utxo_script_bytes.locking_redeem_script_hash() { return Ok(None) }
+
+    if input_script_bytes.bytes() != DataElement::of(redeem_script.bytes()).bytes_with_opcode() {
+        return Err(TransactionVerificationError::WitnessMalleated(n));
+    }
+
+    Ok(Some(redeem_script_bytes))
+}
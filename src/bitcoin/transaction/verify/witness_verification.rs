@@ -0,0 +1,256 @@
+//! Bitcoin segregated witness transaction verification (BIP-141).
+//!
+//! A UTXO whose locking script is a witness program (a version opcode followed by a single data
+//! push) is unlocked by the spending transaction input's segregated witness field, rather than by
+//! its script.
+//!
+//! - Version 0, 20 byte program (P2WPKH); the field is a signature and a public key, checked as
+//!   if by `OP_DUP OP_HASH160 <program> OP_EQUALVERIFY OP_CHECKSIG`.
+//! - Version 0, 32 byte program (P2WSH); the last item of the field is a witness script, whose
+//!   SHA-256 digest is the program. It is executed with the other items as its initial stack.
+//! - Version 1, 32 byte program (P2TR); the field is spent along the key path or the script path
+//!   (BIP-341), and leaf scripts are executed with Tapscript rules (BIP-342).
+//! - Other versions are reserved for future upgrades, and are unencumbered.
+//!
+//! Version 0 witness programs may be wrapped in P2SH; the redeem script is then the witness
+//! program.
+//!
+//! Signatures within version 0 witness scripts commit to the BIP-143 signature hash; Taproot
+//! signatures commit to the BIP-341 signature hash.
+
+use crate::bitcoin::transaction::Transaction;
+use crate::bitcoin::transaction::Amount;
+use crate::bitcoin::transaction::verify::verification::TransactionVerificationError;
+use crate::bitcoin::transaction::provider::utxo_provider::UtxoProvider;
+use crate::bitcoin::segwit::transaction::SegWitTransaction;
+use crate::bitcoin::segwit::field::SegWitField;
+use crate::bitcoin::script::Script;
+use crate::bitcoin::script::ScriptBytes;
+use crate::bitcoin::script::ScriptExecutionContext;
+use crate::bitcoin::script::DataElement;
+use crate::bitcoin::script::Stack;
+use crate::bitcoin::script::SegWitSignatureHashCache;
+use crate::bitcoin::script::TaprootSignatureHashCache;
+use crate::bitcoin::script::taproot_signature_verification;
+use crate::bitcoin::taproot::ANNEX_TAG;
+use crate::bitcoin::taproot::TAPROOT_LEAF_TAPSCRIPT;
+use crate::bitcoin::taproot::control_block::ControlBlock;
+use crate::bitcoin::taproot::hashes::tapleaf_hash;
+use crate::bitcoin::taproot::tapscript::MAX_STACK_SIZE;
+use crate::bitcoin::taproot::tapscript::VALIDATION_BUDGET_OFFSET;
+use crate::crypto::digest::sha_256;
+use crate::util::byte_string::ByteString;
+use crate::util::byte_string::ByteSlice;
+use crate::util::byte_string::ByteVector;
+
+/// The maximum size of a segregated witness item, when executing a witness script.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+impl SegWitTransaction {
+    /// Verifies the transaction, including its segregated witness fields.
+    ///
+    /// A coinbase transaction has no UTXO being spent, and therefore no fee or scripts to verify;
+    /// only its context-free checks are performed.
+    pub fn verify<P>(
+        &self,
+        timestamp: u64,
+        block_height: u64,
+        utxo_provider: &P
+    ) -> Result<bool, TransactionVerificationError>
+    where P: UtxoProvider + ?Sized {
+        self.check_transaction()?;
+
+        if self.is_coinbase() { return Ok(true) }
+
+        self.verify_fee(utxo_provider)?;
+
+        // The digests shared by each transaction input's signature hash are computed only once.
+        let cache = SegWitSignatureHashCache::new(self);
+        let taproot_cache = self.spends_taproot(utxo_provider)?
+            .then(|| TaprootSignatureHashCache::from_provider(self, utxo_provider))
+            .transpose()?;
+
+        for (i, segwit_field) in self.segwit_fields().iter().enumerate() {
+            let verified = self.verify_input_with_witness(
+                i,
+                Some(segwit_field),
+                Some(&cache),
+                taproot_cache.as_ref(),
+                timestamp,
+                block_height,
+                utxo_provider
+            )?;
+
+            if !verified { return Ok(false) }
+        }
+
+        Ok(true)
+    }
+
+    /// Verifies a transaction input, including its segregated witness field.
+    pub fn verify_input<P>(
+        &self,
+        n: usize,
+        timestamp: u64,
+        block_height: u64,
+        utxo_provider: &P
+    ) -> Result<bool, TransactionVerificationError>
+    where P: UtxoProvider + ?Sized {
+        self.verify_input_with_witness(n, self.segwit_fields().get(n), None, None, timestamp, block_height, utxo_provider)
+    }
+
+    /// Indicates that any transaction input spends a P2TR UTXO; i.e.; that the BIP-341 signature
+    /// hash digests (which require every UTXO being spent) are needed.
+    fn spends_taproot<P>(&self, utxo_provider: &P) -> Result<bool, TransactionVerificationError>
+    where P: UtxoProvider + ?Sized {
+        for input in &self.inputs {
+            if utxo_provider.utxo(&input.outpoint)?.utxo.script.is_p2tr_locking() { return Ok(true) }
+        }
+
+        Ok(false)
+    }
+}
+
+impl Transaction {
+    /// Verifies a transaction input's segregated witness field against the witness program it
+    /// spends.
+    ///
+    /// The witness script must leave exactly one data element on the stack.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn verify_witness_program(
+        &self,
+        n: usize,
+        version: u8,
+        program: &[u8],
+        segwit_field: &SegWitField,
+        amount: Amount,
+        cache: Option<&SegWitSignatureHashCache>,
+        timestamp: u64,
+        block_height: u64,
+    ) -> Result<bool, TransactionVerificationError> {
+        // Unencumbered, until future upgrades define other witness versions.
+        if version != 0 { return Ok(true) }
+
+        let (witness_script_bytes, items) = match program.len() {
+            20 => {
+                if segwit_field.len() != 2 { return Err(TransactionVerificationError::WitnessProgramMismatch(n)) }
+
+                let mut bytes: Vec<u8> = vec![0x76_u8, 0xa9_u8, 0x14_u8];
+
+                bytes.extend_from_slice(program);
+                bytes.extend_from_slice(&[0x88_u8, 0xac_u8]);
+
+                (ScriptBytes::of(&bytes), &segwit_field[..])
+            },
+            32 => {
+                let Some((witness_script, items)) = segwit_field.split_last() else {
+                    return Err(TransactionVerificationError::WitnessProgramMismatch(n));
+                };
+
+                if sha_256(witness_script.bytes()) != program {
+                    return Err(TransactionVerificationError::WitnessProgramMismatch(n));
+                }
+
+                (ScriptBytes::of(witness_script.bytes()), items)
+            },
+            _ => return Err(TransactionVerificationError::WitnessProgramWrongLength(n)),
+        };
+
+        if items.iter().any(|item| item.bytes().len() > MAX_SCRIPT_ELEMENT_SIZE) {
+            return Err(TransactionVerificationError::TransactionScriptError(n));
+        }
+
+        let witness_script = Script::try_from(&witness_script_bytes).map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
+
+        let computed_cache;
+        let cache = match cache {
+            Some(cache) => cache,
+            None => {
+                computed_cache = SegWitSignatureHashCache::new(self);
+
+                &computed_cache
+            },
+        };
+
+        let context = ScriptExecutionContext::new(self, n, timestamp, block_height).with_segwit(amount, cache);
+        let data_elements: Vec<DataElement> = items.iter().map(|item| DataElement::of(item.bytes())).collect();
+
+        let mut stack = Stack::with_data_elements(&witness_script, &context, data_elements);
+
+        let result = stack.evaluate().map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
+
+        Ok(result && stack.stack().len() == 1)
+    }
+
+    /// Verifies a transaction input's segregated witness field against the (native) P2TR witness
+    /// program it spends (BIP-341).
+    ///
+    /// - If there are at least two items, and the last begins with `0x50`, it is the annex; it is
+    ///   removed, and is committed to by signatures.
+    /// - A single remaining item is a Schnorr signature for the output key (key path).
+    /// - Otherwise, the last two remaining items are a leaf script and a control block (script
+    ///   path). Leaf scripts of an unknown leaf version, or containing `OP_SUCCESSx`, are
+    ///   unencumbered; Tapscript leaf scripts are executed with the other items as the initial
+    ///   stack, and must leave exactly one data element on the stack.
+    pub(crate) fn verify_taproot_program(
+        &self,
+        n: usize,
+        program: &[u8],
+        segwit_field: &SegWitField,
+        cache: &TaprootSignatureHashCache,
+        timestamp: u64,
+        block_height: u64,
+    ) -> Result<bool, TransactionVerificationError> {
+        let mut output_key = [0_u8; 32];
+
+        output_key.clone_from_slice(program);
+
+        let (annex, items) = match segwit_field.split_last() {
+            Some((last, items)) if !items.is_empty() && last.bytes().first() == Some(&ANNEX_TAG) => (Some(last.bytes()), items),
+            Some(_) => (None, &segwit_field[..]),
+            None => return Err(TransactionVerificationError::WitnessProgramMismatch(n)),
+        };
+
+        // Key path.
+        if items.len() == 1 {
+            return Ok(taproot_signature_verification(items[0].bytes(), &output_key, self, n, annex, None, cache));
+        }
+
+        // Script path.
+        let (control_block_bytes, items) = items.split_last().unwrap();
+        let (leaf_script, items) = items.split_last().unwrap();
+
+        let leaf_script_bytes = ScriptBytes::of(leaf_script.bytes());
+
+        let control_block = ControlBlock::parse_bytes(control_block_bytes.bytes())
+            .ok_or(TransactionVerificationError::TaprootCommitmentMismatch(n))?;
+
+        if !control_block.verify_taproot_commitment(&output_key, &leaf_script_bytes) {
+            return Err(TransactionVerificationError::TaprootCommitmentMismatch(n));
+        }
+
+        // Unencumbered, until future upgrades define other leaf versions.
+        if control_block.leaf_version != TAPROOT_LEAF_TAPSCRIPT { return Ok(true) }
+
+        let op_success = leaf_script_bytes.contains_op_success().map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
+
+        if op_success { return Ok(true) }
+
+        if items.len() > MAX_STACK_SIZE || items.iter().any(|item| item.bytes().len() > MAX_SCRIPT_ELEMENT_SIZE) {
+            return Err(TransactionVerificationError::TransactionScriptError(n));
+        }
+
+        let leaf_script = Script::try_from(&leaf_script_bytes).map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
+        let validation_budget = VALIDATION_BUDGET_OFFSET + i64::try_from(segwit_field.bytes().len())?;
+
+        let context = ScriptExecutionContext::new(self, n, timestamp, block_height)
+            .with_taproot(cache, tapleaf_hash(control_block.leaf_version, &leaf_script_bytes), annex, validation_budget);
+        let data_elements: Vec<DataElement> = items.iter().map(|item| DataElement::of(item.bytes())).collect();
+
+        let mut stack = Stack::with_data_elements(&leaf_script, &context, data_elements);
+
+        let result = stack.evaluate().map_err(|_| TransactionVerificationError::TransactionScriptError(n))?;
+
+        Ok(result && stack.stack().len() == 1)
+    }
+}
@@ -0,0 +1,219 @@
+//! Incremental (streaming) digest computation.
+//!
+//! Data may be provided in parts (e.g.; as it is read), rather than buffered in its entirety. Each
+//! engine also implements `std::io::Write`, so that a reader may be copied into it (see
+//! `std::io::copy`).
+
+use std::io;
+
+use crate::crypto::digest::Sha1;
+use crate::crypto::digest::Sha1Digest;
+use crate::crypto::digest::Sha256;
+use crate::crypto::digest::Sha2Digest;
+use crate::crypto::digest::Ripemd160;
+use crate::crypto::digest::RipemdDigest;
+use crate::crypto::digest::sha_256;
+use crate::crypto::digest::ripemd_160;
+
+/// A digest computed incrementally.
+pub trait DigestEngine {
+    /// The digest value.
+    type Output;
+
+    /// Provide more data.
+    fn update(&mut self, data: impl AsRef<[u8]>);
+
+    /// Complete the digest of all data provided.
+    fn finalize(self) -> Self::Output;
+}
+
+/// SHA-1 digest engine.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Sha1Engine {
+    hasher: Sha1,
+}
+
+/// SHA-256 digest engine.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Sha256Engine {
+    hasher: Sha256,
+}
+
+/// RIPEMD-160 digest engine.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Ripemd160Engine {
+    hasher: Ripemd160,
+}
+
+/// "Double SHA-256" (`HASH_256`) digest engine.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Hash256Engine {
+    engine: Sha256Engine,
+}
+
+/// RIPEMD-160 of SHA-256 (`HASH_160`) digest engine.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Hash160Engine {
+    engine: Sha256Engine,
+}
+
+impl Sha1Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Sha256Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Continue from a SHA-256 hasher that has already been provided some data (e.g.; a tagged
+    /// hash midstate).
+    pub(crate) fn from_hasher(hasher: Sha256) -> Self {
+        Self { hasher: hasher }
+    }
+}
+
+impl Ripemd160Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hash256Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Hash160Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DigestEngine for Sha1Engine {
+    type Output = [u8; 20];
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        Sha1Digest::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Sha1Digest::finalize(self.hasher).into()
+    }
+}
+
+impl DigestEngine for Sha256Engine {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        Sha2Digest::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        Sha2Digest::finalize(self.hasher).into()
+    }
+}
+
+impl DigestEngine for Ripemd160Engine {
+    type Output = [u8; 20];
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        RipemdDigest::update(&mut self.hasher, data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        RipemdDigest::finalize(self.hasher).into()
+    }
+}
+
+impl DigestEngine for Hash256Engine {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.engine.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        sha_256(self.engine.finalize())
+    }
+}
+
+impl DigestEngine for Hash160Engine {
+    type Output = [u8; 20];
+
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.engine.update(data);
+    }
+
+    fn finalize(self) -> Self::Output {
+        ripemd_160(self.engine.finalize())
+    }
+}
+
+impl io::Write for Sha1Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Sha256Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Ripemd160Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Hash256Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Hash160Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
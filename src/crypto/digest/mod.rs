@@ -1,5 +1,8 @@
 //! Cryptographic digests and hash functions.
 
+pub mod engine;
+pub mod tagged;
+
 pub use sha1::{Digest as Sha1Digest, Sha1};
 pub use sha2::{Digest as Sha2Digest, Sha256};
 pub use ripemd::{Digest as RipemdDigest, Ripemd160};
@@ -39,3 +42,16 @@ pub fn hash_256(data: impl AsRef<[u8]>) -> [u8; 32] {
 pub fn hash_160(data: impl AsRef<[u8]>) -> [u8; 20] {
     ripemd_160(sha_256(data))
 }
+
+/// Tagged SHA-256 digest for `data` bytes (BIP-340); i.e.;
+/// `SHA-256(SHA-256(tag) || SHA-256(tag) || data)`.
+///
+/// The tag separates digests of different purposes (e.g.; `TapLeaf`, `BIP0340/challenge`).
+///
+/// The tag is rehashed on each call; for a tag used repeatedly, see `tagged::TaggedHash` (and its
+/// precomputed instances, e.g.; `tagged::TAP_LEAF`).
+///
+/// Returns a 32 byte value.
+pub fn tagged_hash(tag: &str, data: impl AsRef<[u8]>) -> [u8; 32] {
+    tagged::TaggedHash::new(tag).hash(data)
+}
@@ -0,0 +1,84 @@
+//! Tagged SHA-256 digests (BIP-340).
+//!
+//! `SHA-256(SHA-256(tag) || SHA-256(tag) || data)`; the 64 byte prefix is exactly one SHA-256
+//! block, so the hasher's state after processing it (the midstate) may be computed once per tag,
+//! and reused for each digest.
+
+use std::sync::LazyLock;
+
+use crate::crypto::digest::Sha256;
+use crate::crypto::digest::Sha2Digest;
+use crate::crypto::digest::sha_256;
+use crate::crypto::digest::engine::DigestEngine;
+use crate::crypto::digest::engine::Sha256Engine;
+
+/// A tagged SHA-256 digest function, with a precomputed midstate for its tag.
+#[derive(Clone)]
+pub struct TaggedHash {
+    midstate: Sha256,
+}
+
+impl TaggedHash {
+    /// Create a tagged digest function; i.e.; compute the midstate of the tag prefix.
+    pub fn new(tag: &str) -> Self {
+        let tag_digest = sha_256(tag.as_bytes());
+
+        let mut midstate = Sha256::new();
+
+        Sha2Digest::update(&mut midstate, tag_digest);
+        Sha2Digest::update(&mut midstate, tag_digest);
+
+        Self { midstate: midstate }
+    }
+
+    /// Tagged SHA-256 digest for `data` bytes.
+    ///
+    /// Returns a 32 byte value.
+    pub fn hash(&self, data: impl AsRef<[u8]>) -> [u8; 32] {
+        let mut engine = self.engine();
+
+        engine.update(data);
+        engine.finalize()
+    }
+
+    /// Returns a digest engine, to which the data may be provided incrementally.
+    pub fn engine(&self) -> Sha256Engine {
+        Sha256Engine::from_hasher(self.midstate.clone())
+    }
+}
+
+/// `TapLeaf` (BIP-341); leaf script hashes.
+pub static TAP_LEAF: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("TapLeaf"));
+
+/// `TapBranch` (BIP-341); script tree branch hashes.
+pub static TAP_BRANCH: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("TapBranch"));
+
+/// `TapTweak` (BIP-341); internal key tweaks.
+pub static TAP_TWEAK: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("TapTweak"));
+
+/// `TapSighash` (BIP-341); Taproot signature hashes.
+pub static TAP_SIGHASH: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("TapSighash"));
+
+/// `BIP0340/aux` (BIP-340); auxiliary random data.
+pub static BIP0340_AUX: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("BIP0340/aux"));
+
+/// `BIP0340/nonce` (BIP-340); signing nonces.
+pub static BIP0340_NONCE: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("BIP0340/nonce"));
+
+/// `BIP0340/challenge` (BIP-340); signature challenges.
+pub static BIP0340_CHALLENGE: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("BIP0340/challenge"));
+
+/// `KeyAgg list` (BIP-327); the hash of the public keys being aggregated.
+pub static KEYAGG_LIST: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("KeyAgg list"));
+
+/// `KeyAgg coefficient` (BIP-327); key aggregation coefficients.
+pub static KEYAGG_COEFFICIENT: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("KeyAgg coefficient"));
+
+/// `MuSig/aux` (BIP-327); auxiliary random data for nonce generation.
+pub static MUSIG_AUX: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("MuSig/aux"));
+
+/// `MuSig/nonce` (BIP-327); secret nonces.
+pub static MUSIG_NONCE: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("MuSig/nonce"));
+
+/// `MuSig/noncecoef` (BIP-327); the nonce coefficient of a signing session.
+pub static MUSIG_NONCE_COEFFICIENT: LazyLock<TaggedHash> = LazyLock::new(|| TaggedHash::new("MuSig/noncecoef"));
@@ -4,3 +4,4 @@ pub mod digest;
 pub mod secp256k1;
 
 pub mod ecdsa;
+pub mod schnorr;
@@ -0,0 +1,82 @@
+//! Batch verification of Schnorr signatures (BIP-340).
+//!
+//! Rather than verifying each `s_i * G = R_i + e_i * P_i`, a random linear combination of the
+//! equations is verified at once;
+//!
+//! - `(s_1 + a_2 * s_2 + ... + a_u * s_u) * G = (R_1 + a_2 * R_2 + ... + a_u * R_u) +
+//!   (e_1 * P_1 + (a_2 * e_2) * P_2 + ... + (a_u * e_u) * P_u)`
+//!
+//! The coefficients `a_2, ..., a_u` must be unpredictable to whoever produced the signatures.
+//! Here, they are derived from a digest of every signature, message, and public key in the batch
+//! (and an optional caller-provided seed), so any change to the batch changes every coefficient.
+
+use crate::crypto::schnorr::signature::SchnorrSignature;
+use crate::crypto::schnorr::signature::challenge;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::FINITE_FIELD_ORDER;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::crypto::digest::sha_256;
+use crate::crypto::digest::tagged_hash;
+use crate::util::number::U256;
+
+/// A signature to be verified within a batch; the signature, the message, and the x-only public
+/// key.
+pub type BatchItem<'a> = (&'a SchnorrSignature, &'a [u8], &'a [u8; 32]);
+
+impl SchnorrSignature {
+    /// Verifies a batch of signatures; i.e.; succeeds only if every signature would verify.
+    ///
+    /// `seed` is mixed into the derivation of the coefficients (e.g.; 32 random bytes).
+    ///
+    /// An empty batch verifies.
+    pub fn batch_verify_secp256k1(batch: &[BatchItem], seed: &[u8; 32]) -> bool {
+        let mut transcript: Vec<u8> = seed.to_vec();
+
+        for (signature, message, public_key) in batch {
+            transcript.extend_from_slice(&signature.bytes());
+            transcript.extend_from_slice(&sha_256(message));
+            transcript.extend_from_slice(*public_key);
+        }
+
+        let transcript_hash = sha_256(transcript);
+
+        let mut s_sum = U256::ZERO;
+        let mut rhs: Option<Secp256k1Point> = None;
+
+        for (i, (signature, message, public_key)) in batch.iter().enumerate() {
+            let Some(point) = Secp256k1Point::lift_x(U256::from_be_bytes(**public_key)) else { return false };
+
+            if signature.r >= FINITE_FIELD_ORDER || signature.s >= ELLIPTIC_CURVE_ORDER { return false }
+
+            let Some(r_point) = Secp256k1Point::lift_x(signature.r) else { return false };
+
+            let e = challenge(signature.r, public_key, message);
+            let a = coefficient(&transcript_hash, i);
+
+            s_sum = s_sum.add_mod(a.mul_mod(signature.s, ELLIPTIC_CURVE_ORDER), ELLIPTIC_CURVE_ORDER);
+
+            let term = (a * r_point) + (a.mul_mod(e, ELLIPTIC_CURVE_ORDER) * point);
+
+            rhs = Some(match rhs {
+                Some(sum) => sum + term,
+                None => term,
+            });
+        }
+
+        match rhs {
+            Some(rhs) => s_sum * Secp256k1Point::generator_point() == rhs,
+            None => true,
+        }
+    }
+}
+
+/// Derives the coefficient of the `i`th signature of a batch; the first coefficient is one.
+fn coefficient(transcript_hash: &[u8; 32], i: usize) -> U256 {
+    if i == 0 { return U256::from(1) }
+
+    let mut preimage: Vec<u8> = transcript_hash.to_vec();
+
+    preimage.extend_from_slice(&u64::try_from(i).unwrap().to_be_bytes());
+
+    U256::from_be_bytes(tagged_hash("BIP0340/batch", preimage)) % ELLIPTIC_CURVE_ORDER
+}
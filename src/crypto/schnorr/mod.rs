@@ -0,0 +1,7 @@
+//! Schnorr signatures over secp256k1 (BIP-340).
+
+pub mod signature;
+pub mod x_only;
+pub mod nonce;
+pub mod batch;
+pub mod musig;
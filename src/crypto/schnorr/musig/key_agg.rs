@@ -0,0 +1,160 @@
+//! MuSig2 key aggregation (BIP-327).
+//!
+//! - `L = tagged_hash("KeyAgg list", pk_1 || ... || pk_u)`
+//! - `a_i = int(tagged_hash("KeyAgg coefficient", L || pk_i)) mod n`; except for the "second" public
+//!   key (the first that differs from `pk_1`), whose coefficient is 1
+//! - `Q = a_1 * P_1 + ... + a_u * P_u`
+//!
+//! Tweaks are tracked by the accumulated sign `g_acc` and tweak `t_acc`, such that the tweaked key
+//! is `g_acc * Q + t_acc * G`; these are then accounted for when signing.
+
+use crate::crypto::schnorr::musig::MuSigError;
+use crate::crypto::schnorr::musig::compressed_point_bytes;
+use crate::crypto::schnorr::musig::parse_compressed_point;
+use crate::crypto::schnorr::x_only::XOnlyPublicKey;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::crypto::digest::tagged::KEYAGG_LIST;
+use crate::crypto::digest::tagged::KEYAGG_COEFFICIENT;
+use crate::crypto::digest::tagged::TAP_TWEAK;
+use crate::util::number::U256;
+
+/// Sorts public keys (lexicographically); signers may then aggregate their public keys without
+/// agreeing upon an order.
+pub fn key_sort(public_keys: &[[u8; 33]]) -> Vec<[u8; 33]> {
+    let mut public_keys = public_keys.to_vec();
+
+    public_keys.sort();
+
+    public_keys
+}
+
+/// A key aggregation context; i.e.; the aggregated (and possibly tweaked) public key.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct KeyAggContext {
+    public_keys: Vec<[u8; 33]>,
+    list_hash: [u8; 32],
+    second_public_key: [u8; 33],
+    point: Secp256k1Point,
+    g_acc: U256,
+    t_acc: U256,
+}
+
+impl KeyAggContext {
+    /// Aggregate (33 byte, compressed) public keys, in the given order (see `key_sort`).
+    pub fn new(public_keys: &[[u8; 33]]) -> Result<Self, MuSigError> {
+        let mut points: Vec<Secp256k1Point> = Vec::with_capacity(public_keys.len());
+
+        for (i, public_key) in public_keys.iter().enumerate() {
+            points.push(parse_compressed_point(public_key, false).ok_or(MuSigError::InvalidPublicKey(i))?);
+        }
+
+        let first_public_key = public_keys.first().ok_or(MuSigError::InvalidAggregateKey)?;
+
+        let second_public_key = public_keys.iter()
+            .find(|public_key| *public_key != first_public_key)
+            .copied()
+            .unwrap_or([0_u8; 33]);
+
+        let mut context = Self {
+            public_keys: public_keys.to_vec(),
+            list_hash: KEYAGG_LIST.hash(public_keys.concat()),
+            second_public_key: second_public_key,
+            point: U256::ZERO * Secp256k1Point::generator_point(),
+            g_acc: U256::from(1),
+            t_acc: U256::ZERO,
+        };
+
+        for (public_key, point) in public_keys.iter().zip(points) {
+            context.point = context.point + (context.key_coefficient(public_key) * point);
+        }
+
+        if context.point.is_infinity() { return Err(MuSigError::InvalidAggregateKey) }
+
+        Ok(context)
+    }
+
+    /// Returns the aggregated public keys.
+    pub fn public_keys(&self) -> &[[u8; 33]] {
+        &self.public_keys
+    }
+
+    /// Returns the key aggregation coefficient of some public key, if it was aggregated.
+    pub fn coefficient(&self, public_key: &[u8; 33]) -> Option<U256> {
+        if !self.public_keys.contains(public_key) { return None }
+
+        Some(self.key_coefficient(public_key))
+    }
+
+    fn key_coefficient(&self, public_key: &[u8; 33]) -> U256 {
+        if *public_key == self.second_public_key { return U256::from(1) }
+
+        U256::from_be_bytes(KEYAGG_COEFFICIENT.hash([&self.list_hash[..], &public_key[..]].concat())) % ELLIPTIC_CURVE_ORDER
+    }
+
+    /// Tweak the aggregate public key; i.e.; `Q' = g * Q + t * G`, where `g` is `-1` for an x-only
+    /// tweak of a point with an odd `y` coordinate (so that the x-only key is tweaked), or `1`
+    /// otherwise (a plain tweak).
+    ///
+    /// The context is unchanged if the tweak is invalid.
+    pub fn apply_tweak(&mut self, tweak: &[u8; 32], x_only: bool) -> Result<(), MuSigError> {
+        let t = U256::from_be_bytes(*tweak);
+
+        if t >= ELLIPTIC_CURVE_ORDER { return Err(MuSigError::InvalidTweak) }
+
+        let g = if x_only && self.has_odd_y() { ELLIPTIC_CURVE_ORDER - U256::from(1) } else { U256::from(1) };
+
+        let point = (g * self.point) + (t * Secp256k1Point::generator_point());
+
+        if point.is_infinity() { return Err(MuSigError::InvalidAggregateKey) }
+
+        self.point = point;
+        self.g_acc = g.mul_mod(self.g_acc, ELLIPTIC_CURVE_ORDER);
+        self.t_acc = t.add_mod(g.mul_mod(self.t_acc, ELLIPTIC_CURVE_ORDER), ELLIPTIC_CURVE_ORDER);
+
+        Ok(())
+    }
+
+    /// Apply the Taproot tweak (BIP-341), committing to a script tree's merkle root (if any); the
+    /// aggregate x-only public key is then the output key.
+    pub fn apply_taproot_tweak(&mut self, merkle_root: Option<&[u8; 32]>) -> Result<(), MuSigError> {
+        let mut preimage = self.x_only_public_key().bytes().to_vec();
+
+        if let Some(merkle_root) = merkle_root { preimage.extend_from_slice(merkle_root) }
+
+        self.apply_tweak(&TAP_TWEAK.hash(preimage), true)
+    }
+
+    /// Returns the aggregate public key point.
+    pub fn point(&self) -> Secp256k1Point {
+        self.point
+    }
+
+    /// Returns the aggregate (x-only) public key; i.e.; that under which the final signature is
+    /// valid.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        let (public_key, _) = XOnlyPublicKey::from_point(self.point).unwrap();
+
+        public_key
+    }
+
+    /// Returns the aggregate (33 byte, compressed) public key; e.g.; for further (plain) tweaking.
+    pub fn plain_public_key(&self) -> [u8; 33] {
+        compressed_point_bytes(self.point)
+    }
+
+    pub(crate) fn has_odd_y(&self) -> bool {
+        let (_, y) = self.point.coordinates().unwrap();
+
+        y.bit(0)
+    }
+
+    pub(crate) fn g_acc(&self) -> U256 {
+        self.g_acc
+    }
+
+    pub(crate) fn t_acc(&self) -> U256 {
+        self.t_acc
+    }
+}
@@ -0,0 +1,92 @@
+//! MuSig2 multi-signatures (BIP-327).
+//!
+//! Several signers, each with their own key pair, jointly produce a single BIP-340 signature,
+//! valid under an aggregate (x-only) public key; e.g.; an N-of-N Taproot output that, on chain, is
+//! indistinguishable from a single-key key path spend.
+//!
+//! 1. Key aggregation (`key_agg`); each public key is weighted by a coefficient derived from the
+//!    list of all public keys, which prevents a signer from choosing their key to cancel the others.
+//!    The aggregate public key may then be tweaked (e.g.; by Taproot).
+//! 2. Nonce generation (`nonce`); each signer generates a secret nonce (two scalars), and
+//!    publishes the corresponding public nonce (two points). Public nonces are then aggregated.
+//! 3. Signing (`session`); given the aggregate nonce and the message, each signer produces a
+//!    partial signature, which may be verified individually. Partial signatures are then
+//!    aggregated into the final signature.
+//!
+//! A secret nonce must never be used for more than one partial signature; doing so reveals the
+//! signer's secret key.
+
+pub mod key_agg;
+pub mod nonce;
+pub mod session;
+
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::math::elliptic_curve::point::EllipticCurvePoint;
+use crate::util::number::U256;
+
+/// Errors thrown during MuSig2 key aggregation and signing.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum MuSigError {
+    /// A public key (by its index) is not a valid compressed point.
+    InvalidPublicKey(usize),
+
+    /// The aggregate public key is the point at infinity (e.g.; after tweaking).
+    InvalidAggregateKey,
+
+    /// A tweak is not less than the elliptic curve order.
+    InvalidTweak,
+
+    /// A (private key) `secret_e` is zero, or not less than the elliptic curve order.
+    InvalidSecret,
+
+    /// A derived secret nonce is zero (with negligible probability); i.e.; different random data
+    /// should be used.
+    InvalidNonce,
+
+    /// A secret nonce is zero, or not less than the elliptic curve order (e.g.; it was already
+    /// used).
+    InvalidSecretNonce,
+
+    /// The secret nonce was generated for a different public key than the signer's.
+    SecretNonceMismatch,
+
+    /// The signer's public key is not among the aggregated public keys.
+    PublicKeyNotFound,
+
+    /// The produced partial signature does not verify (e.g.; due to a fault during computation).
+    VerificationFailed,
+}
+
+/// Returns the 33 byte compressed representation of a point; i.e.; `cbytes`, or 33 zero bytes for
+/// the point at infinity (`cbytes_ext`).
+pub(crate) fn compressed_point_bytes(point: Secp256k1Point) -> [u8; 33] {
+    let mut bytes = [0_u8; 33];
+
+    if let Some((x, y)) = point.coordinates() {
+        bytes[0] = if y.bit(0) { 3_u8 } else { 2_u8 };
+        bytes[1..33].clone_from_slice(&x.to_be_bytes::<32>());
+    }
+
+    bytes
+}
+
+/// Parse a 33 byte compressed point; i.e.; `cpoint`, or the point at infinity for 33 zero bytes
+/// (`cpoint_ext`), if allowed.
+///
+/// Returns nothing if the bytes do not represent a point.
+pub(crate) fn parse_compressed_point(bytes: &[u8], allow_infinity: bool) -> Option<Secp256k1Point> {
+    if bytes.len() != 33 { return None }
+
+    if allow_infinity && bytes.iter().all(|byte| *byte == 0) {
+        return Some(Secp256k1Point::Point(EllipticCurvePoint::PointAtInfinity));
+    }
+
+    let point = Secp256k1Point::lift_x(U256::from_be_slice(&bytes[1..33]))?;
+
+    match bytes[0] {
+        2_u8 => Some(point),
+        3_u8 => Some(-point),
+        _ => None,
+    }
+}
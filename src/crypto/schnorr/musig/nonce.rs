@@ -0,0 +1,201 @@
+//! MuSig2 nonce generation and aggregation (BIP-327).
+//!
+//! Each signer's secret nonce is a pair of scalars `(k_1, k_2)`, and its public nonce is the pair
+//! of points `(k_1 * G, k_2 * G)`. The aggregate nonce is the pair of sums of each signer's points.
+//!
+//! - `rand = bytes(sk) XOR tagged_hash("MuSig/aux", rand')` (or `rand'`, without a secret key)
+//! - `k_i = int(tagged_hash("MuSig/nonce", rand || len(pk) || pk || len(aggpk) || aggpk ||
+//!   message_prefixed || len(extra_in) || extra_in || i - 1)) mod n`
+//!
+//! Unlike BIP-340 nonces, these may not be derived deterministically from the message alone;
+//! `rand'` must be fresh random data for each signing session.
+
+use crate::crypto::schnorr::musig::MuSigError;
+use crate::crypto::schnorr::musig::compressed_point_bytes;
+use crate::crypto::schnorr::musig::parse_compressed_point;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::crypto::digest::tagged::MUSIG_AUX;
+use crate::crypto::digest::tagged::MUSIG_NONCE;
+use crate::util::number::U256;
+
+/// A secret nonce; i.e.; `k_1 || k_2 || pk` (97 bytes).
+///
+/// It is consumed by signing, as it must not be used again.
+pub struct SecretNonce {
+    bytes: [u8; 97],
+}
+
+/// A public nonce; i.e.; `cbytes(R_1) || cbytes(R_2)` (66 bytes).
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct PublicNonce {
+    bytes: [u8; 66],
+}
+
+/// An aggregate nonce; i.e.; `cbytes_ext(R_1) || cbytes_ext(R_2)` (66 bytes), either of which may
+/// be the point at infinity.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct AggregateNonce {
+    bytes: [u8; 66],
+}
+
+/// Generate a secret nonce and its public nonce, given fresh random data (`rand`), and the signer's
+/// (33 byte, compressed) public key.
+///
+/// Optionally, the signer's (private key) `secret_e`, the aggregate (x-only) public key, the
+/// message, and any extra input may be mixed in; this protects against a faulty source of
+/// randomness.
+pub fn nonce_gen(
+    rand: &[u8; 32],
+    secret_e: Option<U256>,
+    public_key: &[u8; 33],
+    aggregate_public_key: Option<&[u8; 32]>,
+    message: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> Result<(SecretNonce, PublicNonce), MuSigError> {
+    let rand: [u8; 32] = match secret_e {
+        Some(secret_e) => {
+            if secret_e == U256::ZERO || secret_e >= ELLIPTIC_CURVE_ORDER { return Err(MuSigError::InvalidSecret) }
+
+            let aux_hash = MUSIG_AUX.hash(rand);
+
+            std::array::from_fn(|i| secret_e.to_be_bytes::<32>()[i] ^ aux_hash[i])
+        },
+        None => *rand,
+    };
+
+    let aggregate_public_key: &[u8] = aggregate_public_key.map_or(&[], |key| &key[..]);
+    let extra_in = extra_in.unwrap_or(&[]);
+
+    let mut preimage: Vec<u8> = vec![];
+
+    preimage.extend_from_slice(&rand);
+    preimage.push(public_key.len() as u8);
+    preimage.extend_from_slice(public_key);
+    preimage.push(aggregate_public_key.len() as u8);
+    preimage.extend_from_slice(aggregate_public_key);
+
+    match message {
+        Some(message) => {
+            preimage.push(1_u8);
+            preimage.extend_from_slice(&(message.len() as u64).to_be_bytes());
+            preimage.extend_from_slice(message);
+        },
+        None => preimage.push(0_u8),
+    }
+
+    preimage.extend_from_slice(&(extra_in.len() as u32).to_be_bytes());
+    preimage.extend_from_slice(extra_in);
+
+    let mut secret_nonce = [0_u8; 97];
+    let mut public_nonce = [0_u8; 66];
+
+    for i in 0..2 {
+        let k = U256::from_be_bytes(MUSIG_NONCE.hash([&preimage[..], &[i as u8]].concat())) % ELLIPTIC_CURVE_ORDER;
+
+        if k == U256::ZERO { return Err(MuSigError::InvalidNonce) }
+
+        secret_nonce[(32 * i)..(32 * (i + 1))].clone_from_slice(&k.to_be_bytes::<32>());
+        public_nonce[(33 * i)..(33 * (i + 1))].clone_from_slice(&compressed_point_bytes(k * Secp256k1Point::generator_point()));
+    }
+
+    secret_nonce[64..97].clone_from_slice(public_key);
+
+    Ok((SecretNonce { bytes: secret_nonce }, PublicNonce { bytes: public_nonce }))
+}
+
+impl SecretNonce {
+    /// Parse a 97 byte secret nonce (e.g.; one previously generated, and stored).
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self { bytes: bytes.try_into().ok()? })
+    }
+
+    /// Returns the 97 byte representation of the secret nonce (e.g.; to be stored until signing).
+    pub fn bytes(&self) -> [u8; 97] {
+        self.bytes
+    }
+
+    /// Returns the scalars `(k_1, k_2)`.
+    pub(crate) fn scalars(&self) -> (U256, U256) {
+        (U256::from_be_slice(&self.bytes[0..32]), U256::from_be_slice(&self.bytes[32..64]))
+    }
+
+    /// Returns the (33 byte, compressed) public key, for which the nonce was generated.
+    pub fn public_key(&self) -> [u8; 33] {
+        self.bytes[64..97].try_into().unwrap()
+    }
+}
+
+impl PublicNonce {
+    /// Parse a 66 byte public nonce.
+    ///
+    /// Returns nothing if either point is invalid.
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; 66] = bytes.try_into().ok()?;
+
+        parse_compressed_point(&bytes[0..33], false)?;
+        parse_compressed_point(&bytes[33..66], false)?;
+
+        Some(Self { bytes: bytes })
+    }
+
+    /// Returns the 66 byte representation of the public nonce.
+    pub fn bytes(&self) -> [u8; 66] {
+        self.bytes
+    }
+
+    /// Returns the points `(R_1, R_2)`.
+    pub(crate) fn points(&self) -> (Secp256k1Point, Secp256k1Point) {
+        (
+            parse_compressed_point(&self.bytes[0..33], false).unwrap(),
+            parse_compressed_point(&self.bytes[33..66], false).unwrap(),
+        )
+    }
+}
+
+impl AggregateNonce {
+    /// Aggregate the public nonces of every signer.
+    pub fn new(public_nonces: &[PublicNonce]) -> Self {
+        let infinity = U256::ZERO * Secp256k1Point::generator_point();
+
+        let (r_1, r_2) = public_nonces.iter()
+            .map(|public_nonce| public_nonce.points())
+            .fold((infinity, infinity), |(r_1, r_2), (point_1, point_2)| (r_1 + point_1, r_2 + point_2));
+
+        let mut bytes = [0_u8; 66];
+
+        bytes[0..33].clone_from_slice(&compressed_point_bytes(r_1));
+        bytes[33..66].clone_from_slice(&compressed_point_bytes(r_2));
+
+        Self { bytes: bytes }
+    }
+
+    /// Parse a 66 byte aggregate nonce.
+    ///
+    /// Returns nothing if either point is invalid.
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; 66] = bytes.try_into().ok()?;
+
+        parse_compressed_point(&bytes[0..33], true)?;
+        parse_compressed_point(&bytes[33..66], true)?;
+
+        Some(Self { bytes: bytes })
+    }
+
+    /// Returns the 66 byte representation of the aggregate nonce.
+    pub fn bytes(&self) -> [u8; 66] {
+        self.bytes
+    }
+
+    /// Returns the points `(R_1, R_2)`.
+    pub(crate) fn points(&self) -> (Secp256k1Point, Secp256k1Point) {
+        (
+            parse_compressed_point(&self.bytes[0..33], true).unwrap(),
+            parse_compressed_point(&self.bytes[33..66], true).unwrap(),
+        )
+    }
+}
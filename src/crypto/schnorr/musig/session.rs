@@ -0,0 +1,189 @@
+//! MuSig2 signing sessions (BIP-327).
+//!
+//! - `b = int(tagged_hash("MuSig/noncecoef", aggnonce || xbytes(Q) || message)) mod n`
+//! - `R = R_1 + b * R_2` (or `G`, if that is the point at infinity)
+//! - `e = int(tagged_hash("BIP0340/challenge", xbytes(R) || xbytes(Q) || message)) mod n`
+//!
+//! Each signer's partial signature is `s_i = k_1 + b * k_2 + e * a_i * d_i`, with the nonce and
+//! secret key negated as necessary (for the even `y` coordinates of `R` and `Q`, and for tweaks).
+//! The final signature is `(R, s_1 + ... + s_u + e * g * t_acc)`.
+
+use crate::crypto::schnorr::musig::MuSigError;
+use crate::crypto::schnorr::musig::key_agg::KeyAggContext;
+use crate::crypto::schnorr::musig::nonce::SecretNonce;
+use crate::crypto::schnorr::musig::nonce::PublicNonce;
+use crate::crypto::schnorr::musig::nonce::AggregateNonce;
+use crate::crypto::schnorr::musig::compressed_point_bytes;
+use crate::crypto::schnorr::musig::parse_compressed_point;
+use crate::crypto::schnorr::signature::SchnorrSignature;
+use crate::crypto::schnorr::signature::challenge;
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::crypto::digest::tagged::MUSIG_NONCE_COEFFICIENT;
+use crate::util::number::U256;
+
+/// A partial signature; i.e.; a signer's s-value.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct PartialSignature {
+    pub s: U256,
+}
+
+impl PartialSignature {
+    /// Parse a 32 byte partial signature.
+    ///
+    /// Returns nothing if the s-value is not less than the elliptic curve order.
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        let s = U256::from_be_bytes(bytes);
+
+        if s >= ELLIPTIC_CURVE_ORDER { return None }
+
+        Some(Self { s: s })
+    }
+
+    /// Returns the 32 byte representation of the partial signature.
+    pub fn bytes(&self) -> [u8; 32] {
+        self.s.to_be_bytes::<32>()
+    }
+}
+
+/// A signing session; i.e.; the (tweaked) key aggregation context, the aggregate nonce, and the
+/// message, with the values derived from them.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct SessionContext {
+    key_agg: KeyAggContext,
+    message: Vec<u8>,
+    b: U256,
+    r_point: Secp256k1Point,
+    e: U256,
+}
+
+impl SessionContext {
+    /// Create a signing session, given the key aggregation context (with any tweaks applied), the
+    /// aggregate nonce, and the message.
+    pub fn new(key_agg: KeyAggContext, aggregate_nonce: &AggregateNonce, message: &[u8]) -> Self {
+        let aggregate_public_key = key_agg.x_only_public_key().bytes();
+
+        let preimage = [&aggregate_nonce.bytes()[..], &aggregate_public_key[..], message].concat();
+        let b = U256::from_be_bytes(MUSIG_NONCE_COEFFICIENT.hash(preimage)) % ELLIPTIC_CURVE_ORDER;
+
+        let (r_1, r_2) = aggregate_nonce.points();
+        let r_point = r_1 + (b * r_2);
+        let r_point = if r_point.is_infinity() { Secp256k1Point::generator_point() } else { r_point };
+
+        let (r, _) = r_point.coordinates().unwrap();
+
+        Self {
+            e: challenge(r, &aggregate_public_key, message),
+            key_agg: key_agg,
+            message: message.to_vec(),
+            b: b,
+            r_point: r_point,
+        }
+    }
+
+    /// Returns the key aggregation context.
+    pub fn key_agg(&self) -> &KeyAggContext {
+        &self.key_agg
+    }
+
+    /// Returns the message.
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// Produce a partial signature, given the signer's secret nonce and (private key) `secret_e`.
+    ///
+    /// The secret nonce is consumed; the partial signature is verified before being returned.
+    pub fn sign(&self, secret_nonce: SecretNonce, secret_e: U256) -> Result<PartialSignature, MuSigError> {
+        let (k_1, k_2) = secret_nonce.scalars();
+
+        for k in [k_1, k_2] {
+            if k == U256::ZERO || k >= ELLIPTIC_CURVE_ORDER { return Err(MuSigError::InvalidSecretNonce) }
+        }
+
+        if secret_e == U256::ZERO || secret_e >= ELLIPTIC_CURVE_ORDER { return Err(MuSigError::InvalidSecret) }
+
+        let public_key = compressed_point_bytes(secret_e * Secp256k1Point::generator_point());
+
+        if secret_nonce.public_key() != public_key { return Err(MuSigError::SecretNonceMismatch) }
+
+        let a = self.key_agg.coefficient(&public_key).ok_or(MuSigError::PublicKeyNotFound)?;
+
+        let public_nonce = PublicNonce::parse_bytes(
+            &[
+                compressed_point_bytes(k_1 * Secp256k1Point::generator_point()),
+                compressed_point_bytes(k_2 * Secp256k1Point::generator_point()),
+            ].concat()
+        ).unwrap();
+
+        // The nonce point must have an even `y` coordinate.
+        let (k_1, k_2) = if self.has_odd_r_y() {
+            (ELLIPTIC_CURVE_ORDER - k_1, ELLIPTIC_CURVE_ORDER - k_2)
+        } else {
+            (k_1, k_2)
+        };
+
+        let d = self.key_sign().mul_mod(self.key_agg.g_acc(), ELLIPTIC_CURVE_ORDER).mul_mod(secret_e, ELLIPTIC_CURVE_ORDER);
+
+        let s = k_1
+            .add_mod(self.b.mul_mod(k_2, ELLIPTIC_CURVE_ORDER), ELLIPTIC_CURVE_ORDER)
+            .add_mod(self.e.mul_mod(a, ELLIPTIC_CURVE_ORDER).mul_mod(d, ELLIPTIC_CURVE_ORDER), ELLIPTIC_CURVE_ORDER);
+
+        let partial_signature = PartialSignature { s: s };
+
+        if !self.verify_partial_signature(&partial_signature, &public_nonce, &public_key) {
+            return Err(MuSigError::VerificationFailed);
+        }
+
+        Ok(partial_signature)
+    }
+
+    /// Verify a signer's partial signature, given their public nonce and (33 byte, compressed)
+    /// public key; i.e.; `s_i * G = R_1,i + b * R_2,i + e * a_i * g * g_acc * P_i` (with the nonce
+    /// points negated if `R` has an odd `y` coordinate).
+    pub fn verify_partial_signature(&self, partial_signature: &PartialSignature, public_nonce: &PublicNonce, public_key: &[u8; 33]) -> bool {
+        if partial_signature.s >= ELLIPTIC_CURVE_ORDER { return false }
+
+        let Some(point) = parse_compressed_point(public_key, false) else { return false };
+        let Some(a) = self.key_agg.coefficient(public_key) else { return false };
+
+        let (r_1, r_2) = public_nonce.points();
+        let r_point = r_1 + (self.b * r_2);
+        let r_point = if self.has_odd_r_y() { -r_point } else { r_point };
+
+        let g = self.key_sign().mul_mod(self.key_agg.g_acc(), ELLIPTIC_CURVE_ORDER);
+        let factor = self.e.mul_mod(a, ELLIPTIC_CURVE_ORDER).mul_mod(g, ELLIPTIC_CURVE_ORDER);
+
+        partial_signature.s * Secp256k1Point::generator_point() == r_point + (factor * point)
+    }
+
+    /// Aggregate the partial signatures of every signer into the final (BIP-340) signature, valid
+    /// under the aggregate (x-only) public key.
+    pub fn aggregate(&self, partial_signatures: &[PartialSignature]) -> SchnorrSignature {
+        let (r, _) = self.r_point.coordinates().unwrap();
+
+        let s = partial_signatures.iter()
+            .fold(U256::ZERO, |s, partial_signature| s.add_mod(partial_signature.s, ELLIPTIC_CURVE_ORDER));
+
+        let tweak = self.e
+            .mul_mod(self.key_sign(), ELLIPTIC_CURVE_ORDER)
+            .mul_mod(self.key_agg.t_acc(), ELLIPTIC_CURVE_ORDER);
+
+        SchnorrSignature { r: r, s: s.add_mod(tweak, ELLIPTIC_CURVE_ORDER) }
+    }
+
+    /// Returns `g`; i.e.; `-1` if the aggregate public key has an odd `y` coordinate, or `1`.
+    fn key_sign(&self) -> U256 {
+        if self.key_agg.has_odd_y() { ELLIPTIC_CURVE_ORDER - U256::from(1) } else { U256::from(1) }
+    }
+
+    fn has_odd_r_y(&self) -> bool {
+        let (_, y) = self.r_point.coordinates().unwrap();
+
+        y.bit(0)
+    }
+}
@@ -0,0 +1,30 @@
+//! Deterministic nonce generation, with auxiliary randomness (BIP-340).
+//!
+//! The nonce is derived from the secret key, the public key, and the message, so that the same
+//! nonce is never used for two different messages. Auxiliary random data is mixed into the secret
+//! key beforehand, which protects against side-channel attacks; all zeros are acceptable, and the
+//! nonce is then fully deterministic.
+//!
+//! - `t = bytes(d) XOR tagged_hash("BIP0340/aux", aux_rand)`
+//! - `k = int(tagged_hash("BIP0340/nonce", t || bytes(P) || message)) mod n`
+
+use crate::crypto::digest::tagged::BIP0340_AUX;
+use crate::crypto::digest::tagged::BIP0340_NONCE;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::util::number::U256;
+
+/// Derive the nonce `k`, given a (normalized, even `y` coordinate) `secret_e`, its x-only public
+/// key, some `message`, and auxiliary random data.
+///
+/// The nonce may be zero (with negligible probability), in which case signing must fail.
+pub fn deterministic_nonce(secret_e: U256, public_key: &[u8; 32], message: &[u8], aux_rand: &[u8; 32]) -> U256 {
+    let aux_hash = BIP0340_AUX.hash(aux_rand);
+
+    let mut preimage: Vec<u8> = Vec::with_capacity(64 + message.len());
+
+    preimage.extend(secret_e.to_be_bytes::<32>().iter().zip(aux_hash.iter()).map(|(a, b)| a ^ b));
+    preimage.extend_from_slice(public_key);
+    preimage.extend_from_slice(message);
+
+    U256::from_be_bytes(BIP0340_NONCE.hash(preimage)) % ELLIPTIC_CURVE_ORDER
+}
@@ -0,0 +1,124 @@
+//! BIP-340 Schnorr signatures.
+
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::FINITE_FIELD_ORDER;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::crypto::schnorr::x_only::XOnlyPublicKey;
+use crate::crypto::schnorr::x_only::even_y_secret_e;
+use crate::crypto::schnorr::nonce::deterministic_nonce;
+use crate::crypto::digest::tagged::BIP0340_CHALLENGE;
+use crate::util::number::U256;
+
+/// Errors thrown when producing a Schnorr signature.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum SchnorrSigningError {
+    /// The (private key) `secret_e` is zero, or not less than the elliptic curve order.
+    InvalidSecret,
+
+    /// The derived nonce is zero (with negligible probability); i.e.; different auxiliary random
+    /// data should be used.
+    InvalidNonce,
+
+    /// The produced signature does not verify (e.g.; due to a fault during computation).
+    VerificationFailed,
+}
+
+/// A Schnorr signature (BIP-340).
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct SchnorrSignature {
+    /// The `x` coordinate of the nonce point `R`.
+    pub r: U256,
+
+    /// s-value.
+    pub s: U256,
+}
+
+impl SchnorrSignature {
+    /// Produce a signature for some `message`, signed using a (private key) `secret_e`.
+    ///
+    /// The nonce is chosen in a deterministic manner (see `nonce::deterministic_nonce`), with
+    /// auxiliary random data (`aux_rand`); all zeros may be used, at the cost of side-channel
+    /// protection.
+    ///
+    /// The corresponding (x-only) public key is `XOnlyPublicKey::from_secret_e(secret_e)`; the
+    /// signature is verified against it before being returned.
+    pub fn sign_secp256k1(message: &[u8], secret_e: U256, aux_rand: &[u8; 32]) -> Result<Self, SchnorrSigningError> {
+        let secret_e = even_y_secret_e(secret_e).ok_or(SchnorrSigningError::InvalidSecret)?;
+        let public_key = XOnlyPublicKey::from_secret_e(secret_e).ok_or(SchnorrSigningError::InvalidSecret)?.bytes();
+
+        let k = deterministic_nonce(secret_e, &public_key, message, aux_rand);
+
+        if k == U256::ZERO { return Err(SchnorrSigningError::InvalidNonce) }
+
+        let (r, r_y) = (k * Secp256k1Point::generator_point()).coordinates().ok_or(SchnorrSigningError::InvalidNonce)?;
+
+        // The nonce point must have an even `y` coordinate.
+        let k = if r_y.bit(0) { ELLIPTIC_CURVE_ORDER - k } else { k };
+
+        let e = challenge(r, &public_key, message);
+        let s = k.add_mod(e.mul_mod(secret_e, ELLIPTIC_CURVE_ORDER), ELLIPTIC_CURVE_ORDER);
+
+        let signature = Self { r: r, s: s };
+
+        if !signature.verify_secp256k1(message, &public_key) { return Err(SchnorrSigningError::VerificationFailed) }
+
+        Ok(signature)
+    }
+
+    /// Parse a 64 byte signature; i.e.; `r || s`, each 32 bytes (big-endian).
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 64 { return None }
+
+        Some(
+            Self {
+                r: U256::from_be_slice(&bytes[0..32]),
+                s: U256::from_be_slice(&bytes[32..64]),
+            }
+        )
+    }
+
+    /// Returns the 64 byte representation of the signature.
+    pub fn bytes(&self) -> [u8; 64] {
+        let mut bytes = [0_u8; 64];
+
+        bytes[0..32].clone_from_slice(&self.r.to_be_bytes::<32>());
+        bytes[32..64].clone_from_slice(&self.s.to_be_bytes::<32>());
+
+        bytes
+    }
+
+    /// Verify the signature against some `message`, given an x-only public key (the `x`
+    /// coordinate of a point with an even `y` coordinate, 32 bytes).
+    ///
+    /// `e = int(tagged_hash("BIP0340/challenge", r || P || message)) mod n`; the signature is valid
+    /// if `R = s * G - e * P` is not the point at infinity, has an even `y` coordinate, and has the
+    /// `x` coordinate `r`.
+    pub fn verify_secp256k1(&self, message: &[u8], public_key: &[u8; 32]) -> bool {
+        let Some(point) = Secp256k1Point::lift_x(U256::from_be_bytes(*public_key)) else { return false };
+
+        if self.r >= FINITE_FIELD_ORDER || self.s >= ELLIPTIC_CURVE_ORDER { return false }
+
+        let e = challenge(self.r, public_key, message);
+
+        let r_point = (self.s * Secp256k1Point::generator_point()) + (-(e * point));
+
+        match r_point.coordinates() {
+            Some((x, y)) => !y.bit(0) && x == self.r,
+            None => false,
+        }
+    }
+}
+
+/// Computes the challenge `e`, reduced modulo the elliptic curve order.
+pub(crate) fn challenge(r: U256, public_key: &[u8; 32], message: &[u8]) -> U256 {
+    let mut preimage: Vec<u8> = Vec::with_capacity(64 + message.len());
+
+    preimage.extend_from_slice(&r.to_be_bytes::<32>());
+    preimage.extend_from_slice(public_key);
+    preimage.extend_from_slice(message);
+
+    U256::from_be_bytes(BIP0340_CHALLENGE.hash(preimage)) % ELLIPTIC_CURVE_ORDER
+}
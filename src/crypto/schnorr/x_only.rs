@@ -0,0 +1,84 @@
+//! x-only public keys (BIP-340).
+//!
+//! A public key is represented by only the `x` coordinate of its point (32 bytes); the point with
+//! an even `y` coordinate is implied. A secret key whose point has an odd `y` coordinate is
+//! negated before use, so that it corresponds to the implied point.
+
+use crate::crypto::secp256k1::Secp256k1Point;
+use crate::crypto::secp256k1::ELLIPTIC_CURVE_ORDER;
+use crate::util::number::U256;
+
+/// An x-only public key.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct XOnlyPublicKey {
+    bytes: [u8; 32],
+}
+
+impl XOnlyPublicKey {
+    /// Parse a 32 byte x-only public key.
+    ///
+    /// Returns nothing if the key is not the `x` coordinate of a secp256k1 point.
+    pub fn parse_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+
+        Secp256k1Point::lift_x(U256::from_be_bytes(bytes))?;
+
+        Some(Self { bytes: bytes })
+    }
+
+    /// Returns the x-only public key of a point, and whether its `y` coordinate is odd.
+    ///
+    /// Returns nothing for the point at infinity.
+    pub fn from_point(point: Secp256k1Point) -> Option<(Self, bool)> {
+        let (x, y) = point.coordinates()?;
+
+        Some((Self { bytes: x.to_be_bytes::<32>() }, y.bit(0)))
+    }
+
+    /// Returns the x-only public key for a (private key) `secret_e`.
+    ///
+    /// Returns nothing if `secret_e` is zero, or not less than the elliptic curve order.
+    pub fn from_secret_e(secret_e: U256) -> Option<Self> {
+        if secret_e == U256::ZERO || secret_e >= ELLIPTIC_CURVE_ORDER { return None }
+
+        let (public_key, _) = Self::from_point(secret_e * Secp256k1Point::generator_point())?;
+
+        Some(public_key)
+    }
+
+    /// Returns the 32 byte representation of the x-only public key.
+    pub fn bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    /// Returns the implied point; i.e.; the point with this `x` coordinate, and an even `y`
+    /// coordinate.
+    pub fn point(&self) -> Secp256k1Point {
+        Secp256k1Point::lift_x(U256::from_be_bytes(self.bytes)).unwrap()
+    }
+}
+
+impl std::fmt::Display for XOnlyPublicKey {
+    /// Displays the x-only public key (hexadecimal).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Normalizes a (private key) `secret_e`, such that its point has an even `y` coordinate; i.e.;
+/// returns `n - secret_e` if its point's `y` coordinate is odd.
+///
+/// Returns nothing if `secret_e` is zero, or not less than the elliptic curve order.
+pub fn even_y_secret_e(secret_e: U256) -> Option<U256> {
+    if secret_e == U256::ZERO || secret_e >= ELLIPTIC_CURVE_ORDER { return None }
+
+    let (_, odd) = XOnlyPublicKey::from_point(secret_e * Secp256k1Point::generator_point())?;
+
+    if odd { Some(ELLIPTIC_CURVE_ORDER - secret_e) } else { Some(secret_e) }
+}
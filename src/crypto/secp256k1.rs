@@ -5,6 +5,7 @@ use std::ops;
 use crate::math::elliptic_curve::point::EllipticCurvePoint;
 use crate::math::elliptic_curve::rhs::DerivationError;
 use crate::math::finite_field_element::FiniteFieldElement;
+use crate::math::number::Number;
 use crate::crypto::ecdsa::signature::Signature;
 use crate::math::algorithm::tonelli_shanks;
 use crate::util::number::U256;
@@ -71,6 +72,51 @@ impl Secp256k1Point {
     pub fn generator_point() -> Self {
         Self::new(GENERATOR_POINT_X, GENERATOR_POINT_Y)
     }
+
+    /// Returns the secp256k1 point with some `x` coordinate, and an even `y` coordinate (BIP-340).
+    ///
+    /// Returns nothing if `x` is not within the finite field, or no such point exists.
+    pub fn lift_x(x: U256) -> Option<Self> {
+        if x >= FINITE_FIELD_ORDER { return None }
+
+        let y_squared = x.mul_mod(x, FINITE_FIELD_ORDER).mul_mod(x, FINITE_FIELD_ORDER).add_mod(ELLIPTIC_CURVE_B, FINITE_FIELD_ORDER);
+        let (y, _) = tonelli_shanks::square_roots(y_squared, FINITE_FIELD_ORDER).unwrap();
+
+        if y.mul_mod(y, FINITE_FIELD_ORDER) != y_squared { return None }
+
+        if y.bit(0) {
+            Some(Self::new(x, FINITE_FIELD_ORDER - y))
+        } else {
+            Some(Self::new(x, y))
+        }
+    }
+
+    /// Returns the point's `x` and `y` coordinates, unless it is the point at infinity.
+    pub fn coordinates(&self) -> Option<(U256, U256)> {
+        let Self::Point(EllipticCurvePoint::PointOnCurve(point)) = self else { return None };
+
+        match (point.x, point.y) {
+            (Number::FiniteFieldElement(x), Number::FiniteFieldElement(y)) => Some((x.value, y.value)),
+            _ => None,
+        }
+    }
+
+    /// Indicates that this is the point at infinity.
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Self::Point(EllipticCurvePoint::PointAtInfinity))
+    }
+}
+
+/// Point negation.
+impl ops::Neg for Secp256k1Point {
+    type Output = Self;
+
+    /// Reflects the secp256k1 point across the `x` axis.
+    fn neg(self) -> Self::Output {
+        let Self::Point(point) = self;
+
+        Self::Point(-point)
+    }
 }
 
 /// Point addition.
@@ -135,6 +181,14 @@ impl Signature {
         Self::new(data, secret_e, random_commitment, Secp256k1Point::generator_point().into(), ELLIPTIC_CURVE_ORDER)
     }
 
+    /// Indicates that the s-value is in the lower half of the elliptic curve order (BIP-146).
+    ///
+    /// For any valid signature `(r, s)`, the signature `(r, n - s)` is also valid; only low
+    /// s-value signatures are standard.
+    pub fn is_low_s_secp256k1(&self) -> bool {
+        self.s <= (ELLIPTIC_CURVE_ORDER / U256::from(2))
+    }
+
     /// Verify the signature against some `data` value, given a secp256k1 `point`.
     pub fn verify_point_secp256k1(&self, data: U256, point: Secp256k1Point) -> bool {
         self.verify_point(data, point.into(), Secp256k1Point::generator_point().into(), ELLIPTIC_CURVE_ORDER)
@@ -0,0 +1,314 @@
+//! Bech32 and Bech32m encoding schemes (BIP-173, BIP-350).
+//!
+//! A human-readable part (e.g.; `bc`), the separator `1`, and a data part of 5 bit groups, each
+//! encoded as one of 32 characters; the last six characters are a BCH checksum.
+//!
+//! Segregated witness addresses encode the witness version as the first group, followed by the
+//! witness program. Version 0 witness programs use Bech32; other versions use Bech32m, which
+//! differs only in the constant the checksum is compared against.
+//!
+//! The checksum guarantees detection of up to four substituted characters; when decoding fails, a
+//! single substituted character is also located.
+
+/// Human-readable part of mainnet addresses.
+pub const MAINNET_HRP: &str = "bc";
+
+/// Human-readable part of testnet (testnet3, testnet4, and signet) addresses.
+pub const TESTNET_HRP: &str = "tb";
+
+/// Human-readable part of testnet4 addresses (shared with testnet).
+pub const TESTNET4_HRP: &str = "tb";
+
+/// Human-readable part of signet addresses (shared with testnet).
+pub const SIGNET_HRP: &str = "tb";
+
+/// Human-readable part of regtest addresses.
+pub const REGTEST_HRP: &str = "bcrt";
+
+/// The maximum length of an encoded string.
+pub const BECH32_MAX_LENGTH: usize = 90;
+
+pub const BECH32_CHARACTERS: [char; 32] = [
+    'q', 'p', 'z', 'r', 'y', '9', 'x', '8',
+    'g', 'f', '2', 't', 'v', 'd', 'w', '0',
+    's', '3', 'j', 'n', '5', '4', 'k', 'h',
+    'c', 'e', '6', 'm', 'u', 'a', '7', 'l',
+];
+
+/// Errors thrown when decoding a Bech32 (or Bech32m) string, or a segregated witness address.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum Bech32Error {
+    /// The string is longer than 90 characters, or its data part is shorter than the checksum.
+    InvalidLength(usize),
+
+    /// The string contains both upper and lower case characters.
+    MixedCase,
+
+    /// The separator (`1`) is missing, or the human-readable part is empty.
+    MissingSeparator,
+
+    /// A character (by its position) is not within the US-ASCII range `33..=126` (human-readable
+    /// part), or not within the Bech32 character set (data part).
+    InvalidCharacter(usize),
+
+    /// The checksum is invalid for either variant; with the positions of characters that, if
+    /// substituted, would make it valid (if any).
+    InvalidChecksum(Vec<usize>),
+
+    /// The witness version is greater than 16 (or missing).
+    InvalidWitnessVersion(u8),
+
+    /// The witness program is not 2 to 40 bytes long (or, for version 0, not 20 or 32 bytes long).
+    InvalidProgramLength(usize),
+
+    /// The checksum variant does not match the witness version; i.e.; Bech32 for version 0, and
+    /// Bech32m otherwise.
+    InvalidVariant(Bech32Variant),
+
+    /// The witness program has more than 4 bits of padding, or non-zero padding.
+    InvalidPadding,
+}
+
+/// The checksum variant.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub enum Bech32Variant {
+    /// BIP-173; used for version 0 witness programs.
+    Bech32,
+
+    /// BIP-350; used for version 1 (and later) witness programs.
+    Bech32m,
+}
+
+impl Bech32Variant {
+    /// The constant that the checksum polynomial must evaluate to.
+    pub const fn constant(&self) -> u32 {
+        match self {
+            Self::Bech32 => 0x00000001_u32,
+            Self::Bech32m => 0x2bc830a3_u32,
+        }
+    }
+
+    /// Returns the variant whose constant some checksum polynomial evaluates to, if any.
+    fn from_constant(constant: u32) -> Option<Self> {
+        [Self::Bech32, Self::Bech32m].into_iter().find(|variant| variant.constant() == constant)
+    }
+
+    /// Returns the variant used to encode some witness version.
+    pub fn for_witness_version(version: u8) -> Self {
+        if version == 0 { Self::Bech32 } else { Self::Bech32m }
+    }
+}
+
+/// Evaluates the BCH checksum polynomial over some 5 bit groups.
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2_u32, 0x26508e6d_u32, 0x1ea119fa_u32, 0x3d4233dd_u32, 0x2a1462b3_u32];
+
+    let mut checksum: u32 = 1;
+
+    for value in values {
+        let top = checksum >> 25;
+
+        checksum = ((checksum & 0x1ffffff_u32) << 5) ^ u32::from(value);
+
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 { checksum ^= generator }
+        }
+    }
+
+    checksum
+}
+
+/// Expands the human-readable part for checksum computation; i.e.; the high bits of each
+/// character, a zero, then the low bits of each character.
+fn expand_hrp(hrp: &str) -> Vec<u8> {
+    let mut values: Vec<u8> = Vec::with_capacity(hrp.len() * 2 + 1);
+
+    values.extend(hrp.bytes().map(|byte| byte >> 5));
+    values.push(0_u8);
+    values.extend(hrp.bytes().map(|byte| byte & 0x1f_u8));
+
+    values
+}
+
+/// Computes the six 5 bit checksum groups for some human-readable part and data.
+fn create_checksum(hrp: &str, data: &[u8], variant: Bech32Variant) -> [u8; 6] {
+    let values = expand_hrp(hrp).into_iter().chain(data.iter().copied()).chain([0_u8; 6]);
+    let checksum = polymod(values) ^ variant.constant();
+
+    let mut groups = [0_u8; 6];
+
+    for (i, group) in groups.iter_mut().enumerate() {
+        *group = u8::try_from((checksum >> (5 * (5 - i))) & 0x1f_u32).unwrap();
+    }
+
+    groups
+}
+
+/// Regroups bits; e.g.; from bytes (8 bit groups) to 5 bit groups.
+///
+/// With `pad`, any remaining bits are padded with zeros to form a final group. Without `pad`
+/// (i.e.; when decoding), the remaining bits must be fewer than `from` and all zero.
+///
+/// Returns nothing if a value exceeds `from` bits, or the remaining bits are invalid.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut accumulator: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut groups: Vec<u8> = vec![];
+
+    let max_value: u32 = (1 << to) - 1;
+
+    for value in data {
+        let value = u32::from(*value);
+
+        if (value >> from) != 0 { return None }
+
+        accumulator = (accumulator << from) | value;
+        bits += from;
+
+        while bits >= to {
+            bits -= to;
+            groups.push(u8::try_from((accumulator >> bits) & max_value).unwrap());
+        }
+    }
+
+    if pad {
+        if bits > 0 { groups.push(u8::try_from((accumulator << (to - bits)) & max_value).unwrap()) }
+    } else if bits >= from || ((accumulator << (to - bits)) & max_value) != 0 {
+        return None;
+    }
+
+    Some(groups)
+}
+
+/// Encodes a human-readable part and some 5 bit groups (appending the checksum).
+///
+/// Panics if a group exceeds 5 bits.
+pub fn bech32_encode(hrp: &str, data: &[u8], variant: Bech32Variant) -> String {
+    let checksum = create_checksum(hrp, data, variant);
+
+    let mut string = String::with_capacity(hrp.len() + 1 + data.len() + 6);
+
+    string.push_str(hrp);
+    string.push('1');
+
+    for group in data.iter().chain(checksum.iter()) {
+        string.push(BECH32_CHARACTERS[usize::from(*group)]);
+    }
+
+    string
+}
+
+/// Encodes a segregated witness address, given its witness version and witness program.
+pub fn segwit_address_encode(hrp: &str, version: u8, program: &[u8]) -> String {
+    let mut data: Vec<u8> = vec![version];
+
+    data.extend(convert_bits(program, 8, 5, true).unwrap());
+
+    bech32_encode(hrp, &data, Bech32Variant::for_witness_version(version))
+}
+
+/// Decodes a string into its (lower case) human-readable part and 5 bit groups (without the
+/// checksum), and the checksum variant.
+pub fn bech32_decode(string: &str) -> Result<(String, Vec<u8>, Bech32Variant), Bech32Error> {
+    if string.len() > BECH32_MAX_LENGTH { return Err(Bech32Error::InvalidLength(string.len())) }
+
+    if let Some(position) = string.bytes().position(|byte| !(33..=126).contains(&byte)) {
+        return Err(Bech32Error::InvalidCharacter(position));
+    }
+
+    if string.bytes().any(|byte| byte.is_ascii_lowercase()) && string.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        return Err(Bech32Error::MixedCase);
+    }
+
+    let string = string.to_ascii_lowercase();
+
+    let separator = match string.rfind('1') {
+        Some(0) | None => return Err(Bech32Error::MissingSeparator),
+        Some(separator) => separator,
+    };
+
+    let (hrp, data_part) = (&string[..separator], &string[(separator + 1)..]);
+
+    if data_part.len() < 6 { return Err(Bech32Error::InvalidLength(string.len())) }
+
+    let mut data: Vec<u8> = Vec::with_capacity(data_part.len());
+
+    for (i, character) in data_part.chars().enumerate() {
+        let value = BECH32_CHARACTERS.iter().position(|c| *c == character)
+            .ok_or(Bech32Error::InvalidCharacter(separator + 1 + i))?;
+
+        data.push(u8::try_from(value).unwrap());
+    }
+
+    let constant = polymod(expand_hrp(hrp).into_iter().chain(data.iter().copied()));
+
+    let Some(variant) = Bech32Variant::from_constant(constant) else {
+        let positions = locate_substitution(hrp, &data).into_iter().map(|i| separator + 1 + i).collect();
+
+        return Err(Bech32Error::InvalidChecksum(positions));
+    };
+
+    data.truncate(data.len() - 6);
+
+    Ok((String::from(hrp), data, variant))
+}
+
+/// Locates a single substituted character within the data part (including the checksum); i.e.;
+/// returns the positions at which some other character makes the checksum valid (for either
+/// variant).
+///
+/// As the checksum has a minimum distance of five (for strings within the length limit), a single
+/// substitution has exactly one such position; more substitutions are generally not located.
+fn locate_substitution(hrp: &str, data: &[u8]) -> Vec<usize> {
+    let mut positions: Vec<usize> = vec![];
+    let mut candidate = data.to_vec();
+
+    for i in 0..data.len() {
+        for value in (0..32_u8).filter(|value| *value != data[i]) {
+            candidate[i] = value;
+
+            if Bech32Variant::from_constant(polymod(expand_hrp(hrp).into_iter().chain(candidate.iter().copied()))).is_some() {
+                positions.push(i);
+                break;
+            }
+        }
+
+        candidate[i] = data[i];
+    }
+
+    positions
+}
+
+/// Decodes a segregated witness address into its (lower case) human-readable part, witness version,
+/// and witness program.
+pub fn segwit_address_decode(address: &str) -> Result<(String, u8, Vec<u8>), Bech32Error> {
+    let (hrp, data, variant) = bech32_decode(address)?;
+
+    let (version, data) = data.split_first().ok_or(Bech32Error::InvalidWitnessVersion(0))?;
+    let version = *version;
+
+    if version > 16 { return Err(Bech32Error::InvalidWitnessVersion(version)) }
+
+    let program = convert_bits(data, 5, 8, false).ok_or(Bech32Error::InvalidPadding)?;
+
+    validate_witness_program(version, &program)?;
+
+    if variant != Bech32Variant::for_witness_version(version) { return Err(Bech32Error::InvalidVariant(variant)) }
+
+    Ok((hrp, version, program))
+}
+
+/// Validates a witness version and the length of its witness program (BIP-141).
+pub fn validate_witness_program(version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if version > 16 { return Err(Bech32Error::InvalidWitnessVersion(version)) }
+
+    if !(2..=40).contains(&program.len()) || (version == 0 && program.len() != 20 && program.len() != 32) {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+
+    Ok(())
+}
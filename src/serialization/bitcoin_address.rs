@@ -1,122 +1,316 @@
 //! Bitcoin addresses.
 
+use std::str::FromStr;
+
 use crate::serialization::bitcoin_base58check::Base58CheckBitcoinEncoding;
 use crate::serialization::bitcoin_base58check::BitcoinEncodingPrefix;
 use crate::serialization::point::CompressedPointSecFormatBytes;
 use crate::serialization::point::UncompressedPointSecFormatBytes;
+use crate::serialization::bech32::Bech32Error;
+use crate::serialization::bech32::bech32_decode;
+use crate::serialization::bech32::segwit_address_decode;
+use crate::serialization::bech32::segwit_address_encode;
+use crate::serialization::bech32::validate_witness_program;
 use crate::bitcoin::network::BitcoinNetworkType;
 use crate::bitcoin::script::ScriptBytes;
 use crate::bitcoin::script_types::BitcoinTransactionType;
 use crate::crypto::digest::hash_160;
+use crate::crypto::digest::sha_256;
+use crate::util::byte_string::ByteString;
 use crate::util::byte_string::ByteSlice;
 
-/// Represents a Bitcoin address.
+/// Errors thrown when parsing (or validating) a Bitcoin address.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum BitcoinAddressError {
+    /// The Base58Check address contains an invalid character, is of an invalid length, or its
+    /// checksum is invalid.
+    Base58Check,
+
+    /// The Base58Check prefix byte does not denote a known address type.
+    UnknownPrefix(u8),
+
+    /// The address type is not formatted with Base58Check; i.e.; it is not P2PKH or P2SH.
+    NotBase58Check(BitcoinTransactionType),
+
+    /// The hash is not 20 bytes long; with its length.
+    HashLength(usize),
+
+    /// The segregated witness address could not be decoded.
+    Bech32(Bech32Error),
+
+    /// The human-readable part of the segregated witness address does not denote a known network.
+    UnknownNetwork(String),
+
+    /// The address is not of the expected network; with the address's network.
+    NetworkMismatch(BitcoinNetworkType),
+}
+
+/// A witness program of a witness version (or length) without a defined address type; e.g.; a
+/// future witness version.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct WitnessProgram {
+    version: u8,
+    program: [u8; 40],
+    length: usize,
+}
+
+impl WitnessProgram {
+    /// Create a witness program, given its witness version and witness program bytes.
+    pub fn new(version: u8, program: &[u8]) -> Result<Self, Bech32Error> {
+        validate_witness_program(version, program)?;
+
+        let mut buffer = [0_u8; 40];
+
+        buffer[..program.len()].clone_from_slice(program);
+
+        Ok(
+            Self {
+                version: version,
+                program: buffer,
+                length: program.len(),
+            }
+        )
+    }
+
+    /// Returns the witness version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the witness program bytes.
+    pub fn program(&self) -> &[u8] {
+        &self.program[..self.length]
+    }
+}
+
+/// Represents a Bitcoin address; i.e.; a locking script, and the network it is intended for.
 ///
-/// Stores a one byte prefix to denote address type/metadata, as well a 20 byte `HASH_160` digest
-/// of the underlying material (i.e.; public key hash, script hash).
+/// P2PKH and P2SH addresses are formatted with Base58Check, where a one byte prefix denotes the
+/// network and address type. Segregated witness addresses are formatted with Bech32 (version 0)
+/// or Bech32m (version 1 and later), where the human-readable part denotes the network.
 #[derive(Debug)]
 #[derive(Clone, Copy)]
-pub struct BitcoinAddress {
-    bytes: [u8; 21]
+#[derive(PartialEq)]
+pub enum BitcoinAddress {
+    /// P2PKH; a 20 byte public key hash.
+    P2pkh { network: BitcoinNetworkType, hash: [u8; 20] },
+
+    /// P2SH; a 20 byte redeem script hash.
+    P2sh { network: BitcoinNetworkType, hash: [u8; 20] },
+
+    /// P2WPKH; a 20 byte public key hash (version 0 witness program).
+    P2wpkh { network: BitcoinNetworkType, hash: [u8; 20] },
+
+    /// P2WSH; a 32 byte witness script hash (version 0 witness program).
+    P2wsh { network: BitcoinNetworkType, hash: [u8; 32] },
+
+    /// P2TR; a 32 byte (x-only) output key (version 1 witness program).
+    P2tr { network: BitcoinNetworkType, output_key: [u8; 32] },
+
+    /// A witness program without a defined address type.
+    WitnessUnknown { network: BitcoinNetworkType, program: WitnessProgram },
 }
 
 impl std::fmt::Display for BitcoinAddress {
-    /// Displays the Bitcoin address, formatted with Base58Check.
+    /// Displays the Bitcoin address, formatted with Base58Check (or Bech32, Bech32m).
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let encoding = Base58CheckBitcoinEncoding::from_bytes(&self.bytes);
+        match self.witness_program() {
+            Some((version, program)) => {
+                write!(f, "{}", segwit_address_encode(self.network().bech32_hrp(), version, program))
+            },
+            None => {
+                let prefix = Self::base58_encoding_type(self.network(), self.transaction_type())
+                    .map_err(|_| std::fmt::Error)?;
+                let bytes = [BitcoinEncodingPrefix::bytes(prefix), self.hash_bytes()].concat();
 
-        write!(f, "{}", encoding.to_string())
+                write!(f, "{}", Base58CheckBitcoinEncoding::from_bytes(&bytes))
+            },
+        }
     }
 }
 
-const MAINNET_P2PKH: u8 = BitcoinEncodingPrefix::MainnetP2pkhAddress.bytes()[0];
-const MAINNET_P2SH: u8 = BitcoinEncodingPrefix::MainnetP2shAddress.bytes()[0];
-const TESTNET_P2PKH: u8 = BitcoinEncodingPrefix::TestnetP2pkhAddress.bytes()[0];
-const TESTNET_P2SH: u8 = BitcoinEncodingPrefix::TestnetP2shAddress.bytes()[0];
+impl FromStr for BitcoinAddress {
+    type Err = BitcoinAddressError;
+
+    /// Parses a Bitcoin address (see `BitcoinAddress::parse`).
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Self::parse(address)
+    }
+}
 
 impl BitcoinAddress {
-    pub fn base58_encoding_type(network: BitcoinNetworkType, address_type: BitcoinTransactionType) -> BitcoinEncodingPrefix {
-        match (network, address_type) {
-            (BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2pkh) => BitcoinEncodingPrefix::MainnetP2pkhAddress,
-            (BitcoinNetworkType::Mainnet, BitcoinTransactionType::P2sh) => BitcoinEncodingPrefix::MainnetP2shAddress,
+    /// Returns the Base58Check prefix of some network and address type (P2PKH or P2SH).
+    pub fn base58_encoding_type(network: BitcoinNetworkType, address_type: BitcoinTransactionType) -> Result<BitcoinEncodingPrefix, BitcoinAddressError> {
+        let parameters = network.parameters();
 
-            (BitcoinNetworkType::Testnet, BitcoinTransactionType::P2pkh) => BitcoinEncodingPrefix::TestnetP2pkhAddress,
-            (BitcoinNetworkType::Testnet, BitcoinTransactionType::P2sh) => BitcoinEncodingPrefix::TestnetP2shAddress,
+        match address_type {
+            BitcoinTransactionType::P2pkh => Ok(parameters.p2pkh_address_prefix),
+            BitcoinTransactionType::P2sh => Ok(parameters.p2sh_address_prefix),
 
-            _ => panic!("unknown network and/or transaction type")
+            _ => Err(BitcoinAddressError::NotBase58Check(address_type))
         }
     }
 
     /// Returns the network type.
     pub fn network(&self) -> BitcoinNetworkType {
-        match self.bytes[0] {
-            | MAINNET_P2PKH
-            | MAINNET_P2SH
-            => BitcoinNetworkType::Mainnet,
-            | TESTNET_P2PKH
-            | TESTNET_P2SH
-            => BitcoinNetworkType::Testnet,
-            _=> panic!("unexpected prefix byte"),
+        match self {
+            | Self::P2pkh { network, .. }
+            | Self::P2sh { network, .. }
+            | Self::P2wpkh { network, .. }
+            | Self::P2wsh { network, .. }
+            | Self::P2tr { network, .. }
+            | Self::WitnessUnknown { network, .. }
+            => *network,
         }
     }
 
     /// Returns the transaction type.
     pub fn transaction_type(&self) -> BitcoinTransactionType {
-        match self.bytes[0] {
-            | MAINNET_P2PKH
-            | TESTNET_P2PKH
-            => BitcoinTransactionType::P2pkh,
-            | MAINNET_P2SH
-            | TESTNET_P2SH
-            => BitcoinTransactionType::P2sh,
-            _=> panic!("unexpected prefix byte"),
+        match self {
+            Self::P2pkh { .. } => BitcoinTransactionType::P2pkh,
+            Self::P2sh { .. } => BitcoinTransactionType::P2sh,
+            Self::P2wpkh { .. } => BitcoinTransactionType::P2wpkh,
+            Self::P2wsh { .. } => BitcoinTransactionType::P2wsh,
+            Self::P2tr { .. } => BitcoinTransactionType::P2tr,
+            Self::WitnessUnknown { .. } => BitcoinTransactionType::WitnessUnknown,
         }
     }
 
-    /// Returns the 20 byte hash that this address represents.
+    /// Returns the hash that this address represents (or, for a segregated witness address, the
+    /// witness program).
     pub fn hash_bytes(&self) -> &[u8] {
-        &self.bytes[1..]
+        match self {
+            Self::P2pkh { hash, .. } => hash,
+            Self::P2sh { hash, .. } => hash,
+            Self::P2wpkh { hash, .. } => hash,
+            Self::P2wsh { hash, .. } => hash,
+            Self::P2tr { output_key, .. } => output_key,
+            Self::WitnessUnknown { program, .. } => program.program(),
+        }
     }
 
-    /// Creates a Bitcoin address from its Base58Check string representation.
-    pub fn base58(address: &str) -> Self {
-        let mut bytes: [u8; 21] = [0_u8; 21];
-        let data = Base58CheckBitcoinEncoding::decode(21, address).unwrap();
+    /// Returns the witness version and witness program, if this is a segregated witness address.
+    pub fn witness_program(&self) -> Option<(u8, &[u8])> {
+        match self {
+            Self::P2pkh { .. } | Self::P2sh { .. } => None,
+            Self::P2wpkh { hash, .. } => Some((0, hash)),
+            Self::P2wsh { hash, .. } => Some((0, hash)),
+            Self::P2tr { output_key, .. } => Some((1, output_key)),
+            Self::WitnessUnknown { program, .. } => Some((program.version(), program.program())),
+        }
+    }
+
+    /// Indicates that this address is valid on some network; i.e.; that network encodes this kind
+    /// of address identically to the address's own network.
+    ///
+    /// e.g.; a testnet address is also valid on testnet4 and signet, and a testnet Base58Check
+    /// address is also valid on regtest.
+    pub fn is_valid_for_network(&self, network: BitcoinNetworkType) -> bool {
+        let own_network = self.network();
 
-        bytes.clone_from_slice(&data);
+        match self {
+            Self::P2pkh { .. } | Self::P2sh { .. } => {
+                let transaction_type = self.transaction_type();
 
-        Self {
-            bytes: bytes
+                Self::base58_encoding_type(own_network, transaction_type) == Self::base58_encoding_type(network, transaction_type)
+            },
+            _ => own_network.bech32_hrp() == network.bech32_hrp(),
         }
     }
 
-    /// Creates a Bitcoin address for some 20 byte digest.
-    pub fn for_hash_bytes(prefix: BitcoinEncodingPrefix, hash_bytes: &[u8]) -> Self {
-        let mut bytes: [u8; 21] = [0_u8; 21];
-        let prefix_bytes: &[u8] = BitcoinEncodingPrefix::bytes(prefix);
+    /// Returns this address (of some network) if it is valid on that network; i.e.; validates that
+    /// an address is intended for the network it is used on.
+    ///
+    /// A parsed address is attributed to the first network that encodes it (see `parse`); the
+    /// returned address is of the given network.
+    pub fn require_network(self, network: BitcoinNetworkType) -> Result<Self, BitcoinAddressError> {
+        if !self.is_valid_for_network(network) { return Err(BitcoinAddressError::NetworkMismatch(self.network())) }
 
-        bytes[0..=0].clone_from_slice(prefix_bytes);
-        bytes[1..=20].clone_from_slice(&hash_bytes);
+        Ok(self.with_network(network))
+    }
 
-        Self {
-            bytes: bytes
+    /// Returns this address, of some other network.
+    fn with_network(self, network: BitcoinNetworkType) -> Self {
+        match self {
+            Self::P2pkh { hash, .. } => Self::P2pkh { network: network, hash: hash },
+            Self::P2sh { hash, .. } => Self::P2sh { network: network, hash: hash },
+            Self::P2wpkh { hash, .. } => Self::P2wpkh { network: network, hash: hash },
+            Self::P2wsh { hash, .. } => Self::P2wsh { network: network, hash: hash },
+            Self::P2tr { output_key, .. } => Self::P2tr { network: network, output_key: output_key },
+            Self::WitnessUnknown { program, .. } => Self::WitnessUnknown { network: network, program: program },
         }
     }
 
+    /// Creates a Base58Check Bitcoin address for some prefix byte, if it denotes a known address
+    /// type.
+    ///
+    /// Prefixes shared by several networks are attributed to the first of them (see
+    /// `BitcoinNetworkType::ALL`); i.e.; testnet.
+    fn for_prefix_byte(prefix: u8, hash: [u8; 20]) -> Option<Self> {
+        BitcoinNetworkType::ALL.into_iter().find_map(|network| {
+            let parameters = network.parameters();
+
+            if prefix == parameters.p2pkh_address_prefix.bytes()[0] {
+                Some(Self::P2pkh { network: network, hash: hash })
+            } else if prefix == parameters.p2sh_address_prefix.bytes()[0] {
+                Some(Self::P2sh { network: network, hash: hash })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Creates a Bitcoin address from its Base58Check string representation.
+    pub fn base58(address: &str) -> Result<Self, BitcoinAddressError> {
+        let data = Base58CheckBitcoinEncoding::decode(21, address).map_err(|_| BitcoinAddressError::Base58Check)?;
+
+        let mut hash = [0_u8; 20];
+
+        hash.clone_from_slice(&data[1..=20]);
+
+        Self::for_prefix_byte(data[0], hash).ok_or(BitcoinAddressError::UnknownPrefix(data[0]))
+    }
+
+    /// Creates a Bitcoin address from its string representation; i.e.; a segregated witness
+    /// address (Bech32 or Bech32m), or otherwise a Base58Check address (see `base58`).
+    ///
+    /// A string is decoded as a segregated witness address if it begins with the human-readable
+    /// part of a known network (and the separator), or is otherwise a valid Bech32 string.
+    ///
+    /// Testnet, testnet4, and signet addresses (and regtest Base58Check addresses) are encoded
+    /// identically; such an address is attributed to testnet, and may be validated against (and
+    /// attributed to) another network with `require_network`.
+    pub fn parse(address: &str) -> Result<Self, BitcoinAddressError> {
+        let lowercase = address.to_ascii_lowercase();
+
+        let is_segwit = BitcoinNetworkType::ALL.iter()
+            .any(|network| lowercase.starts_with(&format!("{}1", network.bech32_hrp())));
+
+        if !is_segwit && bech32_decode(address).is_err() { return Self::base58(address) }
+
+        let (hrp, version, program) = segwit_address_decode(address).map_err(BitcoinAddressError::Bech32)?;
+        let network = BitcoinNetworkType::from_bech32_hrp(&hrp).ok_or(BitcoinAddressError::UnknownNetwork(hrp))?;
+
+        Self::for_witness_program(network, version, &program).map_err(BitcoinAddressError::Bech32)
+    }
+
+    /// Creates a Bitcoin address for some 20 byte digest, given its Base58Check prefix.
+    pub fn for_hash_bytes(prefix: BitcoinEncodingPrefix, hash_bytes: &[u8]) -> Result<Self, BitcoinAddressError> {
+        let hash: [u8; 20] = hash_bytes.try_into().map_err(|_| BitcoinAddressError::HashLength(hash_bytes.len()))?;
+        let prefix_byte = BitcoinEncodingPrefix::bytes(prefix)[0];
+
+        Self::for_prefix_byte(prefix_byte, hash).ok_or(BitcoinAddressError::UnknownPrefix(prefix_byte))
+    }
+
     /// Creates a P2PKH Bitcoin address for a given (public key) elliptic curve point.
     ///
     /// This is encoded using a digest of the compressed point SEC format
     /// (`CompressedPointSecFormatBytes`).
     pub fn for_compressed_point(network: BitcoinNetworkType, point_bytes: CompressedPointSecFormatBytes) -> Self {
-        let mut bytes: [u8; 21] = [0_u8; 21];
-        let prefix_bytes: &[u8] = BitcoinEncodingPrefix::bytes(Self::base58_encoding_type(network, BitcoinTransactionType::P2pkh));
-
-        bytes[0..=0].clone_from_slice(prefix_bytes);
-        bytes[1..=20].clone_from_slice(&hash_160(point_bytes.bytes()));
-
-        Self {
-            bytes: bytes
-        }
+        Self::P2pkh { network: network, hash: hash_160(point_bytes.bytes()) }
     }
 
     /// Creates a P2PKH Bitcoin address for a given (public key) elliptic curve point.
@@ -124,29 +318,82 @@ impl BitcoinAddress {
     /// This is encoded using a hash of the uncompressed point SEC format
     /// (`UncompressedPointSecFormatBytes`).
     pub fn for_uncompressed_point(network: BitcoinNetworkType, point_bytes: UncompressedPointSecFormatBytes) -> Self {
-        let mut bytes: [u8; 21] = [0_u8; 21];
-        let prefix_bytes: &[u8] = BitcoinEncodingPrefix::bytes(Self::base58_encoding_type(network, BitcoinTransactionType::P2pkh));
-
-        bytes[0..=0].clone_from_slice(prefix_bytes);
-        bytes[1..=20].clone_from_slice(&hash_160(point_bytes.bytes()));
-
-        Self {
-            bytes: bytes
-        }
+        Self::P2pkh { network: network, hash: hash_160(point_bytes.bytes()) }
     }
 
     /// Creates a P2SH Bitcoin address for a given redeem script.
     ///
     /// This is encoded using a digest of the script bytes.
     pub fn for_redeem_script(network: BitcoinNetworkType, redeem_script_bytes: ScriptBytes) -> Self {
-        let mut bytes: [u8; 21] = [0_u8; 21];
-        let prefix_bytes: &[u8] = BitcoinEncodingPrefix::bytes(Self::base58_encoding_type(network, BitcoinTransactionType::P2sh));
+        Self::P2sh { network: network, hash: hash_160(redeem_script_bytes.bytes()) }
+    }
 
-        bytes[0..=0].clone_from_slice(prefix_bytes);
-        bytes[1..=20].clone_from_slice(&hash_160(redeem_script_bytes.bytes()));
+    /// Creates a P2WPKH Bitcoin address for a given (public key) elliptic curve point.
+    pub fn for_compressed_point_p2wpkh(network: BitcoinNetworkType, point_bytes: CompressedPointSecFormatBytes) -> Self {
+        Self::P2wpkh { network: network, hash: hash_160(point_bytes.bytes()) }
+    }
+
+    /// Creates a P2WSH Bitcoin address for a given witness script.
+    pub fn for_witness_script(network: BitcoinNetworkType, witness_script_bytes: &ScriptBytes) -> Self {
+        Self::P2wsh { network: network, hash: sha_256(witness_script_bytes.bytes()) }
+    }
 
-        Self {
-            bytes: bytes
+    /// Creates a P2TR Bitcoin address for a given (x-only) output key.
+    pub fn for_output_key(network: BitcoinNetworkType, output_key: [u8; 32]) -> Self {
+        Self::P2tr { network: network, output_key: output_key }
+    }
+
+    /// Creates a segregated witness address for some witness version and witness program.
+    pub fn for_witness_program(network: BitcoinNetworkType, version: u8, program: &[u8]) -> Result<Self, Bech32Error> {
+        let program = WitnessProgram::new(version, program)?;
+
+        let address = match (version, program.program().len()) {
+            (0, 20) => Self::P2wpkh { network: network, hash: program.program().try_into().unwrap() },
+            (0, _) => Self::P2wsh { network: network, hash: program.program().try_into().unwrap() },
+            (1, 32) => Self::P2tr { network: network, output_key: program.program().try_into().unwrap() },
+            _ => Self::WitnessUnknown { network: network, program: program },
+        };
+
+        Ok(address)
+    }
+
+    /// Creates the Bitcoin address that a locking script represents, if any (e.g.; not for P2PK,
+    /// bare multisig, or null data locking scripts).
+    pub fn from_script(script: &ScriptBytes, network: BitcoinNetworkType) -> Option<Self> {
+        if let Some((version, program)) = script.witness_program() {
+            return Self::for_witness_program(network, version, program).ok();
+        }
+
+        let mut hash = [0_u8; 20];
+
+        match script.locking_script_type() {
+            BitcoinTransactionType::P2pkh => {
+                hash.clone_from_slice(script.locking_public_key_hash());
+
+                Some(Self::P2pkh { network: network, hash: hash })
+            },
+            BitcoinTransactionType::P2sh => {
+                hash.clone_from_slice(script.locking_redeem_script_hash());
+
+                Some(Self::P2sh { network: network, hash: hash })
+            },
+            _ => None,
         }
     }
+
+    /// Creates the locking script (`scriptPubKey`) that this address represents.
+    pub fn script_pubkey(&self) -> ScriptBytes {
+        let bytes = match self {
+            Self::P2pkh { hash, .. } => [&[0x76_u8, 0xa9_u8, 0x14_u8][..], hash, &[0x88_u8, 0xac_u8]].concat(),
+            Self::P2sh { hash, .. } => [&[0xa9_u8, 0x14_u8][..], hash, &[0x87_u8]].concat(),
+            _ => {
+                let (version, program) = self.witness_program().unwrap();
+                let version_opcode = if version == 0 { 0x00_u8 } else { 0x50_u8 + version };
+
+                [&[version_opcode, u8::try_from(program.len()).unwrap()][..], program].concat()
+            },
+        };
+
+        ScriptBytes::of(&bytes)
+    }
 }
@@ -3,6 +3,7 @@
 use std::iter::repeat;
 
 use crate::serialization::base58::Base58Encoding;
+use crate::serialization::base58::BASE58_CHARACTERS;
 use crate::serialization::bytes::count_leading_zero_bytes;
 use crate::crypto::digest::hash_256;
 use crate::util::number::Uint;
@@ -40,6 +41,8 @@ impl <'a> Base58CheckBitcoinEncoding<'a> {
 ///
 /// # Testnet
 ///
+/// Shared by testnet3, testnet4, signet, and regtest.
+///
 /// - `0x6f`: testnet p2pkh address ("`{m,n}...`")
 /// - `0xc4`: testnet p2sh address ("`2...`")
 ///
@@ -47,6 +50,9 @@ impl <'a> Base58CheckBitcoinEncoding<'a> {
 ///
 /// - `0x04358394`: testnet extended private key ("`tprv...`")
 /// - `0x043578cf`: testnet extended public key ("`tpub...`")
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
 pub enum BitcoinEncodingPrefix {
     // Mainnet
     MainnetP2pkhAddress,
@@ -141,15 +147,20 @@ pub struct Base58CheckError;
 impl Base58CheckBitcoinEncoding<'_> {
     /// Decodes a Base58Check string into an expected number of bytes.
     ///
-    /// Verifies the checksum, and returns an error if it could not be verified.
+    /// Verifies the checksum, and returns an error if it could not be verified (or if the string
+    /// contains a character outside of the Base58 character set, or does not decode into the
+    /// expected number of bytes).
     ///
     /// Limited to expressions within 72 bytes (576 bits).
     pub fn decode(n: usize, string: &str) -> Result<Vec<u8>, Base58CheckError> {
+        if string.chars().count() > 98 || !string.chars().all(|c| BASE58_CHARACTERS.contains(&c)) {
+            return Err(Base58CheckError);
+        }
+
         let mut bytes: Vec<u8> = Base58Encoding::decode(string).collect();
         let length = bytes.len();
 
-        assert!(length >= 4);
-        assert!(length <= n + 4);
+        if length < 4 || length > n + 4 { return Err(Base58CheckError) }
 
         let length = length - 4;
 
@@ -157,7 +168,7 @@ impl Base58CheckBitcoinEncoding<'_> {
 
         let data = &bytes[0..n];
 
-        if &bytes[n..(n + 4)] != &hash_256(data)[0..4] {
+        if bytes[n..(n + 4)] != hash_256(data)[0..4] {
             return Err(Base58CheckError);
         }
 
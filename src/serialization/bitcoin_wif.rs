@@ -23,17 +23,13 @@ impl BitcoinWif {
     pub fn for_secret_e(network: BitcoinNetworkType, secret_e: U256, compressed_point: bool) -> Self {
         let mut bytes = [0_u8; 34];
 
-        let prefix_bytes =
-            match network {
-                BitcoinNetworkType::Mainnet => BitcoinEncodingPrefix::bytes(BitcoinEncodingPrefix::MainnetWifPrivateKey),
-                BitcoinNetworkType::Testnet => BitcoinEncodingPrefix::bytes(BitcoinEncodingPrefix::TestnetWifPrivateKey),
-            };
+        let prefix_bytes = BitcoinEncodingPrefix::bytes(network.parameters().wif_private_key_prefix);
 
         bytes[0..=0].clone_from_slice(prefix_bytes);
         bytes[1..=32].clone_from_slice(&secret_e.to_be_bytes::<32>());
 
         if compressed_point {
-            bytes[33..=33].clone_from_slice(&BitcoinEncodingSuffix::bytes(BitcoinEncodingSuffix::WifWithCompressedPoint));
+            bytes[33..=33].clone_from_slice(BitcoinEncodingSuffix::bytes(BitcoinEncodingSuffix::WifWithCompressedPoint));
         }
 
         Self {
@@ -55,6 +51,6 @@ impl std::fmt::Display for BitcoinWif {
 
         let encoding = Base58CheckBitcoinEncoding::from_bytes(bytes);
 
-        write!(f, "{}", encoding.to_string())
+        write!(f, "{}", encoding)
     }
 }
@@ -0,0 +1,453 @@
+//! JSON (RFC 8259) encoding.
+//!
+//! A minimal JSON document model, sufficient for exchanging structures with Bitcoin Core's RPC
+//! interface (e.g.; `decoderawtransaction`).
+//!
+//! Numbers are kept as their literal text, so that amounts (e.g.; `0.00150000`) and large
+//! integers survive a round trip without floating point conversion.
+
+/// The maximum nesting depth of arrays and objects, when parsing.
+pub const JSON_MAX_DEPTH: usize = 512;
+
+/// A JSON value.
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+
+    /// A number, as its literal text.
+    Number(String),
+
+    String(String),
+    Array(Vec<JsonValue>),
+
+    /// An object, whose members retain their order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// Errors thrown when parsing JSON.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum JsonParsingError {
+    /// The document ended unexpectedly.
+    UnexpectedEnd,
+
+    /// An unexpected character was found at some byte offset.
+    UnexpectedCharacter(usize),
+
+    /// A string escape sequence is invalid, at some byte offset.
+    InvalidEscape(usize),
+
+    /// A number is malformed, at some byte offset.
+    InvalidNumber(usize),
+
+    /// Characters follow the top-level value, at some byte offset.
+    TrailingCharacters(usize),
+
+    /// Arrays and objects are nested deeper than `JSON_MAX_DEPTH`, at some byte offset.
+    DepthExceeded(usize),
+}
+
+impl JsonValue {
+    /// Create a number from an integer.
+    pub fn integer(n: impl Into<i128>) -> Self {
+        Self::Number(n.into().to_string())
+    }
+
+    /// Create a string.
+    pub fn string(s: impl Into<String>) -> Self {
+        Self::String(s.into())
+    }
+
+    /// Returns an object member by its key, if this is an object containing that key.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            Self::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the literal text of the number, if this is a number.
+    pub fn as_number(&self) -> Option<&str> {
+        match self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as an unsigned integer, if this is a number representing one.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.parse().ok()
+    }
+
+    /// Returns the number as a signed integer, if this is a number representing one.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.parse().ok()
+    }
+
+    /// Returns the elements, if this is an array.
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            Self::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Parse a JSON document.
+    pub fn parse(s: &str) -> Result<Self, JsonParsingError> {
+        let mut parser = JsonParser { bytes: s.as_bytes(), index: 0, depth: 0 };
+
+        let value = parser.parse_value()?;
+
+        parser.skip_whitespace();
+
+        if parser.index < parser.bytes.len() { return Err(JsonParsingError::TrailingCharacters(parser.index)) }
+
+        Ok(value)
+    }
+
+    /// Format the value with line breaks, indenting nested values by some number of spaces.
+    pub fn to_string_pretty(&self, indent: usize) -> String {
+        let mut s = String::new();
+
+        self.write(&mut s, Some(indent), 0);
+
+        s
+    }
+
+    fn write(&self, s: &mut String, indent: Option<usize>, depth: usize) {
+        let newline = |s: &mut String, depth: usize| {
+            if let Some(indent) = indent {
+                s.push('\n');
+                s.push_str(&" ".repeat(indent * depth));
+            }
+        };
+
+        match self {
+            Self::Null => s.push_str("null"),
+            Self::Bool(b) => s.push_str(if *b { "true" } else { "false" }),
+            Self::Number(n) => s.push_str(n),
+            Self::String(string) => write_string(s, string),
+            Self::Array(elements) => {
+                if elements.is_empty() { return s.push_str("[]") }
+
+                s.push('[');
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 { s.push(',') }
+
+                    newline(s, depth + 1);
+                    element.write(s, indent, depth + 1);
+                }
+
+                newline(s, depth);
+                s.push(']');
+            },
+            Self::Object(members) => {
+                if members.is_empty() { return s.push_str("{}") }
+
+                s.push('{');
+
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 { s.push(',') }
+
+                    newline(s, depth + 1);
+                    write_string(s, key);
+                    s.push_str(if indent.is_some() { ": " } else { ":" });
+                    value.write(s, indent, depth + 1);
+                }
+
+                newline(s, depth);
+                s.push('}');
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for JsonValue {
+    /// Displays the value as compact JSON.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut s = String::new();
+
+        self.write(&mut s, None, 0);
+
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for JsonValue {
+    type Err = JsonParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Write a quoted, escaped JSON string.
+fn write_string(s: &mut String, string: &str) {
+    s.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if u32::from(c) < 0x20 => s.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => s.push(c),
+        }
+    }
+
+    s.push('"');
+}
+
+/// Append a character to a sequence of UTF-8 bytes.
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    let mut buffer = [0_u8; 4];
+
+    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+/// A recursive descent JSON parser.
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    index: usize,
+
+    /// The nesting depth of the array or object being parsed.
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.index) {
+            self.index += 1;
+        }
+    }
+
+    fn peek(&self) -> Result<u8, JsonParsingError> {
+        self.bytes.get(self.index).copied().ok_or(JsonParsingError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonParsingError> {
+        if self.peek()? != byte { return Err(JsonParsingError::UnexpectedCharacter(self.index)) }
+
+        self.index += 1;
+
+        Ok(())
+    }
+
+    fn expect_literal(&mut self, literal: &str) -> Result<(), JsonParsingError> {
+        for byte in literal.bytes() {
+            self.expect(byte)?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, JsonParsingError> {
+        self.skip_whitespace();
+
+        match self.peek()? {
+            b'n' => { self.expect_literal("null")?; Ok(JsonValue::Null) },
+            b't' => { self.expect_literal("true")?; Ok(JsonValue::Bool(true)) },
+            b'f' => { self.expect_literal("false")?; Ok(JsonValue::Bool(false)) },
+            b'"' => Ok(JsonValue::String(self.parse_string()?)),
+            b'[' | b'{' => {
+                // Bound the recursion; i.e.; the stack usage.
+                if self.depth == JSON_MAX_DEPTH { return Err(JsonParsingError::DepthExceeded(self.index)) }
+
+                self.depth += 1;
+
+                let value = if self.peek()? == b'[' { self.parse_array() } else { self.parse_object() };
+
+                self.depth -= 1;
+
+                value
+            },
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            _ => Err(JsonParsingError::UnexpectedCharacter(self.index)),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, JsonParsingError> {
+        let mut elements: Vec<JsonValue> = vec![];
+
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        if self.peek()? == b']' {
+            self.index += 1;
+
+            return Ok(JsonValue::Array(elements));
+        }
+
+        loop {
+            elements.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.peek()? {
+                b',' => self.index += 1,
+                b']' => { self.index += 1; return Ok(JsonValue::Array(elements)) },
+                _ => return Err(JsonParsingError::UnexpectedCharacter(self.index)),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, JsonParsingError> {
+        let mut members: Vec<(String, JsonValue)> = vec![];
+
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        if self.peek()? == b'}' {
+            self.index += 1;
+
+            return Ok(JsonValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            self.expect(b':')?;
+
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+
+            match self.peek()? {
+                b',' => self.index += 1,
+                b'}' => { self.index += 1; return Ok(JsonValue::Object(members)) },
+                _ => return Err(JsonParsingError::UnexpectedCharacter(self.index)),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonParsingError> {
+        let start = self.index;
+
+        let digits = |parser: &mut Self| -> usize {
+            let digits_start = parser.index;
+
+            while let Some(b'0'..=b'9') = parser.bytes.get(parser.index) {
+                parser.index += 1;
+            }
+
+            parser.index - digits_start
+        };
+
+        if self.peek()? == b'-' { self.index += 1 }
+
+        let integer_start = self.index;
+
+        if digits(self) == 0 { return Err(JsonParsingError::InvalidNumber(start)) }
+
+        // The integer part has no leading zeros.
+        if self.bytes[integer_start] == b'0' && self.index - integer_start > 1 { return Err(JsonParsingError::InvalidNumber(start)) }
+
+        if let Some(b'.') = self.bytes.get(self.index) {
+            self.index += 1;
+
+            if digits(self) == 0 { return Err(JsonParsingError::InvalidNumber(start)) }
+        }
+
+        if let Some(b'e' | b'E') = self.bytes.get(self.index) {
+            self.index += 1;
+
+            if let Some(b'+' | b'-') = self.bytes.get(self.index) { self.index += 1 }
+
+            if digits(self) == 0 { return Err(JsonParsingError::InvalidNumber(start)) }
+        }
+
+        let text = std::str::from_utf8(&self.bytes[start..self.index]).unwrap();
+
+        Ok(JsonValue::Number(String::from(text)))
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonParsingError> {
+        let mut bytes: Vec<u8> = vec![];
+
+        self.expect(b'"')?;
+
+        loop {
+            let byte = self.peek()?;
+
+            self.index += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape_index = self.index - 1;
+
+                    let c = match self.peek()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            self.index += 1;
+
+                            let mut code_point = self.parse_hex4()?;
+
+                            // Combine a UTF-16 surrogate pair.
+                            if (0xd800..0xdc00).contains(&code_point) {
+                                self.expect_literal("\\u").map_err(|_| JsonParsingError::InvalidEscape(escape_index))?;
+
+                                let low = self.parse_hex4()?;
+
+                                if !(0xdc00..0xe000).contains(&low) { return Err(JsonParsingError::InvalidEscape(escape_index)) }
+
+                                code_point = 0x10000 + ((code_point - 0xd800) << 10) + (low - 0xdc00);
+                            }
+
+                            push_char(&mut bytes, char::from_u32(code_point).ok_or(JsonParsingError::InvalidEscape(escape_index))?);
+
+                            continue;
+                        },
+                        _ => return Err(JsonParsingError::InvalidEscape(escape_index)),
+                    };
+
+                    self.index += 1;
+
+                    push_char(&mut bytes, c);
+                },
+                byte if byte < 0x20 => return Err(JsonParsingError::UnexpectedCharacter(self.index - 1)),
+                byte => bytes.push(byte),
+            }
+        }
+
+        // The input is a string slice, and escapes produce valid UTF-8.
+        Ok(String::from_utf8(bytes).unwrap())
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonParsingError> {
+        let start = self.index;
+
+        let digits = self.bytes.get(start..(start + 4)).ok_or(JsonParsingError::UnexpectedEnd)?;
+
+        // Exactly four hexadecimal digits; e.g.; not a sign.
+        if !digits.iter().all(u8::is_ascii_hexdigit) { return Err(JsonParsingError::InvalidEscape(start)) }
+
+        let digits = std::str::from_utf8(digits).unwrap();
+
+        self.index += 4;
+
+        u32::from_str_radix(digits, 16).map_err(|_| JsonParsingError::InvalidEscape(start))
+    }
+}
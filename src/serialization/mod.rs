@@ -6,5 +6,7 @@ pub mod signature;
 pub mod secp256k1;
 pub mod base58;
 pub mod bitcoin_base58check;
+pub mod bech32;
 pub mod bitcoin_address;
 pub mod bitcoin_wif;
+pub mod json;
@@ -37,7 +37,7 @@ impl ByteString for SignatureDerFormatBytes {
         assert_eq!(*header_byte, 0x30_u8);
 
         let length_byte = iterator.next().unwrap();
-        let length: usize = (*length_byte + 2_u8).into();
+        let length: usize = usize::from(*length_byte) + 2;
 
         assert!(length <= 72);
 
@@ -66,6 +66,73 @@ impl SignatureDerFormatBytes {
     }
 }
 
+impl SignatureDerFormatBytes {
+    /// Parse a DER format byte representation, if it is structurally sound.
+    ///
+    /// Unlike `SignatureDerFormatBytes::is_strict_encoding`, r-values and s-values need not be
+    /// minimally encoded, or positive; they must fit within 32 bytes (excluding a padding zero
+    /// byte).
+    pub fn try_of(bytes: &[u8]) -> Option<Self> {
+        let length = bytes.len();
+
+        if !(8..=72).contains(&length) { return None }
+
+        if bytes[0] != 0x30_u8 || usize::from(bytes[1]) + 2 != length { return None }
+        if bytes[2] != 0x02_u8 { return None }
+
+        let r_length = usize::from(bytes[3]);
+
+        if !(1..=33).contains(&r_length) || 6 + r_length > length { return None }
+        if bytes[4 + r_length] != 0x02_u8 { return None }
+
+        let s_length = usize::from(bytes[5 + r_length]);
+
+        if !(1..=33).contains(&s_length) || 6 + r_length + s_length != length { return None }
+
+        // A 33 byte value must begin with a padding zero byte.
+        if r_length == 33 && bytes[4] != 0x00_u8 { return None }
+        if s_length == 33 && bytes[6 + r_length] != 0x00_u8 { return None }
+
+        Some(Self::of(bytes))
+    }
+
+    /// Indicates that a sequence of bytes is a strictly encoded DER signature (BIP-66), without a
+    /// trailing `SIGHASH` flag byte.
+    ///
+    /// r-values and s-values must be positive, and minimally encoded (no padding zero bytes,
+    /// unless required to keep the value positive).
+    pub fn is_strict_encoding(bytes: &[u8]) -> bool {
+        let length = bytes.len();
+
+        if !(8..=72).contains(&length) { return false }
+
+        if bytes[0] != 0x30_u8 { return false }
+        if usize::from(bytes[1]) != length - 2 { return false }
+
+        let r_length = usize::from(bytes[3]);
+
+        if 5 + r_length >= length { return false }
+
+        let s_length = usize::from(bytes[5 + r_length]);
+
+        if r_length + s_length + 6 != length { return false }
+
+        // r-value.
+        if bytes[2] != 0x02_u8 { return false }
+        if r_length == 0 { return false }
+        if bytes[4] & 0x80_u8 != 0 { return false }
+        if r_length > 1 && bytes[4] == 0x00_u8 && bytes[5] & 0x80_u8 == 0 { return false }
+
+        // s-value.
+        if bytes[r_length + 4] != 0x02_u8 { return false }
+        if s_length == 0 { return false }
+        if bytes[r_length + 6] & 0x80_u8 != 0 { return false }
+        if s_length > 1 && bytes[r_length + 6] == 0x00_u8 && bytes[r_length + 7] & 0x80_u8 == 0 { return false }
+
+        true
+    }
+}
+
 impl std::fmt::Display for SignatureDerFormatBytes {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let mut buffer = [0_u8; 72 * 2];
@@ -9,6 +9,15 @@ pub trait ByteValue4 {
     fn bytes(&self) -> [u8; 4];
 }
 
+/// Represents a value represented as 8 bytes.
+pub trait ByteValue8 {
+    /// Initialize from a sequence of bytes.
+    fn of(bytes: &[u8]) -> Self;
+
+    /// Return a sequence of bytes.
+    fn bytes(&self) -> [u8; 8];
+}
+
 /// Represents a value represented as 32 bytes.
 pub trait ByteValue32 {
     /// Initialize from a sequence of bytes.